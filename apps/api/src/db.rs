@@ -1,6 +1,11 @@
-use crate::models::{EvidenceIn, EvidenceOut};
+use crate::models::{
+    AdminAuditOut, DeadLetterDetailOut, DeadLetterOut, DiscardIn, EvidenceIn, EvidenceOut,
+    JobAttemptOut, RequeueIn,
+};
 use chrono::Utc;
-use sqlx::{Pool, Row, Sqlite};
+use phoenix_common::outbox::{FailureCategory, DEFAULT_PRIORITY, HELD_NEXT_ATTEMPT_MS};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Pool, Row, Sqlite, Transaction};
 use uuid::Uuid;
 
 pub async fn create_evidence_job(
@@ -13,11 +18,16 @@ pub async fn create_evidence_job(
         .unwrap_or_else(|| Uuid::new_v4().to_string());
     let now = Utc::now().timestamp_millis();
     let result = sqlx::query(
-        "INSERT OR IGNORE INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms) VALUES (?1, ?2, 'queued', 0, ?3, ?3)"
+        "INSERT OR IGNORE INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms, next_attempt_ms, priority, tenant, payload_mime, metadata) VALUES (?1, ?2, 'queued', 0, ?3, ?3, ?6, ?4, ?5, ?7, ?8)"
     )
     .bind(&id)
     .bind(&body.digest_hex)
     .bind(now)
+    .bind(body.priority.unwrap_or(DEFAULT_PRIORITY))
+    .bind(&body.tenant)
+    .bind(body.next_attempt_ms())
+    .bind(&body.payload_mime)
+    .bind(body.metadata_json())
     .execute(pool)
     .await?;
     Ok((id, result.rows_affected()))
@@ -28,9 +38,10 @@ pub async fn get_evidence_by_id(
     id: &str,
 ) -> Result<Option<EvidenceOut>, sqlx::Error> {
     let row = sqlx::query(
-        "SELECT id, status, attempts, last_error, created_ms, updated_ms FROM outbox_jobs WHERE id=?1"
+        "SELECT id, CASE WHEN status='queued' AND next_attempt_ms=?2 THEN 'held' ELSE status END, attempts, last_error, created_ms, updated_ms FROM outbox_jobs WHERE id=?1"
     )
     .bind(id)
+    .bind(HELD_NEXT_ATTEMPT_MS)
     .fetch_optional(pool)
     .await?;
 
@@ -57,10 +68,11 @@ pub async fn list_evidence_jobs(
 
     // Then, get the paginated list of jobs
     let rows = sqlx::query(
-        "SELECT id, status, attempts, last_error, created_ms, updated_ms FROM outbox_jobs ORDER BY created_ms DESC LIMIT ?1 OFFSET ?2"
+        "SELECT id, CASE WHEN status='queued' AND next_attempt_ms=?3 THEN 'held' ELSE status END, attempts, last_error, created_ms, updated_ms FROM outbox_jobs ORDER BY created_ms DESC LIMIT ?1 OFFSET ?2"
     )
     .bind(limit)
     .bind(offset)
+    .bind(HELD_NEXT_ATTEMPT_MS)
     .fetch_all(pool)
     .await?;
 
@@ -346,3 +358,291 @@ pub async fn list_jamming_operations(
 
     Ok((operations, total_count))
 }
+
+// Dead-letter admin functions
+
+/// Result of an admin action on a dead-lettered job
+#[derive(Debug, PartialEq, Eq)]
+pub enum AdminActionOutcome {
+    Applied,
+    NotFound,
+    /// The job is not dead-lettered; carries its current status
+    NotDeadLettered(String),
+}
+
+const DEAD_LETTER_COLUMNS: &str = "id, payload_sha256, status, attempts, last_error, failure_category, provider, created_ms, updated_ms";
+
+fn dead_letter_from_row(row: &SqliteRow) -> DeadLetterOut {
+    let last_error = row.get::<Option<String>, _>(4);
+    // Jobs failed before categories were recorded fall back to their last error
+    let failure_category = row.get::<Option<String>, _>(5).unwrap_or_else(|| {
+        FailureCategory::classify(last_error.as_deref().unwrap_or_default()).to_string()
+    });
+    DeadLetterOut {
+        id: row.get::<String, _>(0),
+        payload_sha256: row.get::<String, _>(1),
+        status: row.get::<String, _>(2),
+        attempts: row.get::<i64, _>(3),
+        last_error,
+        failure_category,
+        provider: row.get::<Option<String>, _>(6),
+        created_ms: row.get::<i64, _>(7),
+        updated_ms: row.get::<i64, _>(8),
+    }
+}
+
+pub async fn list_dead_letter_jobs(
+    pool: &Pool<Sqlite>,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<DeadLetterOut>, i64), sqlx::Error> {
+    let count_row = sqlx::query("SELECT COUNT(*) FROM outbox_jobs WHERE status='failed'")
+        .fetch_one(pool)
+        .await?;
+    let total_count: i64 = count_row.get(0);
+
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM outbox_jobs WHERE status='failed' ORDER BY updated_ms DESC LIMIT ?1 OFFSET ?2",
+        DEAD_LETTER_COLUMNS
+    ))
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok((rows.iter().map(dead_letter_from_row).collect(), total_count))
+}
+
+/// Queued jobs and unconfirmed transaction references, for the `/metrics` gauges
+pub async fn outbox_gauges(pool: &Pool<Sqlite>) -> Result<(i64, i64), sqlx::Error> {
+    sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM outbox_jobs WHERE status='queued' AND next_attempt_ms < ?1), (SELECT COUNT(*) FROM outbox_tx_refs WHERE confirmed = 0 AND dropped_ms IS NULL)",
+    )
+    .bind(HELD_NEXT_ATTEMPT_MS)
+    .fetch_one(pool)
+    .await
+}
+
+/// Result of releasing a held evidence job
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReleaseOutcome {
+    Released,
+    NotFound,
+    /// The job is not held; carries its current status
+    NotHeld(String),
+}
+
+/// Make a held job due now. With `tenant` set, jobs of other tenants are
+/// treated as missing.
+pub async fn release_held_job(
+    pool: &Pool<Sqlite>,
+    id: &str,
+    tenant: Option<&str>,
+) -> Result<ReleaseOutcome, sqlx::Error> {
+    let now = Utc::now().timestamp_millis();
+    let released = sqlx::query(
+        "UPDATE outbox_jobs SET next_attempt_ms=?1, updated_ms=?1 WHERE id=?2 AND status='queued' AND next_attempt_ms=?3 AND (?4 IS NULL OR tenant=?4)",
+    )
+    .bind(now)
+    .bind(id)
+    .bind(HELD_NEXT_ATTEMPT_MS)
+    .bind(tenant)
+    .execute(pool)
+    .await?;
+    if released.rows_affected() > 0 {
+        return Ok(ReleaseOutcome::Released);
+    }
+
+    let status: Option<String> = sqlx::query_scalar(
+        "SELECT status FROM outbox_jobs WHERE id=?1 AND (?2 IS NULL OR tenant=?2)",
+    )
+    .bind(id)
+    .bind(tenant)
+    .fetch_optional(pool)
+    .await?;
+    Ok(status.map_or(ReleaseOutcome::NotFound, ReleaseOutcome::NotHeld))
+}
+
+/// Every attempt at a job, oldest first
+pub async fn list_job_attempts(
+    pool: &Pool<Sqlite>,
+    id: &str,
+) -> Result<Vec<JobAttemptOut>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT attempt, worker, outcome, category, error, started_ms, finished_ms, latency_ms, provider, endpoint FROM outbox_job_attempts WHERE job_id=?1 ORDER BY id",
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| JobAttemptOut {
+            attempt: row.get::<i64, _>(0),
+            worker: row.get::<Option<String>, _>(1),
+            outcome: row.get::<String, _>(2),
+            category: row.get::<Option<String>, _>(3),
+            error: row.get::<Option<String>, _>(4),
+            started_ms: row.get::<Option<i64>, _>(5),
+            finished_ms: row.get::<i64, _>(6),
+            latency_ms: row.get::<Option<i64>, _>(7),
+            provider: row.get::<Option<String>, _>(8),
+            endpoint: row.get::<Option<String>, _>(9),
+        })
+        .collect())
+}
+
+/// A failed or discarded job with its attempt history and admin audit trail
+pub async fn get_dead_letter_job(
+    pool: &Pool<Sqlite>,
+    id: &str,
+) -> Result<Option<DeadLetterDetailOut>, sqlx::Error> {
+    let Some(row) = sqlx::query(&format!(
+        "SELECT {} FROM outbox_jobs WHERE id=?1 AND status IN ('failed', 'discarded')",
+        DEAD_LETTER_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let attempt_history = list_job_attempts(pool, id).await?;
+
+    let audit = sqlx::query(
+        "SELECT id, action, job_id, actor, details, created_ms FROM admin_audit_log WHERE job_id=?1 ORDER BY created_ms, rowid",
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| AdminAuditOut {
+        id: row.get::<String, _>(0),
+        action: row.get::<String, _>(1),
+        job_id: row.get::<Option<String>, _>(2),
+        actor: row.get::<String, _>(3),
+        details: row
+            .get::<Option<String>, _>(4)
+            .and_then(|d| serde_json::from_str(&d).ok()),
+        created_ms: row.get::<i64, _>(5),
+    })
+    .collect();
+
+    Ok(Some(DeadLetterDetailOut {
+        job: dead_letter_from_row(&row),
+        attempt_history,
+        audit,
+    }))
+}
+
+/// Put a dead-lettered job back in the queue, optionally routed to another provider
+pub async fn requeue_dead_letter_job(
+    pool: &Pool<Sqlite>,
+    id: &str,
+    actor: &str,
+    body: &RequeueIn,
+) -> Result<AdminActionOutcome, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let job = match load_dead_letter(&mut tx, id).await? {
+        Ok(job) => job,
+        Err(outcome) => return Ok(outcome),
+    };
+
+    let now = Utc::now().timestamp_millis();
+    sqlx::query(
+        "UPDATE outbox_jobs SET status='queued', failure_category=NULL, provider=COALESCE(?1, provider), next_attempt_ms=?2, updated_ms=?2 WHERE id=?3",
+    )
+    .bind(&body.provider)
+    .bind(now)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    let details = serde_json::json!({
+        "reason": body.reason,
+        "previous_provider": job.provider,
+        "provider": body.provider.as_ref().or(job.provider.as_ref()),
+        "last_error": job.last_error,
+        "failure_category": job.failure_category,
+        "attempts": job.attempts,
+    });
+    record_admin_action(&mut tx, "requeue", id, actor, &details, now).await?;
+    tx.commit().await?;
+    Ok(AdminActionOutcome::Applied)
+}
+
+/// Give up on a dead-lettered job; it stays in the table with status `discarded`
+pub async fn discard_dead_letter_job(
+    pool: &Pool<Sqlite>,
+    id: &str,
+    actor: &str,
+    body: &DiscardIn,
+) -> Result<AdminActionOutcome, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let job = match load_dead_letter(&mut tx, id).await? {
+        Ok(job) => job,
+        Err(outcome) => return Ok(outcome),
+    };
+
+    let now = Utc::now().timestamp_millis();
+    sqlx::query("UPDATE outbox_jobs SET status='discarded', updated_ms=?1 WHERE id=?2")
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    let details = serde_json::json!({
+        "reason": body.reason,
+        "last_error": job.last_error,
+        "failure_category": job.failure_category,
+        "attempts": job.attempts,
+    });
+    record_admin_action(&mut tx, "discard", id, actor, &details, now).await?;
+    tx.commit().await?;
+    Ok(AdminActionOutcome::Applied)
+}
+
+/// Load a job inside an admin transaction, or the outcome to report if it is not dead-lettered.
+/// SQLite transactions are serializable, so the job cannot change between this read
+/// and the update that follows.
+async fn load_dead_letter(
+    tx: &mut Transaction<'_, Sqlite>,
+    id: &str,
+) -> Result<Result<DeadLetterOut, AdminActionOutcome>, sqlx::Error> {
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM outbox_jobs WHERE id=?1",
+        DEAD_LETTER_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok(match row.map(|row| dead_letter_from_row(&row)) {
+        None => Err(AdminActionOutcome::NotFound),
+        Some(job) if job.status != "failed" => Err(AdminActionOutcome::NotDeadLettered(job.status)),
+        Some(job) => Ok(job),
+    })
+}
+
+async fn record_admin_action(
+    tx: &mut Transaction<'_, Sqlite>,
+    action: &str,
+    job_id: &str,
+    actor: &str,
+    details: &serde_json::Value,
+    now: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO admin_audit_log (id, action, job_id, actor, details, created_ms) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(action)
+    .bind(job_id)
+    .bind(actor)
+    .bind(details.to_string())
+    .bind(now)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
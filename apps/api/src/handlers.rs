@@ -1,27 +1,43 @@
 use crate::{
     db::{
         create_countermeasure_deployment, create_evidence_job, create_jamming_operation,
-        create_signal_disruption_audit, get_countermeasure_deployment_by_id, get_evidence_by_id,
+        create_signal_disruption_audit, discard_dead_letter_job,
+        get_countermeasure_deployment_by_id, get_dead_letter_job, get_evidence_by_id,
         get_jamming_operation_by_id, get_signal_disruption_audit_by_id,
-        list_countermeasure_deployments, list_evidence_jobs, list_signal_disruption_audits,
+        list_countermeasure_deployments, list_dead_letter_jobs, list_evidence_jobs,
+        list_job_attempts, list_signal_disruption_audits, outbox_gauges, release_held_job,
+        requeue_dead_letter_job, AdminActionOutcome, ReleaseOutcome,
     },
+    keys::{LaneError, API_KEY_HEADER},
     models::{
-        CountermeasureDeploymentIn, EvidenceIn, JammingOperationIn, Pagination,
-        SignalDisruptionAuditIn,
+        CountermeasureDeploymentIn, DiscardIn, EvidenceIn, JammingOperationIn, Pagination,
+        RequeueIn, SignalDisruptionAuditIn,
     },
+    screening::screen_counterparties,
     AppState,
 };
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use chrono::Utc;
+use phoenix_common::metrics::{install_recorder, set_outbox_gauges, EVIDENCE_SUBMITTED};
 
 pub async fn health() -> &'static str {
     "OK"
 }
 
+/// Prometheus exposition, with the outbox gauges read fresh from the database
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    match outbox_gauges(&state.pool).await {
+        Ok((queued, unconfirmed)) => set_outbox_gauges(queued, unconfirmed),
+        Err(e) => tracing::warn!(error = %e, "Failed to read outbox gauges for metrics"),
+    }
+    install_recorder().render()
+}
+
 pub async fn list_evidence(
     State(state): State<AppState>,
     Query(pagination): Query<Pagination>,
@@ -50,14 +66,39 @@ pub async fn list_evidence(
 
 pub async fn post_evidence(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(body): Json<EvidenceIn>,
 ) -> impl IntoResponse {
+    if body.hold && body.not_before.is_some() {
+        return bad_request("hold and not_before cannot be combined");
+    }
+    if let Err(e) = screen_counterparties(&state.address_policy, body.metadata.as_ref()) {
+        return bad_request(&e);
+    }
+    let lane = match state
+        .keys
+        .lane(api_key(&headers), body.priority, body.tenant.clone())
+    {
+        Ok(lane) => lane,
+        Err(e) => return lane_error_response(e),
+    };
+    let body = EvidenceIn {
+        priority: Some(lane.priority),
+        tenant: lane.tenant,
+        ..body
+    };
     match create_evidence_job(&state.pool, &body).await {
         Ok((id, rows_affected)) => {
             if rows_affected > 0 {
+                metrics::counter!(EVIDENCE_SUBMITTED).increment(1);
+                // Held and scheduled jobs are picked up by a later poll or release
+                if body.next_attempt_ms() <= Utc::now().timestamp_millis() {
+                    state.keeper.job_queued(&id);
+                }
+                let status = if body.hold { "held" } else { "queued" };
                 (
                     StatusCode::OK,
-                    Json(serde_json::json!({ "id": id, "status": "queued" })),
+                    Json(serde_json::json!({ "id": id, "status": status })),
                 )
                     .into_response()
             } else {
@@ -72,6 +113,47 @@ pub async fn post_evidence(
     }
 }
 
+/// Release a job submitted with `hold` so the keeper anchors it
+pub async fn release_evidence(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let tenant = match state.keys.tenant_scope(api_key(&headers)) {
+        Ok(tenant) => tenant,
+        Err(e) => return lane_error_response(e),
+    };
+    match release_held_job(&state.pool, &id, tenant).await {
+        Ok(ReleaseOutcome::Released) => {
+            state.keeper.job_queued(&id);
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({ "id": id, "status": "queued" })),
+            )
+                .into_response()
+        }
+        Ok(ReleaseOutcome::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "id": id, "status": "not_found" })),
+        )
+            .into_response(),
+        Ok(ReleaseOutcome::NotHeld(current)) => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "evidence is not held",
+                "id": id,
+                "status": current,
+            })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
 pub async fn get_evidence(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -99,6 +181,42 @@ pub async fn get_evidence(
 }
 
 // Countermeasure Deployment handlers
+pub async fn get_evidence_attempts(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match get_evidence_by_id(&state.pool, &id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "id": id, "status": "not_found" })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            )
+                .into_response()
+        }
+    }
+
+    match list_job_attempts(&state.pool, &id).await {
+        Ok(attempts) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "id": id, "attempts": attempts })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
 pub async fn post_countermeasure(
     State(state): State<AppState>,
     Json(body): Json<CountermeasureDeploymentIn>,
@@ -310,3 +428,172 @@ pub async fn list_jamming_operations(
             .into_response(),
     }
 }
+
+// Dead-letter admin handlers
+fn admin_action_response(id: &str, outcome: AdminActionOutcome, status: &str) -> Response {
+    match outcome {
+        AdminActionOutcome::Applied => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "id": id, "status": status })),
+        )
+            .into_response(),
+        AdminActionOutcome::NotFound => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "id": id, "status": "not_found" })),
+        )
+            .into_response(),
+        AdminActionOutcome::NotDeadLettered(current) => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "job is not dead-lettered",
+                "id": id,
+                "status": current,
+            })),
+        )
+            .into_response(),
+    }
+}
+
+fn api_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn lane_error_response(e: LaneError) -> Response {
+    let status = match e {
+        LaneError::Unauthorized => StatusCode::UNAUTHORIZED,
+        LaneError::Forbidden(_) => StatusCode::FORBIDDEN,
+        LaneError::Invalid(_) => StatusCode::BAD_REQUEST,
+    };
+    (status, Json(serde_json::json!({ "error": e.to_string() }))).into_response()
+}
+
+fn bad_request(message: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": message })),
+    )
+        .into_response()
+}
+
+/// Provider names are short lowercase identifiers such as `etherlink`
+fn is_valid_provider(provider: &str) -> bool {
+    !provider.is_empty()
+        && provider.len() <= 64
+        && provider
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+pub async fn list_dead_letters(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(pagination): Query<Pagination>,
+) -> impl IntoResponse {
+    if let Err(e) = state.keys.admin(api_key(&headers)) {
+        return lane_error_response(e);
+    }
+    let page = pagination.page.unwrap_or(1).max(1);
+    let per_page = pagination.per_page.unwrap_or(10).max(1);
+    let offset = (page - 1) * per_page;
+
+    match list_dead_letter_jobs(&state.pool, per_page, offset).await {
+        Ok((jobs, total_count)) => {
+            let response = serde_json::json!({
+                "data": jobs,
+                "page": page,
+                "per_page": per_page,
+                "total": total_count,
+            });
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn get_dead_letter(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = state.keys.admin(api_key(&headers)) {
+        return lane_error_response(e);
+    }
+    match get_dead_letter_job(&state.pool, &id).await {
+        Ok(Some(job)) => match serde_json::to_value(job) {
+            Ok(json) => (StatusCode::OK, Json(json)).into_response(),
+            Err(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            )
+                .into_response(),
+        },
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "id": id, "status": "not_found" })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn requeue_dead_letter(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(body): Json<RequeueIn>,
+) -> impl IntoResponse {
+    let actor = match state.keys.admin(api_key(&headers)) {
+        Ok(actor) => actor,
+        Err(e) => return lane_error_response(e),
+    };
+    if let Some(provider) = &body.provider {
+        if !is_valid_provider(provider) {
+            return bad_request("provider must be a lowercase identifier");
+        }
+    }
+
+    match requeue_dead_letter_job(&state.pool, &id, actor, &body).await {
+        Ok(outcome) => {
+            if matches!(outcome, AdminActionOutcome::Applied) {
+                state.keeper.job_queued(&id);
+            }
+            admin_action_response(&id, outcome, "queued")
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn discard_dead_letter(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(body): Json<DiscardIn>,
+) -> impl IntoResponse {
+    let actor = match state.keys.admin(api_key(&headers)) {
+        Ok(actor) => actor,
+        Err(e) => return lane_error_response(e),
+    };
+
+    match discard_dead_letter_job(&state.pool, &id, actor, &body).await {
+        Ok(outcome) => admin_action_response(&id, outcome, "discarded"),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,295 @@
+//! API keys and the priority and tenant each one may submit evidence under.
+
+use phoenix_common::outbox::{DEFAULT_PRIORITY, MAX_PRIORITY, MIN_PRIORITY};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Request header carrying the caller's API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// One entry of the `API_KEYS_FILE` JSON array
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKey {
+    pub key: String,
+    /// Who holds the key, to name it in configuration errors
+    pub name: String,
+    /// Highest priority the key may submit at; lower priorities are always allowed
+    #[serde(default)]
+    pub max_priority: i64,
+    /// Tenant every job submitted with the key belongs to. A key without one
+    /// may name any tenant.
+    pub tenant: Option<String>,
+    /// May use the `/admin` dead-letter routes
+    #[serde(default)]
+    pub admin: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeysError {
+    #[error("failed to read API keys file: {0}")]
+    Io(String),
+    #[error("malformed API keys file: {0}")]
+    Parse(String),
+    #[error("invalid API keys: {0}")]
+    Invalid(String),
+}
+
+/// Why a submission was refused
+#[derive(Debug, thiserror::Error)]
+pub enum LaneError {
+    #[error("missing or unknown API key")]
+    Unauthorized,
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// Priority and tenant a job is queued under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lane {
+    pub priority: i64,
+    pub tenant: Option<String>,
+}
+
+/// Keys accepted by the API. Without any the API is open: callers may name
+/// any tenant but not raise priority above the default.
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys {
+    keys: HashMap<String, ApiKey>,
+}
+
+impl ApiKeys {
+    pub fn new(keys: Vec<ApiKey>) -> Result<Self, KeysError> {
+        let mut by_key = HashMap::new();
+        for key in keys {
+            if key.key.trim().is_empty() {
+                return Err(KeysError::Invalid(format!("key '{}' is empty", key.name)));
+            }
+            if !(MIN_PRIORITY..=MAX_PRIORITY).contains(&key.max_priority) {
+                return Err(KeysError::Invalid(format!(
+                    "key '{}': max_priority {} is outside {}..={}",
+                    key.name, key.max_priority, MIN_PRIORITY, MAX_PRIORITY
+                )));
+            }
+            if let Some(previous) = by_key.insert(key.key.clone(), key) {
+                return Err(KeysError::Invalid(format!(
+                    "key '{}' is listed twice",
+                    previous.name
+                )));
+            }
+        }
+        Ok(Self { keys: by_key })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, KeysError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| KeysError::Io(format!("{}: {}", path.display(), e)))?;
+        let keys = serde_json::from_str(&content).map_err(|e| KeysError::Parse(e.to_string()))?;
+        Self::new(keys)
+    }
+
+    /// Keys from the file named by `API_KEYS_FILE`, or none if it is unset
+    pub fn from_env() -> Result<Self, KeysError> {
+        match std::env::var("API_KEYS_FILE") {
+            Ok(path) if !path.trim().is_empty() => Self::from_file(Path::new(&path)),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// The lane a job submitted with `key` asking for `priority` and `tenant` is
+    /// queued under, or why the key may not submit it
+    pub fn lane(
+        &self,
+        key: Option<&str>,
+        priority: Option<i64>,
+        tenant: Option<String>,
+    ) -> Result<Lane, LaneError> {
+        let priority = priority.unwrap_or(DEFAULT_PRIORITY);
+        if !(MIN_PRIORITY..=MAX_PRIORITY).contains(&priority) {
+            return Err(LaneError::Invalid(format!(
+                "priority must be between {} and {}",
+                MIN_PRIORITY, MAX_PRIORITY
+            )));
+        }
+        if tenant.as_deref().is_some_and(|t| t.trim().is_empty()) {
+            return Err(LaneError::Invalid("tenant must not be empty".to_string()));
+        }
+
+        let (max_priority, key_tenant) = if self.keys.is_empty() {
+            (DEFAULT_PRIORITY, None)
+        } else {
+            let api_key = key
+                .and_then(|k| self.keys.get(k))
+                .ok_or(LaneError::Unauthorized)?;
+            (api_key.max_priority, api_key.tenant.as_deref())
+        };
+        if priority > max_priority {
+            return Err(LaneError::Forbidden(format!(
+                "priority {} exceeds this key's limit of {}",
+                priority, max_priority
+            )));
+        }
+        let tenant = match (key_tenant, tenant) {
+            (Some(own), Some(requested)) if own != requested => {
+                return Err(LaneError::Forbidden(format!(
+                    "this key may not submit for tenant '{}'",
+                    requested
+                )))
+            }
+            (Some(own), _) => Some(own.to_string()),
+            (None, requested) => requested,
+        };
+        Ok(Lane { priority, tenant })
+    }
+
+    /// The tenant whose jobs `key` may act on, or `None` for any tenant
+    pub fn tenant_scope(&self, key: Option<&str>) -> Result<Option<&str>, LaneError> {
+        if self.keys.is_empty() {
+            return Ok(None);
+        }
+        let api_key = key
+            .and_then(|k| self.keys.get(k))
+            .ok_or(LaneError::Unauthorized)?;
+        Ok(api_key.tenant.as_deref())
+    }
+
+    /// The name of the admin `key`, recorded as the actor of admin actions.
+    /// Admin routes need an admin key even when the API is otherwise open.
+    pub fn admin(&self, key: Option<&str>) -> Result<&str, LaneError> {
+        let api_key = key
+            .and_then(|k| self.keys.get(k))
+            .ok_or(LaneError::Unauthorized)?;
+        if !api_key.admin {
+            return Err(LaneError::Forbidden(
+                "this key may not use the admin routes".to_string(),
+            ));
+        }
+        Ok(&api_key.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> ApiKeys {
+        ApiKeys::new(vec![
+            ApiKey {
+                key: "ops-key".to_string(),
+                name: "ops".to_string(),
+                max_priority: 5,
+                tenant: None,
+                admin: true,
+            },
+            ApiKey {
+                key: "acme-key".to_string(),
+                name: "acme".to_string(),
+                max_priority: 0,
+                tenant: Some("acme".to_string()),
+                admin: false,
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_open_api_keeps_default_priority() {
+        let open = ApiKeys::default();
+        assert_eq!(
+            open.lane(None, None, Some("acme".to_string())).unwrap(),
+            Lane {
+                priority: DEFAULT_PRIORITY,
+                tenant: Some("acme".to_string())
+            }
+        );
+        assert_eq!(open.lane(None, Some(-5), None).unwrap().priority, -5);
+        assert!(matches!(
+            open.lane(None, Some(1), None),
+            Err(LaneError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_limits() {
+        let keys = keys();
+        assert!(matches!(
+            keys.lane(None, None, None),
+            Err(LaneError::Unauthorized)
+        ));
+        assert!(matches!(
+            keys.lane(Some("guess"), None, None),
+            Err(LaneError::Unauthorized)
+        ));
+        assert_eq!(
+            keys.lane(Some("ops-key"), Some(5), None).unwrap().priority,
+            5
+        );
+        assert!(matches!(
+            keys.lane(Some("ops-key"), Some(6), None),
+            Err(LaneError::Forbidden(_))
+        ));
+        assert!(matches!(
+            keys.lane(Some("ops-key"), Some(MAX_PRIORITY + 1), None),
+            Err(LaneError::Invalid(_))
+        ));
+
+        // A tenant key submits for its own tenant only
+        assert_eq!(
+            keys.lane(Some("acme-key"), None, None).unwrap().tenant,
+            Some("acme".to_string())
+        );
+        assert!(matches!(
+            keys.lane(Some("acme-key"), None, Some("globex".to_string())),
+            Err(LaneError::Forbidden(_))
+        ));
+        assert!(matches!(
+            keys.lane(Some("ops-key"), None, Some(" ".to_string())),
+            Err(LaneError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_tenant_scope() {
+        let keys = keys();
+        assert_eq!(ApiKeys::default().tenant_scope(None).unwrap(), None);
+        assert!(matches!(
+            keys.tenant_scope(None),
+            Err(LaneError::Unauthorized)
+        ));
+        assert_eq!(keys.tenant_scope(Some("ops-key")).unwrap(), None);
+        assert_eq!(keys.tenant_scope(Some("acme-key")).unwrap(), Some("acme"));
+    }
+
+    #[test]
+    fn test_admin_key() {
+        let keys = keys();
+        assert!(matches!(
+            ApiKeys::default().admin(None),
+            Err(LaneError::Unauthorized)
+        ));
+        assert!(matches!(keys.admin(None), Err(LaneError::Unauthorized)));
+        assert!(matches!(
+            keys.admin(Some("acme-key")),
+            Err(LaneError::Forbidden(_))
+        ));
+        assert_eq!(keys.admin(Some("ops-key")).unwrap(), "ops");
+    }
+
+    #[test]
+    fn test_rejects_duplicate_keys() {
+        let key = ApiKey {
+            key: "same".to_string(),
+            name: "a".to_string(),
+            max_priority: 0,
+            tenant: None,
+            admin: false,
+        };
+        assert!(matches!(
+            ApiKeys::new(vec![key.clone(), key]),
+            Err(KeysError::Invalid(_))
+        ));
+    }
+}
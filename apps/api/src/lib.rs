@@ -3,17 +3,42 @@ use axum::{
     Router,
 };
 use sqlx::{sqlite::SqlitePoolOptions, Pool, Sqlite};
+use std::sync::Arc;
 
 pub mod connection;
 pub mod db;
 pub mod handlers;
+pub mod keys;
 pub mod migrations;
 pub mod models;
+pub mod notify;
+pub mod pg_repository;
 pub mod repository;
+pub mod screening;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: Pool<Sqlite>,
+    pub keeper: notify::KeeperNotifier,
+    /// Limits on the priority and tenant callers may submit evidence under
+    pub keys: Arc<keys::ApiKeys>,
+    /// Allow and deny lists for counterparty addresses in submitted evidence
+    pub address_policy: Arc<address_validation::AddressPolicy>,
+}
+
+/// Operator endpoints for inspecting and resolving dead-lettered evidence jobs
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/admin/dead-letters", get(handlers::list_dead_letters))
+        .route("/admin/dead-letters/{id}", get(handlers::get_dead_letter))
+        .route(
+            "/admin/dead-letters/{id}/requeue",
+            post(handlers::requeue_dead_letter),
+        )
+        .route(
+            "/admin/dead-letters/{id}/discard",
+            post(handlers::discard_dead_letter),
+        )
 }
 
 pub async fn build_app() -> anyhow::Result<(Router, Pool<Sqlite>)> {
@@ -29,14 +54,26 @@ pub async fn build_app() -> anyhow::Result<(Router, Pool<Sqlite>)> {
     let repo = repository::EvidenceRepository::new(pool.clone());
     repo.ensure_schema().await?;
 
-    let state = AppState { pool: pool.clone() };
+    let state = AppState {
+        pool: pool.clone(),
+        keeper: notify::KeeperNotifier::from_env(),
+        keys: Arc::new(keys::ApiKeys::from_env()?),
+        address_policy: Arc::new(screening::policy_from_env()?),
+    };
     let app = Router::new()
         .route("/health", get(handlers::health))
+        .route("/metrics", get(handlers::metrics))
         .route(
             "/evidence",
             post(handlers::post_evidence).get(handlers::list_evidence),
         )
         .route("/evidence/{id}", get(handlers::get_evidence))
+        .route(
+            "/evidence/{id}/attempts",
+            get(handlers::get_evidence_attempts),
+        )
+        .route("/evidence/{id}/release", post(handlers::release_evidence))
+        .merge(admin_routes())
         .with_state(state);
     Ok((app, pool))
 }
@@ -5,17 +5,22 @@ use axum::{
 use sqlx::sqlite::SqlitePoolOptions;
 use sqlx::{Pool, Sqlite};
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio::net::TcpListener;
 use tokio::signal::ctrl_c;
 use tracing_subscriber::prelude::*;
 
 use phoenix_api::handlers::{
-    get_countermeasure, get_evidence, get_jamming_operation, get_signal_disruption, health,
-    list_countermeasures, list_evidence, list_jamming_operations, list_signal_disruptions,
-    post_countermeasure, post_evidence, post_jamming_operation, post_signal_disruption,
+    get_countermeasure, get_evidence, get_evidence_attempts, get_jamming_operation,
+    get_signal_disruption, health, list_countermeasures, list_evidence, list_jamming_operations,
+    list_signal_disruptions, metrics, post_countermeasure, post_evidence, post_jamming_operation,
+    post_signal_disruption, release_evidence,
 };
+use phoenix_api::keys::ApiKeys;
 use phoenix_api::migrations::MigrationManager;
-use phoenix_api::AppState;
+use phoenix_api::notify::KeeperNotifier;
+use phoenix_api::screening::policy_from_env;
+use phoenix_api::{admin_routes, AppState};
 
 pub async fn build_app() -> (Router, Pool<Sqlite>) {
     // DB pool (use API_DB_URL, fallback to KEEPER_DB_URL, then sqlite file)
@@ -36,9 +41,21 @@ pub async fn build_app() -> (Router, Pool<Sqlite>) {
     let migration_manager = MigrationManager::new(pool.clone());
     migration_manager.migrate().await.expect("migration failed");
 
-    let state = AppState { pool: pool.clone() };
+    let state = AppState {
+        pool: pool.clone(),
+        keeper: KeeperNotifier::from_env(),
+        keys: Arc::new(ApiKeys::from_env().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1)
+        })),
+        address_policy: Arc::new(policy_from_env().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1)
+        })),
+    };
     let app = Router::new()
         .route("/health", get(health)) // Using the imported health handler
+        .route("/metrics", get(metrics))
         .route(
             "/countermeasures",
             post(post_countermeasure).get(list_countermeasures),
@@ -46,6 +63,8 @@ pub async fn build_app() -> (Router, Pool<Sqlite>) {
         .route("/countermeasures/{id}", get(get_countermeasure))
         .route("/evidence", post(post_evidence).get(list_evidence))
         .route("/evidence/{id}", get(get_evidence))
+        .route("/evidence/{id}/attempts", get(get_evidence_attempts))
+        .route("/evidence/{id}/release", post(release_evidence))
         .route(
             "/signal-disruptions",
             post(post_signal_disruption).get(list_signal_disruptions),
@@ -56,6 +75,7 @@ pub async fn build_app() -> (Router, Pool<Sqlite>) {
             post(post_jamming_operation).get(list_jamming_operations),
         )
         .route("/jamming-operations/{id}", get(get_jamming_operation))
+        .merge(admin_routes())
         .with_state(state);
     (app, pool)
 }
@@ -68,6 +88,7 @@ async fn main() {
         ))
         .with(tracing_subscriber::fmt::layer())
         .init();
+    phoenix_common::metrics::install_recorder();
 
     let (app, _pool) = build_app().await;
 
@@ -1,3 +1,5 @@
+use chrono::{DateTime, Utc};
+use phoenix_common::outbox::HELD_NEXT_ATTEMPT_MS;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
@@ -12,6 +14,32 @@ pub struct EvidenceIn {
     pub digest_hex: String,
     pub payload_mime: Option<String>,
     pub metadata: Option<serde_json::Value>,
+    /// Claim order lane, higher first; limited by the caller's API key
+    pub priority: Option<i64>,
+    /// Tenant whose fair share of anchoring the job counts against
+    pub tenant: Option<String>,
+    /// Earliest time the keeper may anchor the evidence
+    pub not_before: Option<DateTime<Utc>>,
+    /// Stage the evidence without anchoring it until it is released
+    #[serde(default)]
+    pub hold: bool,
+}
+
+impl EvidenceIn {
+    /// `next_attempt_ms` the job is queued with: held jobs are never due,
+    /// scheduled ones are due at `not_before` and the rest at once
+    pub fn next_attempt_ms(&self) -> i64 {
+        if self.hold {
+            HELD_NEXT_ATTEMPT_MS
+        } else {
+            self.not_before.map_or(0, |at| at.timestamp_millis())
+        }
+    }
+
+    /// `metadata` as stored with the job, for the keeper to rebuild the record
+    pub fn metadata_json(&self) -> Option<String> {
+        self.metadata.as_ref().map(|metadata| metadata.to_string())
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -97,3 +125,63 @@ pub struct JammingOperationOut {
     pub created_ms: i64,
     pub updated_ms: i64,
 }
+
+// Dead-letter admin models
+#[derive(Debug, Serialize)]
+pub struct DeadLetterOut {
+    pub id: String,
+    pub payload_sha256: String,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub failure_category: String,
+    pub provider: Option<String>,
+    pub created_ms: i64,
+    pub updated_ms: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobAttemptOut {
+    pub attempt: i64,
+    pub worker: Option<String>,
+    pub outcome: String,
+    /// Failure category (error class) of a failed attempt
+    pub category: Option<String>,
+    pub error: Option<String>,
+    /// When the worker claimed the job; unknown for attempts recorded without a claim
+    pub started_ms: Option<i64>,
+    pub finished_ms: i64,
+    pub latency_ms: Option<i64>,
+    pub provider: Option<String>,
+    pub endpoint: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminAuditOut {
+    pub id: String,
+    pub action: String,
+    pub job_id: Option<String>,
+    pub actor: String,
+    pub details: Option<serde_json::Value>,
+    pub created_ms: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeadLetterDetailOut {
+    #[serde(flatten)]
+    pub job: DeadLetterOut,
+    pub attempt_history: Vec<JobAttemptOut>,
+    pub audit: Vec<AdminAuditOut>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequeueIn {
+    /// Route the job to a different anchor provider
+    pub provider: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscardIn {
+    pub reason: Option<String>,
+}
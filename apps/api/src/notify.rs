@@ -0,0 +1,54 @@
+//! Tells the keeper that a job was queued, so it picks the job up without
+//! waiting out its poll interval.
+
+use std::time::Duration;
+
+/// The keeper polls anyway, so a slow or missing keeper must not hold up the API
+const WAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Calls the keeper's wake webhook, e.g. `http://keeper:8081/admin/loops/jobs/wake`.
+/// Without a URL the keeper is left to poll.
+#[derive(Clone, Default)]
+pub struct KeeperNotifier {
+    wake_url: Option<String>,
+    client: reqwest::Client,
+}
+
+impl KeeperNotifier {
+    pub fn new(wake_url: Option<String>) -> Self {
+        Self {
+            wake_url,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Webhook from `KEEPER_WAKE_URL`, if set
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("KEEPER_WAKE_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty()),
+        )
+    }
+
+    /// Wake the keeper in the background. Failures are logged and otherwise
+    /// ignored: the job is already queued and the keeper's next poll finds it.
+    pub fn job_queued(&self, id: &str) {
+        let Some(url) = self.wake_url.clone() else {
+            return;
+        };
+        let client = self.client.clone();
+        let id = id.to_string();
+        tokio::spawn(async move {
+            let result = client
+                .post(&url)
+                .timeout(WAKE_TIMEOUT)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(e) = result {
+                tracing::warn!(job_id = %id, error = %e, "Failed to wake keeper");
+            }
+        });
+    }
+}
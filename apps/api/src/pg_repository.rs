@@ -0,0 +1,240 @@
+use crate::models::{EvidenceIn, EvidenceOut};
+use crate::repository::{JobStats, RepositoryError, Result};
+use phoenix_common::outbox::{DEFAULT_PRIORITY, JOBS_QUEUED_CHANNEL};
+use sqlx::postgres::PgRow;
+use sqlx::{Pool, Postgres, Row};
+
+/// PostgreSQL counterpart of [`crate::repository::EvidenceRepository`].
+/// Shares the outbox tables with keeper replicas running against the same database.
+pub struct PgEvidenceRepository {
+    pool: Pool<Postgres>,
+}
+
+fn evidence_from_row(row: PgRow) -> EvidenceOut {
+    EvidenceOut {
+        id: row.get::<String, _>(0),
+        status: row.get::<String, _>(1),
+        attempts: row.get::<i64, _>(2),
+        last_error: row.get::<Option<String>, _>(3),
+        created_ms: row.get::<i64, _>(4),
+        updated_ms: row.get::<i64, _>(5),
+    }
+}
+
+impl PgEvidenceRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Initialize database schema
+    pub async fn ensure_schema(&self) -> Result<()> {
+        phoenix_common::schema::ensure_pg_schema(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Create a new evidence job
+    pub async fn create_evidence_job(&self, evidence: &EvidenceIn) -> Result<String> {
+        let id = evidence
+            .id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        let now = chrono::Utc::now().timestamp_millis();
+
+        // NOTIFY is delivered on commit, so the keeper never sees a job it cannot claim
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            "INSERT INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms, next_attempt_ms, priority, tenant, payload_mime, metadata) VALUES ($1, $2, 'queued', 0, $3, $3, $6, $4, $5, $7, $8) ON CONFLICT (id) DO NOTHING"
+        )
+        .bind(&id)
+        .bind(&evidence.digest_hex)
+        .bind(now)
+        .bind(evidence.priority.unwrap_or(DEFAULT_PRIORITY))
+        .bind(&evidence.tenant)
+        .bind(evidence.next_attempt_ms())
+    .bind(&evidence.payload_mime)
+    .bind(evidence.metadata_json())
+        .bind(&evidence.payload_mime)
+        .bind(evidence.metadata_json())
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::Conflict(format!(
+                "Evidence job with id '{}' already exists",
+                id
+            )));
+        }
+
+        // Keeper replicas LISTEN on this channel and claim the job straight away
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(JOBS_QUEUED_CHANNEL)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    /// Get evidence job by ID
+    pub async fn get_evidence_by_id(&self, id: &str) -> Result<Option<EvidenceOut>> {
+        let row = sqlx::query(
+            "SELECT id, status, attempts, last_error, created_ms, updated_ms FROM outbox_jobs WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(evidence_from_row))
+    }
+
+    /// List evidence jobs with pagination
+    pub async fn list_evidence_jobs(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<EvidenceOut>, i64)> {
+        let total_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM outbox_jobs")
+            .fetch_one(&self.pool)
+            .await?;
+
+        let rows = sqlx::query(
+            "SELECT id, status, attempts, last_error, created_ms, updated_ms FROM outbox_jobs ORDER BY created_ms DESC LIMIT $1 OFFSET $2"
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((
+            rows.into_iter().map(evidence_from_row).collect(),
+            total_count,
+        ))
+    }
+
+    /// Update job status
+    pub async fn update_job_status(
+        &self,
+        id: &str,
+        status: &str,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let result = sqlx::query(
+            "UPDATE outbox_jobs SET status = $1, last_error = $2, updated_ms = $3 WHERE id = $4",
+        )
+        .bind(status)
+        .bind(error)
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound(format!(
+                "Evidence job with id '{}' not found",
+                id
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Get jobs ready for processing
+    pub async fn get_ready_jobs(&self, limit: i64) -> Result<Vec<EvidenceOut>> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let rows = sqlx::query(
+            "SELECT id, status, attempts, last_error, created_ms, updated_ms FROM outbox_jobs WHERE status = 'queued' AND next_attempt_ms <= $1 ORDER BY created_ms ASC LIMIT $2"
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(evidence_from_row).collect())
+    }
+
+    /// Mark job as completed
+    pub async fn mark_completed(&self, id: &str) -> Result<()> {
+        self.update_job_status(id, "done", None).await
+    }
+
+    /// Mark job as failed
+    pub async fn mark_failed(&self, id: &str, error: &str) -> Result<()> {
+        self.update_job_status(id, "failed", Some(error)).await
+    }
+
+    /// Get job statistics
+    pub async fn get_job_stats(&self) -> Result<JobStats> {
+        let stats_row = sqlx::query(
+            "SELECT
+                COUNT(*) as total,
+                COUNT(*) FILTER (WHERE status = 'queued') as queued,
+                COUNT(*) FILTER (WHERE status = 'in_progress') as in_progress,
+                COUNT(*) FILTER (WHERE status = 'done') as done,
+                COUNT(*) FILTER (WHERE status = 'failed') as failed
+            FROM outbox_jobs",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(JobStats {
+            total: stats_row.get::<i64, _>(0),
+            queued: stats_row.get::<i64, _>(1),
+            in_progress: stats_row.get::<i64, _>(2),
+            done: stats_row.get::<i64, _>(3),
+            failed: stats_row.get::<i64, _>(4),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phoenix_common::testing::postgres_test_pool;
+
+    #[tokio::test]
+    async fn test_pg_evidence_roundtrip() {
+        let Some(pool) = postgres_test_pool().await else {
+            return;
+        };
+        let repo = PgEvidenceRepository::new(pool);
+        repo.ensure_schema().await.unwrap();
+
+        let evidence = EvidenceIn {
+            id: Some("pg-123".to_string()),
+            digest_hex: "abcd1234".to_string(),
+            payload_mime: None,
+            metadata: None,
+            priority: None,
+            tenant: None,
+            not_before: None,
+            hold: false,
+        };
+        assert_eq!(repo.create_evidence_job(&evidence).await.unwrap(), "pg-123");
+        assert!(matches!(
+            repo.create_evidence_job(&evidence).await,
+            Err(RepositoryError::Conflict(_))
+        ));
+
+        let job = repo.get_evidence_by_id("pg-123").await.unwrap().unwrap();
+        assert_eq!(job.status, "queued");
+        assert_eq!(repo.get_ready_jobs(10).await.unwrap().len(), 1);
+
+        repo.mark_failed("pg-123", "boom").await.unwrap();
+        let (jobs, total) = repo.list_evidence_jobs(10, 0).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(jobs[0].last_error.as_deref(), Some("boom"));
+
+        let stats = repo.get_job_stats().await.unwrap();
+        assert_eq!((stats.total, stats.failed, stats.queued), (1, 1, 0));
+
+        assert!(matches!(
+            repo.mark_completed("missing").await,
+            Err(RepositoryError::NotFound(_))
+        ));
+    }
+}
@@ -1,4 +1,6 @@
 use crate::models::{EvidenceIn, EvidenceOut};
+use phoenix_common::migrations::MigrationError;
+use phoenix_common::outbox::DEFAULT_PRIORITY;
 use sqlx::{Pool, Row, Sqlite, Transaction};
 use thiserror::Error;
 
@@ -12,6 +14,8 @@ pub enum RepositoryError {
     NotFound(String),
     #[error("Conflict: {0}")]
     Conflict(String),
+    #[error("Migration error: {0}")]
+    Migration(MigrationError),
 }
 
 impl From<sqlx::Error> for RepositoryError {
@@ -20,6 +24,12 @@ impl From<sqlx::Error> for RepositoryError {
     }
 }
 
+impl From<MigrationError> for RepositoryError {
+    fn from(error: MigrationError) -> Self {
+        RepositoryError::Migration(error)
+    }
+}
+
 pub type Result<T> = std::result::Result<T, RepositoryError>;
 
 /// Repository pattern for evidence job management
@@ -35,48 +45,7 @@ impl EvidenceRepository {
 
     /// Initialize database schema
     pub async fn ensure_schema(&self) -> Result<()> {
-        // Create outbox_jobs table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS outbox_jobs (
-                id TEXT PRIMARY KEY,
-                payload_sha256 TEXT NOT NULL,
-                status TEXT NOT NULL DEFAULT 'queued',
-                attempts INTEGER NOT NULL DEFAULT 0,
-                last_error TEXT,
-                created_ms INTEGER NOT NULL,
-                updated_ms INTEGER NOT NULL,
-                next_attempt_ms INTEGER NOT NULL DEFAULT 0
-            );
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Create outbox_tx_refs table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS outbox_tx_refs (
-                job_id TEXT NOT NULL,
-                network TEXT NOT NULL,
-                chain TEXT NOT NULL,
-                tx_id TEXT NOT NULL,
-                confirmed INTEGER NOT NULL DEFAULT 0,
-                timestamp INTEGER,
-                PRIMARY KEY (job_id, network, chain, tx_id)
-            );
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Try to add next_attempt_ms if missing (best-effort migration)
-        let _ = sqlx::query(
-            "ALTER TABLE outbox_jobs ADD COLUMN next_attempt_ms INTEGER NOT NULL DEFAULT 0",
-        )
-        .execute(&self.pool)
-        .await;
-
+        phoenix_common::schema::ensure_schema(&self.pool).await?;
         Ok(())
     }
 
@@ -90,11 +59,18 @@ impl EvidenceRepository {
         let now = chrono::Utc::now().timestamp_millis();
 
         let result = sqlx::query(
-            "INSERT OR IGNORE INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms, next_attempt_ms) VALUES (?1, ?2, 'queued', 0, ?3, ?3, 0)"
+            "INSERT OR IGNORE INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms, next_attempt_ms, priority, tenant, payload_mime, metadata) VALUES (?1, ?2, 'queued', 0, ?3, ?3, ?6, ?4, ?5, ?7, ?8)"
         )
         .bind(&id)
         .bind(&evidence.digest_hex)
         .bind(now)
+        .bind(evidence.priority.unwrap_or(DEFAULT_PRIORITY))
+        .bind(&evidence.tenant)
+        .bind(evidence.next_attempt_ms())
+    .bind(&evidence.payload_mime)
+    .bind(evidence.metadata_json())
+        .bind(&evidence.payload_mime)
+        .bind(evidence.metadata_json())
         .execute(&self.pool)
         .await?;
 
@@ -347,11 +323,18 @@ impl EvidenceRepository {
         let now = chrono::Utc::now().timestamp_millis();
 
         let result = sqlx::query(
-            "INSERT OR IGNORE INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms, next_attempt_ms) VALUES (?1, ?2, 'queued', 0, ?3, ?3, 0)"
+            "INSERT OR IGNORE INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms, next_attempt_ms, priority, tenant, payload_mime, metadata) VALUES (?1, ?2, 'queued', 0, ?3, ?3, ?6, ?4, ?5, ?7, ?8)"
         )
         .bind(&id)
         .bind(&evidence.digest_hex)
         .bind(now)
+        .bind(evidence.priority.unwrap_or(DEFAULT_PRIORITY))
+        .bind(&evidence.tenant)
+        .bind(evidence.next_attempt_ms())
+    .bind(&evidence.payload_mime)
+    .bind(evidence.metadata_json())
+        .bind(&evidence.payload_mime)
+        .bind(evidence.metadata_json())
         .execute(&mut *tx)
         .await?;
 
@@ -402,6 +385,10 @@ mod tests {
             digest_hex: "abcd1234".to_string(),
             payload_mime: Some("application/json".to_string()),
             metadata: Some(serde_json::json!({"key": "value"})),
+            priority: None,
+            tenant: None,
+            not_before: None,
+            hold: false,
         };
 
         let id = repo.create_evidence_job(&evidence).await.unwrap();
@@ -421,6 +408,10 @@ mod tests {
             digest_hex: "abcd1234".to_string(),
             payload_mime: None,
             metadata: None,
+            priority: None,
+            tenant: None,
+            not_before: None,
+            hold: false,
         };
 
         // First creation should succeed
@@ -441,6 +432,10 @@ mod tests {
             digest_hex: "abcd1234".to_string(),
             payload_mime: None,
             metadata: None,
+            priority: None,
+            tenant: None,
+            not_before: None,
+            hold: false,
         };
 
         // Create job
@@ -470,6 +465,10 @@ mod tests {
                 digest_hex: "abcd1234".to_string(),
                 payload_mime: None,
                 metadata: None,
+                priority: None,
+                tenant: None,
+                not_before: None,
+                hold: false,
             };
             repo.create_evidence_job(&evidence).await.unwrap();
         }
@@ -0,0 +1,54 @@
+//! Counterparty addresses named in submitted evidence, screened against the
+//! address policy before the evidence is accepted.
+
+use address_validation::{AddressPolicy, PolicyError};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Metadata key listing the counterparties of the evidence
+pub const COUNTERPARTIES_KEY: &str = "counterparties";
+
+/// One entry of `metadata.counterparties`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Counterparty {
+    pub chain: String,
+    pub address: String,
+}
+
+/// The policy file named by `API_ADDRESS_POLICY`, or an empty policy that
+/// only requires addresses to be valid
+pub fn policy_from_env() -> Result<AddressPolicy, PolicyError> {
+    match std::env::var("API_ADDRESS_POLICY") {
+        Ok(path) if !path.trim().is_empty() => AddressPolicy::from_file(Path::new(&path)),
+        _ => Ok(AddressPolicy::new()),
+    }
+}
+
+/// Check every counterparty listed in `metadata` against `policy`; the error
+/// says which address was refused and why
+pub fn screen_counterparties(
+    policy: &AddressPolicy,
+    metadata: Option<&serde_json::Value>,
+) -> Result<(), String> {
+    let Some(listed) = metadata.and_then(|m| m.get(COUNTERPARTIES_KEY)) else {
+        return Ok(());
+    };
+    let counterparties: Vec<Counterparty> =
+        serde_json::from_value(listed.clone()).map_err(|e| {
+            format!(
+                "metadata.{} must be a list of {{chain, address}}: {}",
+                COUNTERPARTIES_KEY, e
+            )
+        })?;
+    for counterparty in &counterparties {
+        let decision = policy.is_permitted(&counterparty.chain, &counterparty.address);
+        if !decision.permitted {
+            return Err(format!(
+                "counterparty {} on {} is not permitted: {}",
+                counterparty.address, counterparty.chain, decision.reason
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use address_validation::AddressPolicy;
+use axum::{routing::post, serve, Router};
+use phoenix_api::handlers::post_evidence;
+use phoenix_api::notify::KeeperNotifier;
+use phoenix_api::AppState;
+use phoenix_common::testing::sqlite_test_pool;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use sqlx::{Pool, Sqlite};
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+use tokio::net::TcpListener;
+
+const SANCTIONED: &str = "0x8589427373D6D84E98730D7795D8f6f8731FDA16";
+const PARTNER: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+/// Serve `POST /evidence` screening against `policy`; returns the base URL
+async fn start_api(pool: Pool<Sqlite>, policy: AddressPolicy) -> String {
+    let app = Router::new()
+        .route("/evidence", post(post_evidence))
+        .with_state(AppState {
+            pool,
+            keeper: KeeperNotifier::default(),
+            keys: Default::default(),
+            address_policy: Arc::new(policy),
+        });
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        serve(listener, app.into_make_service()).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+async fn submit(base: &str, metadata: Value) -> (StatusCode, Value) {
+    let resp = Client::new()
+        .post(format!("{}/evidence", base))
+        .json(&json!({"digest_hex": "ab12", "metadata": metadata}))
+        .send()
+        .await
+        .unwrap();
+    (resp.status(), resp.json().await.unwrap())
+}
+
+async fn job_count(pool: &Pool<Sqlite>) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM outbox_jobs")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_denylisted_counterparty_is_rejected() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let mut policy = AddressPolicy::new();
+    policy
+        .load_csv(&format!(
+            "list,chain,address,reason\ndeny,evm,{},sanctioned\n",
+            SANCTIONED
+        ))
+        .unwrap();
+    let base = start_api(pool.clone(), policy).await;
+
+    let (status, body) = submit(
+        &base,
+        json!({"counterparties": [
+            {"chain": "ethereum", "address": PARTNER},
+            {"chain": "ethereum", "address": SANCTIONED},
+        ]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error = body["error"].as_str().unwrap();
+    assert!(error.contains(SANCTIONED), "{}", error);
+    assert!(error.contains("denylisted: sanctioned"), "{}", error);
+    assert_eq!(job_count(&pool).await, 0);
+
+    let (status, _) = submit(
+        &base,
+        json!({"counterparties": [{"chain": "ethereum", "address": PARTNER}]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(job_count(&pool).await, 1);
+}
+
+#[tokio::test]
+async fn test_invalid_or_malformed_counterparties_are_rejected() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let base = start_api(pool.clone(), AddressPolicy::new()).await;
+
+    let (status, body) = submit(
+        &base,
+        json!({"counterparties": [{"chain": "ethereum", "address": "0x1234"}]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("invalid address"));
+
+    let (status, body) = submit(&base, json!({"counterparties": PARTNER})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("metadata.counterparties"));
+    assert_eq!(job_count(&pool).await, 0);
+
+    let (status, _) = submit(&base, json!({"source": "sensor-7"})).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,258 @@
+use axum::{serve, Router};
+use phoenix_api::keys::{ApiKey, ApiKeys, API_KEY_HEADER};
+use phoenix_api::notify::KeeperNotifier;
+use phoenix_api::{admin_routes, AppState};
+use phoenix_common::testing::{sqlite_test_pool, TestJob};
+use phoenix_keeper::{JobProvider, JobProviderExt, SqliteJobProvider};
+use reqwest::Client;
+use serde_json::{json, Value};
+use sqlx::{Pool, Row, Sqlite};
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+use tokio::net::TcpListener;
+
+const ADMIN_KEY: &str = "alice-key";
+
+/// Serve the admin routes on an ephemeral port; returns the base URL
+async fn start_admin_api(pool: Pool<Sqlite>) -> (String, tokio::task::JoinHandle<()>) {
+    let app: Router = admin_routes().with_state(AppState {
+        pool,
+        keeper: KeeperNotifier::default(),
+        keys: Arc::new(
+            ApiKeys::new(vec![
+                ApiKey {
+                    key: ADMIN_KEY.to_string(),
+                    name: "alice".to_string(),
+                    max_priority: 0,
+                    tenant: None,
+                    admin: true,
+                },
+                ApiKey {
+                    key: "acme-key".to_string(),
+                    name: "acme".to_string(),
+                    max_priority: 0,
+                    tenant: Some("acme".to_string()),
+                    admin: false,
+                },
+            ])
+            .unwrap(),
+        ),
+        address_policy: Default::default(),
+    });
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        serve(listener, app.into_make_service()).await.unwrap();
+    });
+    (format!("http://{}", addr), server)
+}
+
+#[tokio::test]
+async fn test_dead_letter_admin_flow() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+
+    // job-1 is dead-lettered by the keeper after a retry
+    TestJob::new("job-1")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+    let mut jp = SqliteJobProvider::new(pool.clone());
+    jp.fetch_next().await.unwrap().unwrap();
+    jp.mark_failed_or_backoff("job-1", "network error: timeout", true)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE outbox_jobs SET next_attempt_ms = 0 WHERE id = 'job-1'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    jp.fetch_next().await.unwrap().unwrap();
+    jp.mark_failed_or_backoff("job-1", "provider: nonce too low", false)
+        .await
+        .unwrap();
+    // job-2 failed before categories were recorded
+    TestJob::new("job-2")
+        .with_status("failed")
+        .with_last_error("network error: refused")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+    TestJob::new("job-3")
+        .with_status("done")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+
+    let (base, server) = start_admin_api(pool.clone()).await;
+    let client = Client::new();
+
+    let list: Value = client
+        .get(format!("{}/admin/dead-letters", base))
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(list["total"], 2);
+    let categories: Vec<(&str, &str)> = list["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|j| {
+            (
+                j["id"].as_str().unwrap(),
+                j["failure_category"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert!(categories.contains(&("job-1", "provider")));
+    assert!(categories.contains(&("job-2", "network")));
+
+    let detail: Value = client
+        .get(format!("{}/admin/dead-letters/job-1", base))
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(detail["status"], "failed");
+    assert_eq!(detail["attempts"], 2);
+    let history = detail["attempt_history"].as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["outcome"], "retry");
+    assert_eq!(history[0]["category"], "network");
+    assert_eq!(history[1]["outcome"], "failed");
+    assert_eq!(history[1]["error"], "provider: nonce too low");
+    assert!(detail["audit"].as_array().unwrap().is_empty());
+
+    // Only dead-lettered jobs can be inspected or acted on
+    let resp = client
+        .get(format!("{}/admin/dead-letters/job-3", base))
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    let resp = client
+        .post(format!("{}/admin/dead-letters/job-3/discard", base))
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+    let resp = client
+        .post(format!("{}/admin/dead-letters/missing/requeue", base))
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+
+    // Admin routes need an admin key, and a requeue a well-formed provider
+    let resp = client
+        .get(format!("{}/admin/dead-letters", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 401);
+    let resp = client
+        .post(format!("{}/admin/dead-letters/job-1/requeue", base))
+        .header(API_KEY_HEADER, "acme-key")
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 403);
+    let resp = client
+        .post(format!("{}/admin/dead-letters/job-1/requeue", base))
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .json(&json!({ "provider": "Solana Mainnet" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Requeue to another provider
+    let resp = client
+        .post(format!("{}/admin/dead-letters/job-1/requeue", base))
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .json(&json!({ "provider": "solana", "reason": "nonce fixed" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let row = sqlx::query(
+        "SELECT status, provider, failure_category FROM outbox_jobs WHERE id = 'job-1'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(row.get::<String, _>(0), "queued");
+    assert_eq!(row.get::<Option<String>, _>(1).as_deref(), Some("solana"));
+    assert_eq!(row.get::<Option<String>, _>(2), None);
+
+    let mut etherlink = SqliteJobProvider::new(pool.clone()).with_provider("etherlink");
+    assert!(etherlink.fetch_next().await.unwrap().is_none());
+    let mut solana = SqliteJobProvider::new(pool.clone()).with_provider("solana");
+    assert_eq!(solana.fetch_next().await.unwrap().unwrap().id, "job-1");
+
+    let resp = client
+        .post(format!("{}/admin/dead-letters/job-1/requeue", base))
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+
+    // Discard keeps the job and records the key that did it, whatever the
+    // body claims
+    let resp = client
+        .post(format!("{}/admin/dead-letters/job-2/discard", base))
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .json(&json!({ "actor": "mallory", "reason": "duplicate submission" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let detail: Value = client
+        .get(format!("{}/admin/dead-letters/job-2", base))
+        .header(API_KEY_HEADER, ADMIN_KEY)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(detail["status"], "discarded");
+    let audit = detail["audit"].as_array().unwrap();
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0]["action"], "discard");
+    assert_eq!(audit[0]["actor"], "alice");
+    assert_eq!(audit[0]["details"]["reason"], "duplicate submission");
+    assert_eq!(audit[0]["details"]["failure_category"], "network");
+
+    let actions: Vec<(String, String)> =
+        sqlx::query("SELECT action, job_id FROM admin_audit_log ORDER BY created_ms, rowid")
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect();
+    assert_eq!(
+        actions,
+        vec![
+            ("requeue".to_string(), "job-1".to_string()),
+            ("discard".to_string(), "job-2".to_string())
+        ]
+    );
+
+    server.abort();
+}
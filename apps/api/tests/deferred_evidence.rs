@@ -0,0 +1,216 @@
+use anchor_etherlink::EtherlinkProviderStub;
+use axum::{
+    routing::{get, post},
+    serve, Router,
+};
+use chrono::{Duration as ChronoDuration, Utc};
+use phoenix_api::handlers::{get_evidence, post_evidence, release_evidence};
+use phoenix_api::keys::{ApiKey, ApiKeys, API_KEY_HEADER};
+use phoenix_api::notify::KeeperNotifier;
+use phoenix_api::AppState;
+use phoenix_common::testing::sqlite_test_pool;
+use phoenix_keeper::ops::{ops_router, OpsState};
+use phoenix_keeper::{run_job_loop_with_control, LoopControl, SqliteJobProvider};
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use sqlx::{Pool, Sqlite};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::net::TcpListener;
+use tokio::time::timeout;
+
+/// Serve `app` on an ephemeral port; returns the base URL
+async fn start(app: Router) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        serve(listener, app.into_make_service()).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn api(state: AppState) -> Router {
+    Router::new()
+        .route("/evidence", post(post_evidence))
+        .route("/evidence/{id}", get(get_evidence))
+        .route("/evidence/{id}/release", post(release_evidence))
+        .with_state(state)
+}
+
+async fn post_json(url: String, key: Option<&str>, body: Value) -> (StatusCode, Value) {
+    let mut request = Client::new().post(url).json(&body);
+    if let Some(key) = key {
+        request = request.header(API_KEY_HEADER, key);
+    }
+    let resp = request.send().await.unwrap();
+    (resp.status(), resp.json().await.unwrap())
+}
+
+async fn job_status(pool: &Pool<Sqlite>, id: &str) -> String {
+    sqlx::query_scalar("SELECT status FROM outbox_jobs WHERE id=?1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_held_evidence_waits_for_release() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+
+    // Keeper with an hour-long poll: only a release wakes it in time
+    let jobs = LoopControl::default();
+    let keeper = start(ops_router(OpsState {
+        store: Arc::new(pool.clone()),
+        anchor: Arc::new(EtherlinkProviderStub),
+        jobs: jobs.clone(),
+        confirmations: LoopControl::default(),
+    }))
+    .await;
+    let worker_pool = pool.clone();
+    let worker = tokio::spawn(async move {
+        let mut jp = SqliteJobProvider::new(worker_pool);
+        run_job_loop_with_control(
+            &mut jp,
+            &EtherlinkProviderStub,
+            Duration::from_secs(3600),
+            &jobs,
+        )
+        .await;
+    });
+    let base = start(api(AppState {
+        pool: pool.clone(),
+        keeper: KeeperNotifier::new(Some(format!("{}/admin/loops/jobs/wake", keeper))),
+        keys: Default::default(),
+        address_policy: Default::default(),
+    }))
+    .await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    for id in ["staged-1", "staged-2"] {
+        let (status, body) = post_json(
+            format!("{}/evidence", base),
+            None,
+            json!({ "id": id, "digest_hex": "abcd1234", "hold": true }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "held");
+    }
+    let tonight = Utc::now() + ChronoDuration::hours(1);
+    let (status, _) = post_json(
+        format!("{}/evidence", base),
+        None,
+        json!({ "id": "tonight", "digest_hex": "abcd1234", "not_before": tonight }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = post_json(
+        format!("{}/evidence", base),
+        None,
+        json!({ "id": "both", "digest_hex": "abcd1234", "hold": true, "not_before": tonight }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let held: Value = Client::new()
+        .get(format!("{}/evidence/staged-1", base))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(held["status"], "held");
+
+    for id in ["staged-1", "staged-2"] {
+        let (status, body) =
+            post_json(format!("{}/evidence/{}/release", base, id), None, json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "queued");
+    }
+    timeout(Duration::from_secs(5), async {
+        while job_status(&pool, "staged-1").await != "done"
+            || job_status(&pool, "staged-2").await != "done"
+        {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("released jobs should be anchored");
+    worker.abort();
+    assert_eq!(job_status(&pool, "tonight").await, "queued");
+
+    let (status, body) = post_json(
+        format!("{}/evidence/staged-1/release", base),
+        None,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["status"], "done");
+    let (status, _) = post_json(
+        format!("{}/evidence/tonight/release", base),
+        None,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = post_json(
+        format!("{}/evidence/missing/release", base),
+        None,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_tenant_key_releases_own_jobs_only() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let keys = ApiKeys::new(vec![
+        ApiKey {
+            key: "ops-key".to_string(),
+            name: "ops".to_string(),
+            max_priority: 0,
+            tenant: None,
+            admin: false,
+        },
+        ApiKey {
+            key: "acme-key".to_string(),
+            name: "acme".to_string(),
+            max_priority: 0,
+            tenant: Some("acme".to_string()),
+            admin: false,
+        },
+    ])
+    .unwrap();
+    let base = start(api(AppState {
+        pool: pool.clone(),
+        keeper: KeeperNotifier::default(),
+        keys: Arc::new(keys),
+        address_policy: Default::default(),
+    }))
+    .await;
+
+    let (status, _) = post_json(
+        format!("{}/evidence", base),
+        Some("ops-key"),
+        json!({ "id": "globex-1", "digest_hex": "ab", "tenant": "globex", "hold": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let release = format!("{}/evidence/globex-1/release", base);
+    let (status, _) = post_json(release.clone(), None, json!({})).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    // Another tenant's job is reported missing rather than confirmed to exist
+    let (status, _) = post_json(release.clone(), Some("acme-key"), json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(job_status(&pool, "globex-1").await, "queued");
+    let (status, _) = post_json(release, Some("ops-key"), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -131,6 +131,10 @@ async fn example_evidence_repository_operations() {
             "source": "documentation_test",
             "priority": "high"
         })),
+        priority: None,
+        tenant: None,
+        not_before: None,
+        hold: false,
     };
 
     let job_id = repo.create_evidence_job(&evidence).await.unwrap();
@@ -178,6 +182,10 @@ async fn example_error_handling() {
         digest_hex: "abcd1234".to_string(),
         payload_mime: None,
         metadata: None,
+        priority: None,
+        tenant: None,
+        not_before: None,
+        hold: false,
     };
 
     // First creation should succeed
@@ -223,6 +231,10 @@ async fn example_pagination_and_statistics() {
             digest_hex: format!("hash{}", i),
             payload_mime: None,
             metadata: None,
+            priority: None,
+            tenant: None,
+            not_before: None,
+            hold: false,
         };
         repo.create_evidence_job(&evidence).await.unwrap();
     }
@@ -270,6 +282,10 @@ async fn example_job_processing_workflow() {
             digest_hex: format!("workflow-hash{}", i),
             payload_mime: None,
             metadata: None,
+            priority: None,
+            tenant: None,
+            not_before: None,
+            hold: false,
         };
         repo.create_evidence_job(&evidence).await.unwrap();
     }
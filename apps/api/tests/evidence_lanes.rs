@@ -0,0 +1,145 @@
+use axum::{routing::post, serve, Router};
+use phoenix_api::handlers::post_evidence;
+use phoenix_api::keys::{ApiKey, ApiKeys, API_KEY_HEADER};
+use phoenix_api::notify::KeeperNotifier;
+use phoenix_api::AppState;
+use phoenix_common::testing::sqlite_test_pool;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use sqlx::{Pool, Row, Sqlite};
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+use tokio::net::TcpListener;
+
+/// Serve `POST /evidence` with `keys` on an ephemeral port; returns the base URL
+async fn start_api(pool: Pool<Sqlite>, keys: ApiKeys) -> String {
+    let app = Router::new()
+        .route("/evidence", post(post_evidence))
+        .with_state(AppState {
+            pool,
+            keeper: KeeperNotifier::default(),
+            keys: Arc::new(keys),
+            address_policy: Default::default(),
+        });
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        serve(listener, app.into_make_service()).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+async fn submit(base: &str, key: Option<&str>, body: Value) -> (StatusCode, Value) {
+    let mut request = Client::new().post(format!("{}/evidence", base)).json(&body);
+    if let Some(key) = key {
+        request = request.header(API_KEY_HEADER, key);
+    }
+    let resp = request.send().await.unwrap();
+    (resp.status(), resp.json().await.unwrap())
+}
+
+async fn lane(pool: &Pool<Sqlite>, id: &str) -> (i64, Option<String>) {
+    let row = sqlx::query("SELECT priority, tenant FROM outbox_jobs WHERE id=?1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    (row.get(0), row.get(1))
+}
+
+#[tokio::test]
+async fn test_keys_limit_priority_and_tenant() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let keys = ApiKeys::new(vec![
+        ApiKey {
+            key: "ops-key".to_string(),
+            name: "ops".to_string(),
+            max_priority: 5,
+            tenant: None,
+            admin: false,
+        },
+        ApiKey {
+            key: "archive-key".to_string(),
+            name: "archive backfill".to_string(),
+            max_priority: 0,
+            tenant: Some("archive".to_string()),
+            admin: false,
+        },
+    ])
+    .unwrap();
+    let base = start_api(pool.clone(), keys).await;
+
+    let (status, _) = submit(&base, None, json!({ "id": "anon", "digest_hex": "ab" })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = submit(
+        &base,
+        Some("ops-key"),
+        json!({ "id": "urgent", "digest_hex": "ab", "priority": 5, "tenant": "acme" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(lane(&pool, "urgent").await, (5, Some("acme".to_string())));
+
+    // The backfill key is pinned to its tenant and may only lower priority
+    let (status, body) = submit(
+        &base,
+        Some("archive-key"),
+        json!({ "id": "too-high", "digest_hex": "ab", "priority": 1 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["error"].as_str().unwrap().contains("limit of 0"));
+    let (status, _) = submit(
+        &base,
+        Some("archive-key"),
+        json!({ "id": "other-tenant", "digest_hex": "ab", "tenant": "acme" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = submit(
+        &base,
+        Some("archive-key"),
+        json!({ "id": "backfill", "digest_hex": "ab", "priority": -5 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        lane(&pool, "backfill").await,
+        (-5, Some("archive".to_string()))
+    );
+
+    let (status, _) = submit(
+        &base,
+        Some("ops-key"),
+        json!({ "id": "out-of-range", "digest_hex": "ab", "priority": 99 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM outbox_jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
+
+#[tokio::test]
+async fn test_open_api_queues_at_default_priority() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let base = start_api(pool.clone(), ApiKeys::default()).await;
+
+    let (status, _) = submit(&base, None, json!({ "id": "plain", "digest_hex": "ab" })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(lane(&pool, "plain").await, (0, None));
+
+    let (status, _) = submit(
+        &base,
+        None,
+        json!({ "id": "raised", "digest_hex": "ab", "priority": 3 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -18,6 +18,8 @@ async fn test_http_evidence_flow() {
     let db_path = temp_db.path().to_str().unwrap();
     let db_url = format!("sqlite://{}", db_path);
 
+    phoenix_common::metrics::install_recorder();
+
     // Set env for API and keeper to use same DB
     std::env::set_var("API_DB_URL", &db_url);
     std::env::set_var("KEEPER_DB_URL", &db_url);
@@ -40,7 +42,9 @@ async fn test_http_evidence_flow() {
     // Start keeper
     let keeper_pool = pool.clone();
     let keeper = tokio::spawn(async move {
-        let mut jp = SqliteJobProvider::new(keeper_pool);
+        let mut jp = SqliteJobProvider::new(keeper_pool)
+            .with_provider("etherlink")
+            .with_endpoint("http://etherlink.invalid");
         let anchor = EtherlinkProviderStub;
         run_job_loop(&mut jp, &anchor, Duration::from_millis(100)).await;
     });
@@ -83,7 +87,9 @@ async fn test_http_evidence_flow() {
 
     // Submit evidence
     let evidence_payload = json!({
-        "digest_hex": "deadbeefcafebabe1234567890abcdef1234567890abcdef1234567890abcdef"
+        "digest_hex": "deadbeefcafebabe1234567890abcdef1234567890abcdef1234567890abcdef",
+        "payload_mime": "application/pdf",
+        "metadata": { "sortie": 7 }
     });
 
     let submit_resp = client
@@ -136,6 +142,61 @@ async fn test_http_evidence_flow() {
     let count: i64 = tx_ref_exists.get("count");
     assert_eq!(count, 1, "Should have exactly one tx ref for the job");
 
+    // The rest of the record is kept for the keeper to hash
+    let record = sqlx::query("SELECT payload_mime, metadata FROM outbox_jobs WHERE id = ?1")
+        .bind(job_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(record.get::<String, _>(0), "application/pdf");
+    let metadata: serde_json::Value = serde_json::from_str(record.get(1)).unwrap();
+    assert_eq!(metadata, json!({ "sortie": 7 }));
+
+    // The attempt history records the successful anchor
+    let attempts_json: serde_json::Value = client
+        .get(format!("{}/evidence/{}/attempts", base_url, job_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let attempts = attempts_json["attempts"].as_array().unwrap();
+    assert_eq!(attempts.len(), 1);
+    assert_eq!(attempts[0]["attempt"], 1);
+    assert_eq!(attempts[0]["outcome"], "done");
+    assert_eq!(attempts[0]["provider"], "etherlink");
+    assert_eq!(attempts[0]["endpoint"], "http://etherlink.invalid");
+    let started_ms = attempts[0]["started_ms"].as_i64().unwrap();
+    let finished_ms = attempts[0]["finished_ms"].as_i64().unwrap();
+    assert_eq!(attempts[0]["latency_ms"], finished_ms - started_ms);
+
+    // The submission, the anchor and the outbox gauges are exported
+    let metrics = client
+        .get(format!("{}/metrics", base_url))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    for line in [
+        "phoenix_evidence_submitted_total 1",
+        "phoenix_jobs_anchored_total{network=\"etherlink\"} 1",
+        "phoenix_anchor_latency_seconds_count{network=\"etherlink\"} 1",
+        "phoenix_queue_depth 0",
+        "phoenix_unconfirmed_tx_refs 1",
+    ] {
+        assert!(metrics.contains(line), "missing {} in\n{}", line, metrics);
+    }
+
+    let missing = client
+        .get(format!("{}/evidence/no-such-job/attempts", base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), 404);
+
     // Cleanup
     server.abort();
     keeper.abort();
@@ -0,0 +1,127 @@
+use anchor_etherlink::EtherlinkProviderStub;
+use axum::{routing::post, serve, Router};
+use phoenix_api::handlers::post_evidence;
+use phoenix_api::models::EvidenceIn;
+use phoenix_api::notify::KeeperNotifier;
+use phoenix_api::pg_repository::PgEvidenceRepository;
+use phoenix_api::AppState;
+use phoenix_common::outbox::JOBS_QUEUED_CHANNEL;
+use phoenix_common::testing::{postgres_test_pool, sqlite_test_pool};
+use phoenix_keeper::ops::{ops_router, OpsState};
+use phoenix_keeper::{run_job_loop_with_control, LoopControl, SqliteJobProvider};
+use reqwest::Client;
+use serde_json::json;
+use sqlx::postgres::PgListener;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::net::TcpListener;
+use tokio::time::timeout;
+
+/// Serve `app` on an ephemeral port; returns the base URL
+async fn start(app: Router) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        serve(listener, app.into_make_service()).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_posted_evidence_wakes_keeper() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+
+    // Keeper with an hour-long poll: only the webhook can get the job anchored in time
+    let jobs = LoopControl::default();
+    let keeper = start(ops_router(OpsState {
+        store: Arc::new(pool.clone()),
+        anchor: Arc::new(EtherlinkProviderStub),
+        jobs: jobs.clone(),
+        confirmations: LoopControl::default(),
+    }))
+    .await;
+    let worker_pool = pool.clone();
+    let worker = tokio::spawn(async move {
+        let mut jp = SqliteJobProvider::new(worker_pool);
+        run_job_loop_with_control(
+            &mut jp,
+            &EtherlinkProviderStub,
+            Duration::from_secs(3600),
+            &jobs,
+        )
+        .await;
+    });
+
+    let api = start(
+        Router::new()
+            .route("/evidence", post(post_evidence))
+            .with_state(AppState {
+                pool: pool.clone(),
+                keeper: KeeperNotifier::new(Some(format!("{}/admin/loops/jobs/wake", keeper))),
+                keys: Default::default(),
+                address_policy: Default::default(),
+            }),
+    )
+    .await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let resp = Client::new()
+        .post(format!("{}/evidence", api))
+        .json(&json!({ "id": "wake-me", "digest_hex": "abcd1234" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    timeout(Duration::from_secs(5), async {
+        loop {
+            let status: String =
+                sqlx::query_scalar("SELECT status FROM outbox_jobs WHERE id='wake-me'")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            if status == "done" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("woken keeper should anchor the job");
+    worker.abort();
+}
+
+#[tokio::test]
+async fn test_pg_repository_notifies_queued_jobs() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    let repo = PgEvidenceRepository::new(pool.clone());
+    repo.ensure_schema().await.unwrap();
+    let mut listener = PgListener::connect_with(&pool).await.unwrap();
+    listener.listen(JOBS_QUEUED_CHANNEL).await.unwrap();
+
+    let evidence = EvidenceIn {
+        id: Some("notify-me".to_string()),
+        digest_hex: "abcd1234".to_string(),
+        payload_mime: None,
+        metadata: None,
+        priority: None,
+        tenant: None,
+        not_before: None,
+        hold: false,
+    };
+    repo.create_evidence_job(&evidence).await.unwrap();
+    let notification = timeout(Duration::from_secs(5), listener.recv())
+        .await
+        .expect("job should be notified")
+        .unwrap();
+    assert_eq!(notification.payload(), "notify-me");
+
+    // A duplicate is not queued, so nothing is sent
+    assert!(repo.create_evidence_job(&evidence).await.is_err());
+    assert!(timeout(Duration::from_millis(200), listener.recv())
+        .await
+        .is_err());
+}
@@ -1,3 +1,10 @@
+use address_validation::{
+    evm_address_from_private_key, verify_evm_private_key, AddressError, AddressPolicy,
+    PolicyDecision, PolicyError,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 #[derive(Debug, Clone)]
@@ -5,8 +12,24 @@ pub struct KeeperConfig {
     pub database_url: String,
     pub job_poll_interval: Duration,
     pub confirmation_poll_interval: Duration,
+    /// Longest wait between two confirmation checks of one transaction
+    pub confirmation_max_backoff: Duration,
+    /// Age after which an unconfirmed transaction is dropped and its job requeued
+    pub confirmation_max_age: Duration,
+    /// `confirmation_max_age` by network, for chains slower to reach finality
+    pub confirmation_network_max_age: BTreeMap<String, Duration>,
+    /// Number of concurrent job workers
+    pub workers: usize,
+    /// How long a worker may hold a job before it is requeued
+    pub lease_duration: Duration,
+    /// How long in-flight anchors and confirmations may run after a shutdown signal
+    pub shutdown_timeout: Duration,
     pub http_port: u16,
     pub provider_config: ProviderConfig,
+    /// Allow/deny list file (CSV or JSON) used to screen addresses
+    pub address_policy_path: Option<PathBuf>,
+    /// Share of job claims per tenant when several have jobs at the same priority
+    pub tenant_weights: crate::TenantWeights,
 }
 
 #[derive(Debug, Clone)]
@@ -16,11 +39,36 @@ pub enum ProviderConfig {
         endpoint: String,
         network: String,
         private_key: Option<String>,
+        /// Address the private key is expected to control
+        address: Option<String>,
     },
     Solana {
         endpoint: String,
         network: String,
     },
+    Bitcoin {
+        /// bitcoind wallet RPC URL
+        endpoint: String,
+        network: String,
+        rpc_user: Option<String>,
+        rpc_password: Option<String>,
+        /// Block depth at which a transaction counts as confirmed
+        min_confirmations: i64,
+    },
+    Tsa {
+        endpoint: String,
+        /// Label recorded as the chain, e.g. the TSA operator
+        name: String,
+        /// Where timestamp tokens are kept
+        token_dir: PathBuf,
+        /// PEM or DER certificates the TSA signer must match or be issued by
+        trusted_certs: Vec<PathBuf>,
+    },
+    Notary {
+        ledger_path: PathBuf,
+        /// File holding the hex-encoded Ed25519 signing seed
+        key_path: PathBuf,
+    },
     Multi {
         etherlink: Option<EtherlinkConfig>,
         solana: Option<SolanaConfig>,
@@ -32,6 +80,7 @@ pub struct EtherlinkConfig {
     pub endpoint: String,
     pub network: String,
     pub private_key: Option<String>,
+    pub address: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -46,96 +95,592 @@ impl Default for KeeperConfig {
             database_url: "sqlite://blockchain_outbox.sqlite3".to_string(),
             job_poll_interval: Duration::from_secs(5),
             confirmation_poll_interval: Duration::from_secs(30),
+            confirmation_max_backoff: crate::DEFAULT_CONFIRM_MAX_BACKOFF,
+            confirmation_max_age: crate::DEFAULT_CONFIRM_MAX_AGE,
+            confirmation_network_max_age: crate::default_network_max_ages(),
+            workers: 1,
+            lease_duration: crate::DEFAULT_LEASE,
+            shutdown_timeout: crate::DEFAULT_SHUTDOWN_TIMEOUT,
             http_port: 8081,
             provider_config: ProviderConfig::Stub,
+            address_policy_path: None,
+            tenant_weights: crate::TenantWeights::default(),
         }
     }
 }
 
-impl KeeperConfig {
-    pub fn from_env() -> Self {
-        let mut config = Self::default();
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read config file: {0}")]
+    Io(String),
+    #[error("malformed config file: {0}")]
+    Parse(String),
+    #[error("invalid keeper configuration: {}", .0.join("; "))]
+    Invalid(Vec<String>),
+}
+
+/// Keeper settings as written in a config file or the environment, before
+/// defaults and validation are applied
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    database_url: Option<String>,
+    job_poll_ms: Option<u64>,
+    confirmation_poll_ms: Option<u64>,
+    confirmation_max_backoff_ms: Option<u64>,
+    confirmation_max_age_ms: Option<u64>,
+    confirmation_network_max_age_ms: Option<BTreeMap<String, u64>>,
+    workers: Option<usize>,
+    lease_ms: Option<u64>,
+    shutdown_ms: Option<u64>,
+    http_port: Option<u16>,
+    address_policy: Option<PathBuf>,
+    tenant_weights: Option<BTreeMap<String, u32>>,
+    provider: Option<String>,
+    etherlink: Option<RawEtherlink>,
+    solana: Option<RawSolana>,
+    bitcoin: Option<RawBitcoin>,
+    tsa: Option<RawTsa>,
+    notary: Option<RawNotary>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawEtherlink {
+    endpoint: Option<String>,
+    network: Option<String>,
+    private_key: Option<String>,
+    address: Option<String>,
+}
 
-        // Database
-        if let Ok(db_url) = std::env::var("KEEPER_DB_URL") {
-            config.database_url = db_url;
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSolana {
+    endpoint: Option<String>,
+    network: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawBitcoin {
+    endpoint: Option<String>,
+    network: Option<String>,
+    rpc_user: Option<String>,
+    rpc_password: Option<String>,
+    min_confirmations: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTsa {
+    endpoint: Option<String>,
+    name: Option<String>,
+    token_dir: Option<PathBuf>,
+    trusted_certs: Option<Vec<PathBuf>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawNotary {
+    ledger: Option<PathBuf>,
+    key_file: Option<PathBuf>,
+}
+
+/// Parse an optional numeric environment variable, noting a malformed value
+fn env_number<T: std::str::FromStr>(name: &str, errors: &mut Vec<String>) -> Option<T>
+where
+    T::Err: std::fmt::Display,
+{
+    let value = std::env::var(name).ok()?;
+    match value.trim().parse() {
+        Ok(n) => Some(n),
+        Err(e) => {
+            errors.push(format!(
+                "{}: '{}' is not a valid number ({})",
+                name, value, e
+            ));
+            None
         }
+    }
+}
 
-        // Polling intervals
-        if let Ok(poll_ms) = std::env::var("KEEPER_POLL_MS") {
-            if let Ok(ms) = poll_ms.parse::<u64>() {
-                config.job_poll_interval = Duration::from_millis(ms);
-            }
+/// Parse a `name=number` list such as `KEEPER_TENANT_WEIGHTS=acme=3,archive=1`
+fn env_number_map<T: std::str::FromStr>(
+    var: &str,
+    key: &str,
+    errors: &mut Vec<String>,
+) -> Option<BTreeMap<String, T>>
+where
+    T::Err: std::fmt::Display,
+{
+    let value = std::env::var(var).ok()?;
+    let mut map = BTreeMap::new();
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        match entry.split_once('=') {
+            Some((name, number)) => match number.trim().parse() {
+                Ok(number) => {
+                    map.insert(name.trim().to_string(), number);
+                }
+                Err(e) => errors.push(format!(
+                    "{}: '{}' is not a valid number ({})",
+                    var, entry, e
+                )),
+            },
+            None => errors.push(format!("{}: '{}' is not {}=number", var, entry, key)),
         }
+    }
+    Some(map)
+}
+
+fn check_endpoint(section: &str, endpoint: &str, errors: &mut Vec<String>) {
+    if !(endpoint.starts_with("http://") || endpoint.starts_with("https://")) {
+        errors.push(format!(
+            "{}.endpoint: '{}' is not an http(s) URL",
+            section, endpoint
+        ));
+    }
+}
+
+/// Note a setting the chosen provider cannot do without
+fn required<T>(field: &str, value: Option<T>, errors: &mut Vec<String>) -> Option<T> {
+    if value.is_none() {
+        errors.push(format!("{}: required by this provider", field));
+    }
+    value
+}
+
+impl RawConfig {
+    fn from_env(errors: &mut Vec<String>) -> Self {
+        if std::env::var("KEEPER_USE_STUB").is_ok() {
+            errors.push(
+                "KEEPER_USE_STUB: no longer supported; set KEEPER_PROVIDER=stub or KEEPER_PROVIDER=etherlink instead"
+                    .to_string(),
+            );
+        }
+        let etherlink_vars = [
+            "ETHERLINK_ENDPOINT",
+            "ETHERLINK_NETWORK",
+            "ETHERLINK_PRIVATE_KEY",
+            "ETHERLINK_ADDRESS",
+        ];
+        let etherlink = etherlink_vars
+            .iter()
+            .any(|v| std::env::var(v).is_ok())
+            .then(|| RawEtherlink {
+                endpoint: std::env::var("ETHERLINK_ENDPOINT").ok(),
+                network: std::env::var("ETHERLINK_NETWORK").ok(),
+                private_key: std::env::var("ETHERLINK_PRIVATE_KEY").ok(),
+                address: std::env::var("ETHERLINK_ADDRESS").ok(),
+            });
+        let solana = ["SOLANA_ENDPOINT", "SOLANA_NETWORK"]
+            .iter()
+            .any(|v| std::env::var(v).is_ok())
+            .then(|| RawSolana {
+                endpoint: std::env::var("SOLANA_ENDPOINT").ok(),
+                network: std::env::var("SOLANA_NETWORK").ok(),
+            });
+
+        let bitcoin = [
+            "BITCOIN_ENDPOINT",
+            "BITCOIN_NETWORK",
+            "BITCOIN_RPC_USER",
+            "BITCOIN_RPC_PASSWORD",
+            "BITCOIN_MIN_CONFIRMATIONS",
+        ]
+        .iter()
+        .any(|v| std::env::var(v).is_ok())
+        .then(|| RawBitcoin {
+            endpoint: std::env::var("BITCOIN_ENDPOINT").ok(),
+            network: std::env::var("BITCOIN_NETWORK").ok(),
+            rpc_user: std::env::var("BITCOIN_RPC_USER").ok(),
+            rpc_password: std::env::var("BITCOIN_RPC_PASSWORD").ok(),
+            min_confirmations: env_number("BITCOIN_MIN_CONFIRMATIONS", errors),
+        });
+        let tsa = [
+            "TSA_ENDPOINT",
+            "TSA_NAME",
+            "TSA_TOKEN_DIR",
+            "TSA_TRUSTED_CERTS",
+        ]
+        .iter()
+        .any(|v| std::env::var(v).is_ok())
+        .then(|| RawTsa {
+            endpoint: std::env::var("TSA_ENDPOINT").ok(),
+            name: std::env::var("TSA_NAME").ok(),
+            token_dir: std::env::var("TSA_TOKEN_DIR").ok().map(PathBuf::from),
+            trusted_certs: std::env::var("TSA_TRUSTED_CERTS").ok().map(|paths| {
+                paths
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(PathBuf::from)
+                    .collect()
+            }),
+        });
+        let notary = ["NOTARY_LEDGER", "NOTARY_KEY_FILE"]
+            .iter()
+            .any(|v| std::env::var(v).is_ok())
+            .then(|| RawNotary {
+                ledger: std::env::var("NOTARY_LEDGER").ok().map(PathBuf::from),
+                key_file: std::env::var("NOTARY_KEY_FILE").ok().map(PathBuf::from),
+            });
+
+        Self {
+            database_url: std::env::var("KEEPER_DB_URL").ok(),
+            job_poll_ms: env_number("KEEPER_POLL_MS", errors),
+            confirmation_poll_ms: env_number("KEEPER_CONFIRM_POLL_MS", errors),
+            confirmation_max_backoff_ms: env_number("KEEPER_CONFIRM_MAX_BACKOFF_MS", errors),
+            confirmation_max_age_ms: env_number("KEEPER_CONFIRM_MAX_AGE_MS", errors),
+            confirmation_network_max_age_ms: env_number_map(
+                "KEEPER_CONFIRM_NETWORK_MAX_AGE_MS",
+                "network",
+                errors,
+            ),
+            workers: env_number("KEEPER_WORKERS", errors),
+            lease_ms: env_number("KEEPER_LEASE_MS", errors),
+            shutdown_ms: env_number("KEEPER_SHUTDOWN_MS", errors),
+            http_port: env_number("KEEPER_HTTP_PORT", errors),
+            address_policy: std::env::var("KEEPER_ADDRESS_POLICY")
+                .ok()
+                .map(PathBuf::from),
+            tenant_weights: env_number_map("KEEPER_TENANT_WEIGHTS", "tenant", errors),
+            provider: std::env::var("KEEPER_PROVIDER").ok(),
+            etherlink,
+            solana,
+            bitcoin,
+            tsa,
+            notary,
+        }
+    }
 
-        if let Ok(confirm_ms) = std::env::var("KEEPER_CONFIRM_POLL_MS") {
-            if let Ok(ms) = confirm_ms.parse::<u64>() {
-                config.confirmation_poll_interval = Duration::from_millis(ms);
+    /// Apply defaults and check every setting, reporting all problems at once
+    fn resolve(self, mut errors: Vec<String>) -> Result<KeeperConfig, ConfigError> {
+        let mut config = KeeperConfig::default();
+
+        if let Some(url) = self.database_url {
+            if !(url.starts_with("sqlite:") || is_postgres_url(&url)) {
+                errors.push(format!(
+                    "database_url: '{}' is not a sqlite: or postgres:// URL",
+                    url
+                ));
             }
+            config.database_url = url;
+        }
+
+        let mut millis = |name: &str, value: Option<u64>, target: &mut Duration| match value {
+            Some(0) => errors.push(format!("{}: must be greater than zero", name)),
+            Some(ms) => *target = Duration::from_millis(ms),
+            None => {}
+        };
+        millis(
+            "job_poll_ms",
+            self.job_poll_ms,
+            &mut config.job_poll_interval,
+        );
+        millis(
+            "confirmation_poll_ms",
+            self.confirmation_poll_ms,
+            &mut config.confirmation_poll_interval,
+        );
+        millis(
+            "confirmation_max_backoff_ms",
+            self.confirmation_max_backoff_ms,
+            &mut config.confirmation_max_backoff,
+        );
+        millis(
+            "confirmation_max_age_ms",
+            self.confirmation_max_age_ms,
+            &mut config.confirmation_max_age,
+        );
+        // Listed networks override their defaults; the rest keep them
+        for (network, ms) in self.confirmation_network_max_age_ms.unwrap_or_default() {
+            let mut age = Duration::ZERO;
+            millis(
+                &format!("confirmation_network_max_age_ms.{}", network),
+                Some(ms),
+                &mut age,
+            );
+            config.confirmation_network_max_age.insert(network, age);
         }
+        millis("lease_ms", self.lease_ms, &mut config.lease_duration);
+        millis(
+            "shutdown_ms",
+            self.shutdown_ms,
+            &mut config.shutdown_timeout,
+        );
 
-        // HTTP port
-        if let Ok(port) = std::env::var("KEEPER_HTTP_PORT") {
-            if let Ok(p) = port.parse::<u16>() {
-                config.http_port = p;
+        match self.workers {
+            Some(0) => errors.push("workers: must be at least 1".to_string()),
+            Some(n) => config.workers = n,
+            None => {}
+        }
+        if let Some(port) = self.http_port {
+            config.http_port = port;
+        }
+        config.address_policy_path = self.address_policy;
+        if let Some(weights) = self.tenant_weights {
+            for (tenant, weight) in &weights {
+                if tenant.is_empty() {
+                    errors.push("tenant_weights: tenant names must not be empty".to_string());
+                } else if *weight == 0 {
+                    errors.push(format!("tenant_weights.{}: must be at least 1", tenant));
+                }
             }
+            config.tenant_weights = crate::TenantWeights::new(weights);
         }
 
-        // Provider configuration
-        config.provider_config = match std::env::var("KEEPER_PROVIDER").as_deref() {
-            Ok("etherlink") => {
-                let endpoint = std::env::var("ETHERLINK_ENDPOINT")
-                    .unwrap_or_else(|_| "https://node.ghostnet.etherlink.com".to_string());
-                let network =
-                    std::env::var("ETHERLINK_NETWORK").unwrap_or_else(|_| "ghostnet".to_string());
-                let private_key = std::env::var("ETHERLINK_PRIVATE_KEY").ok();
+        let etherlink = self.etherlink.map(|raw| {
+            let explicit = raw.endpoint.is_some();
+            let endpoint = raw
+                .endpoint
+                .unwrap_or_else(|| "https://node.ghostnet.etherlink.com".to_string());
+            check_endpoint("etherlink", &endpoint, &mut errors);
+            if let Err(e) = check_signer_key(raw.private_key.as_deref(), raw.address.as_deref()) {
+                errors.push(format!("etherlink.private_key: {}", e));
+            }
+            (
+                explicit,
+                EtherlinkConfig {
+                    endpoint,
+                    network: raw.network.unwrap_or_else(|| "ghostnet".to_string()),
+                    private_key: raw.private_key,
+                    address: raw.address,
+                },
+            )
+        });
+        let solana = self.solana.map(|raw| {
+            let explicit = raw.endpoint.is_some();
+            let endpoint = raw
+                .endpoint
+                .unwrap_or_else(|| "https://api.devnet.solana.com".to_string());
+            check_endpoint("solana", &endpoint, &mut errors);
+            (
+                explicit,
+                SolanaConfig {
+                    endpoint,
+                    network: raw.network.unwrap_or_else(|| "devnet".to_string()),
+                },
+            )
+        });
 
+        config.provider_config = match self.provider.as_deref().map(str::trim) {
+            // No default: a keeper left unconfigured must not anchor fake transactions
+            None => {
+                errors.push(
+                    "provider: required (etherlink, solana, bitcoin, tsa, notary, multi, or stub for development)"
+                        .to_string(),
+                );
+                ProviderConfig::Stub
+            }
+            Some("stub") => ProviderConfig::Stub,
+            Some("etherlink") => {
+                let etherlink = etherlink
+                    .map(|(_, c)| c)
+                    .unwrap_or_else(|| EtherlinkConfig {
+                        endpoint: "https://node.ghostnet.etherlink.com".to_string(),
+                        network: "ghostnet".to_string(),
+                        private_key: None,
+                        address: None,
+                    });
                 ProviderConfig::Etherlink {
+                    endpoint: etherlink.endpoint,
+                    network: etherlink.network,
+                    private_key: etherlink.private_key,
+                    address: etherlink.address,
+                }
+            }
+            Some("solana") => {
+                let solana = solana.map(|(_, c)| c).unwrap_or_else(|| SolanaConfig {
+                    endpoint: "https://api.devnet.solana.com".to_string(),
+                    network: "devnet".to_string(),
+                });
+                ProviderConfig::Solana {
+                    endpoint: solana.endpoint,
+                    network: solana.network,
+                }
+            }
+            Some("bitcoin") => {
+                let raw = self.bitcoin.unwrap_or_default();
+                let endpoint =
+                    required("bitcoin.endpoint", raw.endpoint, &mut errors).unwrap_or_default();
+                if !endpoint.is_empty() {
+                    check_endpoint("bitcoin", &endpoint, &mut errors);
+                }
+                let min_confirmations = raw
+                    .min_confirmations
+                    .unwrap_or(anchor_bitcoin::DEFAULT_MIN_CONFIRMATIONS);
+                if min_confirmations < 1 {
+                    errors.push("bitcoin.min_confirmations: must be at least 1".to_string());
+                }
+                ProviderConfig::Bitcoin {
                     endpoint,
-                    network,
-                    private_key,
+                    network: raw.network.unwrap_or_else(|| "mainnet".to_string()),
+                    rpc_user: raw.rpc_user,
+                    rpc_password: raw.rpc_password,
+                    min_confirmations,
                 }
             }
-            Ok("solana") => {
-                let endpoint = std::env::var("SOLANA_ENDPOINT")
-                    .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
-                let network =
-                    std::env::var("SOLANA_NETWORK").unwrap_or_else(|_| "devnet".to_string());
-
-                ProviderConfig::Solana { endpoint, network }
+            Some("tsa") => {
+                let raw = self.tsa.unwrap_or_default();
+                let endpoint =
+                    required("tsa.endpoint", raw.endpoint, &mut errors).unwrap_or_default();
+                if !endpoint.is_empty() {
+                    check_endpoint("tsa", &endpoint, &mut errors);
+                }
+                let token_dir =
+                    required("tsa.token_dir", raw.token_dir, &mut errors).unwrap_or_default();
+                // Tokens from an untrusted signer are never confirmed
+                let trusted_certs = raw.trusted_certs.unwrap_or_default();
+                if trusted_certs.is_empty() {
+                    errors.push(
+                        "tsa.trusted_certs: at least one certificate is required".to_string(),
+                    );
+                }
+                ProviderConfig::Tsa {
+                    endpoint,
+                    name: raw.name.unwrap_or_else(|| "tsa".to_string()),
+                    token_dir,
+                    trusted_certs,
+                }
             }
-            Ok("multi") => {
-                let etherlink = if std::env::var("ETHERLINK_ENDPOINT").is_ok() {
-                    Some(EtherlinkConfig {
-                        endpoint: std::env::var("ETHERLINK_ENDPOINT").unwrap(),
-                        network: std::env::var("ETHERLINK_NETWORK")
-                            .unwrap_or_else(|_| "ghostnet".to_string()),
-                        private_key: std::env::var("ETHERLINK_PRIVATE_KEY").ok(),
-                    })
-                } else {
-                    None
-                };
-
-                let solana = if std::env::var("SOLANA_ENDPOINT").is_ok() {
-                    Some(SolanaConfig {
-                        endpoint: std::env::var("SOLANA_ENDPOINT").unwrap(),
-                        network: std::env::var("SOLANA_NETWORK")
-                            .unwrap_or_else(|_| "devnet".to_string()),
-                    })
-                } else {
-                    None
-                };
-
+            Some("notary") => {
+                let raw = self.notary.unwrap_or_default();
+                ProviderConfig::Notary {
+                    ledger_path: required("notary.ledger", raw.ledger, &mut errors)
+                        .unwrap_or_default(),
+                    key_path: required("notary.key_file", raw.key_file, &mut errors)
+                        .unwrap_or_default(),
+                }
+            }
+            Some("multi") => {
+                // Only chains with an explicit endpoint take part
+                let etherlink = etherlink.filter(|(explicit, _)| *explicit).map(|(_, c)| c);
+                let solana = solana.filter(|(explicit, _)| *explicit).map(|(_, c)| c);
+                if etherlink.is_none() && solana.is_none() {
+                    errors.push(
+                        "provider: 'multi' needs an etherlink or solana endpoint".to_string(),
+                    );
+                }
                 ProviderConfig::Multi { etherlink, solana }
             }
-            _ => ProviderConfig::Stub,
+            Some(other) => {
+                errors.push(format!(
+                    "provider: unknown provider '{}' (expected stub, etherlink, solana, bitcoin, tsa, notary or multi)",
+                    other
+                ));
+                ProviderConfig::Stub
+            }
         };
 
-        config
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError::Invalid(errors))
+        }
+    }
+}
+
+impl KeeperConfig {
+    /// Load from the TOML file named by `KEEPER_CONFIG`, or from the
+    /// environment when it is unset
+    pub fn load() -> Result<Self, ConfigError> {
+        match std::env::var("KEEPER_CONFIG") {
+            Ok(path) => Self::from_file(Path::new(&path)),
+            Err(_) => Self::from_env(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let mut errors = Vec::new();
+        let raw = RawConfig::from_env(&mut errors);
+        raw.resolve(errors)
+    }
+
+    /// Read a TOML config file. Keys mirror the environment variables, e.g.
+    /// `job_poll_ms` for `KEEPER_POLL_MS`, with a table per provider such as
+    /// `[etherlink]` or `[bitcoin]`.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::Io(format!("{}: {}", path.display(), e)))?;
+        Self::from_toml(&text)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let raw: RawConfig = toml::from_str(text).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        raw.resolve(Vec::new())
     }
 
     pub fn is_postgres(&self) -> bool {
-        self.database_url.starts_with("postgres://")
-            || self.database_url.starts_with("postgresql://")
+        is_postgres_url(&self.database_url)
+    }
+
+    /// How the confirmation loop paces its checks of each transaction
+    pub fn confirmation_schedule(&self) -> crate::ConfirmationSchedule {
+        crate::ConfirmationSchedule {
+            poll: self.confirmation_poll_interval,
+            max_backoff: self.confirmation_max_backoff,
+            max_age: self.confirmation_max_age,
+            network_max_age: self.confirmation_network_max_age.clone(),
+        }
+    }
+}
+
+impl ProviderConfig {
+    /// Provider name jobs are routed by, or `None` when this keeper takes any job
+    pub fn route(&self) -> Option<&'static str> {
+        match self {
+            ProviderConfig::Stub | ProviderConfig::Multi { .. } => None,
+            ProviderConfig::Etherlink { .. } => Some("etherlink"),
+            ProviderConfig::Solana { .. } => Some("solana"),
+            ProviderConfig::Bitcoin { .. } => Some("bitcoin"),
+            ProviderConfig::Tsa { .. } => Some("tsa"),
+            ProviderConfig::Notary { .. } => Some("notary"),
+        }
+    }
+
+    /// RPC endpoint recorded with each attempt, when there is exactly one
+    pub fn endpoint(&self) -> Option<&str> {
+        match self {
+            ProviderConfig::Etherlink { endpoint, .. }
+            | ProviderConfig::Solana { endpoint, .. }
+            | ProviderConfig::Bitcoin { endpoint, .. }
+            | ProviderConfig::Tsa { endpoint, .. } => Some(endpoint),
+            ProviderConfig::Stub | ProviderConfig::Notary { .. } | ProviderConfig::Multi { .. } => {
+                None
+            }
+        }
+    }
+}
+
+/// Whether a database URL selects the PostgreSQL backend
+pub fn is_postgres_url(url: &str) -> bool {
+    url.starts_with("postgres://") || url.starts_with("postgresql://")
+}
+
+/// Check a configured signer key before any anchoring happens.
+///
+/// With an expected address the key must derive to it; without one the key only
+/// has to be well-formed. Returns the derived address, or `None` when no key is set.
+pub fn check_signer_key(
+    private_key: Option<&str>,
+    expected_address: Option<&str>,
+) -> Result<Option<String>, AddressError> {
+    match (private_key, expected_address) {
+        (Some(key), Some(address)) => verify_evm_private_key(key, address).map(Some),
+        (Some(key), None) => evm_address_from_private_key(key).map(Some),
+        (None, Some(_)) => Err(AddressError::InvalidKey(
+            "an expected address is set but no private key".to_string(),
+        )),
+        (None, None) => Ok(None),
     }
 }
+
+/// Screen `address` against the policy file at `policy_path`
+pub fn screen_address(
+    policy_path: &Path,
+    chain: &str,
+    address: &str,
+) -> Result<PolicyDecision, PolicyError> {
+    let policy = AddressPolicy::from_file(policy_path)?;
+    Ok(policy.is_permitted(chain, address))
+}
@@ -1,50 +1,41 @@
 use async_trait::async_trait;
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
+use metrics::{counter, histogram};
+use phoenix_common::metrics::{
+    ANCHOR_LATENCY, CONFIRM_LATENCY, JOBS_ANCHORED, JOBS_CONFIRMED, JOBS_FAILED, JOBS_RETRIED,
+    TX_REFS_DROPPED,
+};
+use phoenix_common::migrations::MigrationError;
+use phoenix_common::outbox::{
+    AttemptOutcome, FailureCategory, HELD_NEXT_ATTEMPT_MS, LEASE_EXPIRED_ERROR, SHUTDOWN_ERROR,
+    TX_DROPPED_ERROR,
+};
 use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
-use phoenix_evidence::model::{ChainTxRef, DigestAlgo, EvidenceDigest, EvidenceRecord};
+use phoenix_evidence::model::{
+    ChainTxRef, DigestAlgo, EvidenceDigest, EvidenceRecord, SignedAnchorTx,
+};
 use rand::Rng;
-use sqlx::{Pool, Row, Sqlite};
+use serde::Serialize;
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
 
 pub mod config;
+pub mod maintenance;
+pub mod ops;
+pub mod postgres;
+pub mod providers;
 
 /// Initialize database schema for the keeper
-pub async fn ensure_schema(pool: &Pool<Sqlite>) -> Result<(), sqlx::Error> {
-    // Create outbox_jobs table
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS outbox_jobs (
-            id TEXT PRIMARY KEY,
-            payload_sha256 TEXT NOT NULL,
-            status TEXT NOT NULL DEFAULT 'queued',
-            attempts INTEGER NOT NULL DEFAULT 0,
-            last_error TEXT,
-            created_ms INTEGER NOT NULL,
-            updated_ms INTEGER NOT NULL,
-            next_attempt_ms INTEGER NOT NULL DEFAULT 0
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
-
-    // Create outbox_tx_refs table
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS outbox_tx_refs (
-            job_id TEXT NOT NULL,
-            network TEXT NOT NULL,
-            chain TEXT NOT NULL,
-            tx_id TEXT NOT NULL,
-            confirmed INTEGER NOT NULL DEFAULT 0,
-            timestamp INTEGER,
-            PRIMARY KEY (job_id, network, chain, tx_id)
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
-
-    Ok(())
+///
+/// Runs the migration set shared with the API, so both binaries agree on the schema.
+pub async fn ensure_schema(pool: &Pool<Sqlite>) -> Result<(), MigrationError> {
+    phoenix_common::schema::ensure_schema(pool).await
 }
 
 #[derive(Debug, Clone)]
@@ -52,6 +43,9 @@ pub struct EvidenceJob {
     pub id: String,
     pub payload_sha256: String,
     pub created_ms: i64,
+    pub payload_mime: Option<String>,
+    /// Metadata as submitted, stored as JSON text
+    pub metadata: Option<String>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -84,77 +78,504 @@ pub trait JobProviderExt: JobProvider {
         reason: &str,
         temporary: bool,
     ) -> Result<(), JobError>;
+    /// Record the signed transaction for job `id` before it is broadcast.
+    /// Fails if this worker no longer holds the job.
+    async fn record_intent(&mut self, id: &str, signed: &SignedAnchorTx) -> Result<(), JobError>;
+    /// The signed transaction an earlier attempt at job `id` recorded but did
+    /// not see through to a tx ref
+    async fn pending_intent(&mut self, id: &str) -> Result<Option<SignedAnchorTx>, JobError>;
+    /// Renews this worker's leases while it anchors, or `None` if the
+    /// provider does not lease jobs
+    fn lease_renewer(&self) -> Option<Box<dyn LeaseRenewer>> {
+        None
+    }
+}
+
+/// Extends a worker's lease on a claimed job, so a job anchoring for longer
+/// than one lease is not reaped and handed to another worker
+#[async_trait]
+pub trait LeaseRenewer: Send + Sync {
+    /// How far each renewal extends the lease
+    fn lease(&self) -> Duration;
+    /// Extend the lease on job `id`; false once this worker no longer holds it
+    async fn renew_lease(&self, id: &str) -> Result<bool, sqlx::Error>;
+}
+
+/// A worker's lease on the jobs it claims from `pool`
+#[derive(Debug, Clone)]
+pub struct WorkerLease<P> {
+    pub pool: P,
+    pub worker_id: String,
+    pub lease: Duration,
+}
+
+/// Run `work` while renewing the lease on job `id` every third of the lease
+async fn with_lease_heartbeat<F: Future>(
+    renewer: Option<Box<dyn LeaseRenewer>>,
+    id: &str,
+    work: F,
+) -> F::Output {
+    let Some(renewer) = renewer else {
+        return work.await;
+    };
+    let heartbeat = async {
+        let interval = renewer.lease() / 3;
+        loop {
+            tokio::time::sleep(interval).await;
+            match renewer.renew_lease(id).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    tracing::warn!(job_id = %id, "Lease lost mid-anchor, no longer renewing it");
+                    return;
+                }
+                Err(e) => tracing::warn!(job_id = %id, error = %e, "Failed to renew lease"),
+            }
+        }
+    };
+    tokio::pin!(work);
+    tokio::select! {
+        output = &mut work => return output,
+        _ = heartbeat => {}
+    }
+    work.await
+}
+
+/// How long in-flight work may run after shutdown is requested, by default
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Pause switch for a keeper loop, flipped through the keeper's admin endpoints,
+/// and the shutdown token that stops it
+#[derive(Debug, Clone)]
+pub struct LoopControl {
+    paused: Arc<AtomicBool>,
+    wake: Arc<Notify>,
+    shutdown: CancellationToken,
+    shutdown_timeout: Duration,
+}
+
+impl Default for LoopControl {
+    fn default() -> Self {
+        Self::new(CancellationToken::new(), DEFAULT_SHUTDOWN_TIMEOUT)
+    }
+}
+
+impl LoopControl {
+    /// Control for a loop that stops once `shutdown` is cancelled, giving
+    /// in-flight work up to `shutdown_timeout` to finish
+    pub fn new(shutdown: CancellationToken, shutdown_timeout: Duration) -> Self {
+        Self {
+            paused: Arc::new(AtomicBool::new(false)),
+            wake: Arc::new(Notify::new()),
+            shutdown,
+            shutdown_timeout,
+        }
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown.is_cancelled()
+    }
+
+    /// Sleep for `poll`, waking early on `wake` or shutdown
+    async fn idle(&self, poll: Duration) {
+        tokio::select! {
+            _ = tokio::time::sleep(poll) => {}
+            _ = self.wake.notified() => {}
+            _ = self.shutdown.cancelled() => {}
+        }
+    }
+
+    /// End the current idle wait early, e.g. because a job was just queued.
+    /// A wake that arrives while the loop is busy ends its next wait at once.
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    /// Run `work` to completion, or give up `shutdown_timeout` after shutdown
+    /// is requested
+    async fn drain<F: Future>(&self, work: F) -> Option<F::Output> {
+        tokio::pin!(work);
+        tokio::select! {
+            output = &mut work => return Some(output),
+            _ = self.shutdown.cancelled() => {}
+        }
+        tokio::time::timeout(self.shutdown_timeout, work).await.ok()
+    }
+
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
 }
 
 pub async fn run_job_loop<J: JobProvider + JobProviderExt, A: AnchorProvider + ?Sized>(
     provider: &mut J,
     anchor: &A,
     poll: std::time::Duration,
+) {
+    run_job_loop_with_control(provider, anchor, poll, &LoopControl::default()).await
+}
+
+/// `run_job_loop` that claims no new jobs while `control` is paused and returns
+/// once it is shut down. The lease on a job is renewed while it is anchored. A
+/// job whose anchor is still running when the shutdown timeout passes goes
+/// back to the queue.
+pub async fn run_job_loop_with_control<
+    J: JobProvider + JobProviderExt,
+    A: AnchorProvider + ?Sized,
+>(
+    provider: &mut J,
+    anchor: &A,
+    poll: std::time::Duration,
+    control: &LoopControl,
 ) {
     loop {
+        if control.is_shutting_down() {
+            return;
+        }
+        if control.is_paused() {
+            control.idle(poll).await;
+            continue;
+        }
         match provider.fetch_next().await {
             Ok(Some(job)) => {
-                let ev = EvidenceRecord {
-                    id: job.id.clone(),
-                    created_at: Utc::now(),
-                    digest: EvidenceDigest {
-                        algo: DigestAlgo::Sha256,
-                        hex: job.payload_sha256.clone(),
-                    },
-                    payload_mime: None,
-                    metadata: serde_json::json!({}),
-                };
-                match anchor.anchor(&ev).await {
-                    Ok(txref) => {
-                        let _ = provider.mark_tx_and_done(&job.id, &txref).await;
-                    }
-                    Err(e) => {
-                        let temporary =
-                            matches!(e, AnchorError::Network(_) | AnchorError::Provider(_));
-                        let _ = provider
-                            .mark_failed_or_backoff(&job.id, &e.to_string(), temporary)
-                            .await;
+                let started = Instant::now();
+                let renewer = provider.lease_renewer();
+                let drained = match evidence_record(&job) {
+                    Ok(ev) => {
+                        control
+                            .drain(with_lease_heartbeat(
+                                renewer,
+                                &job.id,
+                                anchor_job(provider, anchor, &job.id, &ev),
+                            ))
+                            .await
                     }
-                }
+                    Err(e) => Some(Err(e)),
+                };
+                let Some(result) = drained else {
+                    tracing::warn!(job_id = %job.id, "Shutdown timeout passed mid-anchor, requeueing job");
+                    let _ = provider
+                        .mark_failed_or_backoff(&job.id, SHUTDOWN_ERROR, true)
+                        .await;
+                    return;
+                };
+                record_anchor_result(provider, anchor, &job.id, &result, started).await;
             }
             Ok(None) => {
-                tokio::time::sleep(poll).await;
+                control.idle(poll).await;
             }
             Err(e) => {
                 tracing::error!(error = %e, "Failed to fetch next job");
-                tokio::time::sleep(poll).await;
+                control.idle(poll).await;
             }
         }
     }
 }
 
-pub async fn run_confirmation_loop<A: AnchorProvider + ?Sized>(
-    pool: &Pool<Sqlite>,
+/// Anchor a job claimed outside the job loop and record the outcome as the
+/// loop would
+pub async fn anchor_claimed_job<J: JobProviderExt + ?Sized, A: AnchorProvider + ?Sized>(
+    provider: &mut J,
     anchor: &A,
-    poll: std::time::Duration,
+    job: &EvidenceJob,
+) -> Result<ChainTxRef, AnchorError> {
+    let started = Instant::now();
+    let renewer = provider.lease_renewer();
+    let result = match evidence_record(job) {
+        Ok(ev) => {
+            with_lease_heartbeat(renewer, &job.id, anchor_job(provider, anchor, &job.id, &ev)).await
+        }
+        Err(e) => Err(e),
+    };
+    record_anchor_result(provider, anchor, &job.id, &result, started).await;
+    result
+}
+
+/// The record the API accepted for `job`, so its anchored hash can be
+/// recomputed from the submitted evidence
+fn evidence_record(job: &EvidenceJob) -> Result<EvidenceRecord, AnchorError> {
+    let metadata = match &job.metadata {
+        Some(raw) => serde_json::from_str(raw)
+            .map_err(|e| AnchorError::Invalid(format!("stored metadata is not JSON: {}", e)))?,
+        None => serde_json::json!({}),
+    };
+    Ok(EvidenceRecord {
+        id: job.id.clone(),
+        // Derived from the job so the anchored record hash can be recomputed
+        created_at: Utc
+            .timestamp_millis_opt(job.created_ms)
+            .single()
+            .unwrap_or_else(Utc::now),
+        digest: EvidenceDigest {
+            algo: DigestAlgo::Sha256,
+            hex: job.payload_sha256.clone(),
+        },
+        payload_mime: job.payload_mime.clone(),
+        metadata,
+    })
+}
+
+/// Count and time an anchor attempt, then mark the job done or back off
+async fn record_anchor_result<J: JobProviderExt + ?Sized, A: AnchorProvider + ?Sized>(
+    provider: &mut J,
+    anchor: &A,
+    id: &str,
+    result: &Result<ChainTxRef, AnchorError>,
+    started: Instant,
 ) {
+    let network = match result {
+        Ok(txref) => txref.network.clone(),
+        Err(_) => anchor.network().to_string(),
+    };
+    histogram!(ANCHOR_LATENCY, "network" => network.clone())
+        .record(started.elapsed().as_secs_f64());
+    match result {
+        Ok(txref) => {
+            counter!(JOBS_ANCHORED, "network" => network).increment(1);
+            let _ = provider.mark_tx_and_done(id, txref).await;
+        }
+        Err(e) => {
+            // An expired transaction is signed again on the next attempt
+            let temporary = matches!(
+                e,
+                AnchorError::Network(_) | AnchorError::Provider(_) | AnchorError::Expired(_)
+            );
+            let name = if temporary { JOBS_RETRIED } else { JOBS_FAILED };
+            counter!(name, "network" => network).increment(1);
+            let _ = provider
+                .mark_failed_or_backoff(id, &e.to_string(), temporary)
+                .await;
+        }
+    }
+}
+
+/// Anchor `ev` for job `id`. When the provider signs ahead of sending, the
+/// signed transaction is recorded as an intent before it is broadcast, and an
+/// intent left by an earlier attempt is looked up on chain instead of signing
+/// a new transaction, so a crash between sending and recording the tx ref does
+/// not anchor the evidence twice. An intent that can no longer land, because
+/// it outlived the provider's signed transaction lifetime or the chain
+/// rejects it as expired, is replaced by a newly signed one.
+async fn anchor_job<J: JobProviderExt + ?Sized, A: AnchorProvider + ?Sized>(
+    provider: &mut J,
+    anchor: &A,
+    id: &str,
+    ev: &EvidenceRecord,
+) -> Result<ChainTxRef, AnchorError> {
+    let outbox_error = |e: JobError| AnchorError::Network(format!("outbox: {}", e));
+    // An intent for another network predates an admin requeue to a different provider
+    let pending = provider
+        .pending_intent(id)
+        .await
+        .map_err(outbox_error)?
+        .filter(|signed| signed.tx.network == anchor.network());
+    if let Some(signed) = pending {
+        if anchor.is_known(&signed.tx).await? {
+            tracing::info!(job_id = %id, tx_id = %signed.tx.tx_id, "Anchor intent already on chain, not resending");
+            return Ok(signed.tx);
+        }
+        if intent_expired(anchor, &signed) {
+            tracing::warn!(job_id = %id, tx_id = %signed.tx.tx_id, "Anchor intent expired before reaching the chain, signing a new one");
+        } else {
+            tracing::warn!(job_id = %id, tx_id = %signed.tx.tx_id, "Anchor intent not on chain, sending it again");
+            match anchor.broadcast(&signed).await {
+                Err(AnchorError::Expired(reason)) => {
+                    tracing::warn!(job_id = %id, tx_id = %signed.tx.tx_id, reason = %reason, "Anchor intent rejected as expired, signing a new one");
+                }
+                result => return result,
+            }
+        }
+    }
+
+    match anchor.prepare(ev).await? {
+        Some(signed) => {
+            provider
+                .record_intent(id, &signed)
+                .await
+                .map_err(outbox_error)?;
+            anchor.broadcast(&signed).await
+        }
+        None => anchor.anchor(ev).await,
+    }
+}
+
+/// Whether `signed`, recorded at its `timestamp`, is past the provider's
+/// signed transaction lifetime
+fn intent_expired<A: AnchorProvider + ?Sized>(anchor: &A, signed: &SignedAnchorTx) -> bool {
+    match (anchor.signed_tx_lifetime(), signed.tx.timestamp) {
+        (Some(lifetime), Some(recorded)) => Utc::now()
+            .signed_duration_since(recorded)
+            .to_std()
+            .is_ok_and(|age| age >= lifetime),
+        _ => false,
+    }
+}
+
+/// An unconfirmed transaction reference with its check schedule
+#[derive(Debug, Clone)]
+pub struct PendingTxRef {
+    pub job_id: String,
+    pub tx: ChainTxRef,
+    /// When the tx ref was recorded; its age counts from here
+    pub created_ms: i64,
+    /// Unconfirmed checks so far
+    pub checks: i64,
+}
+
+/// Longest wait between two confirmation checks of one transaction, by default
+pub const DEFAULT_CONFIRM_MAX_BACKOFF: Duration = Duration::from_secs(600);
+/// Age after which an unconfirmed transaction is given up on, by default
+pub const DEFAULT_CONFIRM_MAX_AGE: Duration = Duration::from_secs(3600);
+/// Maximum ages for networks that take longer than `DEFAULT_CONFIRM_MAX_AGE`
+/// to reach finality: six Bitcoin blocks take an hour on average and often
+/// much longer
+pub const DEFAULT_NETWORK_MAX_AGES: &[(&str, Duration)] =
+    &[("bitcoin", Duration::from_secs(6 * 3600))];
+/// Transactions checked per pass, newest first, so a backlog of old ones
+/// cannot hold up fresh ones
+const CONFIRM_BATCH: i64 = 100;
+
+/// When the confirmation loop checks each transaction, and when it gives up
+#[derive(Debug, Clone)]
+pub struct ConfirmationSchedule {
+    /// Pause between passes, and between the first checks of a transaction
+    pub poll: Duration,
+    pub max_backoff: Duration,
+    /// Age after which an unconfirmed transaction is declared dropped and its
+    /// job requeued
+    pub max_age: Duration,
+    /// `max_age` for transactions on these networks instead
+    pub network_max_age: BTreeMap<String, Duration>,
+}
+
+impl From<Duration> for ConfirmationSchedule {
+    fn from(poll: Duration) -> Self {
+        Self {
+            poll,
+            max_backoff: DEFAULT_CONFIRM_MAX_BACKOFF,
+            max_age: DEFAULT_CONFIRM_MAX_AGE,
+            network_max_age: default_network_max_ages(),
+        }
+    }
+}
+
+/// `DEFAULT_NETWORK_MAX_AGES` as a `ConfirmationSchedule::network_max_age` map
+pub fn default_network_max_ages() -> BTreeMap<String, Duration> {
+    DEFAULT_NETWORK_MAX_AGES
+        .iter()
+        .map(|(network, age)| (network.to_string(), *age))
+        .collect()
+}
+
+impl ConfirmationSchedule {
+    /// When to check a transaction next after its `checks`-th unconfirmed
+    /// check: `poll`, doubling with each further check up to `max_backoff`
+    pub fn next_check_ms(&self, now_ms: i64, checks: i64) -> i64 {
+        let doublings = checks.saturating_sub(1).clamp(0, 20) as u32;
+        let delay = self
+            .poll
+            .saturating_mul(1 << doublings)
+            .min(self.max_backoff);
+        now_ms + delay.as_millis() as i64
+    }
+
+    /// Age after which an unconfirmed transaction on `network` is dropped
+    pub fn max_age_for(&self, network: &str) -> Duration {
+        self.network_max_age
+            .get(network)
+            .copied()
+            .unwrap_or(self.max_age)
+    }
+}
+
+/// Storage for anchored transaction references awaiting confirmation
+#[async_trait]
+pub trait TxRefStore {
+    /// Unconfirmed transactions whose next check is due at `now_ms`, newest first
+    async fn fetch_due_tx_refs(
+        &self,
+        now_ms: i64,
+        limit: i64,
+    ) -> Result<Vec<PendingTxRef>, sqlx::Error>;
+    async fn update_tx_ref_confirmation(&self, tx_ref: &ChainTxRef) -> Result<(), sqlx::Error>;
+    /// Count an unconfirmed check of `tx_ref` and set when to check it next
+    async fn schedule_tx_ref_check(
+        &self,
+        tx_ref: &PendingTxRef,
+        next_check_ms: i64,
+    ) -> Result<(), sqlx::Error>;
+    /// Stop checking `tx_ref` and requeue its job with `reason` as the error
+    async fn drop_tx_ref(&self, tx_ref: &PendingTxRef, reason: &str) -> Result<(), sqlx::Error>;
+}
+
+pub async fn run_confirmation_loop<S: TxRefStore + ?Sized, A: AnchorProvider + ?Sized>(
+    store: &S,
+    anchor: &A,
+    schedule: impl Into<ConfirmationSchedule>,
+) {
+    run_confirmation_loop_with_control(store, anchor, schedule, &LoopControl::default()).await
+}
+
+/// `run_confirmation_loop` that skips its polls while `control` is paused and
+/// returns once it is shut down, after the confirmation check in progress
+pub async fn run_confirmation_loop_with_control<
+    S: TxRefStore + ?Sized,
+    A: AnchorProvider + ?Sized,
+>(
+    store: &S,
+    anchor: &A,
+    schedule: impl Into<ConfirmationSchedule>,
+    control: &LoopControl,
+) {
+    let schedule = schedule.into();
     loop {
-        match fetch_unconfirmed_tx_refs(pool).await {
+        if control.is_shutting_down() {
+            return;
+        }
+        if control.is_paused() {
+            control.idle(schedule.poll).await;
+            continue;
+        }
+        let now_ms = Utc::now().timestamp_millis();
+        match store.fetch_due_tx_refs(now_ms, CONFIRM_BATCH).await {
             Ok(tx_refs) => {
-                for tx_ref in tx_refs {
-                    match anchor.confirm(&tx_ref).await {
-                        Ok(updated_tx) => {
-                            if updated_tx.confirmed != tx_ref.confirmed {
-                                let _ = update_tx_ref_confirmation(pool, &updated_tx).await;
-                                if updated_tx.confirmed {
-                                    tracing::info!(
-                                        tx_id = %updated_tx.tx_id,
-                                        network = %updated_tx.network,
-                                    );
-                                }
-                            }
+                for pending in tx_refs {
+                    if control.is_shutting_down() {
+                        return;
+                    }
+                    let started = Instant::now();
+                    let Some(result) = control.drain(anchor.confirm(&pending.tx)).await else {
+                        return;
+                    };
+                    histogram!(CONFIRM_LATENCY, "network" => pending.tx.network.clone())
+                        .record(started.elapsed().as_secs_f64());
+                    match result {
+                        Ok(updated_tx) if updated_tx.confirmed => {
+                            let _ = store.update_tx_ref_confirmation(&updated_tx).await;
+                            counter!(JOBS_CONFIRMED, "network" => updated_tx.network.clone())
+                                .increment(1);
+                            tracing::info!(
+                                tx_id = %updated_tx.tx_id,
+                                network = %updated_tx.network,
+                            );
+                        }
+                        Ok(_) => {
+                            check_again_or_drop(store, anchor, &schedule, &pending, true).await
                         }
                         Err(e) => {
                             tracing::warn!(
-                                tx_id = %tx_ref.tx_id,
+                                tx_id = %pending.tx.tx_id,
                                 error = %e,
                                 "Failed to check confirmation status"
                             );
+                            check_again_or_drop(store, anchor, &schedule, &pending, false).await;
                         }
                     }
                 }
@@ -163,127 +584,641 @@ pub async fn run_confirmation_loop<A: AnchorProvider + ?Sized>(
                 tracing::error!(error = %e, "Failed to fetch unconfirmed tx refs");
             }
         }
-        tokio::time::sleep(poll).await;
+        control.idle(schedule.poll).await;
     }
 }
 
-async fn fetch_unconfirmed_tx_refs(pool: &Pool<Sqlite>) -> Result<Vec<ChainTxRef>, sqlx::Error> {
-    let rows = sqlx::query(
-        "SELECT job_id, network, chain, tx_id, confirmed, timestamp FROM outbox_tx_refs WHERE confirmed = 0"
-    )
-    .fetch_all(pool)
-    .await?;
+/// Schedule the next check of an unconfirmed transaction, or drop it once it
+/// is past the maximum age for its network. Only a chain that `answered` can
+/// drop a transaction: an RPC outage must not requeue every job in flight. A
+/// transaction the chain still knows, e.g. one waiting in the mempool, is kept
+/// as well, since anchoring again could land the evidence twice.
+async fn check_again_or_drop<S: TxRefStore + ?Sized, A: AnchorProvider + ?Sized>(
+    store: &S,
+    anchor: &A,
+    schedule: &ConfirmationSchedule,
+    pending: &PendingTxRef,
+    answered: bool,
+) {
+    let now_ms = Utc::now().timestamp_millis();
+    let age_ms = now_ms - pending.created_ms;
+    let expired =
+        answered && age_ms >= schedule.max_age_for(&pending.tx.network).as_millis() as i64;
+    let gone = expired
+        && match anchor.is_known(&pending.tx).await {
+            Ok(known) => !known,
+            // The provider cannot look transactions up; go by age alone
+            Err(AnchorError::Invalid(_)) => true,
+            Err(e) => {
+                tracing::warn!(tx_id = %pending.tx.tx_id, error = %e, "Failed to look up expired transaction");
+                false
+            }
+        };
+    let result = if gone {
+        tracing::warn!(
+            job_id = %pending.job_id,
+            tx_id = %pending.tx.tx_id,
+            network = %pending.tx.network,
+            age_ms,
+            "Transaction not confirmed within the maximum age, requeueing job"
+        );
+        counter!(TX_REFS_DROPPED, "network" => pending.tx.network.clone()).increment(1);
+        let reason = format!(
+            "{}: {} not confirmed after {}s",
+            TX_DROPPED_ERROR,
+            pending.tx.tx_id,
+            age_ms / 1000
+        );
+        store.drop_tx_ref(pending, &reason).await
+    } else {
+        if expired {
+            tracing::warn!(
+                job_id = %pending.job_id,
+                tx_id = %pending.tx.tx_id,
+                network = %pending.tx.network,
+                age_ms,
+                "Transaction past the maximum age is still known to the chain, waiting for it"
+            );
+        }
+        let next_check_ms = schedule.next_check_ms(now_ms, pending.checks + 1);
+        store.schedule_tx_ref_check(pending, next_check_ms).await
+    };
+    if let Err(e) = result {
+        tracing::error!(tx_id = %pending.tx.tx_id, error = %e, "Failed to reschedule confirmation check");
+    }
+}
+
+/// Returns jobs whose lease has expired, e.g. after a worker crashed mid-anchor, to the queue
+#[async_trait]
+pub trait LeaseReaper {
+    async fn reap_expired_leases(&self) -> Result<u64, sqlx::Error>;
+}
+
+/// Reap expired leases every `poll` until `shutdown` is cancelled
+pub async fn run_reaper_loop<R: LeaseReaper + ?Sized>(
+    reaper: &R,
+    poll: Duration,
+    shutdown: &CancellationToken,
+) {
+    while !shutdown.is_cancelled() {
+        match reaper.reap_expired_leases().await {
+            Ok(0) => {}
+            Ok(reaped) => {
+                tracing::warn!(jobs = reaped, "Requeued jobs with expired leases");
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to reap expired leases");
+            }
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(poll) => {}
+            _ = shutdown.cancelled() => {}
+        }
+    }
+}
+
+/// Queue and anchoring figures reported by the keeper's `/status` endpoint
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OutboxStatus {
+    pub jobs_by_status: BTreeMap<String, i64>,
+    pub oldest_queued_age_ms: Option<i64>,
+    pub unconfirmed_tx_refs: i64,
+    /// Time of the newest anchored transaction per network
+    pub last_anchor: BTreeMap<String, DateTime<Utc>>,
+}
+
+/// Read-only view of the outbox for health and status checks
+#[async_trait]
+pub trait StatusStore {
+    async fn ping(&self) -> Result<(), sqlx::Error>;
+    async fn outbox_status(&self) -> Result<OutboxStatus, sqlx::Error>;
+}
+
+/// Assemble `OutboxStatus` from the rows of the status queries
+pub(crate) fn outbox_status_from(
+    by_status: Vec<(String, i64)>,
+    oldest_queued_ms: Option<i64>,
+    unconfirmed_tx_refs: i64,
+    last_anchor: Vec<(String, i64)>,
+) -> OutboxStatus {
+    let now_ms = Utc::now().timestamp_millis();
+    OutboxStatus {
+        jobs_by_status: by_status.into_iter().collect(),
+        oldest_queued_age_ms: oldest_queued_ms.map(|created| (now_ms - created).max(0)),
+        unconfirmed_tx_refs,
+        last_anchor: last_anchor
+            .into_iter()
+            .filter_map(|(network, secs)| {
+                Utc.timestamp_opt(secs, 0).single().map(|at| (network, at))
+            })
+            .collect(),
+    }
+}
+
+#[async_trait]
+impl StatusStore for Pool<Sqlite> {
+    async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1").execute(self).await?;
+        Ok(())
+    }
+
+    async fn outbox_status(&self) -> Result<OutboxStatus, sqlx::Error> {
+        let by_status = sqlx::query_as(
+            "SELECT CASE WHEN status='queued' AND next_attempt_ms=?1 THEN 'held' ELSE status END AS s, COUNT(*) FROM outbox_jobs GROUP BY s",
+        )
+        .bind(HELD_NEXT_ATTEMPT_MS)
+        .fetch_all(self)
+        .await?;
+        // Held jobs are not waiting on the keeper, and scheduled ones only from when they fall due
+        let oldest_queued_ms = sqlx::query_scalar(
+            "SELECT MIN(CASE WHEN attempts=0 THEN MAX(created_ms, next_attempt_ms) ELSE created_ms END) FROM outbox_jobs WHERE status='queued' AND next_attempt_ms < ?1",
+        )
+        .bind(HELD_NEXT_ATTEMPT_MS)
+        .fetch_one(self)
+        .await?;
+        let unconfirmed = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM outbox_tx_refs WHERE confirmed = 0 AND dropped_ms IS NULL",
+        )
+        .fetch_one(self)
+        .await?;
+        let last_anchor = sqlx::query_as(
+            "SELECT network, MAX(timestamp) FROM outbox_tx_refs WHERE timestamp IS NOT NULL GROUP BY network",
+        )
+        .fetch_all(self)
+        .await?;
+        Ok(outbox_status_from(
+            by_status,
+            oldest_queued_ms,
+            unconfirmed,
+            last_anchor,
+        ))
+    }
+}
+
+#[async_trait]
+impl TxRefStore for Pool<Sqlite> {
+    async fn fetch_due_tx_refs(
+        &self,
+        now_ms: i64,
+        limit: i64,
+    ) -> Result<Vec<PendingTxRef>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT job_id, network, chain, tx_id, confirmed, timestamp, created_ms, checks FROM outbox_tx_refs WHERE confirmed = 0 AND dropped_ms IS NULL AND next_check_ms <= ?1 ORDER BY created_ms DESC LIMIT ?2",
+        )
+        .bind(now_ms)
+        .bind(limit)
+        .fetch_all(self)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| PendingTxRef {
+                job_id: row.get("job_id"),
+                tx: tx_ref_from_columns(
+                    row.get("network"),
+                    row.get("chain"),
+                    row.get("tx_id"),
+                    row.get("confirmed"),
+                    row.get("timestamp"),
+                ),
+                // Refs inserted without it are treated as new, so they are never dropped
+                created_ms: row.get::<Option<i64>, _>("created_ms").unwrap_or(now_ms),
+                checks: row.get("checks"),
+            })
+            .collect())
+    }
 
-    let mut tx_refs = Vec::new();
-    for row in rows {
-        let timestamp_opt: Option<i64> = row.get("timestamp");
-        let timestamp = timestamp_opt.and_then(|ts| {
-            // Convert seconds to milliseconds and use the non-deprecated API
-            Utc.timestamp_millis_opt(ts * 1000).single()
-        });
+    async fn update_tx_ref_confirmation(&self, tx_ref: &ChainTxRef) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE outbox_tx_refs SET confirmed = ?1 WHERE tx_id = ?2 AND network = ?3 AND chain = ?4",
+        )
+        .bind(if tx_ref.confirmed { 1 } else { 0 })
+        .bind(&tx_ref.tx_id)
+        .bind(&tx_ref.network)
+        .bind(&tx_ref.chain)
+        .execute(self)
+        .await?;
+
+        Ok(())
+    }
 
-        tx_refs.push(ChainTxRef {
-            network: row.get("network"),
-            chain: row.get("chain"),
-            tx_id: row.get("tx_id"),
-            confirmed: row.get::<i32, _>("confirmed") != 0,
-            timestamp,
-        });
+    async fn schedule_tx_ref_check(
+        &self,
+        tx_ref: &PendingTxRef,
+        next_check_ms: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE outbox_tx_refs SET checks = checks + 1, next_check_ms = ?1 WHERE job_id = ?2 AND tx_id = ?3 AND network = ?4 AND chain = ?5",
+        )
+        .bind(next_check_ms)
+        .bind(&tx_ref.job_id)
+        .bind(&tx_ref.tx.tx_id)
+        .bind(&tx_ref.tx.network)
+        .bind(&tx_ref.tx.chain)
+        .execute(self)
+        .await?;
+        Ok(())
     }
 
-    Ok(tx_refs)
+    async fn drop_tx_ref(&self, tx_ref: &PendingTxRef, reason: &str) -> Result<(), sqlx::Error> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut t = self.begin().await?;
+        sqlx::query(
+            "UPDATE outbox_tx_refs SET checks = checks + 1, dropped_ms = ?1 WHERE job_id = ?2 AND tx_id = ?3 AND network = ?4 AND chain = ?5 AND confirmed = 0",
+        )
+        .bind(now_ms)
+        .bind(&tx_ref.job_id)
+        .bind(&tx_ref.tx.tx_id)
+        .bind(&tx_ref.tx.network)
+        .bind(&tx_ref.tx.chain)
+        .execute(&mut *t)
+        .await?;
+        sqlx::query(
+            "UPDATE outbox_jobs SET status='queued', last_error=?1, failure_category=?2, updated_ms=?3, next_attempt_ms=?3 WHERE id=?4 AND status='done'",
+        )
+        .bind(reason)
+        .bind(FailureCategory::Dropped.as_str())
+        .bind(now_ms)
+        .bind(&tx_ref.job_id)
+        .execute(&mut *t)
+        .await?;
+        t.commit().await?;
+        Ok(())
+    }
+}
+
+/// Build a `ChainTxRef` from an `outbox_tx_refs` row; `timestamp` is stored in seconds
+pub(crate) fn tx_ref_from_columns(
+    network: String,
+    chain: String,
+    tx_id: String,
+    confirmed: i32,
+    timestamp: Option<i64>,
+) -> ChainTxRef {
+    ChainTxRef {
+        network,
+        chain,
+        tx_id,
+        confirmed: confirmed != 0,
+        // Convert seconds to milliseconds and use the non-deprecated API
+        timestamp: timestamp.and_then(|ts| Utc.timestamp_millis_opt(ts * 1000).single()),
+    }
+}
+
+/// Build a `SignedAnchorTx` from an `outbox_anchor_intents` row
+pub(crate) fn intent_from_columns(
+    network: String,
+    chain: String,
+    tx_id: String,
+    raw_tx: String,
+    created_ms: i64,
+) -> SignedAnchorTx {
+    SignedAnchorTx {
+        tx: ChainTxRef {
+            network,
+            chain,
+            tx_id,
+            confirmed: false,
+            timestamp: Utc.timestamp_millis_opt(created_ms).single(),
+        },
+        raw_tx,
+    }
+}
+
+/// When a temporarily failed job should be retried: exponential backoff from
+/// 5s, capped at 5m, plus up to 1s of jitter
+pub(crate) fn next_attempt_ms(now_ms: i64, attempts: i64) -> i64 {
+    let base: i64 = 5000; // 5s
+    let cap: i64 = 300000; // 5m
+    let exp: u32 = attempts.clamp(0, 20) as u32;
+    let backoff = (base.saturating_mul(2i64.pow(exp))).min(cap);
+    let jitter = rand::rng().random_range(0..1000);
+    now_ms + backoff + jitter
+}
+
+/// How long a worker may hold a claimed job before the reaper requeues it
+pub const DEFAULT_LEASE: Duration = Duration::from_secs(300);
+
+/// Lease owner name for worker `index` of this process
+pub fn worker_id(index: usize) -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "keeper".to_string());
+    format!("{}-{}-{}", host, std::process::id(), index)
 }
 
-async fn update_tx_ref_confirmation(
-    pool: &Pool<Sqlite>,
-    tx_ref: &ChainTxRef,
+/// Relative share of claims each tenant gets while several have jobs queued
+/// at the same priority. Unlisted tenants, and jobs without one, weigh 1.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TenantWeights(BTreeMap<String, u32>);
+
+impl TenantWeights {
+    pub fn new(weights: BTreeMap<String, u32>) -> Self {
+        Self(weights)
+    }
+
+    pub fn weight(&self, tenant: Option<&str>) -> u32 {
+        tenant
+            .and_then(|t| self.0.get(t))
+            .copied()
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    /// Virtual time a claim charges the tenant: a tenant weighing 3 is
+    /// charged a third, so it is picked three times as often
+    pub(crate) fn stride(&self, tenant: Option<&str>) -> f64 {
+        1.0 / self.weight(tenant) as f64
+    }
+}
+
+/// A failure update matched no row because the lease was reaped and the job
+/// handed to another worker; that worker's outcome wins
+pub(crate) fn lease_lost(id: &str, worker_id: &str, rows_affected: u64) -> bool {
+    if rows_affected == 0 {
+        tracing::warn!(job_id = %id, worker = %worker_id, "Lease lost before job finished");
+        return true;
+    }
+    false
+}
+
+/// Who made an attempt and against which endpoint
+pub(crate) struct AttemptSource<'a> {
+    pub worker_id: &'a str,
+    pub provider: Option<&'a str>,
+    pub endpoint: Option<&'a str>,
+    /// When the job was claimed, if this worker claimed it
+    pub started_ms: Option<i64>,
+}
+
+/// Append the outcome of the job's current attempt to `outbox_job_attempts`
+async fn record_attempt(
+    conn: &mut SqliteConnection,
+    id: &str,
+    source: &AttemptSource<'_>,
+    outcome: AttemptOutcome,
+    error: Option<&str>,
 ) -> Result<(), sqlx::Error> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
     sqlx::query(
-        "UPDATE outbox_tx_refs SET confirmed = ?1 WHERE tx_id = ?2 AND network = ?3 AND chain = ?4",
+        "INSERT INTO outbox_job_attempts (job_id, attempt, worker, outcome, category, error, started_ms, finished_ms, latency_ms, provider, endpoint) SELECT id, attempts, ?1, ?2, ?3, ?4, ?5, ?6, ?7, COALESCE(?8, provider), ?9 FROM outbox_jobs WHERE id=?10",
     )
-    .bind(if tx_ref.confirmed { 1 } else { 0 })
-    .bind(&tx_ref.tx_id)
-    .bind(&tx_ref.network)
-    .bind(&tx_ref.chain)
-    .execute(pool)
+    .bind(source.worker_id)
+    .bind(outcome.as_str())
+    .bind(error.map(|e| FailureCategory::classify(e).as_str()))
+    .bind(error)
+    .bind(source.started_ms)
+    .bind(now_ms)
+    .bind(source.started_ms.map(|started| now_ms - started))
+    .bind(source.provider)
+    .bind(source.endpoint)
+    .bind(id)
+    .execute(conn)
     .await?;
-
     Ok(())
 }
 
 pub struct SqliteJobProvider {
     pool: Pool<Sqlite>,
+    worker_id: String,
+    lease_ms: i64,
+    provider: Option<String>,
+    endpoint: Option<String>,
+    tenant_weights: TenantWeights,
+    /// Job this worker last claimed and when
+    claimed: Option<(String, i64)>,
 }
 
 impl SqliteJobProvider {
     pub fn new(pool: Pool<Sqlite>) -> Self {
-        Self { pool }
+        Self::with_lease(pool, worker_id(0), DEFAULT_LEASE)
     }
-}
 
-#[async_trait]
-impl JobProvider for SqliteJobProvider {
-    async fn fetch_next(&mut self) -> Result<Option<EvidenceJob>, JobError> {
-        let mut tx = self.pool.begin().await?;
+    /// Provider for one of several workers sharing the outbox
+    pub fn with_lease(pool: Pool<Sqlite>, worker_id: impl Into<String>, lease: Duration) -> Self {
+        Self {
+            pool,
+            worker_id: worker_id.into(),
+            lease_ms: lease.as_millis() as i64,
+            provider: None,
+            endpoint: None,
+            tenant_weights: TenantWeights::default(),
+            claimed: None,
+        }
+    }
+
+    /// Only claim jobs routed to `provider` or not routed at all
+    pub fn with_provider(mut self, provider: impl Into<String>) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    /// Endpoint recorded with each attempt, e.g. the RPC URL
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Share claims between tenants by `weights` instead of equally
+    pub fn with_tenant_weights(mut self, weights: TenantWeights) -> Self {
+        self.tenant_weights = weights;
+        self
+    }
+
+    pub fn worker_id(&self) -> &str {
+        &self.worker_id
+    }
+
+    /// Provider this worker claims jobs for, if it is routed
+    pub fn route(&self) -> Option<&str> {
+        self.provider.as_deref()
+    }
+
+    /// Claim job `id` if it is queued, whether or not it is due yet. A held
+    /// job, or one routed to another provider, is only claimed with `force`.
+    /// The claim is not charged to the tenant's share.
+    pub async fn claim(&mut self, id: &str, force: bool) -> Result<Option<EvidenceJob>, JobError> {
         let now_ms = chrono::Utc::now().timestamp_millis();
-        if let Some(row) = sqlx::query(
-            "SELECT id, payload_sha256, created_ms FROM outbox_jobs WHERE status='queued' AND next_attempt_ms <= ?1 ORDER BY created_ms ASC LIMIT 1",
+        let row = sqlx::query(
+            r#"
+            UPDATE outbox_jobs
+            SET status='in_progress', updated_ms=?1, attempts=attempts+1, lease_owner=?2, lease_until_ms=?3
+            WHERE id=?4 AND status='queued'
+                AND (?5 OR (next_attempt_ms <> ?6 AND (?7 IS NULL OR provider IS NULL OR provider = ?7)))
+            RETURNING id, payload_sha256, created_ms, payload_mime, metadata
+            "#,
         )
         .bind(now_ms)
-        .fetch_optional(&mut *tx)
-        .await?
-        {
-            let id: String = row.get(0);
-            sqlx::query(
-                "UPDATE outbox_jobs SET status='in_progress', updated_ms=?1, attempts=attempts+1 WHERE id=?2",
-            )
-            .bind(now_ms)
-            .bind(&id)
-            .execute(&mut *tx)
-            .await?;
-            tx.commit().await?;
-            let payload_sha256: String = row.get(1);
-            let created_ms: i64 = row.get(2);
-            return Ok(Some(EvidenceJob {
-                id,
-                payload_sha256,
-                created_ms,
-            }));
+        .bind(&self.worker_id)
+        .bind(now_ms + self.lease_ms)
+        .bind(id)
+        .bind(force)
+        .bind(HELD_NEXT_ATTEMPT_MS)
+        .bind(&self.provider)
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let job = EvidenceJob {
+            id: row.get(0),
+            payload_sha256: row.get(1),
+            created_ms: row.get(2),
+            payload_mime: row.get("payload_mime"),
+            metadata: row.get("metadata"),
+        };
+        self.claimed = Some((job.id.clone(), now_ms));
+        Ok(Some(job))
+    }
+
+    fn attempt_source(&self, id: &str) -> AttemptSource<'_> {
+        AttemptSource {
+            worker_id: &self.worker_id,
+            provider: self.provider.as_deref(),
+            endpoint: self.endpoint.as_deref(),
+            started_ms: self
+                .claimed
+                .as_ref()
+                .filter(|(claimed_id, _)| claimed_id == id)
+                .map(|(_, started_ms)| *started_ms),
         }
-        tx.commit().await?;
-        Ok(None)
     }
 
-    async fn mark_done(&mut self, id: &str) -> Result<(), JobError> {
+    async fn finish(
+        &self,
+        id: &str,
+        outcome: AttemptOutcome,
+        reason: &str,
+        next_attempt_ms: i64,
+    ) -> Result<(), JobError> {
+        let status = if outcome == AttemptOutcome::Failed {
+            "failed"
+        } else {
+            "queued"
+        };
+        let category =
+            (outcome == AttemptOutcome::Failed).then(|| FailureCategory::classify(reason).as_str());
         let now_ms = chrono::Utc::now().timestamp_millis();
-        sqlx::query("UPDATE outbox_jobs SET status='done', updated_ms=?1 WHERE id=?2")
-            .bind(now_ms)
-            .bind(id)
-            .execute(&self.pool)
-            .await?;
+        let mut t = self.pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE outbox_jobs SET status=?1, last_error=?2, failure_category=?3, updated_ms=?4, next_attempt_ms=?5, lease_owner=NULL, lease_until_ms=NULL WHERE id=?6 AND (lease_owner IS NULL OR lease_owner=?7)",
+        )
+        .bind(status)
+        .bind(reason)
+        .bind(category)
+        .bind(now_ms)
+        .bind(next_attempt_ms)
+        .bind(id)
+        .bind(&self.worker_id)
+        .execute(&mut *t)
+        .await?;
+        if lease_lost(id, &self.worker_id, result.rows_affected()) {
+            return Ok(());
+        }
+        record_attempt(&mut t, id, &self.attempt_source(id), outcome, Some(reason)).await?;
+        t.commit().await?;
         Ok(())
     }
+}
 
-    async fn mark_failed(&mut self, id: &str, reason: &str) -> Result<(), JobError> {
+#[async_trait]
+impl JobProvider for SqliteJobProvider {
+    async fn fetch_next(&mut self) -> Result<Option<EvidenceJob>, JobError> {
         let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut t = self.pool.begin().await?;
+        // The UPDATE takes the write lock up front, so two workers cannot claim the same job.
+        // Highest priority first; within it, the tenant furthest behind its
+        // share (lowest pass, no lower than the virtual time) and its oldest job.
+        let row = sqlx::query(
+            r#"
+            UPDATE outbox_jobs
+            SET status='in_progress', updated_ms=?1, attempts=attempts+1, lease_owner=?2, lease_until_ms=?3
+            WHERE id = (
+                SELECT j.id FROM outbox_jobs j
+                LEFT JOIN outbox_tenant_shares s ON s.tenant = COALESCE(j.tenant, '')
+                WHERE j.status='queued' AND j.next_attempt_ms <= ?1
+                    AND (?4 IS NULL OR j.provider IS NULL OR j.provider = ?4)
+                ORDER BY j.priority DESC,
+                    MAX(COALESCE(s.pass, 0), (SELECT COALESCE(MAX(last_start), 0) FROM outbox_tenant_shares)) ASC,
+                    j.created_ms ASC
+                LIMIT 1
+            )
+            RETURNING id, payload_sha256, created_ms, tenant, payload_mime, metadata
+            "#,
+        )
+        .bind(now_ms)
+        .bind(&self.worker_id)
+        .bind(now_ms + self.lease_ms)
+        .bind(&self.provider)
+        .fetch_optional(&mut *t)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let tenant: Option<String> = row.get(3);
         sqlx::query(
-            "UPDATE outbox_jobs SET status='failed', last_error=?1, updated_ms=?2, next_attempt_ms=?2 WHERE id=?3",
+            r#"
+            INSERT INTO outbox_tenant_shares (tenant, last_start, pass)
+            SELECT ?1, v, v + ?2 FROM (SELECT COALESCE(MAX(last_start), 0) AS v FROM outbox_tenant_shares) WHERE true
+            ON CONFLICT(tenant) DO UPDATE SET
+                last_start = MAX(pass, excluded.last_start),
+                pass = MAX(pass, excluded.last_start) + ?2
+            "#,
+        )
+        .bind(tenant.as_deref().unwrap_or(""))
+        .bind(self.tenant_weights.stride(tenant.as_deref()))
+        .execute(&mut *t)
+        .await?;
+        t.commit().await?;
+
+        let job = EvidenceJob {
+            id: row.get(0),
+            payload_sha256: row.get(1),
+            created_ms: row.get(2),
+            payload_mime: row.get("payload_mime"),
+            metadata: row.get("metadata"),
+        };
+        self.claimed = Some((job.id.clone(), now_ms));
+        Ok(Some(job))
+    }
+
+    async fn mark_done(&mut self, id: &str) -> Result<(), JobError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut t = self.pool.begin().await?;
+        sqlx::query(
+            "UPDATE outbox_jobs SET status='done', failure_category=NULL, updated_ms=?1, lease_owner=NULL, lease_until_ms=NULL WHERE id=?2",
         )
-        .bind(reason)
         .bind(now_ms)
         .bind(id)
-        .execute(&self.pool)
+        .execute(&mut *t)
+        .await?;
+        record_attempt(
+            &mut t,
+            id,
+            &self.attempt_source(id),
+            AttemptOutcome::Done,
+            None,
+        )
         .await?;
+        t.commit().await?;
         Ok(())
     }
+
+    async fn mark_failed(&mut self, id: &str, reason: &str) -> Result<(), JobError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        self.finish(id, AttemptOutcome::Failed, reason, now_ms)
+            .await
+    }
 }
 
 #[async_trait]
 impl JobProviderExt for SqliteJobProvider {
     async fn mark_tx_and_done(&mut self, id: &str, tx: &ChainTxRef) -> Result<(), JobError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
         let mut t = self.pool.begin().await?;
+        // Replacing a dropped ref also resets its check schedule
         sqlx::query(
-            "INSERT OR REPLACE INTO outbox_tx_refs (job_id, network, chain, tx_id, confirmed, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT OR REPLACE INTO outbox_tx_refs (job_id, network, chain, tx_id, confirmed, timestamp, created_ms) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         )
         .bind(id)
         .bind(&tx.network)
@@ -291,14 +1226,29 @@ impl JobProviderExt for SqliteJobProvider {
         .bind(&tx.tx_id)
         .bind(if tx.confirmed { 1 } else { 0 })
         .bind(tx.timestamp.map(|dt| dt.timestamp()))
+        .bind(now_ms)
         .execute(&mut *t)
         .await?;
-        let now_ms = chrono::Utc::now().timestamp_millis();
-        sqlx::query("UPDATE outbox_jobs SET status='done', updated_ms=?1 WHERE id=?2")
-            .bind(now_ms)
+        sqlx::query("DELETE FROM outbox_anchor_intents WHERE job_id=?1")
             .bind(id)
             .execute(&mut *t)
             .await?;
+        // Not fenced by the lease: the evidence is anchored even if the lease ran out
+        sqlx::query(
+            "UPDATE outbox_jobs SET status='done', failure_category=NULL, updated_ms=?1, lease_owner=NULL, lease_until_ms=NULL WHERE id=?2",
+        )
+        .bind(now_ms)
+        .bind(id)
+        .execute(&mut *t)
+        .await?;
+        record_attempt(
+            &mut t,
+            id,
+            &self.attempt_source(id),
+            AttemptOutcome::Done,
+            None,
+        )
+        .await?;
         t.commit().await?;
         Ok(())
     }
@@ -309,38 +1259,110 @@ impl JobProviderExt for SqliteJobProvider {
         reason: &str,
         temporary: bool,
     ) -> Result<(), JobError> {
+        if !temporary {
+            return self.mark_failed(id, reason).await;
+        }
+
         let now_ms = chrono::Utc::now().timestamp_millis();
-        if temporary {
-            let rec = sqlx::query("SELECT attempts FROM outbox_jobs WHERE id=?1")
-                .bind(id)
-                .fetch_one(&self.pool)
-                .await?;
-            let attempts: i64 = rec.get(0);
-            let base: i64 = 5000; // 5s
-            let cap: i64 = 300000; // 5m
-            let exp: u32 = attempts.clamp(0, 20) as u32;
-            let backoff = (base.saturating_mul(2i64.pow(exp))).min(cap);
-            let jitter = rand::rng().random_range(0..1000);
-            let next = now_ms + backoff + jitter;
-            sqlx::query(
-                "UPDATE outbox_jobs SET status='queued', last_error=?1, updated_ms=?2, next_attempt_ms=?3 WHERE id=?4",
-            )
-            .bind(reason)
-            .bind(now_ms)
-            .bind(next)
+        let rec = sqlx::query("SELECT attempts FROM outbox_jobs WHERE id=?1")
             .bind(id)
-            .execute(&self.pool)
+            .fetch_one(&self.pool)
             .await?;
-            return Ok(());
-        }
-        sqlx::query(
-            "UPDATE outbox_jobs SET status='failed', last_error=?1, updated_ms=?2, next_attempt_ms=?2 WHERE id=?3",
+        let attempts: i64 = rec.get(0);
+        self.finish(
+            id,
+            AttemptOutcome::Retry,
+            reason,
+            next_attempt_ms(now_ms, attempts),
         )
-        .bind(reason)
+        .await
+    }
+
+    async fn record_intent(&mut self, id: &str, signed: &SignedAnchorTx) -> Result<(), JobError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO outbox_anchor_intents (job_id, network, chain, tx_id, raw_tx, created_ms) SELECT id, ?1, ?2, ?3, ?4, ?5 FROM outbox_jobs WHERE id=?6 AND lease_owner=?7",
+        )
+        .bind(&signed.tx.network)
+        .bind(&signed.tx.chain)
+        .bind(&signed.tx.tx_id)
+        .bind(&signed.raw_tx)
         .bind(now_ms)
         .bind(id)
+        .bind(&self.worker_id)
         .execute(&self.pool)
         .await?;
+        if lease_lost(id, &self.worker_id, result.rows_affected()) {
+            return Err(JobError::Temporary(
+                "lease lost before broadcast".to_string(),
+            ));
+        }
         Ok(())
     }
+
+    async fn pending_intent(&mut self, id: &str) -> Result<Option<SignedAnchorTx>, JobError> {
+        let row: Option<(String, String, String, String, i64)> = sqlx::query_as(
+            "SELECT network, chain, tx_id, raw_tx, created_ms FROM outbox_anchor_intents WHERE job_id=?1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|(network, chain, tx_id, raw_tx, created_ms)| {
+            intent_from_columns(network, chain, tx_id, raw_tx, created_ms)
+        }))
+    }
+
+    fn lease_renewer(&self) -> Option<Box<dyn LeaseRenewer>> {
+        Some(Box::new(WorkerLease {
+            pool: self.pool.clone(),
+            worker_id: self.worker_id.clone(),
+            lease: Duration::from_millis(self.lease_ms as u64),
+        }))
+    }
+}
+
+#[async_trait]
+impl LeaseRenewer for WorkerLease<Pool<Sqlite>> {
+    fn lease(&self) -> Duration {
+        self.lease
+    }
+
+    async fn renew_lease(&self, id: &str) -> Result<bool, sqlx::Error> {
+        let lease_until_ms = chrono::Utc::now().timestamp_millis() + self.lease.as_millis() as i64;
+        let result = sqlx::query(
+            "UPDATE outbox_jobs SET lease_until_ms=?1 WHERE id=?2 AND lease_owner=?3 AND status='in_progress'",
+        )
+        .bind(lease_until_ms)
+        .bind(id)
+        .bind(&self.worker_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[async_trait]
+impl LeaseReaper for Pool<Sqlite> {
+    async fn reap_expired_leases(&self) -> Result<u64, sqlx::Error> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut t = self.begin().await?;
+        sqlx::query(
+            "INSERT INTO outbox_job_attempts (job_id, attempt, worker, outcome, category, error, started_ms, finished_ms, latency_ms, provider) SELECT id, attempts, lease_owner, ?1, ?2, ?3, updated_ms, ?4, ?4 - updated_ms, provider FROM outbox_jobs WHERE status='in_progress' AND lease_until_ms < ?4",
+        )
+        .bind(AttemptOutcome::LeaseExpired.as_str())
+        .bind(FailureCategory::LeaseExpired.as_str())
+        .bind(LEASE_EXPIRED_ERROR)
+        .bind(now_ms)
+        .execute(&mut *t)
+        .await?;
+        let result = sqlx::query(
+            "UPDATE outbox_jobs SET status='queued', last_error=?1, updated_ms=?2, next_attempt_ms=?2, lease_owner=NULL, lease_until_ms=NULL WHERE status='in_progress' AND lease_until_ms < ?2",
+        )
+        .bind(LEASE_EXPIRED_ERROR)
+        .bind(now_ms)
+        .execute(&mut *t)
+        .await?;
+        t.commit().await?;
+        Ok(result.rows_affected())
+    }
 }
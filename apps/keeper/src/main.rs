@@ -1,152 +1,518 @@
-use anchor_etherlink::{EtherlinkProvider, EtherlinkProviderStub};
-use axum::{routing::get, Router};
-use phoenix_evidence::anchor::AnchorProvider;
-use phoenix_keeper::{ensure_schema, run_confirmation_loop, run_job_loop, SqliteJobProvider};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use phoenix_keeper::config::{
+    check_signer_key, screen_address, ConfigError, KeeperConfig, ProviderConfig,
+};
+use phoenix_keeper::maintenance;
+use phoenix_keeper::ops::{ops_router, OpsState};
+use phoenix_keeper::postgres::{run_job_listener, PostgresJobProvider};
+use phoenix_keeper::providers::{build_anchor_provider, SharedAnchor};
+use phoenix_keeper::{
+    ensure_schema, run_confirmation_loop_with_control, run_job_loop_with_control, run_reaper_loop,
+    worker_id, JobProvider, JobProviderExt, LeaseReaper, LoopControl, SqliteJobProvider,
+    StatusStore, TxRefStore,
+};
+use sqlx::postgres::PgPoolOptions;
 use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::signal;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-
-/// Creates the appropriate Etherlink provider based on environment configuration
-fn create_etherlink_provider() -> Box<dyn AnchorProvider + Send + Sync> {
-    let use_stub = match std::env::var("KEEPER_USE_STUB") {
-        Ok(val) => {
-            match val.trim().to_lowercase().as_str() {
-                "true" | "1" | "yes" | "on" => true,
-                "false" | "0" | "no" | "off" => false,
-                other => {
-                    // Emit warning for unrecognized values
-                    tracing::warn!("Invalid KEEPER_USE_STUB value '{}'. Expected true/false/1/0/yes/no/on/off. Using real provider for safety.", other);
-                    false // Default to false (real provider) for unrecognized values
-                }
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
+
+/// Load the configuration, listing every problem before exiting
+fn load_config() -> KeeperConfig {
+    match KeeperConfig::load() {
+        Ok(config) => config,
+        Err(ConfigError::Invalid(errors)) => {
+            for error in &errors {
+                tracing::error!("config: {}", error);
             }
+            tracing::error!(count = errors.len(), "Exiting due to invalid configuration");
+            std::process::exit(1);
         }
-        Err(_) => false, // Default to false (real provider) if env var is missing or unparsable
+        Err(e) => {
+            tracing::error!(error = %e, "Exiting due to unreadable configuration");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Screen the Etherlink signer against the address policy, if both are configured.
+/// The key itself was checked when the configuration was loaded.
+fn screen_signer(config: &KeeperConfig) {
+    let (private_key, expected) = match &config.provider_config {
+        ProviderConfig::Etherlink {
+            private_key,
+            address,
+            ..
+        } => (private_key.as_deref(), address.as_deref()),
+        ProviderConfig::Multi {
+            etherlink: Some(etherlink),
+            ..
+        } => (
+            etherlink.private_key.as_deref(),
+            etherlink.address.as_deref(),
+        ),
+        _ => return,
+    };
+    let Ok(Some(address)) = check_signer_key(private_key, expected) else {
+        return;
     };
+    tracing::info!(signer = %address, "Etherlink signer key verified");
 
-    if use_stub {
-        tracing::info!("Using EtherlinkProviderStub for development/testing");
-        Box::new(EtherlinkProviderStub)
-    } else {
-        tracing::info!("Using real EtherlinkProvider for production");
-
-        let endpoint = std::env::var("ETHERLINK_ENDPOINT")
-            .unwrap_or_else(|_| "https://node.etherlink.com".to_string());
-        let network = std::env::var("ETHERLINK_NETWORK").unwrap_or_else(|_| "mainnet".to_string());
-        let private_key = std::env::var("ETHERLINK_PRIVATE_KEY").ok();
-
-        match EtherlinkProvider::new(endpoint.clone(), network.clone(), private_key) {
-            Ok(provider) => {
-                tracing::info!(
-                    endpoint = %endpoint,
-                    network = %network,
-                    "Successfully created EtherlinkProvider"
-                );
-                Box::new(provider)
-            }
-            Err(e) => {
-                tracing::error!(
-                    endpoint = %endpoint,
-                    network = %network,
-                    error = %e,
-                    "Failed to create EtherlinkProvider"
-                );
-                std::process::exit(1);
+    let Some(policy_path) = &config.address_policy_path else {
+        return;
+    };
+    match screen_address(policy_path, "etherlink", &address) {
+        Ok(decision) if decision.permitted => {
+            tracing::info!(signer = %address, reason = %decision.reason, "Signer passed address screening");
+        }
+        Ok(decision) => {
+            tracing::error!(signer = %address, reason = %decision.reason, "Signer rejected by address policy");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            tracing::error!(path = %policy_path.display(), error = %e, "Failed to load address policy");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Serve the operational HTTP API
+async fn serve_ops(port: u16, state: OpsState) {
+    let addr = format!("0.0.0.0:{}", port);
+    tracing::info!(%addr, "keeper http starting");
+
+    let listener = match tokio::net::TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(_e) => {
+            tracing::error!(address=%addr, error=%_e, "Failed to bind HTTP server");
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(_e) = axum::serve(listener, ops_router(state).into_make_service()).await {
+        tracing::error!(error=%_e, "HTTP server runtime error");
+        std::process::exit(1);
+    }
+}
+
+/// Run the job workers, confirmation loop, lease reaper and HTTP API until any
+/// of them exits or `shutdown` is cancelled. On shutdown the job and
+/// confirmation loops are drained before returning. With `listen`, the job
+/// workers are also woken by PostgreSQL notifications of queued jobs.
+async fn run_loops<J, S>(
+    workers: Vec<J>,
+    store: S,
+    anchor: SharedAnchor,
+    config: &KeeperConfig,
+    listen: Option<Pool<Postgres>>,
+    shutdown: CancellationToken,
+) where
+    J: JobProvider + JobProviderExt + Send + 'static,
+    S: TxRefStore + LeaseReaper + StatusStore + Clone + Send + Sync + 'static,
+{
+    // Loops that hold in-flight work and stop on shutdown
+    let mut loops = JoinSet::new();
+    // Loops that are simply dropped on shutdown
+    let mut services = JoinSet::new();
+    let poll_interval = config.job_poll_interval;
+    let lease = config.lease_duration;
+    let ops = OpsState {
+        store: Arc::new(store.clone()),
+        anchor: anchor.clone(),
+        jobs: LoopControl::new(shutdown.clone(), config.shutdown_timeout),
+        confirmations: LoopControl::new(shutdown.clone(), config.shutdown_timeout),
+    };
+
+    // Start job workers; they share one signer
+    tracing::info!(
+        workers = workers.len(),
+        lease_ms = lease.as_millis() as u64,
+        "Starting job workers"
+    );
+    for mut jp in workers {
+        let anchor = anchor.clone();
+        let control = ops.jobs.clone();
+        loops.spawn(async move {
+            run_job_loop_with_control(&mut jp, anchor.as_ref(), poll_interval, &control).await;
+            "Job loop"
+        });
+    }
+
+    // Start confirmation polling loop
+    let confirm_schedule = config.confirmation_schedule();
+    let confirm_store = store.clone();
+    let control = ops.confirmations.clone();
+    loops.spawn(async move {
+        run_confirmation_loop_with_control(
+            &confirm_store,
+            anchor.as_ref(),
+            confirm_schedule,
+            &control,
+        )
+        .await;
+        "Confirmation loop"
+    });
+
+    // Requeue jobs from crashed workers, checking several times per lease
+    let reap_interval = (lease / 4).max(Duration::from_secs(1));
+    let reaper_shutdown = shutdown.clone();
+    services.spawn(async move {
+        run_reaper_loop(&store, reap_interval, &reaper_shutdown).await;
+        "Lease reaper"
+    });
+
+    if let Some(pool) = listen {
+        let control = ops.jobs.clone();
+        services.spawn(async move {
+            run_job_listener(&pool, &control).await;
+            "Job listener"
+        });
+    }
+
+    let port = config.http_port;
+    services.spawn(async move {
+        serve_ops(port, ops).await;
+        "HTTP server"
+    });
+
+    // Wait for shutdown or for any loop to complete (they shouldn't)
+    // Services such as the reaper also return on shutdown, so check it first
+    let exited = tokio::select! {
+        biased;
+        _ = shutdown.cancelled() => None,
+        Some(exited) = loops.join_next() => Some(exited),
+        Some(exited) = services.join_next() => Some(exited),
+    };
+    match exited {
+        Some(Ok(name)) => tracing::warn!("{} exited unexpectedly", name),
+        Some(Err(e)) => tracing::error!(error = %e, "Keeper loop panicked"),
+        None => {
+            tracing::info!(
+                loops = loops.len(),
+                "Draining in-flight jobs and confirmations"
+            );
+            while let Some(exited) = loops.join_next().await {
+                if let Err(e) = exited {
+                    tracing::error!(error = %e, "Keeper loop panicked while draining");
+                }
             }
+            tracing::info!("Keeper loops drained");
         }
     }
 }
 
+/// Resolve on Ctrl+C or, on Unix, SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(unix)]
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    #[cfg(not(unix))]
+    ctrl_c.await;
+}
+
+#[derive(Parser)]
+#[command(about = "Anchors queued evidence and maintains the outbox")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the keeper (the default)
+    Run,
+    /// Apply pending schema migrations and exit
+    Migrate,
+    /// Put every job in a status back in the queue
+    Requeue {
+        /// Status of the jobs to requeue: failed or discarded
+        #[arg(long)]
+        status: String,
+        /// Name recorded in the admin audit log
+        #[arg(long, default_value = "keeper-cli")]
+        actor: String,
+    },
+    /// Check transactions recorded since a time against the chain again
+    Reconfirm {
+        /// RFC 3339 time, e.g. 2026-10-19T00:00:00Z
+        #[arg(long)]
+        since: DateTime<Utc>,
+    },
+    /// Print job counts and anchoring progress as JSON
+    Stats,
+    /// Anchor one queued job now, whether or not it is due
+    AnchorOnce {
+        id: String,
+        /// Also anchor a held job, or one routed to another provider
+        #[arg(long)]
+        force: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() {
+    let command = Cli::parse().command.unwrap_or(Command::Run);
+    // One-shot commands keep stdout for their output
+    let logs = tracing_subscriber::fmt::layer();
+    let logs = if matches!(command, Command::Run) {
+        logs.boxed()
+    } else {
+        logs.with_writer(std::io::stderr).boxed()
+    };
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::new(
             std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
         ))
-        .with(tracing_subscriber::fmt::layer())
+        .with(logs)
         .init();
 
-    // HTTP health endpoint
-    let app = Router::new().route("/health", get(|| async { "OK" }));
-    let http = tokio::spawn(async move {
-        let addr = "0.0.0.0:8081";
-        tracing::info!(%addr, "keeper http starting");
-
-        let listener = match tokio::net::TcpListener::bind(addr).await {
-            Ok(listener) => listener,
-            Err(_e) => {
-                tracing::error!(address=%addr, error=%_e, "Failed to bind HTTP server");
+    match command {
+        Command::Run => run().await,
+        command => {
+            let config = load_config();
+            if let Err(e) = run_maintenance(command, &config).await {
+                tracing::error!(error = %e, "Maintenance command failed");
                 std::process::exit(1);
             }
-        };
+        }
+    }
+}
+
+/// Run a one-shot subcommand against the configured outbox
+async fn run_maintenance(
+    command: Command,
+    config: &KeeperConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if config.is_postgres() {
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(&config.database_url)
+            .await?;
+        phoenix_keeper::postgres::ensure_schema(&pool).await?;
+        match command {
+            Command::Run => unreachable!("run is not a maintenance command"),
+            Command::Migrate => println!("schema is up to date"),
+            Command::Requeue { status, actor } => {
+                let ids = maintenance::postgres::requeue_jobs(&pool, &status, &actor).await?;
+                print_requeued(&ids, &status);
+            }
+            Command::Reconfirm { since } => {
+                let anchor = build_anchor_provider(&config.provider_config)?;
+                let report = maintenance::postgres::reconfirm(
+                    &pool,
+                    anchor.as_ref(),
+                    since.timestamp_millis(),
+                )
+                .await?;
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+            Command::Stats => print_stats(&pool).await?,
+            Command::AnchorOnce { id, force } => {
+                screen_signer(config);
+                let anchor = build_anchor_provider(&config.provider_config)?;
+                let mut jp = PostgresJobProvider::with_lease(
+                    pool.clone(),
+                    worker_id(0),
+                    config.lease_duration,
+                );
+                if let Some(route) = config.provider_config.route() {
+                    jp = jp.with_provider(route);
+                }
+                if let Some(endpoint) = config.provider_config.endpoint() {
+                    jp = jp.with_endpoint(endpoint);
+                }
+                let tx =
+                    maintenance::postgres::anchor_once(&pool, &mut jp, anchor.as_ref(), &id, force)
+                        .await?;
+                println!("{}", serde_json::to_string_pretty(&tx)?);
+            }
+        }
+        return Ok(());
+    }
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&config.database_url)
+        .await?;
+    ensure_schema(&pool).await?;
+    match command {
+        Command::Run => unreachable!("run is not a maintenance command"),
+        Command::Migrate => println!("schema is up to date"),
+        Command::Requeue { status, actor } => {
+            let ids = maintenance::requeue_jobs(&pool, &status, &actor).await?;
+            print_requeued(&ids, &status);
+        }
+        Command::Reconfirm { since } => {
+            let anchor = build_anchor_provider(&config.provider_config)?;
+            let report =
+                maintenance::reconfirm(&pool, anchor.as_ref(), since.timestamp_millis()).await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        Command::Stats => print_stats(&pool).await?,
+        Command::AnchorOnce { id, force } => {
+            screen_signer(config);
+            let anchor = build_anchor_provider(&config.provider_config)?;
+            let mut jp =
+                SqliteJobProvider::with_lease(pool.clone(), worker_id(0), config.lease_duration);
+            if let Some(route) = config.provider_config.route() {
+                jp = jp.with_provider(route);
+            }
+            if let Some(endpoint) = config.provider_config.endpoint() {
+                jp = jp.with_endpoint(endpoint);
+            }
+            let tx = maintenance::anchor_once(&pool, &mut jp, anchor.as_ref(), &id, force).await?;
+            println!("{}", serde_json::to_string_pretty(&tx)?);
+        }
+    }
+    Ok(())
+}
 
-        if let Err(_e) = axum::serve(listener, app.into_make_service()).await {
-            tracing::error!(error=%_e, "HTTP server runtime error");
+fn print_requeued(ids: &[String], status: &str) {
+    for id in ids {
+        println!("{}", id);
+    }
+    tracing::info!(count = ids.len(), %status, "Requeued jobs");
+}
+
+async fn print_stats<S: StatusStore>(store: &S) -> Result<(), Box<dyn std::error::Error>> {
+    let status = store.outbox_status().await?;
+    println!("{}", serde_json::to_string_pretty(&status)?);
+    Ok(())
+}
+
+/// Run the keeper until a shutdown signal, draining in-flight work
+async fn run() {
+    phoenix_common::metrics::install_recorder();
+
+    let config = load_config();
+    screen_signer(&config);
+    let anchor = match build_anchor_provider(&config.provider_config) {
+        Ok(anchor) => anchor,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to create anchor provider");
             std::process::exit(1);
         }
-    });
+    };
+    tracing::info!(provider = ?config.provider_config.route(), endpoint = ?config.provider_config.endpoint(), "Anchor provider ready");
+
+    let shutdown = CancellationToken::new();
+    let loops_shutdown = shutdown.clone();
+    let shutdown_timeout = config.shutdown_timeout;
 
     // Job runner
-    let runner = tokio::spawn(async move {
-        let poll_interval = std::env::var("KEEPER_POLL_MS")
-            .ok()
-            .and_then(|v| v.parse::<u64>().ok())
-            .map(Duration::from_millis)
-            .unwrap_or_else(|| Duration::from_secs(5));
-
-        let db_url = std::env::var("KEEPER_DB_URL")
-            .unwrap_or_else(|_| "sqlite://blockchain_outbox.sqlite3".to_string());
-        match SqlitePoolOptions::new()
-            .max_connections(5)
-            .connect(&db_url)
-            .await
-        {
-            Ok(pool) => {
-                if let Err(_e) = ensure_schema(&pool).await {
-                    tracing::error!(error=%_e, "schema init failed");
-                    tracing::error!("Exiting due to schema initialization failure");
+    let mut runner = tokio::spawn(async move {
+        let shutdown = loops_shutdown;
+        let route = config.provider_config.route();
+        let endpoint = config.provider_config.endpoint().map(str::to_string);
+        let lease = config.lease_duration;
+        if config.is_postgres() {
+            match PgPoolOptions::new()
+                .max_connections(config.workers as u32 + 4)
+                .connect(&config.database_url)
+                .await
+            {
+                Ok(pool) => {
+                    if let Err(_e) = phoenix_keeper::postgres::ensure_schema(&pool).await {
+                        tracing::error!(error=%_e, "schema init failed");
+                        tracing::error!("Exiting due to schema initialization failure");
+                        std::process::exit(1);
+                    }
+                    tracing::info!("Using PostgreSQL outbox");
+                    let workers = (0..config.workers)
+                        .map(|i| {
+                            let mut jp =
+                                PostgresJobProvider::with_lease(pool.clone(), worker_id(i), lease)
+                                    .with_tenant_weights(config.tenant_weights.clone());
+                            if let Some(route) = route {
+                                jp = jp.with_provider(route);
+                            }
+                            if let Some(endpoint) = &endpoint {
+                                jp = jp.with_endpoint(endpoint.clone());
+                            }
+                            jp
+                        })
+                        .collect();
+                    let listen = Some(pool.clone());
+                    run_loops(workers, pool, anchor, &config, listen, shutdown).await;
+                }
+                Err(e) => {
+                    tracing::error!(error=%e, "db connect failed");
+                    tracing::error!("Exiting due to database connection failure");
                     std::process::exit(1);
                 }
-
-                let mut jp = SqliteJobProvider::new(pool.clone());
-                let anchor = create_etherlink_provider();
-
-                // Start job processing loop
-                let job_anchor = anchor;
-                let job_handle = tokio::spawn(async move {
-                    run_job_loop(&mut jp, job_anchor.as_ref(), poll_interval).await;
-                });
-
-                // Start confirmation polling loop
-                let confirm_interval = Duration::from_secs(30); // Check confirmations every 30s
-                let confirm_anchor = create_etherlink_provider();
-                let confirm_handle = tokio::spawn(async move {
-                    run_confirmation_loop(&pool, confirm_anchor.as_ref(), confirm_interval).await;
-                });
-
-                // Wait for either loop to complete (they shouldn't)
-                tokio::select! {
-                    _ = job_handle => {
-                        tracing::warn!("Job loop exited unexpectedly");
-                    }
-                    _ = confirm_handle => {
-                        tracing::warn!("Confirmation loop exited unexpectedly");
+            }
+        } else {
+            match SqlitePoolOptions::new()
+                .max_connections(config.workers as u32 + 4)
+                .connect(&config.database_url)
+                .await
+            {
+                Ok(pool) => {
+                    if let Err(_e) = ensure_schema(&pool).await {
+                        tracing::error!(error=%_e, "schema init failed");
+                        tracing::error!("Exiting due to schema initialization failure");
+                        std::process::exit(1);
                     }
+                    let workers = (0..config.workers)
+                        .map(|i| {
+                            let mut jp =
+                                SqliteJobProvider::with_lease(pool.clone(), worker_id(i), lease)
+                                    .with_tenant_weights(config.tenant_weights.clone());
+                            if let Some(route) = route {
+                                jp = jp.with_provider(route);
+                            }
+                            if let Some(endpoint) = &endpoint {
+                                jp = jp.with_endpoint(endpoint.clone());
+                            }
+                            jp
+                        })
+                        .collect();
+                    run_loops(workers, pool, anchor, &config, None, shutdown).await;
+                }
+                Err(e) => {
+                    tracing::error!(error=%e, "db connect failed");
+                    tracing::error!("Exiting due to database connection failure");
+                    std::process::exit(1);
                 }
-            }
-            Err(e) => {
-                tracing::error!(error=%e, "db connect failed; keeper idle");
-                tokio::time::sleep(Duration::from_secs(10)).await;
             }
         }
     });
 
-    // Wait for Ctrl+C
     tokio::select! {
-        _ = signal::ctrl_c() => {
-            tracing::info!("shutdown signal received");
+        _ = shutdown_signal() => {
+            tracing::info!(
+                timeout_ms = shutdown_timeout.as_millis() as u64,
+                "shutdown signal received, finishing in-flight work"
+            );
+            shutdown.cancel();
+            // Loops give up on in-flight work after the timeout; allow time to record that
+            let grace = shutdown_timeout + Duration::from_secs(5);
+            if tokio::time::timeout(grace, &mut runner).await.is_err() {
+                tracing::error!("Keeper loops did not stop in time, exiting anyway");
+            }
         }
-        _ = http => {}
-        _ = runner => {}
+        _ = &mut runner => {}
     }
 }
@@ -0,0 +1,325 @@
+//! One-shot maintenance tasks run by the keeper's subcommands instead of raw
+//! SQL against the outbox. The functions here work on the SQLite outbox;
+//! [`postgres`] has the same ones for PostgreSQL.
+
+use crate::{anchor_claimed_job, tx_ref_from_columns, JobError, SqliteJobProvider};
+use phoenix_common::outbox::HELD_NEXT_ATTEMPT_MS;
+use phoenix_evidence::anchor::AnchorProvider;
+use phoenix_evidence::model::ChainTxRef;
+use serde::Serialize;
+use sqlx::{Pool, Row, Sqlite};
+use uuid::Uuid;
+
+/// Statuses `requeue_jobs` accepts. Queued and in-progress jobs are already
+/// live and done jobs are requeued by the confirmation loop if dropped.
+pub const REQUEUE_STATUSES: &[&str] = &["failed", "discarded"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum MaintenanceError {
+    #[error("database error: {0}")]
+    Database(String),
+    #[error("cannot requeue jobs in status '{0}', only failed or discarded ones")]
+    Status(String),
+    #[error("job '{0}' not found")]
+    NotFound(String),
+    #[error("job '{0}' is {1}, not queued")]
+    NotQueued(String, String),
+    #[error("job '{0}' is held; release it first or force the claim")]
+    Held(String),
+    #[error("job '{0}' is routed to provider '{1}'; force the claim to anchor it here")]
+    Routed(String, String),
+    #[error("anchor failed: {0}")]
+    Anchor(String),
+}
+
+impl From<sqlx::Error> for MaintenanceError {
+    fn from(e: sqlx::Error) -> Self {
+        MaintenanceError::Database(e.to_string())
+    }
+}
+
+impl From<JobError> for MaintenanceError {
+    fn from(e: JobError) -> Self {
+        MaintenanceError::Database(e.to_string())
+    }
+}
+
+/// Put every job in `status` back in the queue, due now. Each requeue is
+/// written to `admin_audit_log` under `actor`, as the API's dead-letter
+/// requeue is. Returns the requeued job ids.
+pub async fn requeue_jobs(
+    pool: &Pool<Sqlite>,
+    status: &str,
+    actor: &str,
+) -> Result<Vec<String>, MaintenanceError> {
+    if !REQUEUE_STATUSES.contains(&status) {
+        return Err(MaintenanceError::Status(status.to_string()));
+    }
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let mut t = pool.begin().await?;
+    let ids: Vec<String> = sqlx::query_scalar(
+        "UPDATE outbox_jobs SET status='queued', failure_category=NULL, next_attempt_ms=?1, updated_ms=?1, lease_owner=NULL, lease_until_ms=NULL WHERE status=?2 RETURNING id",
+    )
+    .bind(now_ms)
+    .bind(status)
+    .fetch_all(&mut *t)
+    .await?;
+
+    let details = requeue_details(status);
+    for id in &ids {
+        sqlx::query(
+            "INSERT INTO admin_audit_log (id, action, job_id, actor, details, created_ms) VALUES (?1, 'requeue', ?2, ?3, ?4, ?5)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(id)
+        .bind(actor)
+        .bind(&details)
+        .bind(now_ms)
+        .execute(&mut *t)
+        .await?;
+    }
+    t.commit().await?;
+    Ok(ids)
+}
+
+fn requeue_details(status: &str) -> String {
+    serde_json::json!({ "source": "keeper-cli", "previous_status": status }).to_string()
+}
+
+/// What `reconfirm` found on chain
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReconfirmReport {
+    pub checked: u64,
+    pub confirmed: u64,
+    /// Not confirmed yet; left to the confirmation loop
+    pub pending: u64,
+    /// Recorded as confirmed but no longer confirmed on chain, e.g. after a reorg
+    pub reverted: u64,
+    pub errors: u64,
+}
+
+/// Check every transaction recorded since `since_ms` against the chain again,
+/// confirmed or not, and record what the provider reports. Reverted and still
+/// pending transactions are made due so the confirmation loop takes them over,
+/// dropping and requeueing them once they pass the maximum age.
+pub async fn reconfirm<A: AnchorProvider + ?Sized>(
+    pool: &Pool<Sqlite>,
+    anchor: &A,
+    since_ms: i64,
+) -> Result<ReconfirmReport, MaintenanceError> {
+    let rows = sqlx::query(
+        "SELECT job_id, network, chain, tx_id, confirmed, timestamp FROM outbox_tx_refs WHERE created_ms >= ?1 AND dropped_ms IS NULL ORDER BY created_ms",
+    )
+    .bind(since_ms)
+    .fetch_all(pool)
+    .await?;
+
+    let mut report = ReconfirmReport::default();
+    for row in &rows {
+        let job_id: String = row.get("job_id");
+        let tx: ChainTxRef = tx_ref_from_columns(
+            row.get("network"),
+            row.get("chain"),
+            row.get("tx_id"),
+            row.get("confirmed"),
+            row.get("timestamp"),
+        );
+        let Some(confirmed) = check_again(anchor, &job_id, &tx, &mut report).await else {
+            continue;
+        };
+        sqlx::query(
+            "UPDATE outbox_tx_refs SET confirmed=?1, next_check_ms=CASE WHEN ?1 = 0 THEN 0 ELSE next_check_ms END WHERE job_id=?2 AND tx_id=?3 AND network=?4 AND chain=?5",
+        )
+        .bind(confirmed as i64)
+        .bind(&job_id)
+        .bind(&tx.tx_id)
+        .bind(&tx.network)
+        .bind(&tx.chain)
+        .execute(pool)
+        .await?;
+    }
+    Ok(report)
+}
+
+/// Check `tx` of job `job_id` on chain and count the outcome in `report`.
+/// Returns whether it is confirmed, or `None` if the check failed.
+async fn check_again<A: AnchorProvider + ?Sized>(
+    anchor: &A,
+    job_id: &str,
+    tx: &ChainTxRef,
+    report: &mut ReconfirmReport,
+) -> Option<bool> {
+    report.checked += 1;
+    let checked = match anchor.confirm(tx).await {
+        Ok(checked) => checked,
+        Err(e) => {
+            tracing::warn!(job_id = %job_id, tx_id = %tx.tx_id, error = %e, "Reconfirm check failed");
+            report.errors += 1;
+            return None;
+        }
+    };
+    match (tx.confirmed, checked.confirmed) {
+        (_, true) => report.confirmed += 1,
+        (true, false) => {
+            tracing::warn!(job_id = %job_id, tx_id = %tx.tx_id, "Transaction no longer confirmed");
+            report.reverted += 1;
+        }
+        (false, false) => report.pending += 1,
+    }
+    Some(checked.confirmed)
+}
+
+/// Claim queued job `id`, due or not, and anchor it now. A held job, or one
+/// routed to a provider other than `provider`'s, is refused unless `force`.
+/// The attempt is recorded and a failure backs the job off as in the job loop.
+pub async fn anchor_once<A: AnchorProvider + ?Sized>(
+    pool: &Pool<Sqlite>,
+    provider: &mut SqliteJobProvider,
+    anchor: &A,
+    id: &str,
+    force: bool,
+) -> Result<ChainTxRef, MaintenanceError> {
+    let Some(job) = provider.claim(id, force).await? else {
+        let row: Option<(String, i64, Option<String>)> =
+            sqlx::query_as("SELECT status, next_attempt_ms, provider FROM outbox_jobs WHERE id=?1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await?;
+        return Err(claim_refused(id, row, provider.route()));
+    };
+    anchor_claimed_job(provider, anchor, &job)
+        .await
+        .map_err(|e| MaintenanceError::Anchor(e.to_string()))
+}
+
+/// Why job `id`, with `(status, next_attempt_ms, provider)` from `row`, was
+/// not claimed by a worker routed to `route`
+fn claim_refused(
+    id: &str,
+    row: Option<(String, i64, Option<String>)>,
+    route: Option<&str>,
+) -> MaintenanceError {
+    let id = id.to_string();
+    match row {
+        None => MaintenanceError::NotFound(id),
+        Some((status, _, _)) if status != "queued" => MaintenanceError::NotQueued(id, status),
+        Some((_, HELD_NEXT_ATTEMPT_MS, _)) => MaintenanceError::Held(id),
+        Some((_, _, Some(routed))) if route.is_some_and(|route| route != routed) => {
+            MaintenanceError::Routed(id, routed)
+        }
+        // Claimed by another worker in between
+        Some((status, _, _)) => MaintenanceError::NotQueued(id, status),
+    }
+}
+
+/// The maintenance tasks for the PostgreSQL outbox
+pub mod postgres {
+    use super::{
+        check_again, claim_refused, requeue_details, MaintenanceError, ReconfirmReport,
+        REQUEUE_STATUSES,
+    };
+    use crate::postgres::PostgresJobProvider;
+    use crate::{anchor_claimed_job, tx_ref_from_columns};
+    use phoenix_evidence::anchor::AnchorProvider;
+    use phoenix_evidence::model::ChainTxRef;
+    use sqlx::{Pool, Postgres, Row};
+    use uuid::Uuid;
+
+    /// [`super::requeue_jobs`] for PostgreSQL
+    pub async fn requeue_jobs(
+        pool: &Pool<Postgres>,
+        status: &str,
+        actor: &str,
+    ) -> Result<Vec<String>, MaintenanceError> {
+        if !REQUEUE_STATUSES.contains(&status) {
+            return Err(MaintenanceError::Status(status.to_string()));
+        }
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut t = pool.begin().await?;
+        let ids: Vec<String> = sqlx::query_scalar(
+            "UPDATE outbox_jobs SET status='queued', failure_category=NULL, next_attempt_ms=$1, updated_ms=$1, lease_owner=NULL, lease_until_ms=NULL WHERE status=$2 RETURNING id",
+        )
+        .bind(now_ms)
+        .bind(status)
+        .fetch_all(&mut *t)
+        .await?;
+
+        let details = requeue_details(status);
+        for id in &ids {
+            sqlx::query(
+                "INSERT INTO admin_audit_log (id, action, job_id, actor, details, created_ms) VALUES ($1, 'requeue', $2, $3, $4, $5)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(id)
+            .bind(actor)
+            .bind(&details)
+            .bind(now_ms)
+            .execute(&mut *t)
+            .await?;
+        }
+        t.commit().await?;
+        Ok(ids)
+    }
+
+    /// [`super::reconfirm`] for PostgreSQL
+    pub async fn reconfirm<A: AnchorProvider + ?Sized>(
+        pool: &Pool<Postgres>,
+        anchor: &A,
+        since_ms: i64,
+    ) -> Result<ReconfirmReport, MaintenanceError> {
+        let rows = sqlx::query(
+            "SELECT job_id, network, chain, tx_id, confirmed, timestamp FROM outbox_tx_refs WHERE created_ms >= $1 AND dropped_ms IS NULL ORDER BY created_ms",
+        )
+        .bind(since_ms)
+        .fetch_all(pool)
+        .await?;
+
+        let mut report = ReconfirmReport::default();
+        for row in &rows {
+            let job_id: String = row.get("job_id");
+            let tx: ChainTxRef = tx_ref_from_columns(
+                row.get("network"),
+                row.get("chain"),
+                row.get("tx_id"),
+                row.get("confirmed"),
+                row.get("timestamp"),
+            );
+            let Some(confirmed) = check_again(anchor, &job_id, &tx, &mut report).await else {
+                continue;
+            };
+            sqlx::query(
+                "UPDATE outbox_tx_refs SET confirmed=$1, next_check_ms=CASE WHEN $1 = 0 THEN 0 ELSE next_check_ms END WHERE job_id=$2 AND tx_id=$3 AND network=$4 AND chain=$5",
+            )
+            .bind(confirmed as i32)
+            .bind(&job_id)
+            .bind(&tx.tx_id)
+            .bind(&tx.network)
+            .bind(&tx.chain)
+            .execute(pool)
+            .await?;
+        }
+        Ok(report)
+    }
+
+    /// [`super::anchor_once`] for PostgreSQL
+    pub async fn anchor_once<A: AnchorProvider + ?Sized>(
+        pool: &Pool<Postgres>,
+        provider: &mut PostgresJobProvider,
+        anchor: &A,
+        id: &str,
+        force: bool,
+    ) -> Result<ChainTxRef, MaintenanceError> {
+        let Some(job) = provider.claim(id, force).await? else {
+            let row: Option<(String, i64, Option<String>)> = sqlx::query_as(
+                "SELECT status, next_attempt_ms, provider FROM outbox_jobs WHERE id=$1",
+            )
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+            return Err(claim_refused(id, row, provider.route()));
+        };
+        anchor_claimed_job(provider, anchor, &job)
+            .await
+            .map_err(|e| MaintenanceError::Anchor(e.to_string()))
+    }
+}
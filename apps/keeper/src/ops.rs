@@ -0,0 +1,170 @@
+//! Operational HTTP endpoints: liveness, readiness, status, metrics and loop control.
+
+use crate::providers::SharedAnchor;
+use crate::{LoopControl, StatusStore};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use phoenix_common::metrics::{install_recorder, set_outbox_gauges};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long `/ready` waits for the database and the provider
+const READY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone)]
+pub struct OpsState {
+    pub store: Arc<dyn StatusStore + Send + Sync>,
+    pub anchor: SharedAnchor,
+    pub jobs: LoopControl,
+    pub confirmations: LoopControl,
+}
+
+impl OpsState {
+    fn control(&self, name: &str) -> Option<&LoopControl> {
+        match name {
+            "jobs" => Some(&self.jobs),
+            "confirmations" => Some(&self.confirmations),
+            _ => None,
+        }
+    }
+}
+
+pub fn ops_router(state: OpsState) -> Router {
+    Router::new()
+        .route("/health", get(|| async { "OK" }))
+        .route("/ready", get(ready))
+        .route("/status", get(status))
+        .route("/metrics", get(metrics))
+        .route("/admin/loops/{name}/pause", post(pause_loop))
+        .route("/admin/loops/{name}/resume", post(resume_loop))
+        .route("/admin/loops/{name}/wake", post(wake_loop))
+        .with_state(state)
+}
+
+fn check_result<E: std::fmt::Display>(
+    result: Result<Result<(), E>, tokio::time::error::Elapsed>,
+) -> Result<(), String> {
+    match result {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("timed out after {}s", READY_TIMEOUT.as_secs())),
+    }
+}
+
+/// Ready when the outbox database answers and the anchor provider is reachable
+async fn ready(State(state): State<OpsState>) -> Response {
+    let (database, provider) = tokio::join!(
+        tokio::time::timeout(READY_TIMEOUT, state.store.ping()),
+        tokio::time::timeout(READY_TIMEOUT, state.anchor.health()),
+    );
+    let database = check_result(database);
+    let provider = check_result(provider);
+    let ready = database.is_ok() && provider.is_ok();
+    let describe = |r: Result<(), String>| r.err().unwrap_or_else(|| "ok".to_string());
+
+    let code = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        code,
+        Json(serde_json::json!({
+            "ready": ready,
+            "database": describe(database),
+            "provider": describe(provider),
+        })),
+    )
+        .into_response()
+}
+
+async fn status(State(state): State<OpsState>) -> Response {
+    match state.store.outbox_status().await {
+        Ok(outbox) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "jobs_by_status": outbox.jobs_by_status,
+                "oldest_queued_age_ms": outbox.oldest_queued_age_ms,
+                "unconfirmed_tx_refs": outbox.unconfirmed_tx_refs,
+                "last_anchor": outbox.last_anchor,
+                "paused": {
+                    "jobs": state.jobs.is_paused(),
+                    "confirmations": state.confirmations.is_paused(),
+                },
+            })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+/// Prometheus exposition, with the outbox gauges read fresh from the database
+async fn metrics(State(state): State<OpsState>) -> Response {
+    match state.store.outbox_status().await {
+        Ok(outbox) => set_outbox_gauges(
+            outbox.jobs_by_status.get("queued").copied().unwrap_or(0),
+            outbox.unconfirmed_tx_refs,
+        ),
+        Err(e) => tracing::warn!(error = %e, "Failed to read outbox gauges for metrics"),
+    }
+    install_recorder().render().into_response()
+}
+
+fn unknown_loop(name: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "error": format!("unknown loop '{}' (expected jobs or confirmations)", name)
+        })),
+    )
+        .into_response()
+}
+
+fn set_paused(state: &OpsState, name: &str, paused: bool) -> Response {
+    let Some(control) = state.control(name) else {
+        return unknown_loop(name);
+    };
+    if paused {
+        control.pause();
+    } else {
+        control.resume();
+    }
+    tracing::warn!(loop_name = %name, paused, "Keeper loop toggled via admin API");
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "loop": name, "paused": paused })),
+    )
+        .into_response()
+}
+
+async fn pause_loop(State(state): State<OpsState>, Path(name): Path<String>) -> Response {
+    set_paused(&state, &name, true)
+}
+
+async fn resume_loop(State(state): State<OpsState>, Path(name): Path<String>) -> Response {
+    set_paused(&state, &name, false)
+}
+
+/// Check for work now instead of at the end of the poll interval. The API
+/// calls this for the job loop when it queues evidence.
+async fn wake_loop(State(state): State<OpsState>, Path(name): Path<String>) -> Response {
+    let Some(control) = state.control(&name) else {
+        return unknown_loop(&name);
+    };
+    control.wake();
+    tracing::debug!(loop_name = %name, "Keeper loop woken via admin API");
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "loop": name, "woken": true })),
+    )
+        .into_response()
+}
@@ -0,0 +1,629 @@
+//! PostgreSQL outbox backend. Unlike SQLite, several keeper replicas can share
+//! one database: jobs are claimed with `FOR UPDATE SKIP LOCKED`, so each job
+//! goes to exactly one replica.
+
+use crate::{
+    intent_from_columns, lease_lost, next_attempt_ms, outbox_status_from, tx_ref_from_columns,
+    worker_id, AttemptSource, EvidenceJob, JobError, JobProvider, JobProviderExt, LeaseReaper,
+    LeaseRenewer, LoopControl, OutboxStatus, PendingTxRef, StatusStore, TenantWeights, TxRefStore,
+    WorkerLease, DEFAULT_LEASE,
+};
+use async_trait::async_trait;
+use phoenix_common::migrations::MigrationError;
+use phoenix_common::outbox::{
+    AttemptOutcome, FailureCategory, HELD_NEXT_ATTEMPT_MS, JOBS_QUEUED_CHANNEL, LEASE_EXPIRED_ERROR,
+};
+use phoenix_evidence::model::{ChainTxRef, SignedAnchorTx};
+use sqlx::postgres::PgListener;
+use sqlx::{PgConnection, Pool, Postgres, Row};
+use std::time::Duration;
+
+/// Initialize database schema for the keeper on PostgreSQL
+pub async fn ensure_schema(pool: &Pool<Postgres>) -> Result<(), MigrationError> {
+    phoenix_common::schema::ensure_pg_schema(pool).await
+}
+
+/// How long to wait before listening again after the listener connection fails
+const LISTEN_RETRY: Duration = Duration::from_secs(5);
+
+/// Wake the job loop whenever a job is queued on `JOBS_QUEUED_CHANNEL`.
+/// Notifications sent while the listener is reconnecting are lost, so the job
+/// loop keeps polling as a fallback.
+pub async fn run_job_listener(pool: &Pool<Postgres>, jobs: &LoopControl) {
+    loop {
+        let mut listener = match PgListener::connect_with(pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to connect job listener");
+                tokio::time::sleep(LISTEN_RETRY).await;
+                continue;
+            }
+        };
+        if let Err(e) = listener.listen(JOBS_QUEUED_CHANNEL).await {
+            tracing::warn!(error = %e, "Failed to listen for queued jobs");
+            tokio::time::sleep(LISTEN_RETRY).await;
+            continue;
+        }
+        tracing::info!(channel = JOBS_QUEUED_CHANNEL, "Listening for queued jobs");
+        loop {
+            match listener.recv().await {
+                Ok(notification) => {
+                    tracing::debug!(job_id = %notification.payload(), "Job queued, waking job loop");
+                    jobs.wake();
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Job listener connection lost");
+                    tokio::time::sleep(LISTEN_RETRY).await;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Append the outcome of the job's current attempt to `outbox_job_attempts`
+async fn record_attempt(
+    conn: &mut PgConnection,
+    id: &str,
+    source: &AttemptSource<'_>,
+    outcome: AttemptOutcome,
+    error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    sqlx::query(
+        "INSERT INTO outbox_job_attempts (job_id, attempt, worker, outcome, category, error, started_ms, finished_ms, latency_ms, provider, endpoint) SELECT id, attempts, $1, $2, $3, $4, $5, $6, $7, COALESCE($8, provider), $9 FROM outbox_jobs WHERE id=$10",
+    )
+    .bind(source.worker_id)
+    .bind(outcome.as_str())
+    .bind(error.map(|e| FailureCategory::classify(e).as_str()))
+    .bind(error)
+    .bind(source.started_ms)
+    .bind(now_ms)
+    .bind(source.started_ms.map(|started| now_ms - started))
+    .bind(source.provider)
+    .bind(source.endpoint)
+    .bind(id)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+pub struct PostgresJobProvider {
+    pool: Pool<Postgres>,
+    worker_id: String,
+    lease_ms: i64,
+    provider: Option<String>,
+    endpoint: Option<String>,
+    tenant_weights: TenantWeights,
+    /// Job this worker last claimed and when
+    claimed: Option<(String, i64)>,
+}
+
+impl PostgresJobProvider {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self::with_lease(pool, worker_id(0), DEFAULT_LEASE)
+    }
+
+    /// Provider for one of several workers sharing the outbox
+    pub fn with_lease(pool: Pool<Postgres>, worker_id: impl Into<String>, lease: Duration) -> Self {
+        Self {
+            pool,
+            worker_id: worker_id.into(),
+            lease_ms: lease.as_millis() as i64,
+            provider: None,
+            endpoint: None,
+            tenant_weights: TenantWeights::default(),
+            claimed: None,
+        }
+    }
+
+    /// Only claim jobs routed to `provider` or not routed at all
+    pub fn with_provider(mut self, provider: impl Into<String>) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    /// Endpoint recorded with each attempt, e.g. the RPC URL
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Share claims between tenants by `weights` instead of equally
+    pub fn with_tenant_weights(mut self, weights: TenantWeights) -> Self {
+        self.tenant_weights = weights;
+        self
+    }
+
+    pub fn worker_id(&self) -> &str {
+        &self.worker_id
+    }
+
+    /// Provider this worker claims jobs for, if it is routed
+    pub fn route(&self) -> Option<&str> {
+        self.provider.as_deref()
+    }
+
+    /// Claim job `id` if it is queued, whether or not it is due yet. A held
+    /// job, or one routed to another provider, is only claimed with `force`.
+    /// The claim is not charged to the tenant's share.
+    pub async fn claim(&mut self, id: &str, force: bool) -> Result<Option<EvidenceJob>, JobError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let row = sqlx::query(
+            r#"
+            UPDATE outbox_jobs
+            SET status='in_progress', updated_ms=$1, attempts=attempts+1, lease_owner=$2, lease_until_ms=$3
+            WHERE id=$4 AND status='queued'
+                AND ($5 OR (next_attempt_ms <> $6 AND ($7::TEXT IS NULL OR provider IS NULL OR provider = $7)))
+            RETURNING id, payload_sha256, created_ms, payload_mime, metadata
+            "#,
+        )
+        .bind(now_ms)
+        .bind(&self.worker_id)
+        .bind(now_ms + self.lease_ms)
+        .bind(id)
+        .bind(force)
+        .bind(HELD_NEXT_ATTEMPT_MS)
+        .bind(&self.provider)
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let job = EvidenceJob {
+            id: row.get(0),
+            payload_sha256: row.get(1),
+            created_ms: row.get(2),
+            payload_mime: row.get("payload_mime"),
+            metadata: row.get("metadata"),
+        };
+        self.claimed = Some((job.id.clone(), now_ms));
+        Ok(Some(job))
+    }
+
+    fn attempt_source(&self, id: &str) -> AttemptSource<'_> {
+        AttemptSource {
+            worker_id: &self.worker_id,
+            provider: self.provider.as_deref(),
+            endpoint: self.endpoint.as_deref(),
+            started_ms: self
+                .claimed
+                .as_ref()
+                .filter(|(claimed_id, _)| claimed_id == id)
+                .map(|(_, started_ms)| *started_ms),
+        }
+    }
+
+    async fn finish(
+        &self,
+        id: &str,
+        outcome: AttemptOutcome,
+        reason: &str,
+        next_attempt_ms: i64,
+    ) -> Result<(), JobError> {
+        let status = if outcome == AttemptOutcome::Failed {
+            "failed"
+        } else {
+            "queued"
+        };
+        let category =
+            (outcome == AttemptOutcome::Failed).then(|| FailureCategory::classify(reason).as_str());
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut t = self.pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE outbox_jobs SET status=$1, last_error=$2, failure_category=$3, updated_ms=$4, next_attempt_ms=$5, lease_owner=NULL, lease_until_ms=NULL WHERE id=$6 AND (lease_owner IS NULL OR lease_owner=$7)",
+        )
+        .bind(status)
+        .bind(reason)
+        .bind(category)
+        .bind(now_ms)
+        .bind(next_attempt_ms)
+        .bind(id)
+        .bind(&self.worker_id)
+        .execute(&mut *t)
+        .await?;
+        if lease_lost(id, &self.worker_id, result.rows_affected()) {
+            return Ok(());
+        }
+        record_attempt(&mut t, id, &self.attempt_source(id), outcome, Some(reason)).await?;
+        t.commit().await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl JobProvider for PostgresJobProvider {
+    async fn fetch_next(&mut self) -> Result<Option<EvidenceJob>, JobError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut t = self.pool.begin().await?;
+        // Claim and mark in one statement; rows locked by other replicas are skipped.
+        // Highest priority first; within it, the tenant furthest behind its
+        // share (lowest pass, no lower than the virtual time) and its oldest job.
+        let row = sqlx::query(
+            r#"
+            UPDATE outbox_jobs
+            SET status='in_progress', updated_ms=$1, attempts=attempts+1, lease_owner=$2, lease_until_ms=$3
+            WHERE id = (
+                SELECT j.id FROM outbox_jobs j
+                LEFT JOIN outbox_tenant_shares s ON s.tenant = COALESCE(j.tenant, '')
+                WHERE j.status='queued' AND j.next_attempt_ms <= $1
+                    AND ($4::TEXT IS NULL OR j.provider IS NULL OR j.provider = $4)
+                ORDER BY j.priority DESC,
+                    GREATEST(COALESCE(s.pass, 0), (SELECT COALESCE(MAX(last_start), 0) FROM outbox_tenant_shares)) ASC,
+                    j.created_ms ASC
+                LIMIT 1
+                FOR UPDATE OF j SKIP LOCKED
+            )
+            RETURNING id, payload_sha256, created_ms, tenant, payload_mime, metadata
+            "#,
+        )
+        .bind(now_ms)
+        .bind(&self.worker_id)
+        .bind(now_ms + self.lease_ms)
+        .bind(&self.provider)
+        .fetch_optional(&mut *t)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let tenant: Option<String> = row.get(3);
+        sqlx::query(
+            r#"
+            INSERT INTO outbox_tenant_shares (tenant, last_start, pass)
+            SELECT $1, v, v + $2 FROM (SELECT COALESCE(MAX(last_start), 0) AS v FROM outbox_tenant_shares) clock
+            ON CONFLICT (tenant) DO UPDATE SET
+                last_start = GREATEST(outbox_tenant_shares.pass, EXCLUDED.last_start),
+                pass = GREATEST(outbox_tenant_shares.pass, EXCLUDED.last_start) + $2
+            "#,
+        )
+        .bind(tenant.as_deref().unwrap_or(""))
+        .bind(self.tenant_weights.stride(tenant.as_deref()))
+        .execute(&mut *t)
+        .await?;
+        t.commit().await?;
+
+        let job = EvidenceJob {
+            id: row.get(0),
+            payload_sha256: row.get(1),
+            created_ms: row.get(2),
+            payload_mime: row.get("payload_mime"),
+            metadata: row.get("metadata"),
+        };
+        self.claimed = Some((job.id.clone(), now_ms));
+        Ok(Some(job))
+    }
+
+    async fn mark_done(&mut self, id: &str) -> Result<(), JobError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut t = self.pool.begin().await?;
+        sqlx::query(
+            "UPDATE outbox_jobs SET status='done', failure_category=NULL, updated_ms=$1, lease_owner=NULL, lease_until_ms=NULL WHERE id=$2",
+        )
+        .bind(now_ms)
+        .bind(id)
+        .execute(&mut *t)
+        .await?;
+        record_attempt(
+            &mut t,
+            id,
+            &self.attempt_source(id),
+            AttemptOutcome::Done,
+            None,
+        )
+        .await?;
+        t.commit().await?;
+        Ok(())
+    }
+
+    async fn mark_failed(&mut self, id: &str, reason: &str) -> Result<(), JobError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        self.finish(id, AttemptOutcome::Failed, reason, now_ms)
+            .await
+    }
+}
+
+#[async_trait]
+impl JobProviderExt for PostgresJobProvider {
+    async fn mark_tx_and_done(&mut self, id: &str, tx: &ChainTxRef) -> Result<(), JobError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut t = self.pool.begin().await?;
+        // Re-recording a dropped transaction starts its check schedule over
+        sqlx::query(
+            r#"
+            INSERT INTO outbox_tx_refs (job_id, network, chain, tx_id, confirmed, timestamp, created_ms)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (job_id, network, chain, tx_id)
+            DO UPDATE SET confirmed = EXCLUDED.confirmed, timestamp = EXCLUDED.timestamp,
+                created_ms = EXCLUDED.created_ms, checks = 0, next_check_ms = 0, dropped_ms = NULL
+            "#,
+        )
+        .bind(id)
+        .bind(&tx.network)
+        .bind(&tx.chain)
+        .bind(&tx.tx_id)
+        .bind(if tx.confirmed { 1 } else { 0 })
+        .bind(tx.timestamp.map(|dt| dt.timestamp()))
+        .bind(now_ms)
+        .execute(&mut *t)
+        .await?;
+        sqlx::query("DELETE FROM outbox_anchor_intents WHERE job_id=$1")
+            .bind(id)
+            .execute(&mut *t)
+            .await?;
+        // Not fenced by the lease: the evidence is anchored even if the lease ran out
+        sqlx::query(
+            "UPDATE outbox_jobs SET status='done', failure_category=NULL, updated_ms=$1, lease_owner=NULL, lease_until_ms=NULL WHERE id=$2",
+        )
+        .bind(now_ms)
+        .bind(id)
+        .execute(&mut *t)
+        .await?;
+        record_attempt(
+            &mut t,
+            id,
+            &self.attempt_source(id),
+            AttemptOutcome::Done,
+            None,
+        )
+        .await?;
+        t.commit().await?;
+        Ok(())
+    }
+
+    async fn mark_failed_or_backoff(
+        &mut self,
+        id: &str,
+        reason: &str,
+        temporary: bool,
+    ) -> Result<(), JobError> {
+        if !temporary {
+            return self.mark_failed(id, reason).await;
+        }
+
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let rec = sqlx::query("SELECT attempts FROM outbox_jobs WHERE id=$1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        let attempts: i64 = rec.get(0);
+        self.finish(
+            id,
+            AttemptOutcome::Retry,
+            reason,
+            next_attempt_ms(now_ms, attempts),
+        )
+        .await
+    }
+
+    async fn record_intent(&mut self, id: &str, signed: &SignedAnchorTx) -> Result<(), JobError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO outbox_anchor_intents (job_id, network, chain, tx_id, raw_tx, created_ms)
+            SELECT id, $1, $2, $3, $4, $5 FROM outbox_jobs WHERE id=$6 AND lease_owner=$7
+            ON CONFLICT (job_id) DO UPDATE SET network = EXCLUDED.network, chain = EXCLUDED.chain,
+                tx_id = EXCLUDED.tx_id, raw_tx = EXCLUDED.raw_tx, created_ms = EXCLUDED.created_ms
+            "#,
+        )
+        .bind(&signed.tx.network)
+        .bind(&signed.tx.chain)
+        .bind(&signed.tx.tx_id)
+        .bind(&signed.raw_tx)
+        .bind(now_ms)
+        .bind(id)
+        .bind(&self.worker_id)
+        .execute(&self.pool)
+        .await?;
+        if lease_lost(id, &self.worker_id, result.rows_affected()) {
+            return Err(JobError::Temporary(
+                "lease lost before broadcast".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn pending_intent(&mut self, id: &str) -> Result<Option<SignedAnchorTx>, JobError> {
+        let row: Option<(String, String, String, String, i64)> = sqlx::query_as(
+            "SELECT network, chain, tx_id, raw_tx, created_ms FROM outbox_anchor_intents WHERE job_id=$1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|(network, chain, tx_id, raw_tx, created_ms)| {
+            intent_from_columns(network, chain, tx_id, raw_tx, created_ms)
+        }))
+    }
+
+    fn lease_renewer(&self) -> Option<Box<dyn LeaseRenewer>> {
+        Some(Box::new(WorkerLease {
+            pool: self.pool.clone(),
+            worker_id: self.worker_id.clone(),
+            lease: Duration::from_millis(self.lease_ms as u64),
+        }))
+    }
+}
+
+#[async_trait]
+impl LeaseRenewer for WorkerLease<Pool<Postgres>> {
+    fn lease(&self) -> Duration {
+        self.lease
+    }
+
+    async fn renew_lease(&self, id: &str) -> Result<bool, sqlx::Error> {
+        let lease_until_ms = chrono::Utc::now().timestamp_millis() + self.lease.as_millis() as i64;
+        let result = sqlx::query(
+            "UPDATE outbox_jobs SET lease_until_ms=$1 WHERE id=$2 AND lease_owner=$3 AND status='in_progress'",
+        )
+        .bind(lease_until_ms)
+        .bind(id)
+        .bind(&self.worker_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[async_trait]
+impl LeaseReaper for Pool<Postgres> {
+    async fn reap_expired_leases(&self) -> Result<u64, sqlx::Error> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        // One statement, so the history rows match exactly the jobs that were requeued
+        let result = sqlx::query(
+            r#"
+            WITH expired AS (
+                SELECT id, lease_owner, updated_ms, provider FROM outbox_jobs
+                WHERE status='in_progress' AND lease_until_ms < $2
+                FOR UPDATE SKIP LOCKED
+            ), reaped AS (
+                UPDATE outbox_jobs j
+                SET status='queued', last_error=$1, updated_ms=$2, next_attempt_ms=$2, lease_owner=NULL, lease_until_ms=NULL
+                FROM expired e
+                WHERE j.id = e.id
+                RETURNING j.id, j.attempts, e.lease_owner, e.updated_ms AS started_ms, e.provider
+            )
+            INSERT INTO outbox_job_attempts (job_id, attempt, worker, outcome, category, error, started_ms, finished_ms, latency_ms, provider)
+            SELECT id, attempts, lease_owner, $3, $4, $1, started_ms, $2, $2 - started_ms, provider FROM reaped
+            "#,
+        )
+        .bind(LEASE_EXPIRED_ERROR)
+        .bind(now_ms)
+        .bind(AttemptOutcome::LeaseExpired.as_str())
+        .bind(FailureCategory::LeaseExpired.as_str())
+        .execute(self)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl StatusStore for Pool<Postgres> {
+    async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1").execute(self).await?;
+        Ok(())
+    }
+
+    async fn outbox_status(&self) -> Result<OutboxStatus, sqlx::Error> {
+        let by_status = sqlx::query_as(
+            "SELECT CASE WHEN status='queued' AND next_attempt_ms=$1 THEN 'held' ELSE status END AS s, COUNT(*) FROM outbox_jobs GROUP BY s",
+        )
+        .bind(HELD_NEXT_ATTEMPT_MS)
+        .fetch_all(self)
+        .await?;
+        // Held jobs are not waiting on the keeper, and scheduled ones only from when they fall due
+        let oldest_queued_ms = sqlx::query_scalar(
+            "SELECT MIN(CASE WHEN attempts=0 THEN GREATEST(created_ms, next_attempt_ms) ELSE created_ms END) FROM outbox_jobs WHERE status='queued' AND next_attempt_ms < $1",
+        )
+        .bind(HELD_NEXT_ATTEMPT_MS)
+        .fetch_one(self)
+        .await?;
+        let unconfirmed = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM outbox_tx_refs WHERE confirmed = 0 AND dropped_ms IS NULL",
+        )
+        .fetch_one(self)
+        .await?;
+        let last_anchor = sqlx::query_as(
+            "SELECT network, MAX(timestamp) FROM outbox_tx_refs WHERE timestamp IS NOT NULL GROUP BY network",
+        )
+        .fetch_all(self)
+        .await?;
+        Ok(outbox_status_from(
+            by_status,
+            oldest_queued_ms,
+            unconfirmed,
+            last_anchor,
+        ))
+    }
+}
+
+#[async_trait]
+impl TxRefStore for Pool<Postgres> {
+    async fn fetch_due_tx_refs(
+        &self,
+        now_ms: i64,
+        limit: i64,
+    ) -> Result<Vec<PendingTxRef>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT job_id, network, chain, tx_id, confirmed, timestamp, created_ms, checks FROM outbox_tx_refs WHERE confirmed = 0 AND dropped_ms IS NULL AND next_check_ms <= $1 ORDER BY created_ms DESC NULLS LAST LIMIT $2",
+        )
+        .bind(now_ms)
+        .bind(limit)
+        .fetch_all(self)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| PendingTxRef {
+                job_id: row.get("job_id"),
+                tx: tx_ref_from_columns(
+                    row.get("network"),
+                    row.get("chain"),
+                    row.get("tx_id"),
+                    row.get("confirmed"),
+                    row.get("timestamp"),
+                ),
+                created_ms: row.get::<Option<i64>, _>("created_ms").unwrap_or(now_ms),
+                checks: row.get("checks"),
+            })
+            .collect())
+    }
+
+    async fn update_tx_ref_confirmation(&self, tx_ref: &ChainTxRef) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE outbox_tx_refs SET confirmed = $1 WHERE tx_id = $2 AND network = $3 AND chain = $4",
+        )
+        .bind(if tx_ref.confirmed { 1 } else { 0 })
+        .bind(&tx_ref.tx_id)
+        .bind(&tx_ref.network)
+        .bind(&tx_ref.chain)
+        .execute(self)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn schedule_tx_ref_check(
+        &self,
+        tx_ref: &PendingTxRef,
+        next_check_ms: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE outbox_tx_refs SET checks = checks + 1, next_check_ms = $1 WHERE job_id = $2 AND tx_id = $3 AND network = $4 AND chain = $5",
+        )
+        .bind(next_check_ms)
+        .bind(&tx_ref.job_id)
+        .bind(&tx_ref.tx.tx_id)
+        .bind(&tx_ref.tx.network)
+        .bind(&tx_ref.tx.chain)
+        .execute(self)
+        .await?;
+        Ok(())
+    }
+
+    async fn drop_tx_ref(&self, tx_ref: &PendingTxRef, reason: &str) -> Result<(), sqlx::Error> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let mut t = self.begin().await?;
+        sqlx::query(
+            "UPDATE outbox_tx_refs SET checks = checks + 1, dropped_ms = $1 WHERE job_id = $2 AND tx_id = $3 AND network = $4 AND chain = $5 AND confirmed = 0",
+        )
+        .bind(now_ms)
+        .bind(&tx_ref.job_id)
+        .bind(&tx_ref.tx.tx_id)
+        .bind(&tx_ref.tx.network)
+        .bind(&tx_ref.tx.chain)
+        .execute(&mut *t)
+        .await?;
+        sqlx::query(
+            "UPDATE outbox_jobs SET status='queued', last_error=$1, failure_category=$2, updated_ms=$3, next_attempt_ms=$3 WHERE id=$4 AND status='done'",
+        )
+        .bind(reason)
+        .bind(FailureCategory::Dropped.as_str())
+        .bind(now_ms)
+        .bind(&tx_ref.job_id)
+        .execute(&mut *t)
+        .await?;
+        t.commit().await?;
+        Ok(())
+    }
+}
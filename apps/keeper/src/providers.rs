@@ -0,0 +1,181 @@
+//! Anchor providers built from `ProviderConfig`.
+
+use crate::config::ProviderConfig;
+use anchor_bitcoin::BitcoinProvider;
+use anchor_etherlink::{EtherlinkProvider, EtherlinkProviderStub};
+use anchor_notary::LocalNotaryProvider;
+use anchor_solana::SolanaProvider;
+use anchor_tsa::{read_certificate, TsaProvider};
+use async_trait::async_trait;
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use std::sync::Arc;
+
+pub type SharedAnchor = Arc<dyn AnchorProvider + Send + Sync>;
+
+/// Anchors with the first chain that accepts the evidence, in configuration
+/// order. Confirmations go to the chain named by the transaction's `network`.
+///
+/// Does not sign ahead: an intent recorded for one chain would pin the job to
+/// it, so a retry could no longer fail over to the next.
+pub struct MultiAnchorProvider {
+    chains: Vec<(String, SharedAnchor)>,
+    /// The configured networks joined with `+`, e.g. `etherlink+solana`
+    network: String,
+}
+
+impl MultiAnchorProvider {
+    pub fn new(chains: Vec<(String, SharedAnchor)>) -> Self {
+        let network = chains
+            .iter()
+            .map(|(network, _)| network.as_str())
+            .collect::<Vec<_>>()
+            .join("+");
+        Self { chains, network }
+    }
+
+    /// The provider for the chain `tx` was anchored to
+    fn chain_of(&self, tx: &ChainTxRef) -> Result<&SharedAnchor, AnchorError> {
+        self.chains
+            .iter()
+            .find(|(network, _)| *network == tx.network)
+            .map(|(_, provider)| provider)
+            .ok_or_else(|| {
+                AnchorError::Invalid(format!("no provider configured for network {}", tx.network))
+            })
+    }
+}
+
+#[async_trait]
+impl AnchorProvider for MultiAnchorProvider {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        let mut last_error = AnchorError::Invalid("no chains configured".to_string());
+        for (network, provider) in &self.chains {
+            match provider.anchor(evidence).await {
+                Ok(tx) => return Ok(tx),
+                Err(e) => {
+                    tracing::warn!(network = %network, error = %e, "Anchor failed, trying next chain");
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        self.chain_of(tx)?.confirm(tx).await
+    }
+
+    async fn is_known(&self, tx: &ChainTxRef) -> Result<bool, AnchorError> {
+        self.chain_of(tx)?.is_known(tx).await
+    }
+
+    /// Labels failures that exhausted every chain, e.g. in metrics
+    fn network(&self) -> &str {
+        &self.network
+    }
+
+    /// Healthy while at least one chain can take anchors
+    async fn health(&self) -> Result<(), AnchorError> {
+        let mut last_error = AnchorError::Invalid("no chains configured".to_string());
+        for (_, provider) in &self.chains {
+            match provider.health().await {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// Build the anchor provider described by `config`
+pub fn build_anchor_provider(config: &ProviderConfig) -> Result<SharedAnchor, AnchorError> {
+    match config {
+        ProviderConfig::Stub => Ok(Arc::new(EtherlinkProviderStub)),
+        ProviderConfig::Etherlink {
+            endpoint,
+            network,
+            private_key,
+            ..
+        } => etherlink(endpoint, network, private_key.clone()),
+        ProviderConfig::Solana { endpoint, network } => Ok(Arc::new(SolanaProvider::new(
+            endpoint.clone(),
+            network.clone(),
+        ))),
+        ProviderConfig::Bitcoin {
+            endpoint,
+            network,
+            rpc_user,
+            rpc_password,
+            min_confirmations,
+        } => {
+            let provider = BitcoinProvider::new(
+                endpoint.clone(),
+                network.clone(),
+                rpc_user.clone(),
+                rpc_password.clone(),
+            )
+            .map_err(AnchorError::Provider)?
+            .with_min_confirmations(*min_confirmations);
+            Ok(Arc::new(provider))
+        }
+        ProviderConfig::Tsa {
+            endpoint,
+            name,
+            token_dir,
+            trusted_certs,
+        } => {
+            std::fs::create_dir_all(token_dir).map_err(|e| {
+                AnchorError::Provider(format!(
+                    "failed to create token directory {}: {}",
+                    token_dir.display(),
+                    e
+                ))
+            })?;
+            let mut provider = TsaProvider::new(endpoint.clone(), name.clone(), token_dir.clone())
+                .map_err(AnchorError::Provider)?;
+            for path in trusted_certs {
+                provider = provider.with_trusted_certificate(
+                    read_certificate(path).map_err(AnchorError::Provider)?,
+                );
+            }
+            Ok(Arc::new(provider))
+        }
+        ProviderConfig::Notary {
+            ledger_path,
+            key_path,
+        } => {
+            let provider = LocalNotaryProvider::from_key_file(ledger_path.clone(), key_path)
+                .map_err(AnchorError::Provider)?;
+            Ok(Arc::new(provider))
+        }
+        ProviderConfig::Multi {
+            etherlink: e,
+            solana: s,
+        } => {
+            let mut chains = Vec::new();
+            if let Some(e) = e {
+                chains.push((
+                    "etherlink".to_string(),
+                    etherlink(&e.endpoint, &e.network, e.private_key.clone())?,
+                ));
+            }
+            if let Some(s) = s {
+                let solana: SharedAnchor =
+                    Arc::new(SolanaProvider::new(s.endpoint.clone(), s.network.clone()));
+                chains.push(("solana".to_string(), solana));
+            }
+            Ok(Arc::new(MultiAnchorProvider::new(chains)))
+        }
+    }
+}
+
+fn etherlink(
+    endpoint: &str,
+    network: &str,
+    private_key: Option<String>,
+) -> Result<SharedAnchor, AnchorError> {
+    let provider = EtherlinkProvider::new(endpoint.to_string(), network.to_string(), private_key)
+        .map_err(AnchorError::Provider)?;
+    Ok(Arc::new(provider))
+}
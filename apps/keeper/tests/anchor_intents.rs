@@ -0,0 +1,293 @@
+use chrono::Utc;
+use phoenix_common::testing::{sqlite_test_pool, TestJob};
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord, SignedAnchorTx};
+use phoenix_keeper::{run_job_loop, JobError, JobProvider, JobProviderExt, SqliteJobProvider};
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::time::timeout;
+
+/// Signs ahead of sending, checking that the outbox holds the intent by the
+/// time a transaction is broadcast. Raw transactions in `expired` are
+/// rejected as expired.
+#[derive(Clone)]
+struct SigningProvider {
+    pool: Pool<Sqlite>,
+    lifetime: Option<Duration>,
+    on_chain: Arc<Mutex<HashSet<String>>>,
+    expired: Arc<Mutex<HashSet<String>>>,
+    broadcasts: Arc<Mutex<Vec<SignedAnchorTx>>>,
+}
+
+impl SigningProvider {
+    fn new(pool: Pool<Sqlite>) -> Self {
+        Self {
+            pool,
+            lifetime: None,
+            on_chain: Arc::new(Mutex::new(HashSet::new())),
+            expired: Arc::new(Mutex::new(HashSet::new())),
+            broadcasts: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn broadcasts(&self) -> Vec<SignedAnchorTx> {
+        self.broadcasts.lock().unwrap().clone()
+    }
+}
+
+fn signed_tx(tx_id: &str, raw_tx: &str) -> SignedAnchorTx {
+    SignedAnchorTx {
+        tx: ChainTxRef {
+            network: "etherlink".to_string(),
+            chain: "testnet".to_string(),
+            tx_id: tx_id.to_string(),
+            confirmed: false,
+            timestamp: None,
+        },
+        raw_tx: raw_tx.to_string(),
+    }
+}
+
+#[async_trait::async_trait]
+impl AnchorProvider for SigningProvider {
+    async fn anchor(&self, _evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        panic!("signing providers are anchored through prepare and broadcast");
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        Ok(tx.clone())
+    }
+
+    fn network(&self) -> &str {
+        "etherlink"
+    }
+
+    async fn prepare(
+        &self,
+        evidence: &EvidenceRecord,
+    ) -> Result<Option<SignedAnchorTx>, AnchorError> {
+        Ok(Some(signed_tx(
+            &format!("0x{}", evidence.digest.hex),
+            "raw-signed-now",
+        )))
+    }
+
+    fn signed_tx_lifetime(&self) -> Option<Duration> {
+        self.lifetime
+    }
+
+    async fn broadcast(&self, signed: &SignedAnchorTx) -> Result<ChainTxRef, AnchorError> {
+        if self.expired.lock().unwrap().contains(&signed.raw_tx) {
+            return Err(AnchorError::Expired("blockhash not found".to_string()));
+        }
+        let recorded: Option<String> =
+            sqlx::query_scalar("SELECT tx_id FROM outbox_anchor_intents WHERE tx_id=?1")
+                .bind(&signed.tx.tx_id)
+                .fetch_optional(&self.pool)
+                .await
+                .unwrap();
+        assert!(
+            recorded.is_some(),
+            "broadcast before the intent was recorded"
+        );
+
+        self.broadcasts.lock().unwrap().push(signed.clone());
+        self.on_chain
+            .lock()
+            .unwrap()
+            .insert(signed.tx.tx_id.clone());
+        let mut tx = signed.tx.clone();
+        tx.timestamp = Some(Utc::now());
+        Ok(tx)
+    }
+
+    async fn is_known(&self, tx: &ChainTxRef) -> Result<bool, AnchorError> {
+        Ok(self.on_chain.lock().unwrap().contains(&tx.tx_id))
+    }
+}
+
+/// The intent a worker leaves behind when it dies between broadcast and
+/// recording the tx ref
+async fn insert_intent(pool: &Pool<Sqlite>, network: &str, tx_id: &str, raw_tx: &str) {
+    sqlx::query(
+        "INSERT INTO outbox_anchor_intents (job_id, network, chain, tx_id, raw_tx, created_ms) VALUES ('job-1', ?1, 'testnet', ?2, ?3, 0)",
+    )
+    .bind(network)
+    .bind(tx_id)
+    .bind(raw_tx)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+/// Run the job loop until job-1 is done and return its recorded tx id
+async fn run_until_done(pool: &Pool<Sqlite>, anchor: &SigningProvider) -> String {
+    let mut jp = SqliteJobProvider::new(pool.clone());
+    let worker_anchor = anchor.clone();
+    let worker = tokio::spawn(async move {
+        run_job_loop(&mut jp, &worker_anchor, Duration::from_millis(10)).await;
+    });
+    let tx_id = timeout(Duration::from_secs(5), async {
+        loop {
+            let tx_id: Option<String> =
+                sqlx::query_scalar("SELECT tx_id FROM outbox_tx_refs WHERE job_id='job-1'")
+                    .fetch_optional(pool)
+                    .await
+                    .unwrap();
+            if let Some(tx_id) = tx_id {
+                break tx_id;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("job should be anchored");
+    worker.abort();
+
+    let row = sqlx::query("SELECT status FROM outbox_jobs WHERE id='job-1'")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<String, _>(0), "done");
+    let intents: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM outbox_anchor_intents")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    assert_eq!(intents, 0, "intent should be cleared with the tx ref");
+    tx_id
+}
+
+#[tokio::test]
+async fn test_intent_recorded_before_broadcast() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+    let anchor = SigningProvider::new(pool.clone());
+
+    let tx_id = run_until_done(&pool, &anchor).await;
+
+    assert_eq!(tx_id, "0xabcd1234");
+    let broadcasts = anchor.broadcasts();
+    assert_eq!(broadcasts.len(), 1);
+    assert_eq!(broadcasts[0].raw_tx, "raw-signed-now");
+}
+
+#[tokio::test]
+async fn test_known_intent_is_not_resent() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+    let anchor = SigningProvider::new(pool.clone());
+    insert_intent(&pool, "etherlink", "0xearlier", "raw-signed-earlier").await;
+    anchor
+        .on_chain
+        .lock()
+        .unwrap()
+        .insert("0xearlier".to_string());
+
+    let tx_id = run_until_done(&pool, &anchor).await;
+
+    assert_eq!(tx_id, "0xearlier");
+    assert!(anchor.broadcasts().is_empty());
+}
+
+#[tokio::test]
+async fn test_unknown_intent_is_resent_unchanged() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+    let anchor = SigningProvider::new(pool.clone());
+    insert_intent(&pool, "etherlink", "0xearlier", "raw-signed-earlier").await;
+
+    let tx_id = run_until_done(&pool, &anchor).await;
+
+    assert_eq!(tx_id, "0xearlier");
+    let broadcasts = anchor.broadcasts();
+    assert_eq!(broadcasts.len(), 1);
+    assert_eq!(broadcasts[0].raw_tx, "raw-signed-earlier");
+}
+
+#[tokio::test]
+async fn test_intent_past_lifetime_is_signed_again() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+    let anchor = SigningProvider {
+        lifetime: Some(Duration::from_secs(120)),
+        ..SigningProvider::new(pool.clone())
+    };
+    // Recorded long ago and never seen by the chain: it can no longer land
+    insert_intent(&pool, "etherlink", "0xearlier", "raw-signed-earlier").await;
+
+    let tx_id = run_until_done(&pool, &anchor).await;
+
+    assert_eq!(tx_id, "0xabcd1234");
+    let broadcasts = anchor.broadcasts();
+    assert_eq!(broadcasts.len(), 1);
+    assert_eq!(broadcasts[0].raw_tx, "raw-signed-now");
+}
+
+#[tokio::test]
+async fn test_intent_rejected_as_expired_is_signed_again() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+    let anchor = SigningProvider::new(pool.clone());
+    insert_intent(&pool, "etherlink", "0xearlier", "raw-signed-earlier").await;
+    anchor
+        .expired
+        .lock()
+        .unwrap()
+        .insert("raw-signed-earlier".to_string());
+
+    let tx_id = run_until_done(&pool, &anchor).await;
+
+    assert_eq!(tx_id, "0xabcd1234");
+    let broadcasts = anchor.broadcasts();
+    assert_eq!(broadcasts.len(), 1);
+    assert_eq!(broadcasts[0].raw_tx, "raw-signed-now");
+}
+
+#[tokio::test]
+async fn test_intent_for_other_network_is_replaced() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+    let anchor = SigningProvider::new(pool.clone());
+    insert_intent(&pool, "solana", "sol-earlier", "raw-solana").await;
+
+    let tx_id = run_until_done(&pool, &anchor).await;
+
+    assert_eq!(tx_id, "0xabcd1234");
+    assert_eq!(anchor.broadcasts()[0].raw_tx, "raw-signed-now");
+}
+
+#[tokio::test]
+async fn test_record_intent_requires_lease() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+    let signed = signed_tx("0xabcd1234", "raw");
+
+    // Not claimed yet: no worker holds the lease
+    let mut other = SqliteJobProvider::with_lease(pool.clone(), "other", Duration::from_secs(60));
+    assert!(matches!(
+        other.record_intent("job-1", &signed).await,
+        Err(JobError::Temporary(_))
+    ));
+
+    let mut owner = SqliteJobProvider::with_lease(pool.clone(), "owner", Duration::from_secs(60));
+    let job = owner.fetch_next().await.unwrap().unwrap();
+    owner.record_intent(&job.id, &signed).await.unwrap();
+    assert!(matches!(
+        other.record_intent("job-1", &signed).await,
+        Err(JobError::Temporary(_))
+    ));
+
+    let mut pending = owner.pending_intent("job-1").await.unwrap().unwrap();
+    assert!(pending.tx.timestamp.take().is_some());
+    assert_eq!(pending, signed);
+}
@@ -0,0 +1,174 @@
+//! Keeper loops end to end against `EtherlinkProvider` on the chain simulator.
+//!
+//! The provider's send is still simulated, so the tests play the network's
+//! part: each transaction the keeper records is submitted to the simulator's
+//! mempool before blocks are mined.
+
+use anchor_etherlink::EtherlinkProvider;
+use chain_simulator::{ChainSimulator, SimulatorConfig, TxStatus};
+use phoenix_common::outbox::TX_DROPPED_ERROR;
+use phoenix_common::testing::{sqlite_test_pool, TestJob};
+use phoenix_keeper::{
+    run_confirmation_loop, run_job_loop, ConfirmationSchedule, SqliteJobProvider,
+};
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+fn provider(sim: &ChainSimulator) -> EtherlinkProvider {
+    EtherlinkProvider::new(sim.endpoint().to_string(), "testnet".to_string(), None).unwrap()
+}
+
+/// Checks every few milliseconds and drops anything the chain no longer knows
+fn eager_schedule() -> ConfirmationSchedule {
+    ConfirmationSchedule {
+        poll: Duration::from_millis(10),
+        max_backoff: Duration::from_millis(40),
+        max_age: Duration::ZERO,
+        network_max_age: BTreeMap::new(),
+    }
+}
+
+fn spawn_job_loop(pool: &Pool<Sqlite>, anchor: EtherlinkProvider) -> JoinHandle<()> {
+    let mut jp = SqliteJobProvider::new(pool.clone());
+    tokio::spawn(async move {
+        run_job_loop(&mut jp, &anchor, Duration::from_millis(10)).await;
+    })
+}
+
+fn spawn_confirmation_loop(pool: &Pool<Sqlite>, anchor: EtherlinkProvider) -> JoinHandle<()> {
+    let store = pool.clone();
+    tokio::spawn(async move {
+        run_confirmation_loop(&store, &anchor, eager_schedule()).await;
+    })
+}
+
+/// Wait until `sql` returns a row, then return its first column
+async fn wait_for(pool: &Pool<Sqlite>, sql: &str, what: &str) -> String {
+    timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(row) = sqlx::query(sql).fetch_optional(pool).await.unwrap() {
+                return row.get::<String, _>(0);
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("timed out waiting for {}", what))
+}
+
+/// Wait for the job loop to anchor `job_id` and return the live transaction id
+async fn wait_for_anchor(pool: &Pool<Sqlite>, job_id: &str) -> String {
+    wait_for(
+        pool,
+        &format!(
+            "SELECT r.tx_id FROM outbox_tx_refs r JOIN outbox_jobs j ON j.id = r.job_id WHERE r.job_id = '{}' AND r.dropped_ms IS NULL AND j.status = 'done'",
+            job_id
+        ),
+        "the job to be anchored",
+    )
+    .await
+}
+
+async fn wait_for_confirmation(pool: &Pool<Sqlite>, tx_id: &str) {
+    wait_for(
+        pool,
+        &format!(
+            "SELECT tx_id FROM outbox_tx_refs WHERE tx_id = '{}' AND confirmed = 1",
+            tx_id
+        ),
+        "the transaction to be confirmed",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_anchored_job_confirmed_once_mined() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+
+    let worker = spawn_job_loop(&pool, provider(&sim));
+    let tx_id = wait_for_anchor(&pool, "job-1").await;
+    worker.abort();
+    assert!(sim.submit(&tx_id));
+
+    let confirmer = spawn_confirmation_loop(&pool, provider(&sim));
+    // Waiting in the mempool: checked, still known to the chain, so kept
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let row = sqlx::query("SELECT confirmed, checks, dropped_ms FROM outbox_tx_refs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<i64, _>(0), 0);
+    assert!(row.get::<i64, _>(1) > 0);
+    assert!(row.get::<Option<i64>, _>(2).is_none());
+
+    let block = sim.mine();
+    wait_for_confirmation(&pool, &tx_id).await;
+    confirmer.abort();
+    assert!(matches!(sim.status(&tx_id), TxStatus::Included { block: b, .. } if b == block));
+    let status: String = sqlx::query_scalar("SELECT status FROM outbox_jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "done");
+}
+
+#[tokio::test]
+async fn test_reorged_and_dropped_tx_requeues_job() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+
+    let worker = spawn_job_loop(&pool, provider(&sim));
+    let tx_id = wait_for_anchor(&pool, "job-1").await;
+    worker.abort();
+    sim.submit(&tx_id);
+    sim.mine();
+
+    // The block is reorged out and the mempool then evicts the transaction
+    sim.reorg(1);
+    assert_eq!(sim.status(&tx_id), TxStatus::Pending);
+    assert!(sim.drop_tx(&tx_id));
+    assert_eq!(sim.status(&tx_id), TxStatus::Unknown);
+
+    let confirmer = spawn_confirmation_loop(&pool, provider(&sim));
+    wait_for(
+        &pool,
+        "SELECT status FROM outbox_jobs WHERE status = 'queued'",
+        "the job to be requeued",
+    )
+    .await;
+    confirmer.abort();
+    let row = sqlx::query("SELECT last_error, failure_category FROM outbox_jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(row.get::<String, _>(0).starts_with(TX_DROPPED_ERROR));
+    assert_eq!(row.get::<String, _>(1), "dropped");
+    let dropped: Option<i64> = sqlx::query_scalar("SELECT dropped_ms FROM outbox_tx_refs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(dropped.is_some());
+
+    // Anchored again, this time the transaction lands and is confirmed
+    let worker = spawn_job_loop(&pool, provider(&sim));
+    let tx_id = wait_for_anchor(&pool, "job-1").await;
+    worker.abort();
+    sim.submit(&tx_id);
+    sim.mine();
+    let confirmer = spawn_confirmation_loop(&pool, provider(&sim));
+    wait_for_confirmation(&pool, &tx_id).await;
+    confirmer.abort();
+}
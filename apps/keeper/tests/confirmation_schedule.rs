@@ -0,0 +1,330 @@
+use anchor_etherlink::EtherlinkProviderStub;
+use phoenix_common::outbox::TX_DROPPED_ERROR;
+use phoenix_common::testing::sqlite_test_pool;
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use phoenix_keeper::{
+    run_confirmation_loop, run_job_loop, ConfirmationSchedule, SqliteJobProvider, TxRefStore,
+};
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::time::timeout;
+
+const HOUR_MS: i64 = 3_600_000;
+
+/// Never sees a transaction confirmed; with `outage` every check fails instead.
+/// `known` answers `is_known`; without it the provider cannot look
+/// transactions up.
+#[derive(Clone, Default)]
+struct PendingProvider {
+    outage: bool,
+    known: Option<bool>,
+    checks: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl AnchorProvider for PendingProvider {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        EtherlinkProviderStub.anchor(evidence).await
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        self.checks.fetch_add(1, Ordering::SeqCst);
+        if self.outage {
+            Err(AnchorError::Network("connection refused".to_string()))
+        } else {
+            Ok(tx.clone())
+        }
+    }
+
+    async fn is_known(&self, tx: &ChainTxRef) -> Result<bool, AnchorError> {
+        match self.known {
+            Some(known) => Ok(known),
+            None => EtherlinkProviderStub.is_known(tx).await,
+        }
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// A done job and the unconfirmed Etherlink transaction it was anchored with,
+/// recorded `age_ms` ago
+async fn insert_anchored(pool: &Pool<Sqlite>, job_id: &str, age_ms: i64) {
+    insert_anchored_on(pool, job_id, "etherlink", age_ms).await
+}
+
+async fn insert_anchored_on(pool: &Pool<Sqlite>, job_id: &str, network: &str, age_ms: i64) {
+    sqlx::query(
+        "INSERT INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms, next_attempt_ms) VALUES (?1, 'abcd1234', 'done', 1, 0, 0, 0)",
+    )
+    .bind(job_id)
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO outbox_tx_refs (job_id, network, chain, tx_id, confirmed, timestamp, created_ms) VALUES (?1, ?2, 'testnet', ?3, 0, NULL, ?4)",
+    )
+    .bind(job_id)
+    .bind(network)
+    .bind(format!("tx-{}", job_id))
+    .bind(now_ms() - age_ms)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+/// Run the confirmation loop for `duration`
+async fn confirm_for(
+    pool: &Pool<Sqlite>,
+    anchor: &PendingProvider,
+    schedule: ConfirmationSchedule,
+    duration: Duration,
+) {
+    let store = pool.clone();
+    let anchor = anchor.clone();
+    let confirmer = tokio::spawn(async move {
+        run_confirmation_loop(&store, &anchor, schedule).await;
+    });
+    tokio::time::sleep(duration).await;
+    confirmer.abort();
+}
+
+#[test]
+fn test_next_check_backs_off_to_max() {
+    let schedule = ConfirmationSchedule {
+        poll: Duration::from_secs(10),
+        max_backoff: Duration::from_secs(60),
+        max_age: Duration::from_secs(3600),
+        network_max_age: BTreeMap::new(),
+    };
+
+    let delays: Vec<i64> = (1..=5)
+        .map(|checks| schedule.next_check_ms(0, checks))
+        .collect();
+    assert_eq!(delays, vec![10_000, 20_000, 40_000, 60_000, 60_000]);
+    // Far past the cap the doubling must not overflow
+    assert_eq!(schedule.next_check_ms(1_000, 200), 61_000);
+}
+
+#[tokio::test]
+async fn test_due_tx_refs_newest_first() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    insert_anchored(&pool, "oldest", 3 * HOUR_MS).await;
+    insert_anchored(&pool, "middle", 2 * HOUR_MS).await;
+    insert_anchored(&pool, "newest", HOUR_MS).await;
+    insert_anchored(&pool, "not-due", 0).await;
+    sqlx::query("UPDATE outbox_tx_refs SET next_check_ms=?1 WHERE job_id='not-due'")
+        .bind(now_ms() + HOUR_MS)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let due = pool.fetch_due_tx_refs(now_ms(), 2).await.unwrap();
+
+    let jobs: Vec<&str> = due.iter().map(|p| p.job_id.as_str()).collect();
+    assert_eq!(jobs, vec!["newest", "middle"]);
+    assert_eq!(due[0].tx.tx_id, "tx-newest");
+    assert_eq!(due[0].checks, 0);
+}
+
+#[tokio::test]
+async fn test_unconfirmed_checks_back_off() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    insert_anchored(&pool, "job-1", 0).await;
+    let anchor = PendingProvider::default();
+    let schedule = ConfirmationSchedule {
+        poll: Duration::from_millis(20),
+        max_backoff: Duration::from_secs(60),
+        max_age: Duration::from_secs(3600),
+        network_max_age: BTreeMap::new(),
+    };
+
+    confirm_for(&pool, &anchor, schedule, Duration::from_millis(600)).await;
+
+    // A fixed 20ms poll would have checked around 30 times
+    let checks = anchor.checks.load(Ordering::SeqCst);
+    assert!((2..=7).contains(&checks), "checked {} times", checks);
+    let row = sqlx::query("SELECT checks, next_check_ms, dropped_ms FROM outbox_tx_refs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<i64, _>(0), checks as i64);
+    assert!(row.get::<i64, _>(1) > now_ms());
+    assert!(row.get::<Option<i64>, _>(2).is_none());
+}
+
+#[tokio::test]
+async fn test_expired_tx_is_dropped_and_job_requeued() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    insert_anchored(&pool, "job-1", 2 * HOUR_MS).await;
+    let anchor = PendingProvider::default();
+
+    confirm_for(
+        &pool,
+        &anchor,
+        Duration::from_millis(10).into(),
+        Duration::from_millis(200),
+    )
+    .await;
+
+    assert_eq!(anchor.checks.load(Ordering::SeqCst), 1);
+    let row = sqlx::query("SELECT status, last_error, failure_category FROM outbox_jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<String, _>(0), "queued");
+    assert!(row.get::<String, _>(1).starts_with(TX_DROPPED_ERROR));
+    assert_eq!(row.get::<String, _>(2), "dropped");
+    let dropped: Option<i64> = sqlx::query_scalar("SELECT dropped_ms FROM outbox_tx_refs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(dropped.is_some());
+    assert!(pool
+        .fetch_due_tx_refs(now_ms(), 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Anchoring again replaces the dropped transaction with a fresh one
+    let mut jp = SqliteJobProvider::new(pool.clone());
+    let worker = tokio::spawn(async move {
+        run_job_loop(&mut jp, &EtherlinkProviderStub, Duration::from_millis(10)).await;
+    });
+    timeout(Duration::from_secs(5), async {
+        loop {
+            let status: String = sqlx::query_scalar("SELECT status FROM outbox_jobs")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            if status == "done" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("job should be anchored again");
+    worker.abort();
+    let due = pool.fetch_due_tx_refs(now_ms(), 10).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].tx.tx_id, "fake:abcd1234");
+    assert_eq!(due[0].checks, 0);
+}
+
+#[tokio::test]
+async fn test_rpc_outage_does_not_drop() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    insert_anchored(&pool, "job-1", 2 * HOUR_MS).await;
+    let anchor = PendingProvider {
+        outage: true,
+        ..Default::default()
+    };
+
+    confirm_for(
+        &pool,
+        &anchor,
+        Duration::from_millis(10).into(),
+        Duration::from_millis(200),
+    )
+    .await;
+
+    assert!(anchor.checks.load(Ordering::SeqCst) >= 1);
+    let status: String = sqlx::query_scalar("SELECT status FROM outbox_jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "done");
+    let row = sqlx::query("SELECT checks, dropped_ms FROM outbox_tx_refs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(row.get::<i64, _>(0) >= 1);
+    assert!(row.get::<Option<i64>, _>(1).is_none());
+}
+
+/// Job ids whose transactions were dropped
+async fn dropped_jobs(pool: &Pool<Sqlite>) -> Vec<String> {
+    sqlx::query_scalar(
+        "SELECT job_id FROM outbox_tx_refs WHERE dropped_ms IS NOT NULL ORDER BY job_id",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_max_age_per_network() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    insert_anchored_on(&pool, "evm", "etherlink", 2 * HOUR_MS).await;
+    // Six blocks can take well over an hour
+    insert_anchored_on(&pool, "btc", "bitcoin", 2 * HOUR_MS).await;
+    insert_anchored_on(&pool, "btc-stale", "bitcoin", 7 * HOUR_MS).await;
+    let anchor = PendingProvider::default();
+
+    confirm_for(
+        &pool,
+        &anchor,
+        Duration::from_millis(10).into(),
+        Duration::from_millis(200),
+    )
+    .await;
+
+    assert_eq!(dropped_jobs(&pool).await, vec!["btc-stale", "evm"]);
+}
+
+#[tokio::test]
+async fn test_expired_tx_still_known_is_kept() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    insert_anchored(&pool, "job-1", 2 * HOUR_MS).await;
+    let waiting = PendingProvider {
+        known: Some(true),
+        ..Default::default()
+    };
+
+    confirm_for(
+        &pool,
+        &waiting,
+        Duration::from_millis(10).into(),
+        Duration::from_millis(200),
+    )
+    .await;
+
+    // Still in the mempool: anchoring again could pay for it twice
+    assert!(dropped_jobs(&pool).await.is_empty());
+    let status: String = sqlx::query_scalar("SELECT status FROM outbox_jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "done");
+
+    // Once the chain has forgotten it, it is dropped and the job requeued
+    sqlx::query("UPDATE outbox_tx_refs SET next_check_ms=0")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let gone = PendingProvider {
+        known: Some(false),
+        ..Default::default()
+    };
+    confirm_for(
+        &pool,
+        &gone,
+        Duration::from_millis(10).into(),
+        Duration::from_millis(200),
+    )
+    .await;
+    assert_eq!(dropped_jobs(&pool).await, vec!["job-1"]);
+}
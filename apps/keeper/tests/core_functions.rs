@@ -1,9 +1,9 @@
 use chrono::Utc;
 use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
-use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord, SignedAnchorTx};
 use phoenix_keeper::{
-    run_confirmation_loop, run_job_loop, EvidenceJob, JobError, JobProvider, JobProviderExt,
-    SqliteJobProvider,
+    ensure_schema, run_confirmation_loop, run_job_loop, EvidenceJob, JobError, JobProvider,
+    JobProviderExt, SqliteJobProvider,
 };
 use serial_test::serial;
 use std::sync::{Arc, Mutex};
@@ -79,6 +79,14 @@ impl JobProviderExt for MockJobProvider {
     ) -> Result<(), JobError> {
         Ok(())
     }
+
+    async fn record_intent(&mut self, _id: &str, _signed: &SignedAnchorTx) -> Result<(), JobError> {
+        Ok(())
+    }
+
+    async fn pending_intent(&mut self, _id: &str) -> Result<Option<SignedAnchorTx>, JobError> {
+        Ok(None)
+    }
 }
 
 #[derive(Clone)]
@@ -142,6 +150,8 @@ async fn test_run_job_loop_success() {
         id: "test-job-1".to_string(),
         payload_sha256: "abcd1234".to_string(),
         created_ms: Utc::now().timestamp_millis(),
+        payload_mime: None,
+        metadata: None,
     });
 
     let mut provider = provider;
@@ -171,6 +181,8 @@ async fn test_run_job_loop_anchor_failure() {
         id: "test-job-1".to_string(),
         payload_sha256: "abcd1234".to_string(),
         created_ms: Utc::now().timestamp_millis(),
+        payload_mime: None,
+        metadata: None,
     });
 
     let mut provider = provider;
@@ -227,20 +239,7 @@ async fn test_run_confirmation_loop_success() {
         .await
         .unwrap();
 
-    // Create schema
-    sqlx::query(
-        "CREATE TABLE IF NOT EXISTS outbox_tx_refs (
-            job_id TEXT PRIMARY KEY,
-            network TEXT NOT NULL,
-            chain TEXT NOT NULL,
-            tx_id TEXT NOT NULL,
-            confirmed INTEGER NOT NULL DEFAULT 0,
-            timestamp INTEGER
-        )",
-    )
-    .execute(&pool)
-    .await
-    .unwrap();
+    ensure_schema(&pool).await.unwrap();
 
     // Insert a test unconfirmed transaction
     sqlx::query(
@@ -288,20 +287,7 @@ async fn test_run_confirmation_loop_anchor_failure() {
         .await
         .unwrap();
 
-    // Create schema
-    sqlx::query(
-        "CREATE TABLE IF NOT EXISTS outbox_tx_refs (
-            job_id TEXT PRIMARY KEY,
-            network TEXT NOT NULL,
-            chain TEXT NOT NULL,
-            tx_id TEXT NOT NULL,
-            confirmed INTEGER NOT NULL DEFAULT 0,
-            timestamp INTEGER
-        )",
-    )
-    .execute(&pool)
-    .await
-    .unwrap();
+    ensure_schema(&pool).await.unwrap();
 
     // Insert a test unconfirmed transaction
     sqlx::query(
@@ -350,38 +336,7 @@ async fn test_sqlite_job_provider() {
         .await
         .unwrap();
 
-    // Create schema with all required columns
-    sqlx::query(
-        "CREATE TABLE IF NOT EXISTS outbox_jobs (
-            id TEXT PRIMARY KEY,
-            payload_sha256 TEXT NOT NULL,
-            status TEXT NOT NULL DEFAULT 'queued',
-            attempts INTEGER NOT NULL DEFAULT 0,
-            last_error TEXT,
-            created_ms INTEGER NOT NULL,
-            updated_ms INTEGER NOT NULL,
-            next_attempt_ms INTEGER NOT NULL DEFAULT 0
-        )",
-    )
-    .execute(&pool)
-    .await
-    .unwrap();
-
-    // Create outbox_tx_refs table
-    sqlx::query(
-        "CREATE TABLE IF NOT EXISTS outbox_tx_refs (
-            job_id TEXT NOT NULL,
-            network TEXT NOT NULL,
-            chain TEXT NOT NULL,
-            tx_id TEXT NOT NULL,
-            confirmed INTEGER NOT NULL,
-            timestamp INTEGER,
-            PRIMARY KEY (job_id, network, chain)
-        )",
-    )
-    .execute(&pool)
-    .await
-    .unwrap();
+    ensure_schema(&pool).await.unwrap();
 
     // Insert a test job
     let now = Utc::now().timestamp_millis();
@@ -459,9 +414,71 @@ fn test_evidence_job() {
         id: "test-job".to_string(),
         payload_sha256: "abcd1234".to_string(),
         created_ms: now,
+        payload_mime: None,
+        metadata: None,
     };
 
     assert_eq!(job.id, "test-job");
     assert_eq!(job.payload_sha256, "abcd1234");
     assert_eq!(job.created_ms, now);
 }
+
+#[test]
+fn test_check_signer_key() {
+    use phoenix_keeper::config::check_signer_key;
+
+    // Hardhat/Anvil default account #0
+    let key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    let address = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+    assert_eq!(
+        check_signer_key(Some(key), Some(address))
+            .unwrap()
+            .as_deref(),
+        Some(address)
+    );
+    assert_eq!(
+        check_signer_key(Some(key), None).unwrap().as_deref(),
+        Some(address)
+    );
+    assert_eq!(check_signer_key(None, None).unwrap(), None);
+
+    // Mis-pasted key: wrong address, truncated, or missing
+    assert!(check_signer_key(
+        Some(key),
+        Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e")
+    )
+    .is_err());
+    assert!(check_signer_key(Some(&key[..40]), None).is_err());
+    assert!(check_signer_key(None, Some(address)).is_err());
+}
+
+#[test]
+fn test_screen_address_against_policy_file() {
+    use phoenix_keeper::config::screen_address;
+
+    let signer = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("policy.csv");
+    std::fs::write(
+        &path,
+        format!(
+            "list,chain,address,reason\ndeny,evm,{},compromised\n",
+            signer
+        ),
+    )
+    .unwrap();
+
+    let decision = screen_address(&path, "etherlink", signer).unwrap();
+    assert!(!decision.permitted);
+    assert!(
+        screen_address(
+            &path,
+            "etherlink",
+            "0x742d35Cc6634C0532925a3b844Bc454e4438f44e"
+        )
+        .unwrap()
+        .permitted
+    );
+    assert!(screen_address(&dir.path().join("missing.csv"), "etherlink", signer).is_err());
+}
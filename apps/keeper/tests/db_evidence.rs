@@ -1,10 +1,16 @@
 use anchor_etherlink::EtherlinkProviderStub;
 use async_trait::async_trait;
+use phoenix_common::testing::sqlite_test_pool;
 use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
-use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use phoenix_evidence::hash::record_hash_hex;
+use phoenix_evidence::model::{
+    ChainTxRef, DigestAlgo, EvidenceDigest, EvidenceRecord, SignedAnchorTx,
+};
 use phoenix_keeper::{
-    ensure_schema, EvidenceJob, JobError, JobProvider, JobProviderExt, SqliteJobProvider,
+    anchor_claimed_job, ensure_schema, EvidenceJob, JobError, JobProvider, JobProviderExt,
+    SqliteJobProvider,
 };
+use serde_json::json;
 use sqlx::{sqlite::SqlitePoolOptions, Row};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -147,6 +153,8 @@ impl JobProvider for TestSqliteJobProvider {
                 id,
                 payload_sha256: row.get(1),
                 created_ms: row.get(2),
+                payload_mime: None,
+                metadata: None,
             }))
         } else {
             Ok(None)
@@ -237,6 +245,14 @@ impl JobProviderExt for TestSqliteJobProvider {
         .await?;
         Ok(())
     }
+
+    async fn record_intent(&mut self, _id: &str, _signed: &SignedAnchorTx) -> Result<(), JobError> {
+        Ok(())
+    }
+
+    async fn pending_intent(&mut self, _id: &str) -> Result<Option<SignedAnchorTx>, JobError> {
+        Ok(None)
+    }
 }
 
 // Test provider that fails once then succeeds
@@ -410,3 +426,77 @@ async fn test_db_evidence_retry_flow() {
     let count: i64 = tx_ref_count.get("count");
     assert_eq!(count, 1);
 }
+
+// Anchors the record hash the way the chain providers do
+struct RecordHashProvider;
+
+#[async_trait]
+impl AnchorProvider for RecordHashProvider {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        Ok(ChainTxRef {
+            network: "test".to_string(),
+            chain: "testnet".to_string(),
+            tx_id: record_hash_hex(evidence),
+            confirmed: false,
+            timestamp: Some(chrono::Utc::now()),
+        })
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        Ok(tx.clone())
+    }
+}
+
+#[tokio::test]
+async fn test_db_evidence_anchors_submitted_record() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+
+    let created_ms = 1_700_000_000_123;
+    let metadata = json!({ "sortie": 7, "classification": "internal" });
+    for (id, metadata) in [
+        ("with-record", Some(metadata.to_string())),
+        ("bad-metadata", Some("{not json".to_string())),
+    ] {
+        sqlx::query(
+            "INSERT INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms, next_attempt_ms, payload_mime, metadata) VALUES (?1, 'abcd1234', 'queued', 0, ?2, ?2, 0, 'application/pdf', ?3)",
+        )
+        .bind(id)
+        .bind(created_ms)
+        .bind(metadata)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    let mut jp = SqliteJobProvider::new(pool.clone());
+
+    // The anchored hash can be recomputed from the evidence as submitted
+    let job = jp.claim("with-record", false).await.unwrap().unwrap();
+    let tx = anchor_claimed_job(&mut jp, &RecordHashProvider, &job)
+        .await
+        .unwrap();
+    let submitted = EvidenceRecord {
+        id: "with-record".to_string(),
+        created_at: chrono::DateTime::from_timestamp_millis(created_ms).unwrap(),
+        digest: EvidenceDigest {
+            algo: DigestAlgo::Sha256,
+            hex: "abcd1234".to_string(),
+        },
+        payload_mime: Some("application/pdf".to_string()),
+        metadata,
+    };
+    assert_eq!(tx.tx_id, record_hash_hex(&submitted));
+
+    // Metadata that no longer parses cannot be anchored faithfully
+    let job = jp.claim("bad-metadata", false).await.unwrap().unwrap();
+    assert!(matches!(
+        anchor_claimed_job(&mut jp, &RecordHashProvider, &job).await,
+        Err(AnchorError::Invalid(_))
+    ));
+    let status: String =
+        sqlx::query_scalar("SELECT status FROM outbox_jobs WHERE id='bad-metadata'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "failed");
+}
@@ -0,0 +1,138 @@
+use phoenix_common::outbox::HELD_NEXT_ATTEMPT_MS;
+use phoenix_common::testing::sqlite_test_pool;
+use phoenix_keeper::{JobProvider, SqliteJobProvider, TenantWeights};
+use sqlx::{Pool, Sqlite};
+use std::collections::BTreeMap;
+use tempfile::NamedTempFile;
+
+/// Queue `count` jobs named `{tenant}-{n}` at `priority`, created from `created_ms` on
+async fn queue_jobs(
+    pool: &Pool<Sqlite>,
+    tenant: Option<&str>,
+    priority: i64,
+    count: usize,
+    created_ms: i64,
+) {
+    for n in 0..count {
+        sqlx::query(
+            "INSERT INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms, next_attempt_ms, priority, tenant) VALUES (?1, 'abcd1234', 'queued', 0, ?2, ?2, 0, ?3, ?4)",
+        )
+        .bind(format!("{}-{}", tenant.unwrap_or("none"), n))
+        .bind(created_ms + n as i64)
+        .bind(priority)
+        .bind(tenant)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+}
+
+/// Claim `count` jobs and return the tenant part of each job id, in claim order
+async fn claim(jp: &mut SqliteJobProvider, count: usize) -> Vec<String> {
+    let mut tenants = Vec::new();
+    for _ in 0..count {
+        let job = jp
+            .fetch_next()
+            .await
+            .unwrap()
+            .expect("a job should be queued");
+        jp.mark_done(&job.id).await.unwrap();
+        tenants.push(job.id.rsplit_once('-').unwrap().0.to_string());
+    }
+    tenants
+}
+
+#[tokio::test]
+async fn test_higher_priority_claimed_first() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    queue_jobs(&pool, Some("backfill"), -5, 3, 0).await;
+    queue_jobs(&pool, None, 0, 1, 100).await;
+    queue_jobs(&pool, Some("urgent"), 5, 1, 200).await;
+
+    let mut jp = SqliteJobProvider::new(pool.clone());
+
+    assert_eq!(
+        claim(&mut jp, 5).await,
+        vec!["urgent", "none", "backfill", "backfill", "backfill"]
+    );
+}
+
+#[tokio::test]
+async fn test_tenants_share_a_lane() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    // A large, older backfill does not hold up a newer tenant at the same priority
+    queue_jobs(&pool, Some("bulk"), 0, 20, 0).await;
+    queue_jobs(&pool, Some("acme"), 0, 3, 1000).await;
+
+    let mut jp = SqliteJobProvider::new(pool.clone());
+
+    assert_eq!(
+        claim(&mut jp, 7).await,
+        vec!["bulk", "acme", "bulk", "acme", "bulk", "acme", "bulk"]
+    );
+}
+
+#[tokio::test]
+async fn test_weights_set_each_tenants_share() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    queue_jobs(&pool, Some("bulk"), 0, 20, 0).await;
+    queue_jobs(&pool, Some("acme"), 0, 20, 1000).await;
+
+    let weights = TenantWeights::new(BTreeMap::from([("acme".to_string(), 3)]));
+    let mut jp = SqliteJobProvider::new(pool.clone()).with_tenant_weights(weights);
+
+    let claimed = claim(&mut jp, 12).await;
+    assert_eq!(claimed.iter().filter(|t| *t == "acme").count(), 9);
+    assert_eq!(claimed.iter().filter(|t| *t == "bulk").count(), 3);
+}
+
+#[tokio::test]
+async fn test_returning_tenant_gets_no_backlog_of_credit() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let mut jp = SqliteJobProvider::new(pool.clone());
+
+    // acme is served once, then idles while bulk is served alone for a while
+    queue_jobs(&pool, Some("acme"), 0, 1, 0).await;
+    claim(&mut jp, 1).await;
+    queue_jobs(&pool, Some("bulk"), 0, 30, 100).await;
+    claim(&mut jp, 10).await;
+
+    // Back with a burst, acme shares from now on instead of catching up on the last ten
+    sqlx::query("DELETE FROM outbox_jobs WHERE tenant='acme'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    queue_jobs(&pool, Some("acme"), 0, 10, 1000).await;
+    let claimed = claim(&mut jp, 6).await;
+    assert_eq!(claimed.iter().filter(|t| *t == "acme").count(), 3);
+}
+
+#[tokio::test]
+async fn test_held_and_scheduled_jobs_are_not_claimed() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    queue_jobs(&pool, Some("staged"), 5, 1, 0).await;
+    queue_jobs(&pool, Some("tonight"), 5, 1, 0).await;
+    queue_jobs(&pool, Some("now"), 0, 1, 100).await;
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    sqlx::query("UPDATE outbox_jobs SET next_attempt_ms=?1 WHERE tenant='staged'")
+        .bind(HELD_NEXT_ATTEMPT_MS)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE outbox_jobs SET next_attempt_ms=?1 WHERE tenant='tonight'")
+        .bind(now_ms + 3_600_000)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let mut jp = SqliteJobProvider::new(pool.clone());
+
+    // Priority does not pull a job forward before it is due
+    assert_eq!(claim(&mut jp, 1).await, vec!["now"]);
+    assert!(jp.fetch_next().await.unwrap().is_none());
+}
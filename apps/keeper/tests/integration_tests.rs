@@ -9,7 +9,8 @@ use phoenix_evidence::{
     model::{ChainTxRef, DigestAlgo, EvidenceDigest, EvidenceRecord},
 };
 use phoenix_keeper::{
-    run_confirmation_loop, run_job_loop, JobProvider, JobProviderExt, SqliteJobProvider,
+    ensure_schema, run_confirmation_loop, run_job_loop, JobProvider, JobProviderExt,
+    SqliteJobProvider,
 };
 use serde_json::json;
 use sqlx::{sqlite::SqlitePoolOptions, Row};
@@ -91,42 +92,7 @@ async fn setup_test_db() -> sqlx::Pool<sqlx::Sqlite> {
         .await
         .unwrap();
 
-    // Create schema manually to ensure it's created
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS outbox_jobs (
-            id TEXT PRIMARY KEY,
-            payload_sha256 TEXT NOT NULL,
-            status TEXT NOT NULL DEFAULT 'queued',
-            attempts INTEGER NOT NULL DEFAULT 0,
-            last_error TEXT,
-            created_ms INTEGER NOT NULL,
-            updated_ms INTEGER NOT NULL,
-            next_attempt_ms INTEGER NOT NULL DEFAULT 0
-        );
-        "#,
-    )
-    .execute(&pool)
-    .await
-    .unwrap();
-
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS outbox_tx_refs (
-            job_id TEXT NOT NULL,
-            network TEXT NOT NULL,
-            chain TEXT NOT NULL,
-            tx_id TEXT NOT NULL,
-            confirmed INTEGER NOT NULL,
-            timestamp INTEGER,
-            PRIMARY KEY (job_id, network, chain)
-        );
-        "#,
-    )
-    .execute(&pool)
-    .await
-    .unwrap();
-
+    ensure_schema(&pool).await.unwrap();
     pool
 }
 
@@ -0,0 +1,198 @@
+use phoenix_common::testing::{sqlite_test_pool, TestJob};
+use phoenix_keeper::{JobProvider, JobProviderExt, LeaseReaper, SqliteJobProvider};
+use sqlx::{Pool, Row, Sqlite};
+use std::time::Duration;
+use tempfile::NamedTempFile;
+
+/// (attempt, worker, outcome, category, error) rows for a job, oldest first
+async fn attempts(
+    pool: &Pool<Sqlite>,
+    id: &str,
+) -> Vec<(i64, Option<String>, String, Option<String>, Option<String>)> {
+    sqlx::query(
+        "SELECT attempt, worker, outcome, category, error FROM outbox_job_attempts WHERE job_id = ?1 ORDER BY id",
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+    .into_iter()
+    .map(|row| (row.get(0), row.get(1), row.get(2), row.get(3), row.get(4)))
+    .collect()
+}
+
+/// Release a job that is waiting out its backoff
+async fn make_due(pool: &Pool<Sqlite>, id: &str) {
+    sqlx::query("UPDATE outbox_jobs SET next_attempt_ms = 0 WHERE id = ?1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_attempt_history_through_dead_letter() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+
+    let lease = Duration::from_millis(50);
+    let mut crashed = SqliteJobProvider::with_lease(pool.clone(), "worker-a", lease);
+    let mut jp = SqliteJobProvider::with_lease(pool.clone(), "worker-b", Duration::from_secs(60));
+
+    crashed.fetch_next().await.unwrap().unwrap();
+    tokio::time::sleep(lease * 2).await;
+    pool.reap_expired_leases().await.unwrap();
+
+    jp.fetch_next().await.unwrap().unwrap();
+    jp.mark_failed_or_backoff("job-1", "network error: connection reset", true)
+        .await
+        .unwrap();
+    make_due(&pool, "job-1").await;
+
+    jp.fetch_next().await.unwrap().unwrap();
+    jp.mark_failed_or_backoff("job-1", "invalid state: digest rejected", false)
+        .await
+        .unwrap();
+
+    let row = sqlx::query("SELECT status, failure_category FROM outbox_jobs WHERE id = 'job-1'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<String, _>(0), "failed");
+    assert_eq!(row.get::<Option<String>, _>(1).as_deref(), Some("invalid"));
+
+    let history = attempts(&pool, "job-1").await;
+    assert_eq!(history.len(), 3);
+    assert_eq!(
+        history[0],
+        (
+            1,
+            Some("worker-a".into()),
+            "lease_expired".into(),
+            Some("lease_expired".into()),
+            Some("lease expired".into())
+        )
+    );
+    assert_eq!(history[1].0, 2);
+    assert_eq!(history[1].2, "retry");
+    assert_eq!(history[1].3.as_deref(), Some("network"));
+    assert_eq!(history[2].0, 3);
+    assert_eq!(history[2].1.as_deref(), Some("worker-b"));
+    assert_eq!(history[2].2, "failed");
+    assert_eq!(history[2].3.as_deref(), Some("invalid"));
+}
+
+#[tokio::test]
+async fn test_success_clears_failure_category() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+
+    let mut jp = SqliteJobProvider::new(pool.clone());
+    jp.fetch_next().await.unwrap().unwrap();
+    jp.mark_failed_or_backoff("job-1", "provider: nonce too low", true)
+        .await
+        .unwrap();
+    make_due(&pool, "job-1").await;
+    jp.fetch_next().await.unwrap().unwrap();
+    jp.mark_done("job-1").await.unwrap();
+
+    let category: Option<String> =
+        sqlx::query_scalar("SELECT failure_category FROM outbox_jobs WHERE id = 'job-1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(category, None);
+    let outcomes: Vec<String> = attempts(&pool, "job-1")
+        .await
+        .into_iter()
+        .map(|a| a.2)
+        .collect();
+    assert_eq!(outcomes, vec!["retry", "done"]);
+}
+
+#[tokio::test]
+async fn test_workers_only_claim_their_provider() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("for-solana")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .with_provider("solana")
+        .insert(&pool)
+        .await;
+    TestJob::new("unrouted")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+
+    let mut etherlink = SqliteJobProvider::new(pool.clone()).with_provider("etherlink");
+    let job = etherlink.fetch_next().await.unwrap().unwrap();
+    assert_eq!(job.id, "unrouted");
+    assert!(etherlink.fetch_next().await.unwrap().is_none());
+
+    let mut solana = SqliteJobProvider::new(pool.clone()).with_provider("solana");
+    assert_eq!(solana.fetch_next().await.unwrap().unwrap().id, "for-solana");
+}
+
+#[tokio::test]
+async fn test_attempts_record_timing_and_endpoint() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+    TestJob::new("job-2")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+
+    let mut jp = SqliteJobProvider::new(pool.clone())
+        .with_provider("etherlink")
+        .with_endpoint("https://rpc.example");
+    jp.fetch_next().await.unwrap().unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    jp.mark_failed_or_backoff("job-1", "network error: timeout", true)
+        .await
+        .unwrap();
+
+    // Reaped attempts are timed from the claim
+    let lease = Duration::from_millis(50);
+    let mut crashed = SqliteJobProvider::with_lease(pool.clone(), "worker-a", lease);
+    crashed.fetch_next().await.unwrap().unwrap();
+    tokio::time::sleep(lease * 2).await;
+    pool.reap_expired_leases().await.unwrap();
+
+    let rows = sqlx::query(
+        "SELECT job_id, started_ms, finished_ms, latency_ms, provider, endpoint FROM outbox_job_attempts ORDER BY id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(rows.len(), 2);
+
+    let started: i64 = rows[0].get(1);
+    let finished: i64 = rows[0].get(2);
+    assert_eq!(rows[0].get::<String, _>(0), "job-1");
+    assert!(rows[0].get::<i64, _>(3) >= 20);
+    assert_eq!(rows[0].get::<i64, _>(3), finished - started);
+    assert_eq!(
+        rows[0].get::<Option<String>, _>(4).as_deref(),
+        Some("etherlink")
+    );
+    assert_eq!(
+        rows[0].get::<Option<String>, _>(5).as_deref(),
+        Some("https://rpc.example")
+    );
+
+    assert_eq!(rows[1].get::<String, _>(0), "job-2");
+    assert!(rows[1].get::<i64, _>(3) >= 100);
+    assert_eq!(rows[1].get::<Option<String>, _>(5), None);
+}
@@ -0,0 +1,237 @@
+use anchor_etherlink::EtherlinkProviderStub;
+use phoenix_common::testing::{sqlite_test_pool, TestJob};
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use phoenix_keeper::{
+    run_job_loop_with_control, run_reaper_loop, JobProvider, JobProviderExt, LeaseReaper,
+    LoopControl, SqliteJobProvider,
+};
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashSet;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+
+const DIGEST: &str = "deadbeefcafebabe1234567890abcdef1234567890abcdef1234567890abcdef";
+
+/// Anchors like the stub, but only after `delay`
+struct SlowProvider {
+    delay: Duration,
+}
+
+#[async_trait::async_trait]
+impl AnchorProvider for SlowProvider {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        tokio::time::sleep(self.delay).await;
+        EtherlinkProviderStub.anchor(evidence).await
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        EtherlinkProviderStub.confirm(tx).await
+    }
+}
+
+/// (status, attempts, lease_owner) for a job
+async fn job_lease(pool: &Pool<Sqlite>, id: &str) -> (String, i64, Option<String>) {
+    let row = sqlx::query("SELECT status, attempts, lease_owner FROM outbox_jobs WHERE id = ?1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    (row.get(0), row.get(1), row.get(2))
+}
+
+#[tokio::test]
+async fn test_crashed_worker_job_is_reaped_and_reclaimed() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1")
+        .with_digest(DIGEST)
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+
+    let lease = Duration::from_millis(100);
+    let mut crashed = SqliteJobProvider::with_lease(pool.clone(), "worker-a", lease);
+    let mut survivor = SqliteJobProvider::with_lease(pool.clone(), "worker-b", lease);
+
+    let job = crashed.fetch_next().await.unwrap().unwrap();
+    assert_eq!(
+        job_lease(&pool, &job.id).await,
+        ("in_progress".into(), 1, Some("worker-a".into()))
+    );
+    // Worker A dies mid-anchor without reporting back
+    drop(crashed);
+
+    // The lease is still live, so nothing is reaped or claimable yet
+    assert_eq!(pool.reap_expired_leases().await.unwrap(), 0);
+    assert!(survivor.fetch_next().await.unwrap().is_none());
+
+    tokio::time::sleep(lease * 2).await;
+    assert_eq!(pool.reap_expired_leases().await.unwrap(), 1);
+    let row = sqlx::query(
+        "SELECT status, last_error, lease_until_ms FROM outbox_jobs WHERE id = 'job-1'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(row.get::<String, _>(0), "queued");
+    assert_eq!(
+        row.get::<Option<String>, _>(1).as_deref(),
+        Some("lease expired")
+    );
+    assert_eq!(row.get::<Option<i64>, _>(2), None);
+
+    let job = survivor.fetch_next().await.unwrap().unwrap();
+    assert_eq!(job.id, "job-1");
+    assert_eq!(
+        job_lease(&pool, &job.id).await,
+        ("in_progress".into(), 2, Some("worker-b".into()))
+    );
+    survivor.mark_done(&job.id).await.unwrap();
+    assert_eq!(job_lease(&pool, &job.id).await, ("done".into(), 2, None));
+}
+
+#[tokio::test]
+async fn test_stale_worker_cannot_requeue_reclaimed_job() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1")
+        .with_digest(DIGEST)
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+
+    let lease = Duration::from_millis(50);
+    let mut slow = SqliteJobProvider::with_lease(pool.clone(), "worker-a", lease);
+    let mut other =
+        SqliteJobProvider::with_lease(pool.clone(), "worker-b", Duration::from_secs(60));
+
+    let job = slow.fetch_next().await.unwrap().unwrap();
+    tokio::time::sleep(lease * 2).await;
+    pool.reap_expired_leases().await.unwrap();
+    other.fetch_next().await.unwrap().unwrap();
+
+    // Worker A comes back after its lease was handed on; its failure must not win
+    slow.mark_failed_or_backoff(&job.id, "rpc timeout", true)
+        .await
+        .unwrap();
+    slow.mark_failed(&job.id, "rejected").await.unwrap();
+    assert_eq!(
+        job_lease(&pool, &job.id).await,
+        ("in_progress".into(), 2, Some("worker-b".into()))
+    );
+
+    other
+        .mark_failed_or_backoff(&job.id, "rpc timeout", true)
+        .await
+        .unwrap();
+    assert_eq!(job_lease(&pool, &job.id).await, ("queued".into(), 2, None));
+}
+
+#[tokio::test]
+async fn test_concurrent_workers_claim_each_job_once() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    for i in 0..40 {
+        TestJob::new(&format!("job-{:02}", i))
+            .with_digest(DIGEST)
+            .with_created_ms(now_ms + i)
+            .insert(&pool)
+            .await;
+    }
+
+    let workers: Vec<_> = (0..4)
+        .map(|i| {
+            let mut jp = SqliteJobProvider::with_lease(
+                pool.clone(),
+                format!("worker-{}", i),
+                Duration::from_secs(60),
+            );
+            tokio::spawn(async move {
+                let mut claimed = Vec::new();
+                while let Some(job) = jp.fetch_next().await.unwrap() {
+                    jp.mark_done(&job.id).await.unwrap();
+                    claimed.push(job.id);
+                }
+                claimed
+            })
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    for worker in workers {
+        for id in worker.await.unwrap() {
+            assert!(seen.insert(id.clone()), "{} claimed twice", id);
+        }
+    }
+    assert_eq!(seen.len(), 40);
+
+    let attempts: i64 = sqlx::query_scalar("SELECT SUM(attempts) FROM outbox_jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(attempts, 40);
+}
+
+#[tokio::test]
+async fn test_lease_is_renewed_while_anchoring() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1")
+        .with_digest(DIGEST)
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+
+    // The anchor takes several leases; the reaper runs throughout
+    let lease = Duration::from_millis(150);
+    let shutdown = CancellationToken::new();
+    let reaper_pool = pool.clone();
+    let reaper_shutdown = shutdown.clone();
+    let reaper = tokio::spawn(async move {
+        run_reaper_loop(&reaper_pool, Duration::from_millis(20), &reaper_shutdown).await;
+    });
+    let control = LoopControl::new(shutdown.clone(), Duration::from_secs(5));
+    let mut jp = SqliteJobProvider::with_lease(pool.clone(), "worker-a", lease);
+    let worker = tokio::spawn(async move {
+        run_job_loop_with_control(
+            &mut jp,
+            &SlowProvider {
+                delay: Duration::from_millis(600),
+            },
+            Duration::from_millis(10),
+            &control,
+        )
+        .await;
+    });
+
+    timeout(Duration::from_secs(5), async {
+        while job_lease(&pool, "job-1").await.0 != "done" {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("job should be anchored");
+
+    // Claimed once and never reaped
+    assert_eq!(job_lease(&pool, "job-1").await, ("done".into(), 1, None));
+    let outcomes: Vec<String> =
+        sqlx::query_scalar("SELECT outcome FROM outbox_job_attempts WHERE job_id = 'job-1'")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(outcomes, vec!["done".to_string()]);
+
+    // Both loops return once shut down
+    shutdown.cancel();
+    timeout(Duration::from_secs(1), async {
+        worker.await.unwrap();
+        reaper.await.unwrap();
+    })
+    .await
+    .expect("loops should stop on shutdown");
+}
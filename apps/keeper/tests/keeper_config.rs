@@ -0,0 +1,370 @@
+use anchor_etherlink::EtherlinkProviderStub;
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::model::{ChainTxRef, DigestAlgo, EvidenceDigest, EvidenceRecord};
+use phoenix_keeper::config::{ConfigError, KeeperConfig, ProviderConfig};
+use phoenix_keeper::providers::{build_anchor_provider, MultiAnchorProvider, SharedAnchor};
+use serial_test::serial;
+use std::sync::Arc;
+use std::time::Duration;
+
+const ENV_VARS: &[&str] = &[
+    "KEEPER_DB_URL",
+    "KEEPER_POLL_MS",
+    "KEEPER_CONFIRM_POLL_MS",
+    "KEEPER_CONFIRM_MAX_BACKOFF_MS",
+    "KEEPER_CONFIRM_MAX_AGE_MS",
+    "KEEPER_CONFIRM_NETWORK_MAX_AGE_MS",
+    "KEEPER_WORKERS",
+    "KEEPER_LEASE_MS",
+    "KEEPER_HTTP_PORT",
+    "KEEPER_ADDRESS_POLICY",
+    "KEEPER_TENANT_WEIGHTS",
+    "KEEPER_PROVIDER",
+    "KEEPER_USE_STUB",
+    "ETHERLINK_ENDPOINT",
+    "ETHERLINK_NETWORK",
+    "ETHERLINK_PRIVATE_KEY",
+    "ETHERLINK_ADDRESS",
+    "SOLANA_ENDPOINT",
+    "SOLANA_NETWORK",
+    "BITCOIN_ENDPOINT",
+    "BITCOIN_NETWORK",
+    "BITCOIN_RPC_USER",
+    "BITCOIN_RPC_PASSWORD",
+    "BITCOIN_MIN_CONFIRMATIONS",
+    "TSA_ENDPOINT",
+    "TSA_NAME",
+    "TSA_TOKEN_DIR",
+    "TSA_TRUSTED_CERTS",
+    "NOTARY_LEDGER",
+    "NOTARY_KEY_FILE",
+];
+
+fn clear_env() {
+    for var in ENV_VARS {
+        std::env::remove_var(var);
+    }
+}
+
+fn invalid(result: Result<KeeperConfig, ConfigError>) -> Vec<String> {
+    match result {
+        Err(ConfigError::Invalid(errors)) => errors,
+        other => panic!("expected validation errors, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_config_file() {
+    let config = KeeperConfig::from_toml(
+        r#"
+        database_url = "postgres://keeper@db/phoenix"
+        job_poll_ms = 250
+        confirmation_poll_ms = 10000
+        confirmation_max_backoff_ms = 120000
+        confirmation_max_age_ms = 1800000
+        workers = 4
+        http_port = 9090
+        provider = "solana"
+
+        [solana]
+        endpoint = "https://api.mainnet-beta.solana.com"
+        network = "mainnet-beta"
+
+        [tenant_weights]
+        acme = 3
+
+        [confirmation_network_max_age_ms]
+        solana = 600000
+        "#,
+    )
+    .unwrap();
+
+    assert!(config.is_postgres());
+    assert_eq!(config.job_poll_interval, Duration::from_millis(250));
+    assert_eq!(config.confirmation_poll_interval, Duration::from_secs(10));
+    assert_eq!(config.confirmation_max_backoff, Duration::from_secs(120));
+    assert_eq!(config.confirmation_max_age, Duration::from_secs(1800));
+    let schedule = config.confirmation_schedule();
+    assert_eq!(schedule.max_age_for("solana"), Duration::from_secs(600));
+    assert_eq!(schedule.max_age_for("etherlink"), Duration::from_secs(1800));
+    // Bitcoin keeps its longer default unless overridden
+    assert_eq!(
+        schedule.max_age_for("bitcoin"),
+        Duration::from_secs(6 * 3600)
+    );
+    assert_eq!(config.workers, 4);
+    assert_eq!(config.http_port, 9090);
+    assert_eq!(config.lease_duration, phoenix_keeper::DEFAULT_LEASE);
+    assert_eq!(config.tenant_weights.weight(Some("acme")), 3);
+    assert_eq!(config.tenant_weights.weight(Some("globex")), 1);
+    assert_eq!(config.tenant_weights.weight(None), 1);
+    assert!(matches!(
+        &config.provider_config,
+        ProviderConfig::Solana { network, .. } if network == "mainnet-beta"
+    ));
+    assert_eq!(config.provider_config.route(), Some("solana"));
+    assert_eq!(
+        config.provider_config.endpoint(),
+        Some("https://api.mainnet-beta.solana.com")
+    );
+}
+
+#[test]
+fn test_config_lists_every_error() {
+    let errors = invalid(KeeperConfig::from_toml(
+        r#"
+        database_url = "mysql://db"
+        workers = 0
+        lease_ms = 0
+        provider = "tezos"
+
+        [etherlink]
+        endpoint = "ftp://node"
+        address = "0x0000000000000000000000000000000000000001"
+
+        [tenant_weights]
+        bulk = 0
+
+        [confirmation_network_max_age_ms]
+        bitcoin = 0
+        "#,
+    ));
+    assert_eq!(errors.len(), 8, "{:?}", errors);
+    for field in [
+        "database_url",
+        "workers",
+        "lease_ms",
+        "provider",
+        "etherlink.endpoint",
+        "etherlink.private_key",
+        "tenant_weights.bulk",
+        "confirmation_network_max_age_ms.bitcoin",
+    ] {
+        assert!(
+            errors.iter().any(|e| e.starts_with(field)),
+            "no error for {} in {:?}",
+            field,
+            errors
+        );
+    }
+
+    let errors = invalid(KeeperConfig::from_toml(r#"provider = "multi""#));
+    assert_eq!(errors.len(), 1);
+    let errors = invalid(KeeperConfig::from_toml(r#"workers = 2"#));
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("provider"));
+
+    assert!(matches!(
+        KeeperConfig::from_toml("job_poll = 5"),
+        Err(ConfigError::Parse(_))
+    ));
+}
+
+#[test]
+#[serial]
+fn test_config_from_env() {
+    clear_env();
+    std::env::set_var("KEEPER_PROVIDER", "multi");
+    std::env::set_var("ETHERLINK_ENDPOINT", "https://node.ghostnet.etherlink.com");
+    std::env::set_var("SOLANA_NETWORK", "devnet");
+    std::env::set_var("KEEPER_HTTP_PORT", "8181");
+    std::env::set_var("KEEPER_TENANT_WEIGHTS", "acme=3, archive=1");
+    std::env::set_var("KEEPER_CONFIRM_NETWORK_MAX_AGE_MS", "bitcoin=43200000");
+    let config = KeeperConfig::from_env().unwrap();
+    assert_eq!(config.http_port, 8181);
+    assert_eq!(
+        config.confirmation_network_max_age["bitcoin"],
+        Duration::from_secs(12 * 3600)
+    );
+    assert_eq!(config.tenant_weights.weight(Some("acme")), 3);
+    // Solana has no explicit endpoint, so only Etherlink takes part
+    assert!(matches!(
+        config.provider_config,
+        ProviderConfig::Multi {
+            etherlink: Some(_),
+            solana: None
+        }
+    ));
+    assert_eq!(config.provider_config.route(), None);
+
+    std::env::set_var("KEEPER_POLL_MS", "5s");
+    std::env::set_var("KEEPER_PROVIDER", "tezos");
+    std::env::set_var("KEEPER_TENANT_WEIGHTS", "acme");
+    let errors = invalid(KeeperConfig::from_env());
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    assert!(errors[0].starts_with("KEEPER_POLL_MS"));
+    assert!(errors[1].starts_with("KEEPER_TENANT_WEIGHTS"));
+    clear_env();
+
+    // The provider must be chosen explicitly, and the old stub switch is refused
+    let errors = invalid(KeeperConfig::from_env());
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].starts_with("provider"));
+    std::env::set_var("KEEPER_USE_STUB", "false");
+    std::env::set_var("KEEPER_PROVIDER", "etherlink");
+    let errors = invalid(KeeperConfig::from_env());
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].starts_with("KEEPER_USE_STUB"));
+    clear_env();
+
+    std::env::set_var("KEEPER_PROVIDER", "stub");
+    let config = KeeperConfig::from_env().unwrap();
+    assert!(matches!(config.provider_config, ProviderConfig::Stub));
+    assert_eq!(config.http_port, 8081);
+    clear_env();
+}
+
+#[test]
+fn test_bitcoin_tsa_and_notary_config() {
+    let config = KeeperConfig::from_toml(
+        r#"
+        provider = "bitcoin"
+
+        [bitcoin]
+        endpoint = "http://127.0.0.1:8332/wallet/phoenix"
+        rpc_user = "keeper"
+        rpc_password = "secret"
+        min_confirmations = 3
+        "#,
+    )
+    .unwrap();
+    assert!(matches!(
+        &config.provider_config,
+        ProviderConfig::Bitcoin { network, min_confirmations: 3, .. } if network == "mainnet"
+    ));
+    assert_eq!(config.provider_config.route(), Some("bitcoin"));
+    assert_eq!(
+        config.provider_config.endpoint(),
+        Some("http://127.0.0.1:8332/wallet/phoenix")
+    );
+
+    let config = KeeperConfig::from_toml(
+        r#"
+        provider = "tsa"
+
+        [tsa]
+        endpoint = "https://freetsa.example/tsr"
+        token_dir = "/var/lib/phoenix/tokens"
+        trusted_certs = ["/etc/phoenix/tsa-ca.pem"]
+        "#,
+    )
+    .unwrap();
+    assert!(matches!(
+        &config.provider_config,
+        ProviderConfig::Tsa { name, trusted_certs, .. } if name == "tsa" && trusted_certs.len() == 1
+    ));
+    assert_eq!(config.provider_config.route(), Some("tsa"));
+
+    // Each provider lists what it cannot do without
+    let errors = invalid(KeeperConfig::from_toml(
+        "provider = \"bitcoin\"\n[bitcoin]\nmin_confirmations = 0",
+    ));
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(errors[0].starts_with("bitcoin.endpoint"));
+    assert!(errors[1].starts_with("bitcoin.min_confirmations"));
+    let errors = invalid(KeeperConfig::from_toml(
+        "provider = \"tsa\"\n[tsa]\nendpoint = \"ftp://tsa\"",
+    ));
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    for field in ["tsa.endpoint", "tsa.token_dir", "tsa.trusted_certs"] {
+        assert!(errors.iter().any(|e| e.starts_with(field)), "{:?}", errors);
+    }
+    let errors = invalid(KeeperConfig::from_toml(r#"provider = "notary""#));
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+}
+
+#[test]
+#[serial]
+fn test_notary_and_tsa_from_env() {
+    clear_env();
+    let dir = tempfile::tempdir().unwrap();
+    let key_path = dir.path().join("notary.key");
+    std::fs::write(&key_path, "07".repeat(32)).unwrap();
+    std::env::set_var("KEEPER_PROVIDER", "notary");
+    std::env::set_var("NOTARY_LEDGER", dir.path().join("ledger.jsonl"));
+    std::env::set_var("NOTARY_KEY_FILE", &key_path);
+    let config = KeeperConfig::from_env().unwrap();
+    assert_eq!(config.provider_config.route(), Some("notary"));
+    assert_eq!(config.provider_config.endpoint(), None);
+    let notary = build_anchor_provider(&config.provider_config).unwrap();
+    assert_eq!(notary.network(), anchor_notary::NOTARY_NETWORK);
+    clear_env();
+
+    std::env::set_var("KEEPER_PROVIDER", "tsa");
+    std::env::set_var("TSA_ENDPOINT", "https://tsa.example");
+    std::env::set_var("TSA_TOKEN_DIR", dir.path().join("tokens"));
+    std::env::set_var(
+        "TSA_TRUSTED_CERTS",
+        format!("{}, ", dir.path().join("missing.pem").display()),
+    );
+    let config = KeeperConfig::from_env().unwrap();
+    assert!(matches!(
+        &config.provider_config,
+        ProviderConfig::Tsa { trusted_certs, .. } if trusted_certs.len() == 1
+    ));
+    // The certificate is read when the provider is built
+    assert!(matches!(
+        build_anchor_provider(&config.provider_config),
+        Err(AnchorError::Provider(msg)) if msg.contains("missing.pem")
+    ));
+    clear_env();
+}
+
+struct FailingProvider;
+
+#[async_trait::async_trait]
+impl AnchorProvider for FailingProvider {
+    async fn anchor(&self, _evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        Err(AnchorError::Network("connection refused".to_string()))
+    }
+
+    async fn confirm(&self, _tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        Err(AnchorError::Network("connection refused".to_string()))
+    }
+}
+
+#[tokio::test]
+async fn test_multi_provider_falls_back_and_routes_confirmations() {
+    let evidence = EvidenceRecord {
+        id: "job-1".to_string(),
+        created_at: chrono::Utc::now(),
+        digest: EvidenceDigest {
+            algo: DigestAlgo::Sha256,
+            hex: "abcd1234".to_string(),
+        },
+        payload_mime: None,
+        metadata: serde_json::json!({}),
+    };
+    let failing: SharedAnchor = Arc::new(FailingProvider);
+    let stub: SharedAnchor = Arc::new(EtherlinkProviderStub);
+
+    let multi = MultiAnchorProvider::new(vec![
+        ("solana".to_string(), failing.clone()),
+        ("etherlink".to_string(), stub),
+    ]);
+    assert_eq!(multi.network(), "solana+etherlink");
+    let tx = multi.anchor(&evidence).await.unwrap();
+    assert_eq!(tx.network, "etherlink");
+    assert!(multi.confirm(&tx).await.unwrap().confirmed);
+
+    let only_failing = MultiAnchorProvider::new(vec![("solana".to_string(), failing)]);
+    assert!(matches!(
+        only_failing.anchor(&evidence).await,
+        Err(AnchorError::Network(_))
+    ));
+    assert!(matches!(
+        only_failing.confirm(&tx).await,
+        Err(AnchorError::Invalid(_))
+    ));
+
+    let config = KeeperConfig::from_toml(r#"provider = "stub""#).unwrap();
+    let stub = build_anchor_provider(&config.provider_config).unwrap();
+    assert_eq!(stub.anchor(&evidence).await.unwrap().network, "etherlink");
+
+    let config = KeeperConfig::from_toml(
+        "provider = \"bitcoin\"\n[bitcoin]\nendpoint = \"http://127.0.0.1:8332\"",
+    )
+    .unwrap();
+    let bitcoin = build_anchor_provider(&config.provider_config).unwrap();
+    assert_eq!(bitcoin.network(), "bitcoin");
+}
@@ -0,0 +1,214 @@
+use anchor_etherlink::EtherlinkProviderStub;
+use phoenix_common::outbox::HELD_NEXT_ATTEMPT_MS;
+use phoenix_common::testing::{sqlite_test_pool, TestJob};
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use phoenix_keeper::maintenance::{
+    anchor_once, reconfirm, requeue_jobs, MaintenanceError, ReconfirmReport,
+};
+use phoenix_keeper::SqliteJobProvider;
+use sqlx::{Pool, Row, Sqlite};
+use tempfile::NamedTempFile;
+
+/// Reports `tx-confirmed` confirmed, fails on `tx-unreachable` and sees
+/// everything else as pending
+struct ChainView;
+
+#[async_trait::async_trait]
+impl AnchorProvider for ChainView {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        EtherlinkProviderStub.anchor(evidence).await
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        match tx.tx_id.as_str() {
+            "tx-unreachable" => Err(AnchorError::Network("connection refused".to_string())),
+            tx_id => Ok(ChainTxRef {
+                confirmed: tx_id == "tx-confirmed",
+                ..tx.clone()
+            }),
+        }
+    }
+}
+
+async fn insert_tx_ref(pool: &Pool<Sqlite>, tx_id: &str, confirmed: bool, created_ms: i64) {
+    sqlx::query(
+        "INSERT INTO outbox_tx_refs (job_id, network, chain, tx_id, confirmed, timestamp, created_ms, next_check_ms) VALUES (?1, 'etherlink', 'testnet', ?1, ?2, NULL, ?3, ?4)",
+    )
+    .bind(tx_id)
+    .bind(confirmed as i64)
+    .bind(created_ms)
+    .bind(i64::MAX)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn job_status(pool: &Pool<Sqlite>, id: &str) -> String {
+    sqlx::query_scalar("SELECT status FROM outbox_jobs WHERE id=?1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_requeue_by_status_is_audited() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("dead-1")
+        .with_status("failed")
+        .with_attempts(1)
+        .insert(&pool)
+        .await;
+    TestJob::new("dead-2")
+        .with_status("failed")
+        .with_attempts(1)
+        .insert(&pool)
+        .await;
+    TestJob::new("gone")
+        .with_status("discarded")
+        .with_attempts(1)
+        .insert(&pool)
+        .await;
+    TestJob::new("anchored")
+        .with_status("done")
+        .with_attempts(1)
+        .insert(&pool)
+        .await;
+
+    let mut ids = requeue_jobs(&pool, "failed", "alice").await.unwrap();
+    ids.sort();
+
+    assert_eq!(ids, vec!["dead-1", "dead-2"]);
+    assert_eq!(job_status(&pool, "dead-1").await, "queued");
+    assert_eq!(job_status(&pool, "gone").await, "discarded");
+    let audit = sqlx::query("SELECT action, actor FROM admin_audit_log WHERE job_id='dead-1'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(audit.get::<String, _>(0), "requeue");
+    assert_eq!(audit.get::<String, _>(1), "alice");
+
+    assert!(matches!(
+        requeue_jobs(&pool, "done", "alice").await,
+        Err(MaintenanceError::Status(_))
+    ));
+    assert_eq!(job_status(&pool, "anchored").await, "done");
+}
+
+#[tokio::test]
+async fn test_reconfirm_rechecks_since() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    insert_tx_ref(&pool, "tx-before", false, 1_000).await;
+    insert_tx_ref(&pool, "tx-confirmed", false, 5_000).await;
+    insert_tx_ref(&pool, "tx-reorged", true, 5_000).await;
+    insert_tx_ref(&pool, "tx-pending", false, 5_000).await;
+    insert_tx_ref(&pool, "tx-unreachable", true, 5_000).await;
+
+    let report = reconfirm(&pool, &ChainView, 2_000).await.unwrap();
+
+    assert_eq!(
+        report,
+        ReconfirmReport {
+            checked: 4,
+            confirmed: 1,
+            pending: 1,
+            reverted: 1,
+            errors: 1,
+        }
+    );
+    let rows = sqlx::query("SELECT tx_id, confirmed, next_check_ms FROM outbox_tx_refs")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    for row in rows {
+        let tx_id: String = row.get(0);
+        let (confirmed, next_check_ms): (i64, i64) = (row.get(1), row.get(2));
+        match tx_id.as_str() {
+            "tx-confirmed" => assert_eq!(confirmed, 1),
+            // Handed back to the confirmation loop
+            "tx-reorged" | "tx-pending" => assert_eq!((confirmed, next_check_ms), (0, 0)),
+            // Failed checks and refs before the cutoff are left alone
+            "tx-unreachable" => assert_eq!((confirmed, next_check_ms), (1, i64::MAX)),
+            _ => assert_eq!((confirmed, next_check_ms), (0, i64::MAX)),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_anchor_once_ignores_schedule() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("staged")
+        .with_attempts(1)
+        .with_next_attempt_ms(i64::MAX - 1)
+        .insert(&pool)
+        .await;
+    TestJob::new("anchored")
+        .with_status("done")
+        .with_attempts(1)
+        .insert(&pool)
+        .await;
+    let mut jp = SqliteJobProvider::new(pool.clone());
+
+    let tx = anchor_once(&pool, &mut jp, &EtherlinkProviderStub, "staged", false)
+        .await
+        .unwrap();
+
+    assert_eq!(tx.tx_id, "fake:abcd1234");
+    assert_eq!(job_status(&pool, "staged").await, "done");
+    let outcome: String = sqlx::query_scalar(
+        "SELECT outcome FROM outbox_job_attempts WHERE job_id='staged' ORDER BY id DESC LIMIT 1",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(outcome, "done");
+
+    assert!(matches!(
+        anchor_once(&pool, &mut jp, &EtherlinkProviderStub, "anchored", false).await,
+        Err(MaintenanceError::NotQueued(_, status)) if status == "done"
+    ));
+    assert!(matches!(
+        anchor_once(&pool, &mut jp, &EtherlinkProviderStub, "missing", false).await,
+        Err(MaintenanceError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_anchor_once_needs_force_for_held_or_routed_jobs() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("held")
+        .with_attempts(1)
+        .with_next_attempt_ms(HELD_NEXT_ATTEMPT_MS)
+        .insert(&pool)
+        .await;
+    TestJob::new("routed").with_attempts(1).insert(&pool).await;
+    sqlx::query("UPDATE outbox_jobs SET provider='solana' WHERE id='routed'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let mut jp = SqliteJobProvider::new(pool.clone()).with_provider("etherlink");
+
+    assert!(matches!(
+        anchor_once(&pool, &mut jp, &EtherlinkProviderStub, "held", false).await,
+        Err(MaintenanceError::Held(_))
+    ));
+    assert!(matches!(
+        anchor_once(&pool, &mut jp, &EtherlinkProviderStub, "routed", false).await,
+        Err(MaintenanceError::Routed(_, provider)) if provider == "solana"
+    ));
+    // Refused claims leave the jobs untouched
+    assert_eq!(job_status(&pool, "held").await, "queued");
+    assert_eq!(job_status(&pool, "routed").await, "queued");
+
+    for id in ["held", "routed"] {
+        anchor_once(&pool, &mut jp, &EtherlinkProviderStub, id, true)
+            .await
+            .unwrap();
+        assert_eq!(job_status(&pool, id).await, "done");
+    }
+}
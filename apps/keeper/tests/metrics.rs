@@ -0,0 +1,148 @@
+use anchor_etherlink::{EtherlinkProvider, EtherlinkProviderStub};
+use phoenix_common::testing::{sqlite_test_pool, TestJob};
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use phoenix_keeper::ops::{ops_router, OpsState};
+use phoenix_keeper::{run_confirmation_loop, run_job_loop, LoopControl, SqliteJobProvider};
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::net::TcpListener;
+
+/// Fails "retry-me" with a network error and every other job permanently
+struct RejectingProvider;
+
+#[async_trait::async_trait]
+impl AnchorProvider for RejectingProvider {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        if evidence.id == "retry-me" {
+            Err(AnchorError::Network("connection refused".to_string()))
+        } else {
+            Err(AnchorError::Invalid("digest rejected".to_string()))
+        }
+    }
+
+    async fn confirm(&self, _tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        Err(AnchorError::Invalid("not supported".to_string()))
+    }
+
+    fn network(&self) -> &str {
+        "solana"
+    }
+}
+
+/// Scrape `/metrics` until every expected line is present
+async fn wait_for_metrics(client: &Client, base: &str, expected: &[&str]) -> String {
+    let mut metrics = String::new();
+    for _ in 0..50 {
+        metrics = client
+            .get(format!("{}/metrics", base))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        if expected.iter().all(|line| metrics.contains(line)) {
+            return metrics;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("missing one of {:?} in\n{}", expected, metrics);
+}
+
+#[tokio::test]
+async fn test_metrics_cover_jobs_confirmations_and_rpc() {
+    phoenix_common::metrics::install_recorder();
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let state = OpsState {
+        store: Arc::new(pool.clone()),
+        anchor: Arc::new(EtherlinkProviderStub),
+        jobs: LoopControl::default(),
+        confirmations: LoopControl::default(),
+    };
+    tokio::spawn(async move {
+        axum::serve(listener, ops_router(state).into_make_service())
+            .await
+            .unwrap();
+    });
+    let client = Client::new();
+
+    // One anchored job, then one permanent and one temporary failure
+    TestJob::new("anchor-me").insert(&pool).await;
+    let mut jp = SqliteJobProvider::new(pool.clone());
+    let worker = tokio::spawn(async move {
+        run_job_loop(&mut jp, &EtherlinkProviderStub, Duration::from_millis(10)).await;
+    });
+    wait_for_metrics(
+        &client,
+        &base,
+        &[
+            "phoenix_jobs_anchored_total{network=\"etherlink\"} 1",
+            "phoenix_unconfirmed_tx_refs 1",
+        ],
+    )
+    .await;
+    worker.abort();
+
+    TestJob::new("reject-me").insert(&pool).await;
+    TestJob::new("retry-me").insert(&pool).await;
+    let mut jp = SqliteJobProvider::new(pool.clone());
+    let worker = tokio::spawn(async move {
+        run_job_loop(&mut jp, &RejectingProvider, Duration::from_millis(10)).await;
+    });
+    let metrics = wait_for_metrics(
+        &client,
+        &base,
+        &[
+            "phoenix_jobs_failed_total{network=\"solana\"} 1",
+            "phoenix_jobs_retried_total{network=\"solana\"} 1",
+            // The retried job waits out its backoff
+            "phoenix_queue_depth 1",
+        ],
+    )
+    .await;
+    worker.abort();
+    assert!(metrics.contains("phoenix_anchor_latency_seconds_count{network=\"solana\"} 2"));
+
+    let confirm_pool = pool.clone();
+    let confirmer = tokio::spawn(async move {
+        run_confirmation_loop(
+            &confirm_pool,
+            &EtherlinkProviderStub,
+            Duration::from_millis(10),
+        )
+        .await;
+    });
+    let metrics = wait_for_metrics(
+        &client,
+        &base,
+        &[
+            "phoenix_jobs_confirmed_total{network=\"etherlink\"} 1",
+            "phoenix_unconfirmed_tx_refs 0",
+        ],
+    )
+    .await;
+    confirmer.abort();
+    assert!(metrics.contains("phoenix_confirm_latency_seconds_bucket{network=\"etherlink\""));
+
+    // RPC calls are timed by the provider, including ones that fail
+    let unreachable = EtherlinkProvider::new(
+        "http://127.0.0.1:1".to_string(),
+        "ghostnet".to_string(),
+        None,
+    )
+    .unwrap();
+    assert!(unreachable.health().await.is_err());
+    wait_for_metrics(
+        &client,
+        &base,
+        &["phoenix_rpc_call_duration_seconds_count{network=\"etherlink\",method=\"eth_blockNumber\",outcome=\"error\"} 1"],
+    )
+    .await;
+}
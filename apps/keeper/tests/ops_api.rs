@@ -0,0 +1,304 @@
+use anchor_etherlink::EtherlinkProviderStub;
+use phoenix_common::outbox::HELD_NEXT_ATTEMPT_MS;
+use phoenix_common::testing::{sqlite_test_pool, TestJob};
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use phoenix_keeper::ops::{ops_router, OpsState};
+use phoenix_keeper::providers::SharedAnchor;
+use phoenix_keeper::{run_job_loop_with_control, LoopControl, SqliteJobProvider};
+use reqwest::Client;
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::net::TcpListener;
+
+struct UnreachableProvider;
+
+#[async_trait::async_trait]
+impl AnchorProvider for UnreachableProvider {
+    async fn anchor(&self, _evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        Err(AnchorError::Network("connection refused".to_string()))
+    }
+
+    async fn confirm(&self, _tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        Err(AnchorError::Network("connection refused".to_string()))
+    }
+
+    async fn health(&self) -> Result<(), AnchorError> {
+        Err(AnchorError::Network("connection refused".to_string()))
+    }
+}
+
+/// Serve the ops API on an ephemeral port; returns the base URL
+async fn start_ops(state: OpsState) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, ops_router(state).into_make_service())
+            .await
+            .unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn ops_state(pool: &Pool<Sqlite>, anchor: SharedAnchor) -> OpsState {
+    OpsState {
+        store: Arc::new(pool.clone()),
+        anchor,
+        jobs: LoopControl::default(),
+        confirmations: LoopControl::default(),
+    }
+}
+
+async fn insert_tx_ref(
+    pool: &Pool<Sqlite>,
+    job_id: &str,
+    network: &str,
+    confirmed: bool,
+    ts: Option<i64>,
+) {
+    sqlx::query(
+        "INSERT INTO outbox_tx_refs (job_id, network, chain, tx_id, confirmed, timestamp) VALUES (?1, ?2, 'testnet', ?1, ?3, ?4)",
+    )
+    .bind(job_id)
+    .bind(network)
+    .bind(confirmed as i64)
+    .bind(ts)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn get_json(client: &Client, url: String) -> (u16, Value) {
+    let resp = client.get(url).send().await.unwrap();
+    let status = resp.status().as_u16();
+    (status, resp.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_status_reports_queue_and_anchors() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    TestJob::new("old")
+        .with_created_ms(now_ms - 60_000)
+        .insert(&pool)
+        .await;
+    TestJob::new("new")
+        .with_created_ms(now_ms)
+        .insert(&pool)
+        .await;
+    TestJob::new("done-1")
+        .with_status("done")
+        .with_created_ms(now_ms)
+        .insert(&pool)
+        .await;
+    TestJob::new("done-2")
+        .with_status("done")
+        .with_created_ms(now_ms)
+        .insert(&pool)
+        .await;
+    TestJob::new("dead")
+        .with_status("failed")
+        .with_created_ms(now_ms)
+        .insert(&pool)
+        .await;
+    insert_tx_ref(&pool, "a", "etherlink", true, Some(1_700_000_000)).await;
+    insert_tx_ref(&pool, "b", "etherlink", false, Some(1_700_000_100)).await;
+    insert_tx_ref(&pool, "c", "solana", false, None).await;
+
+    let base = start_ops(ops_state(&pool, Arc::new(EtherlinkProviderStub))).await;
+    let client = Client::new();
+
+    let (code, status) = get_json(&client, format!("{}/status", base)).await;
+    assert_eq!(code, 200);
+    assert_eq!(status["jobs_by_status"]["queued"], 2);
+    assert_eq!(status["jobs_by_status"]["done"], 2);
+    assert_eq!(status["jobs_by_status"]["failed"], 1);
+    assert!(status["oldest_queued_age_ms"].as_i64().unwrap() >= 60_000);
+    assert_eq!(status["unconfirmed_tx_refs"], 2);
+    assert_eq!(status["last_anchor"]["etherlink"], "2023-11-14T22:15:00Z");
+    assert!(status["last_anchor"].get("solana").is_none());
+    assert_eq!(status["paused"]["jobs"], false);
+
+    let (code, body) = get_json(&client, format!("{}/ready", base)).await;
+    assert_eq!(code, 200);
+    assert_eq!(body["ready"], true);
+}
+
+#[tokio::test]
+async fn test_status_sets_held_and_scheduled_jobs_apart() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    TestJob::new("staged")
+        .with_created_ms(now_ms - 86_400_000)
+        .insert(&pool)
+        .await;
+    TestJob::new("tonight")
+        .with_created_ms(now_ms - 3_600_000)
+        .insert(&pool)
+        .await;
+    TestJob::new("waiting")
+        .with_created_ms(now_ms - 60_000)
+        .insert(&pool)
+        .await;
+    sqlx::query("UPDATE outbox_jobs SET next_attempt_ms=?1 WHERE id='staged'")
+        .bind(HELD_NEXT_ATTEMPT_MS)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE outbox_jobs SET next_attempt_ms=?1 WHERE id='tonight'")
+        .bind(now_ms + 3_600_000)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let base = start_ops(ops_state(&pool, Arc::new(EtherlinkProviderStub))).await;
+    let (code, status) = get_json(&Client::new(), format!("{}/status", base)).await;
+    assert_eq!(code, 200);
+    assert_eq!(status["jobs_by_status"]["held"], 1);
+    assert_eq!(status["jobs_by_status"]["queued"], 2);
+    // Only the job that has been due for a minute counts towards the age
+    let age = status["oldest_queued_age_ms"].as_i64().unwrap();
+    assert!((60_000..3_600_000).contains(&age), "age {}", age);
+}
+
+#[tokio::test]
+async fn test_ready_reports_failing_dependencies() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let client = Client::new();
+
+    let base = start_ops(ops_state(&pool, Arc::new(UnreachableProvider))).await;
+    let (code, body) = get_json(&client, format!("{}/ready", base)).await;
+    assert_eq!(code, 503);
+    assert_eq!(body["database"], "ok");
+    assert_eq!(body["provider"], "network error: connection refused");
+
+    let base = start_ops(ops_state(&pool, Arc::new(EtherlinkProviderStub))).await;
+    pool.close().await;
+    let (code, body) = get_json(&client, format!("{}/ready", base)).await;
+    assert_eq!(code, 503);
+    assert_eq!(body["ready"], false);
+    assert_ne!(body["database"], "ok");
+    assert_eq!(body["provider"], "ok");
+}
+
+#[tokio::test]
+async fn test_pause_and_resume_job_loop() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let state = ops_state(&pool, Arc::new(EtherlinkProviderStub));
+    let jobs = state.jobs.clone();
+    let base = start_ops(state).await;
+    let client = Client::new();
+
+    let resp = client
+        .post(format!("{}/admin/loops/jobs/pause", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    assert!(jobs.is_paused());
+    let (_, status) = get_json(&client, format!("{}/status", base)).await;
+    assert_eq!(status["paused"]["jobs"], true);
+    assert_eq!(status["paused"]["confirmations"], false);
+
+    TestJob::new("job-1")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+    let worker_pool = pool.clone();
+    let control = jobs.clone();
+    let worker = tokio::spawn(async move {
+        let mut jp = SqliteJobProvider::new(worker_pool);
+        run_job_loop_with_control(
+            &mut jp,
+            &EtherlinkProviderStub,
+            Duration::from_millis(10),
+            &control,
+        )
+        .await;
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let (_, status) = get_json(&client, format!("{}/status", base)).await;
+    assert_eq!(status["jobs_by_status"]["queued"], 1);
+
+    let resp = client
+        .post(format!("{}/admin/loops/jobs/resume", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let (_, status) = get_json(&client, format!("{}/status", base)).await;
+    assert_eq!(status["jobs_by_status"]["done"], 1);
+    assert_eq!(status["paused"]["jobs"], false);
+
+    let resp = client
+        .post(format!("{}/admin/loops/reaper/pause", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+
+    worker.abort();
+}
+
+#[tokio::test]
+async fn test_wake_picks_up_job_before_poll_interval() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    let state = ops_state(&pool, Arc::new(EtherlinkProviderStub));
+    let jobs = state.jobs.clone();
+    let base = start_ops(state).await;
+    let client = Client::new();
+
+    // An hour-long poll: only the wake can get the job picked up within the test
+    let worker_pool = pool.clone();
+    let worker = tokio::spawn(async move {
+        let mut jp = SqliteJobProvider::new(worker_pool);
+        run_job_loop_with_control(
+            &mut jp,
+            &EtherlinkProviderStub,
+            Duration::from_secs(3600),
+            &jobs,
+        )
+        .await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    TestJob::new("job-1")
+        .with_created_ms(chrono::Utc::now().timestamp_millis())
+        .insert(&pool)
+        .await;
+
+    let resp = client
+        .post(format!("{}/admin/loops/jobs/wake", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let mut done = false;
+    for _ in 0..50 {
+        let (_, status) = get_json(&client, format!("{}/status", base)).await;
+        if status["jobs_by_status"]["done"] == 1 {
+            done = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(done, "woken job loop should anchor the queued job");
+
+    let resp = client
+        .post(format!("{}/admin/loops/reaper/wake", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+
+    worker.abort();
+}
@@ -0,0 +1,534 @@
+//! PostgreSQL backend tests. Set PHOENIX_TEST_POSTGRES_URL to run them.
+
+use anchor_etherlink::EtherlinkProviderStub;
+use chrono::Utc;
+use phoenix_common::outbox::{HELD_NEXT_ATTEMPT_MS, JOBS_QUEUED_CHANNEL};
+use phoenix_common::testing::{postgres_test_pool, TestJob};
+use phoenix_evidence::model::{ChainTxRef, SignedAnchorTx};
+use phoenix_keeper::maintenance::{postgres as maintenance, MaintenanceError};
+use phoenix_keeper::postgres::{ensure_schema, run_job_listener, PostgresJobProvider};
+use phoenix_keeper::{
+    run_job_loop_with_control, JobProvider, JobProviderExt, LeaseReaper, LoopControl, StatusStore,
+    TxRefStore,
+};
+use sqlx::{Pool, Postgres, Row};
+use std::collections::HashSet;
+use std::time::Duration;
+
+const DIGEST: &str = "deadbeefcafebabe1234567890abcdef1234567890abcdef1234567890abcdef";
+
+async fn job_status(pool: &Pool<Postgres>, id: &str) -> (String, i64) {
+    let row = sqlx::query("SELECT status, attempts FROM outbox_jobs WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    (row.get(0), row.get(1))
+}
+
+#[tokio::test]
+async fn test_pg_job_lifecycle() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    ensure_schema(&pool).await.unwrap();
+
+    let now_ms = Utc::now().timestamp_millis();
+    TestJob::new("older")
+        .with_digest(DIGEST)
+        .with_created_ms(now_ms - 1000)
+        .insert_postgres(&pool)
+        .await;
+    TestJob::new("newer")
+        .with_digest(DIGEST)
+        .with_created_ms(now_ms)
+        .insert_postgres(&pool)
+        .await;
+
+    let mut jp = PostgresJobProvider::new(pool.clone());
+
+    // Oldest first, and claimed jobs are not handed out again
+    let job = jp.fetch_next().await.unwrap().unwrap();
+    assert_eq!(job.id, "older");
+    assert_eq!(job.created_ms, now_ms - 1000);
+    assert_eq!(job_status(&pool, "older").await, ("in_progress".into(), 1));
+
+    let tx = ChainTxRef {
+        network: "ghostnet".to_string(),
+        chain: "etherlink".to_string(),
+        tx_id: "0xabc".to_string(),
+        confirmed: false,
+        timestamp: Some(Utc::now()),
+    };
+    jp.mark_tx_and_done(&job.id, &tx).await.unwrap();
+    // Re-recording the same transaction is an upsert
+    jp.mark_tx_and_done(&job.id, &tx).await.unwrap();
+    assert_eq!(job_status(&pool, "older").await.0, "done");
+
+    let unconfirmed = pool.fetch_due_tx_refs(now_ms, 10).await.unwrap();
+    assert_eq!(unconfirmed.len(), 1);
+    assert_eq!(unconfirmed[0].tx.tx_id, "0xabc");
+
+    pool.update_tx_ref_confirmation(&ChainTxRef {
+        confirmed: true,
+        ..tx
+    })
+    .await
+    .unwrap();
+    assert!(pool.fetch_due_tx_refs(now_ms, 10).await.unwrap().is_empty());
+
+    // Temporary failures are requeued in the future, permanent ones fail
+    let job = jp.fetch_next().await.unwrap().unwrap();
+    assert_eq!(job.id, "newer");
+    jp.mark_failed_or_backoff(&job.id, "rpc timeout", true)
+        .await
+        .unwrap();
+    assert_eq!(job_status(&pool, "newer").await.0, "queued");
+    assert!(jp.fetch_next().await.unwrap().is_none());
+
+    jp.mark_failed_or_backoff(&job.id, "rejected", false)
+        .await
+        .unwrap();
+    assert_eq!(job_status(&pool, "newer").await.0, "failed");
+}
+
+#[tokio::test]
+async fn test_pg_replicas_claim_each_job_once() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    ensure_schema(&pool).await.unwrap();
+
+    let now_ms = Utc::now().timestamp_millis();
+    for i in 0..40 {
+        TestJob::new(&format!("job-{:02}", i))
+            .with_digest(DIGEST)
+            .with_created_ms(now_ms + i)
+            .insert_postgres(&pool)
+            .await;
+    }
+
+    let replicas: Vec<_> = (0..4)
+        .map(|_| {
+            let mut jp = PostgresJobProvider::new(pool.clone());
+            tokio::spawn(async move {
+                let mut claimed = Vec::new();
+                while let Some(job) = jp.fetch_next().await.unwrap() {
+                    jp.mark_done(&job.id).await.unwrap();
+                    claimed.push(job.id);
+                }
+                claimed
+            })
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    for replica in replicas {
+        for id in replica.await.unwrap() {
+            assert!(seen.insert(id.clone()), "{} claimed twice", id);
+        }
+    }
+    assert_eq!(seen.len(), 40);
+
+    let attempts: i64 = sqlx::query_scalar("SELECT SUM(attempts)::BIGINT FROM outbox_jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(attempts, 40);
+}
+
+#[tokio::test]
+async fn test_pg_crashed_worker_job_is_reaped() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    ensure_schema(&pool).await.unwrap();
+    TestJob::new("job-1")
+        .with_digest(DIGEST)
+        .with_created_ms(Utc::now().timestamp_millis())
+        .insert_postgres(&pool)
+        .await;
+
+    let lease = Duration::from_millis(100);
+    let mut crashed = PostgresJobProvider::with_lease(pool.clone(), "worker-a", lease);
+    let mut survivor = PostgresJobProvider::with_lease(pool.clone(), "worker-b", lease);
+
+    crashed.fetch_next().await.unwrap().unwrap();
+    drop(crashed);
+    assert_eq!(pool.reap_expired_leases().await.unwrap(), 0);
+
+    tokio::time::sleep(lease * 2).await;
+    assert_eq!(pool.reap_expired_leases().await.unwrap(), 1);
+
+    let job = survivor.fetch_next().await.unwrap().unwrap();
+    assert_eq!(job.id, "job-1");
+    let owner: Option<String> =
+        sqlx::query_scalar("SELECT lease_owner FROM outbox_jobs WHERE id = 'job-1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(owner.as_deref(), Some("worker-b"));
+    assert_eq!(job_status(&pool, "job-1").await, ("in_progress".into(), 2));
+
+    // The reaped attempt is kept in the job's history under the crashed worker
+    let row = sqlx::query(
+        "SELECT attempt, worker, outcome, latency_ms FROM outbox_job_attempts WHERE job_id = 'job-1'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(row.get::<i64, _>(0), 1);
+    assert_eq!(row.get::<Option<String>, _>(1).as_deref(), Some("worker-a"));
+    assert_eq!(row.get::<String, _>(2), "lease_expired");
+    assert!(row.get::<i64, _>(3) >= 200);
+}
+
+#[tokio::test]
+async fn test_pg_lease_renewal() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    ensure_schema(&pool).await.unwrap();
+    TestJob::new("job-1")
+        .with_digest(DIGEST)
+        .with_created_ms(Utc::now().timestamp_millis())
+        .insert_postgres(&pool)
+        .await;
+
+    let lease = Duration::from_millis(100);
+    let mut slow = PostgresJobProvider::with_lease(pool.clone(), "worker-a", lease);
+    let mut other = PostgresJobProvider::with_lease(pool.clone(), "worker-b", lease);
+    slow.fetch_next().await.unwrap().unwrap();
+    let renewer = slow.lease_renewer().unwrap();
+
+    // Renewed past its original expiry, the job is not reaped
+    tokio::time::sleep(lease / 2).await;
+    assert!(renewer.renew_lease("job-1").await.unwrap());
+    tokio::time::sleep(lease * 3 / 4).await;
+    assert_eq!(pool.reap_expired_leases().await.unwrap(), 0);
+
+    // Once reaped and handed on, the old holder cannot renew it
+    tokio::time::sleep(lease * 2).await;
+    assert_eq!(pool.reap_expired_leases().await.unwrap(), 1);
+    other.fetch_next().await.unwrap().unwrap();
+    assert!(!renewer.renew_lease("job-1").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_pg_anchor_intent_outlives_crashed_worker() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    ensure_schema(&pool).await.unwrap();
+    TestJob::new("job-1")
+        .with_digest(DIGEST)
+        .with_created_ms(Utc::now().timestamp_millis())
+        .insert_postgres(&pool)
+        .await;
+
+    let lease = Duration::from_millis(100);
+    let mut crashed = PostgresJobProvider::with_lease(pool.clone(), "worker-a", lease);
+    let mut survivor = PostgresJobProvider::with_lease(pool.clone(), "worker-b", lease);
+    let signed = SignedAnchorTx {
+        tx: ChainTxRef {
+            network: "etherlink".to_string(),
+            chain: "ghostnet".to_string(),
+            tx_id: "0xabc".to_string(),
+            confirmed: false,
+            timestamp: None,
+        },
+        raw_tx: "0xsigned".to_string(),
+    };
+
+    // Only the lease holder may record an intent
+    assert!(survivor.record_intent("job-1", &signed).await.is_err());
+    crashed.fetch_next().await.unwrap().unwrap();
+    crashed.record_intent("job-1", &signed).await.unwrap();
+    drop(crashed);
+
+    tokio::time::sleep(lease * 2).await;
+    assert_eq!(pool.reap_expired_leases().await.unwrap(), 1);
+    survivor.fetch_next().await.unwrap().unwrap();
+
+    let mut pending = survivor.pending_intent("job-1").await.unwrap().unwrap();
+    assert!(pending.tx.timestamp.take().is_some());
+    assert_eq!(pending, signed);
+
+    survivor
+        .mark_tx_and_done("job-1", &signed.tx)
+        .await
+        .unwrap();
+    assert!(survivor.pending_intent("job-1").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_pg_tx_ref_schedule_and_drop() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    ensure_schema(&pool).await.unwrap();
+
+    let now_ms = Utc::now().timestamp_millis();
+    TestJob::new("job-1")
+        .with_digest(DIGEST)
+        .with_created_ms(now_ms)
+        .insert_postgres(&pool)
+        .await;
+    let mut jp = PostgresJobProvider::new(pool.clone());
+    jp.fetch_next().await.unwrap().unwrap();
+    let tx = ChainTxRef {
+        network: "etherlink".to_string(),
+        chain: "ghostnet".to_string(),
+        tx_id: "0xabc".to_string(),
+        confirmed: false,
+        timestamp: Some(Utc::now()),
+    };
+    jp.mark_tx_and_done("job-1", &tx).await.unwrap();
+
+    let due = pool.fetch_due_tx_refs(now_ms, 10).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].checks, 0);
+    assert!(due[0].created_ms >= now_ms);
+
+    // Checked once and not due again until the scheduled time
+    pool.schedule_tx_ref_check(&due[0], now_ms + 60_000)
+        .await
+        .unwrap();
+    assert!(pool.fetch_due_tx_refs(now_ms, 10).await.unwrap().is_empty());
+    let due = pool.fetch_due_tx_refs(now_ms + 60_000, 10).await.unwrap();
+    assert_eq!(due[0].checks, 1);
+
+    pool.drop_tx_ref(
+        &due[0],
+        "transaction dropped: 0xabc not confirmed after 3600s",
+    )
+    .await
+    .unwrap();
+    let row = sqlx::query("SELECT status, failure_category FROM outbox_jobs WHERE id = 'job-1'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<String, _>(0), "queued");
+    assert_eq!(row.get::<String, _>(1), "dropped");
+    assert!(pool
+        .fetch_due_tx_refs(now_ms + 60_000, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(pool.outbox_status().await.unwrap().unconfirmed_tx_refs, 0);
+
+    // Anchoring again starts the transaction's schedule over
+    jp.fetch_next().await.unwrap().unwrap();
+    jp.mark_tx_and_done("job-1", &tx).await.unwrap();
+    let due = pool.fetch_due_tx_refs(now_ms, 10).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].checks, 0);
+}
+
+#[tokio::test]
+async fn test_pg_claims_by_priority_then_tenant_share() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    ensure_schema(&pool).await.unwrap();
+
+    let jobs = [
+        ("bulk-0", -5, "bulk"),
+        ("bulk-1", 0, "bulk"),
+        ("bulk-2", 0, "bulk"),
+        ("bulk-3", 0, "bulk"),
+        ("acme-0", 0, "acme"),
+        ("acme-1", 0, "acme"),
+        ("urgent-0", 5, "acme"),
+    ];
+    for (created_ms, (id, priority, tenant)) in jobs.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO outbox_jobs (id, payload_sha256, status, attempts, created_ms, updated_ms, next_attempt_ms, priority, tenant) VALUES ($1, 'abcd1234', 'queued', 0, $2, $2, 0, $3, $4)",
+        )
+        .bind(id)
+        .bind(created_ms as i64)
+        .bind(priority)
+        .bind(tenant)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let mut jp = PostgresJobProvider::new(pool.clone());
+    let mut claimed = Vec::new();
+    while let Some(job) = jp.fetch_next().await.unwrap() {
+        claimed.push(job.id);
+    }
+
+    // The urgent job counts against acme's share, so bulk goes twice before acme's turn
+    assert_eq!(
+        claimed,
+        vec!["urgent-0", "bulk-1", "bulk-2", "acme-0", "bulk-3", "acme-1", "bulk-0"]
+    );
+}
+
+#[tokio::test]
+async fn test_pg_outbox_status() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    ensure_schema(&pool).await.unwrap();
+    pool.ping().await.unwrap();
+
+    let now_ms = Utc::now().timestamp_millis();
+    TestJob::new("job-1")
+        .with_digest(DIGEST)
+        .with_created_ms(now_ms - 5000)
+        .insert_postgres(&pool)
+        .await;
+    TestJob::new("job-2")
+        .with_digest(DIGEST)
+        .with_created_ms(now_ms)
+        .insert_postgres(&pool)
+        .await;
+    TestJob::new("staged")
+        .with_digest(DIGEST)
+        .with_created_ms(now_ms - 86_400_000)
+        .insert_postgres(&pool)
+        .await;
+    sqlx::query("UPDATE outbox_jobs SET next_attempt_ms=$1 WHERE id='staged'")
+        .bind(HELD_NEXT_ATTEMPT_MS)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let mut jp = PostgresJobProvider::new(pool.clone());
+    let job = jp.fetch_next().await.unwrap().unwrap();
+    let tx = ChainTxRef {
+        network: "etherlink".to_string(),
+        chain: "ghostnet".to_string(),
+        tx_id: "0xabc".to_string(),
+        confirmed: false,
+        timestamp: Some(Utc::now()),
+    };
+    jp.mark_tx_and_done(&job.id, &tx).await.unwrap();
+
+    let status = pool.outbox_status().await.unwrap();
+    assert_eq!(status.jobs_by_status.get("done"), Some(&1));
+    assert_eq!(status.jobs_by_status.get("queued"), Some(&1));
+    assert_eq!(status.jobs_by_status.get("held"), Some(&1));
+    assert!(status.oldest_queued_age_ms.unwrap() < 86_400_000);
+    assert_eq!(status.unconfirmed_tx_refs, 1);
+    assert_eq!(
+        status.last_anchor["etherlink"].timestamp(),
+        tx.timestamp.unwrap().timestamp()
+    );
+}
+
+#[tokio::test]
+async fn test_pg_notification_wakes_job_loop() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    ensure_schema(&pool).await.unwrap();
+
+    let control = LoopControl::default();
+    let listener_pool = pool.clone();
+    let listener_control = control.clone();
+    let listener = tokio::spawn(async move {
+        run_job_listener(&listener_pool, &listener_control).await;
+    });
+    // An hour-long poll: only a notification can get the job picked up within the test
+    let mut jp = PostgresJobProvider::new(pool.clone());
+    let worker = tokio::spawn(async move {
+        run_job_loop_with_control(
+            &mut jp,
+            &EtherlinkProviderStub,
+            Duration::from_secs(3600),
+            &control,
+        )
+        .await;
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    TestJob::new("job-1")
+        .with_digest(DIGEST)
+        .with_created_ms(Utc::now().timestamp_millis())
+        .insert_postgres(&pool)
+        .await;
+
+    // Notify until the job is done: the listener may not be subscribed yet
+    let mut done = false;
+    for _ in 0..50 {
+        sqlx::query("SELECT pg_notify($1, 'job-1')")
+            .bind(JOBS_QUEUED_CHANNEL)
+            .execute(&pool)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        if job_status(&pool, "job-1").await.0 == "done" {
+            done = true;
+            break;
+        }
+    }
+    worker.abort();
+    listener.abort();
+    assert!(done, "notified job loop should anchor the queued job");
+}
+
+#[tokio::test]
+async fn test_pg_maintenance_commands() {
+    let Some(pool) = postgres_test_pool().await else {
+        return;
+    };
+    ensure_schema(&pool).await.unwrap();
+    let now_ms = Utc::now().timestamp_millis();
+    TestJob::new("dead")
+        .with_digest(DIGEST)
+        .with_created_ms(now_ms)
+        .insert_postgres(&pool)
+        .await;
+    TestJob::new("held")
+        .with_digest(DIGEST)
+        .with_created_ms(now_ms)
+        .insert_postgres(&pool)
+        .await;
+    sqlx::query("UPDATE outbox_jobs SET status='failed' WHERE id='dead'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE outbox_jobs SET next_attempt_ms=$1 WHERE id='held'")
+        .bind(HELD_NEXT_ATTEMPT_MS)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let ids = maintenance::requeue_jobs(&pool, "failed", "alice")
+        .await
+        .unwrap();
+    assert_eq!(ids, vec!["dead".to_string()]);
+    let actor: String =
+        sqlx::query_scalar("SELECT actor FROM admin_audit_log WHERE job_id = 'dead'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(actor, "alice");
+
+    let mut jp = PostgresJobProvider::new(pool.clone());
+    assert!(matches!(
+        maintenance::anchor_once(&pool, &mut jp, &EtherlinkProviderStub, "held", false).await,
+        Err(MaintenanceError::Held(_))
+    ));
+    maintenance::anchor_once(&pool, &mut jp, &EtherlinkProviderStub, "held", true)
+        .await
+        .unwrap();
+    maintenance::anchor_once(&pool, &mut jp, &EtherlinkProviderStub, "dead", false)
+        .await
+        .unwrap();
+    assert_eq!(job_status(&pool, "held").await.0, "done");
+    assert_eq!(job_status(&pool, "dead").await.0, "done");
+
+    let report = maintenance::reconfirm(&pool, &EtherlinkProviderStub, now_ms - 1000)
+        .await
+        .unwrap();
+    assert_eq!((report.checked, report.confirmed), (2, 2));
+    let unconfirmed: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM outbox_tx_refs WHERE confirmed = 0")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(unconfirmed, 0);
+}
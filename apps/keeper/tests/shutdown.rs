@@ -0,0 +1,174 @@
+use anchor_etherlink::EtherlinkProviderStub;
+use phoenix_common::outbox::SHUTDOWN_ERROR;
+use phoenix_common::testing::{sqlite_test_pool, TestJob};
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use phoenix_keeper::{
+    run_confirmation_loop_with_control, run_job_loop_with_control, LoopControl, SqliteJobProvider,
+};
+use sqlx::{Pool, Row, Sqlite};
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+
+/// Anchors like the stub, but only after `delay`
+struct SlowProvider {
+    delay: Duration,
+}
+
+#[async_trait::async_trait]
+impl AnchorProvider for SlowProvider {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        tokio::time::sleep(self.delay).await;
+        EtherlinkProviderStub.anchor(evidence).await
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        tokio::time::sleep(self.delay).await;
+        EtherlinkProviderStub.confirm(tx).await
+    }
+}
+
+/// Start a job loop, wait until it has claimed job-1, then request shutdown
+async fn shut_down_mid_anchor(pool: &Pool<Sqlite>, delay: Duration, shutdown_timeout: Duration) {
+    let shutdown = CancellationToken::new();
+    let control = LoopControl::new(shutdown.clone(), shutdown_timeout);
+    let mut jp = SqliteJobProvider::new(pool.clone());
+    let worker = tokio::spawn(async move {
+        run_job_loop_with_control(
+            &mut jp,
+            &SlowProvider { delay },
+            Duration::from_millis(10),
+            &control,
+        )
+        .await;
+    });
+
+    timeout(Duration::from_secs(5), async {
+        loop {
+            let status: String =
+                sqlx::query_scalar("SELECT status FROM outbox_jobs WHERE id='job-1'")
+                    .fetch_one(pool)
+                    .await
+                    .unwrap();
+            if status == "in_progress" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("job should be claimed");
+
+    shutdown.cancel();
+    timeout(Duration::from_secs(5), worker)
+        .await
+        .expect("job loop should stop after shutdown")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_shutdown_finishes_in_flight_anchor() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+
+    shut_down_mid_anchor(&pool, Duration::from_millis(200), Duration::from_secs(5)).await;
+
+    let row = sqlx::query("SELECT status, lease_owner FROM outbox_jobs WHERE id='job-1'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<String, _>(0), "done");
+    assert!(row.get::<Option<String>, _>(1).is_none());
+    let tx_refs: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM outbox_tx_refs WHERE job_id='job-1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(tx_refs, 1);
+}
+
+#[tokio::test]
+async fn test_shutdown_timeout_requeues_in_flight_job() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+
+    shut_down_mid_anchor(&pool, Duration::from_secs(60), Duration::from_millis(100)).await;
+
+    let row =
+        sqlx::query("SELECT status, last_error, lease_owner FROM outbox_jobs WHERE id='job-1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(row.get::<String, _>(0), "queued");
+    assert_eq!(row.get::<String, _>(1), SHUTDOWN_ERROR);
+    assert!(row.get::<Option<String>, _>(2).is_none());
+
+    let attempt =
+        sqlx::query("SELECT outcome, category FROM outbox_job_attempts WHERE job_id='job-1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(attempt.get::<String, _>(0), "retry");
+    assert_eq!(attempt.get::<String, _>(1), "interrupted");
+}
+
+#[tokio::test]
+async fn test_idle_loops_stop_on_shutdown() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let pool = sqlite_test_pool(temp_db.path()).await;
+    TestJob::new("job-1").insert(&pool).await;
+    sqlx::query("DELETE FROM outbox_jobs")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO outbox_tx_refs (job_id, network, chain, tx_id, confirmed, timestamp) VALUES ('job-0', 'etherlink', 'testnet', 'fake:0', 0, NULL)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let shutdown = CancellationToken::new();
+    let control = LoopControl::new(shutdown.clone(), Duration::from_secs(5));
+    // Hour-long polls: only the shutdown can end these loops within the test
+    let poll = Duration::from_secs(3600);
+
+    let mut jp = SqliteJobProvider::new(pool.clone());
+    let job_control = control.clone();
+    let jobs = tokio::spawn(async move {
+        run_job_loop_with_control(&mut jp, &EtherlinkProviderStub, poll, &job_control).await;
+    });
+    let confirm_pool = pool.clone();
+    let confirm_control = control.clone();
+    let confirmations = tokio::spawn(async move {
+        run_confirmation_loop_with_control(
+            &confirm_pool,
+            &EtherlinkProviderStub,
+            poll,
+            &confirm_control,
+        )
+        .await;
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    shutdown.cancel();
+    timeout(Duration::from_secs(1), jobs)
+        .await
+        .expect("job loop should stop")
+        .unwrap();
+    timeout(Duration::from_secs(1), confirmations)
+        .await
+        .expect("confirmation loop should stop")
+        .unwrap();
+
+    // The confirmation check made before shutdown was kept
+    let confirmed: i64 = sqlx::query_scalar("SELECT confirmed FROM outbox_tx_refs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(confirmed, 1);
+}
@@ -0,0 +1,244 @@
+use crate::registry::{AddressValidator, ParsedAddress};
+use crate::{AddressError, AddressMetadata};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BitcoinNetwork {
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+impl BitcoinNetwork {
+    fn p2pkh_version(self) -> u8 {
+        match self {
+            BitcoinNetwork::Mainnet => 0x00,
+            BitcoinNetwork::Testnet | BitcoinNetwork::Regtest => 0x6f,
+        }
+    }
+
+    fn p2sh_version(self) -> u8 {
+        match self {
+            BitcoinNetwork::Mainnet => 0x05,
+            BitcoinNetwork::Testnet | BitcoinNetwork::Regtest => 0xc4,
+        }
+    }
+
+    fn bech32_hrp(self) -> &'static str {
+        match self {
+            BitcoinNetwork::Mainnet => "bc",
+            BitcoinNetwork::Testnet => "tb",
+            BitcoinNetwork::Regtest => "bcrt",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitcoinAddressKind {
+    P2pkh,
+    P2sh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+    /// Segwit version 1-16 program not covered above
+    WitnessUnknown(u8),
+}
+
+/// Base58Check (P2PKH/P2SH) and bech32/bech32m segwit addresses for one network
+#[derive(Debug, Clone)]
+pub struct BitcoinValidator {
+    pub network: BitcoinNetwork,
+}
+
+impl BitcoinValidator {
+    pub fn new(network: BitcoinNetwork) -> Self {
+        Self { network }
+    }
+
+    fn parse_base58(&self, address: &str) -> Result<ParsedAddress, AddressError> {
+        let decoded = bs58::decode(address)
+            .with_check(None)
+            .into_vec()
+            .map_err(|e| match e {
+                bs58::decode::Error::InvalidChecksum { .. } => AddressError::InvalidChecksum,
+                other => AddressError::Base58Error(other.to_string()),
+            })?;
+
+        if decoded.len() != 21 {
+            return Err(AddressError::InvalidLength {
+                expected: 21,
+                actual: decoded.len(),
+            });
+        }
+
+        let kind = match decoded[0] {
+            v if v == self.network.p2pkh_version() => BitcoinAddressKind::P2pkh,
+            v if v == self.network.p2sh_version() => BitcoinAddressKind::P2sh,
+            v => {
+                return Err(AddressError::InvalidPrefix(format!(
+                    "version byte 0x{:02x} is not valid on {:?}",
+                    v, self.network
+                )))
+            }
+        };
+
+        Ok(ParsedAddress::Bitcoin {
+            address: address.to_string(),
+            network: self.network,
+            kind,
+            payload: decoded[1..].to_vec(),
+        })
+    }
+
+    fn parse_segwit(&self, address: &str) -> Result<ParsedAddress, AddressError> {
+        // Picks bech32 for v0 and bech32m for v1+ and checks program lengths
+        let (hrp, version, program) = bech32::segwit::decode(address)
+            .map_err(|e| AddressError::Bech32Error(e.to_string()))?;
+
+        if hrp.to_lowercase() != self.network.bech32_hrp() {
+            return Err(AddressError::InvalidPrefix(format!(
+                "expected {}1 on {:?}",
+                self.network.bech32_hrp(),
+                self.network
+            )));
+        }
+
+        let kind = match (version.to_u8(), program.len()) {
+            (0, 20) => BitcoinAddressKind::P2wpkh,
+            (0, 32) => BitcoinAddressKind::P2wsh,
+            (1, 32) => BitcoinAddressKind::P2tr,
+            (v, _) => BitcoinAddressKind::WitnessUnknown(v),
+        };
+
+        Ok(ParsedAddress::Bitcoin {
+            address: address.to_lowercase(),
+            network: self.network,
+            kind,
+            payload: program,
+        })
+    }
+}
+
+impl AddressValidator for BitcoinValidator {
+    fn chain(&self) -> &str {
+        "bitcoin"
+    }
+
+    fn metadata(&self) -> AddressMetadata {
+        let example = match self.network {
+            BitcoinNetwork::Mainnet => "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            BitcoinNetwork::Testnet => "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+            BitcoinNetwork::Regtest => "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+        };
+        AddressMetadata {
+            chain: "bitcoin".to_string(),
+            address_format: format!(
+                "Base58Check P2PKH/P2SH, or bech32 (v0) / bech32m (v1+) segwit with HRP '{}'.",
+                self.network.bech32_hrp()
+            ),
+            address_example: example.to_string(),
+        }
+    }
+
+    fn parse(&self, address: &str) -> Result<ParsedAddress, AddressError> {
+        let separator = format!("{}1", self.network.bech32_hrp());
+        if address.to_lowercase().starts_with(&separator) {
+            self.parse_segwit(address)
+        } else {
+            self.parse_base58(address)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mainnet() -> BitcoinValidator {
+        BitcoinValidator::new(BitcoinNetwork::Mainnet)
+    }
+
+    fn kind_of(validator: &BitcoinValidator, address: &str) -> BitcoinAddressKind {
+        match validator.parse(address).unwrap() {
+            ParsedAddress::Bitcoin { kind, .. } => kind,
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_base58_addresses() {
+        let v = mainnet();
+        assert_eq!(
+            kind_of(&v, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+            BitcoinAddressKind::P2pkh
+        );
+        assert_eq!(
+            kind_of(&v, "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"),
+            BitcoinAddressKind::P2sh
+        );
+
+        // Altered last character
+        assert!(matches!(
+            v.parse("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb"),
+            Err(AddressError::InvalidChecksum)
+        ));
+        // Mainnet address on testnet
+        assert!(matches!(
+            BitcoinValidator::new(BitcoinNetwork::Testnet)
+                .parse("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"),
+            Err(AddressError::InvalidPrefix(_))
+        ));
+    }
+
+    #[test]
+    fn test_segwit_addresses() {
+        let v = mainnet();
+        assert_eq!(
+            kind_of(&v, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"),
+            BitcoinAddressKind::P2wpkh
+        );
+        assert_eq!(
+            kind_of(&v, "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4"),
+            BitcoinAddressKind::P2wpkh
+        );
+        assert_eq!(
+            kind_of(
+                &v,
+                "bc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297"
+            ),
+            BitcoinAddressKind::P2tr
+        );
+        assert_eq!(
+            kind_of(
+                &BitcoinValidator::new(BitcoinNetwork::Testnet),
+                "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+            ),
+            BitcoinAddressKind::P2wpkh
+        );
+
+        // v0 program with a bech32m checksum (BIP-350 invalid vector)
+        assert!(matches!(
+            v.parse("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh"),
+            Err(AddressError::Bech32Error(_))
+        ));
+        // Testnet address on mainnet
+        assert!(v
+            .parse("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx")
+            .is_err());
+    }
+
+    #[test]
+    fn test_metadata_examples_parse() {
+        for network in [
+            BitcoinNetwork::Mainnet,
+            BitcoinNetwork::Testnet,
+            BitcoinNetwork::Regtest,
+        ] {
+            let validator = BitcoinValidator::new(network);
+            let example = validator.metadata().address_example;
+            assert!(validator.parse(&example).is_ok(), "{}", example);
+        }
+    }
+}
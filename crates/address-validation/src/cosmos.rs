@@ -0,0 +1,117 @@
+use crate::registry::{AddressValidator, ParsedAddress};
+use crate::{AddressError, AddressMetadata};
+use bech32::primitives::decode::CheckedHrpstring;
+use bech32::Bech32;
+
+/// Bech32 account (20-byte) and contract/module (32-byte) addresses for one
+/// Cosmos SDK chain, identified by its human-readable prefix
+#[derive(Debug, Clone)]
+pub struct CosmosValidator {
+    pub hrp: String,
+}
+
+impl CosmosValidator {
+    pub fn new(hrp: &str) -> Self {
+        Self {
+            hrp: hrp.to_lowercase(),
+        }
+    }
+}
+
+impl AddressValidator for CosmosValidator {
+    fn chain(&self) -> &str {
+        "cosmos"
+    }
+
+    fn metadata(&self) -> AddressMetadata {
+        AddressMetadata {
+            chain: "cosmos".to_string(),
+            address_format: format!(
+                "bech32 with HRP '{}'; 20-byte accounts or 32-byte contracts.",
+                self.hrp
+            ),
+            address_example: "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu".to_string(),
+        }
+    }
+
+    fn parse(&self, address: &str) -> Result<ParsedAddress, AddressError> {
+        let checked = CheckedHrpstring::new::<Bech32>(address)
+            .map_err(|e| AddressError::Bech32Error(e.to_string()))?;
+
+        let hrp = checked.hrp().to_lowercase();
+        if hrp != self.hrp {
+            return Err(AddressError::InvalidPrefix(format!(
+                "expected HRP '{}', got '{}'",
+                self.hrp, hrp
+            )));
+        }
+
+        let bytes: Vec<u8> = checked.byte_iter().collect();
+        if bytes.len() != 20 && bytes.len() != 32 {
+            return Err(AddressError::InvalidLength {
+                expected: 20,
+                actual: bytes.len(),
+            });
+        }
+
+        Ok(ParsedAddress::Cosmos {
+            address: address.to_lowercase(),
+            hrp,
+            bytes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bech32::{Bech32m, Hrp};
+
+    fn encode<Ck: bech32::Checksum>(hrp: &str, data: &[u8]) -> String {
+        bech32::encode::<Ck>(Hrp::parse(hrp).unwrap(), data).unwrap()
+    }
+
+    #[test]
+    fn test_cosmos_account_and_contract() {
+        let validator = CosmosValidator::new("cosmos");
+
+        let account = encode::<Bech32>("cosmos", &[7u8; 20]);
+        assert_eq!(
+            validator.parse(&account).unwrap(),
+            ParsedAddress::Cosmos {
+                address: account.clone(),
+                hrp: "cosmos".to_string(),
+                bytes: vec![7u8; 20],
+            }
+        );
+
+        let contract = encode::<Bech32>("cosmos", &[9u8; 32]);
+        assert!(validator.parse(&contract).is_ok());
+
+        let example = validator.metadata().address_example;
+        assert!(validator.parse(&example).is_ok());
+    }
+
+    #[test]
+    fn test_cosmos_invalid() {
+        let validator = CosmosValidator::new("cosmos");
+
+        let other_chain = encode::<Bech32>("osmo", &[7u8; 20]);
+        assert!(matches!(
+            validator.parse(&other_chain),
+            Err(AddressError::InvalidPrefix(_))
+        ));
+
+        let bech32m = encode::<Bech32m>("cosmos", &[7u8; 20]);
+        assert!(matches!(
+            validator.parse(&bech32m),
+            Err(AddressError::Bech32Error(_))
+        ));
+
+        let short = encode::<Bech32>("cosmos", &[7u8; 10]);
+        assert!(matches!(
+            validator.parse(&short),
+            Err(AddressError::InvalidLength { .. })
+        ));
+    }
+}
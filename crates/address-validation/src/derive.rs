@@ -0,0 +1,211 @@
+//! Deriving addresses from keys, and checking configured keys against expected addresses.
+//!
+//! Errors never include key material.
+
+use crate::{to_eip55_checksum, validate_evm_address, validate_solana_address, AddressError};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use sha3::{Digest, Keccak256};
+
+/// EVM address of a secp256k1 public key in SEC1 form (33-byte compressed or
+/// 65-byte uncompressed): the last 20 bytes of Keccak-256 over the uncompressed
+/// point without its 0x04 tag, EIP-55 checksummed.
+pub fn evm_address_from_public_key(public_key: &[u8]) -> Result<String, AddressError> {
+    let key = k256::PublicKey::from_sec1_bytes(public_key)
+        .map_err(|_| AddressError::InvalidKey("not a secp256k1 public key".to_string()))?;
+    Ok(evm_address_from_k256(&key))
+}
+
+/// EVM address for a hex private key, with or without `0x`
+pub fn evm_address_from_private_key(private_key: &str) -> Result<String, AddressError> {
+    let hex_part = private_key.trim();
+    let hex_part = hex_part.strip_prefix("0x").unwrap_or(hex_part);
+    let bytes = hex::decode(hex_part)
+        .map_err(|_| AddressError::InvalidKey("private key is not hex".to_string()))?;
+    if bytes.len() != 32 {
+        return Err(AddressError::InvalidKey(format!(
+            "private key must be 32 bytes, got {}",
+            bytes.len()
+        )));
+    }
+
+    let secret = k256::SecretKey::from_slice(&bytes)
+        .map_err(|_| AddressError::InvalidKey("private key out of range".to_string()))?;
+    Ok(evm_address_from_k256(&secret.public_key()))
+}
+
+fn evm_address_from_k256(key: &k256::PublicKey) -> String {
+    let point = key.to_encoded_point(false);
+    let hash = Keccak256::digest(&point.as_bytes()[1..]);
+    let address = format!("0x{}", hex::encode(&hash[12..]));
+    to_eip55_checksum(&address).unwrap_or(address)
+}
+
+/// Solana address of a 32-byte Ed25519 public key
+pub fn solana_address_from_public_key(public_key: &[u8]) -> Result<String, AddressError> {
+    let bytes: [u8; 32] = public_key
+        .try_into()
+        .map_err(|_| AddressError::InvalidLength {
+            expected: 32,
+            actual: public_key.len(),
+        })?;
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|_| AddressError::InvalidKey("not an Ed25519 public key".to_string()))?;
+    Ok(bs58::encode(bytes).into_string())
+}
+
+/// Solana address for a 32-byte seed or a 64-byte keypair (seed followed by public key,
+/// as in `solana-keygen` files). For keypairs the embedded public key must match the seed.
+pub fn solana_address_from_secret_key(secret: &[u8]) -> Result<String, AddressError> {
+    let (seed, embedded) = match secret.len() {
+        32 => (secret, None),
+        64 => (&secret[..32], Some(&secret[32..])),
+        other => {
+            return Err(AddressError::InvalidKey(format!(
+                "secret key must be 32 or 64 bytes, got {}",
+                other
+            )))
+        }
+    };
+
+    let seed: [u8; 32] = seed.try_into().expect("length checked above");
+    let public = SigningKey::from_bytes(&seed).verifying_key();
+    if embedded.is_some_and(|embedded| embedded != public.as_bytes()) {
+        return Err(AddressError::InvalidKey(
+            "keypair public half does not match its secret".to_string(),
+        ));
+    }
+
+    Ok(bs58::encode(public.as_bytes()).into_string())
+}
+
+/// Check that `private_key` controls `expected_address`; returns the derived address
+pub fn verify_evm_private_key(
+    private_key: &str,
+    expected_address: &str,
+) -> Result<String, AddressError> {
+    validate_evm_address(expected_address, false)?;
+    let derived = evm_address_from_private_key(private_key)?;
+    if !derived.eq_ignore_ascii_case(expected_address) {
+        return Err(AddressError::KeyMismatch {
+            expected: expected_address.to_string(),
+            derived,
+        });
+    }
+    Ok(derived)
+}
+
+/// Check that `secret` controls `expected_address`; returns the derived address
+pub fn verify_solana_secret_key(
+    secret: &[u8],
+    expected_address: &str,
+) -> Result<String, AddressError> {
+    validate_solana_address(expected_address)?;
+    let derived = solana_address_from_secret_key(secret)?;
+    if derived != expected_address {
+        return Err(AddressError::KeyMismatch {
+            expected: expected_address.to_string(),
+            derived,
+        });
+    }
+    Ok(derived)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hardhat/Anvil default account #0
+    const EVM_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const EVM_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+    // RFC 8032 Ed25519 test 1
+    const ED25519_SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const ED25519_PUBLIC: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+    #[test]
+    fn test_evm_address_from_private_key() {
+        assert_eq!(evm_address_from_private_key(EVM_KEY).unwrap(), EVM_ADDRESS);
+        assert_eq!(
+            evm_address_from_private_key(&EVM_KEY[2..]).unwrap(),
+            EVM_ADDRESS
+        );
+        assert!(matches!(
+            evm_address_from_private_key("0x1234"),
+            Err(AddressError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            evm_address_from_private_key(&"0".repeat(64)),
+            Err(AddressError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_evm_address_from_public_key_forms() {
+        let secret = k256::SecretKey::from_slice(&hex::decode(&EVM_KEY[2..]).unwrap()).unwrap();
+        let public = secret.public_key();
+
+        let uncompressed = public.to_encoded_point(false);
+        let compressed = public.to_encoded_point(true);
+        assert_eq!(
+            evm_address_from_public_key(uncompressed.as_bytes()).unwrap(),
+            EVM_ADDRESS
+        );
+        assert_eq!(
+            evm_address_from_public_key(compressed.as_bytes()).unwrap(),
+            EVM_ADDRESS
+        );
+        assert!(evm_address_from_public_key(&[4u8; 65]).is_err());
+    }
+
+    #[test]
+    fn test_verify_evm_private_key() {
+        assert_eq!(
+            verify_evm_private_key(EVM_KEY, &EVM_ADDRESS.to_lowercase()).unwrap(),
+            EVM_ADDRESS
+        );
+
+        let other = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+        match verify_evm_private_key(EVM_KEY, other) {
+            Err(AddressError::KeyMismatch { expected, derived }) => {
+                assert_eq!(expected, other);
+                assert_eq!(derived, EVM_ADDRESS);
+            }
+            other => panic!("expected mismatch, got {:?}", other),
+        }
+
+        // Key material must not leak into error text
+        let err = verify_evm_private_key("0xzz", EVM_ADDRESS).unwrap_err();
+        assert!(!err.to_string().contains("zz"));
+    }
+
+    #[test]
+    fn test_solana_address_derivation() {
+        let seed = hex::decode(ED25519_SEED).unwrap();
+        let public = hex::decode(ED25519_PUBLIC).unwrap();
+        let expected = bs58::encode(&public).into_string();
+
+        assert_eq!(solana_address_from_public_key(&public).unwrap(), expected);
+        assert_eq!(solana_address_from_secret_key(&seed).unwrap(), expected);
+
+        let mut keypair = seed.clone();
+        keypair.extend_from_slice(&public);
+        assert_eq!(solana_address_from_secret_key(&keypair).unwrap(), expected);
+        assert_eq!(
+            verify_solana_secret_key(&keypair, &expected).unwrap(),
+            expected
+        );
+
+        // Keypair whose halves do not belong together
+        keypair[40] ^= 1;
+        assert!(matches!(
+            solana_address_from_secret_key(&keypair),
+            Err(AddressError::InvalidKey(_))
+        ));
+
+        assert!(matches!(
+            verify_solana_secret_key(&seed, "11111111111111111111111111111111"),
+            Err(AddressError::KeyMismatch { .. })
+        ));
+    }
+}
@@ -2,6 +2,22 @@ use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 use thiserror::Error;
 
+pub mod bitcoin;
+pub mod cosmos;
+pub mod derive;
+pub mod policy;
+pub mod registry;
+pub mod solana;
+pub mod tezos;
+
+pub use derive::{
+    evm_address_from_private_key, evm_address_from_public_key, solana_address_from_public_key,
+    solana_address_from_secret_key, verify_evm_private_key, verify_solana_secret_key,
+};
+pub use policy::{AddressPolicy, ListKind, PolicyDecision, PolicyEntry, PolicyError, PolicyReason};
+pub use registry::{AddressRegistry, AddressValidator, ParsedAddress};
+pub use solana::{find_program_address, SolanaAddressKind, WellKnownProgram};
+
 #[derive(Debug, Error)]
 pub enum AddressError {
     #[error("invalid length: expected {expected}, got {actual}")]
@@ -14,6 +30,18 @@ pub enum AddressError {
     InvalidChecksum,
     #[error("base58 decode error: {0}")]
     Base58Error(String),
+    #[error("bech32 decode error: {0}")]
+    Bech32Error(String),
+    #[error("unsupported chain: {0}")]
+    UnsupportedChain(String),
+    #[error("invalid seeds: {0}")]
+    InvalidSeeds(String),
+    #[error("address cannot sign: {0}")]
+    NotASigner(String),
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+    #[error("key does not match address: expected {expected}, derived {derived}")]
+    KeyMismatch { expected: String, derived: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,31 +58,51 @@ pub struct EvmAddressInfo {
     pub address_example: String,
     pub normalized_address: String,
     pub checksum_valid: bool,
+    pub checksum_scheme: ChecksumScheme,
     pub validation_reason: String,
 }
 
 pub fn get_address_metadata(chain: &str) -> Result<AddressMetadata, AddressError> {
-    match chain.to_lowercase().as_str() {
-        "ethereum" | "etherlink" | "evm" => Ok(AddressMetadata {
-            chain: "evm".to_string(),
-            address_format: "0x-prefixed hex (42 chars, 20 bytes). EIP-55 checksum recommended."
-                .to_string(),
-            address_example: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
-        }),
-        "solana" => Ok(AddressMetadata {
-            chain: "solana".to_string(),
-            address_format: "Base58 encoded; decodes to exactly 32 bytes (length varies)."
-                .to_string(),
-            address_example: "4Nd1mY3iQz9dKqG2m9X3pQxvGXn3a6TT5p7H1cDJ5b5P".to_string(),
-        }),
-        _ => Err(AddressError::InvalidPrefix(format!(
-            "Unsupported chain: {}",
-            chain
-        ))),
+    AddressRegistry::with_defaults()
+        .get(chain)
+        .map(|validator| validator.metadata())
+}
+
+/// Chains whose wallets checksum with EIP-1191 rather than plain EIP-55 (RSK mainnet and testnet)
+pub const EIP1191_CHAIN_IDS: &[u64] = &[30, 31];
+
+/// Which mixed-case checksum an EVM address carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "scheme", rename_all = "snake_case")]
+pub enum ChecksumScheme {
+    /// Single-case hex, which carries no checksum
+    None,
+    Eip55,
+    Eip1191 {
+        chain_id: u64,
+    },
+    /// Mixed case that matches none of the schemes checked
+    Invalid,
+}
+
+impl ChecksumScheme {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, ChecksumScheme::Eip55 | ChecksumScheme::Eip1191 { .. })
     }
 }
 
-pub fn validate_evm_address(address: &str, require_checksum: bool) -> Result<(), AddressError> {
+/// Clean up an address pasted from a wallet or config: surrounding whitespace is
+/// trimmed and an upper-case `0X` prefix is lowered. Case of the hex digits is kept
+/// so the checksum can still be checked.
+pub fn normalize_evm_input(address: &str) -> String {
+    let trimmed = address.trim();
+    match trimmed.strip_prefix("0X") {
+        Some(rest) => format!("0x{}", rest),
+        None => trimmed.to_string(),
+    }
+}
+
+fn check_evm_format(address: &str) -> Result<(), AddressError> {
     // Check prefix
     if !address.starts_with("0x") {
         return Err(AddressError::InvalidPrefix(
@@ -70,67 +118,135 @@ pub fn validate_evm_address(address: &str, require_checksum: bool) -> Result<(),
         });
     }
 
-    let hex_part = &address[2..];
-
     // Check hex characters
-    if !hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
+    if !address[2..].chars().all(|c| c.is_ascii_hexdigit()) {
         return Err(AddressError::InvalidCharacters(
             "contains non-hex characters".to_string(),
         ));
     }
 
-    // Check EIP-55 checksum if required
-    if require_checksum {
-        let expected_checksum = to_eip55_checksum(address)?;
-        if address != expected_checksum {
-            return Err(AddressError::InvalidChecksum);
-        }
+    Ok(())
+}
+
+pub fn validate_evm_address(address: &str, require_checksum: bool) -> Result<(), AddressError> {
+    validate_evm_address_for_chain(address, require_checksum, None)
+}
+
+/// Like [`validate_evm_address`]; with a chain id, an EIP-1191 checksum for that chain
+/// is accepted as well as EIP-55.
+pub fn validate_evm_address_for_chain(
+    address: &str,
+    require_checksum: bool,
+    chain_id: Option<u64>,
+) -> Result<(), AddressError> {
+    check_evm_format(address)?;
+
+    if require_checksum && !detect_checksum_scheme(address, chain_id)?.is_valid() {
+        return Err(AddressError::InvalidChecksum);
     }
 
     Ok(())
 }
 
 pub fn to_eip55_checksum(address: &str) -> Result<String, AddressError> {
+    to_checksum(address, None)
+}
+
+/// Checksum an address with EIP-55, or with EIP-1191 when a chain id is given
+pub fn to_checksum(address: &str, chain_id: Option<u64>) -> Result<String, AddressError> {
     // Validate basic format first
-    validate_evm_address(address, false)?;
+    check_evm_format(address)?;
 
     let hex_part = &address[2..].to_lowercase();
-    let hash = Keccak256::digest(hex_part.as_bytes());
+    // EIP-1191 salts the hash with the chain id
+    let preimage = match chain_id {
+        Some(id) => format!("{}0x{}", id, hex_part),
+        None => hex_part.clone(),
+    };
+    let hash = Keccak256::digest(preimage.as_bytes());
     let hash_hex = hex::encode(hash);
 
     let mut result = String::with_capacity(42);
     result.push_str("0x");
 
-    for (i, c) in hex_part.chars().enumerate() {
-        if c.is_ascii_digit() {
+    for (c, hash_char) in hex_part.chars().zip(hash_hex.chars()) {
+        if c.is_ascii_digit() || hash_char < '8' {
             result.push(c);
         } else {
-            let hash_char = hash_hex.chars().nth(i).unwrap();
-            if hash_char >= '8' {
-                result.push(c.to_ascii_uppercase());
-            } else {
-                result.push(c);
-            }
+            result.push(c.to_ascii_uppercase());
         }
     }
 
     Ok(result)
 }
 
+/// Work out which checksum scheme, if any, an address was written with
+pub fn detect_checksum_scheme(
+    address: &str,
+    chain_id: Option<u64>,
+) -> Result<ChecksumScheme, AddressError> {
+    check_evm_format(address)?;
+
+    if to_checksum(address, None)? == address {
+        return Ok(ChecksumScheme::Eip55);
+    }
+    if let Some(id) = chain_id {
+        if to_checksum(address, Some(id))? == address {
+            return Ok(ChecksumScheme::Eip1191 { chain_id: id });
+        }
+    }
+
+    let hex_part = &address[2..];
+    let has_lower = hex_part.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = hex_part.chars().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        Ok(ChecksumScheme::Invalid)
+    } else {
+        Ok(ChecksumScheme::None)
+    }
+}
+
 pub fn get_evm_address_info(address: &str, require_checksum: bool) -> EvmAddressInfo {
-    let metadata = get_address_metadata("evm").unwrap();
+    get_evm_address_info_for_chain(address, require_checksum, None)
+}
 
-    match validate_evm_address(address, require_checksum) {
-        Ok(()) => {
-            let normalized = to_eip55_checksum(address).unwrap_or_else(|_| address.to_string());
-            let checksum_valid = validate_evm_address(address, true).is_ok();
+/// Chain id to checksum with when normalizing: the scheme the input was written with,
+/// or for unchecksummed input EIP-1191 on chains listed in [`EIP1191_CHAIN_IDS`] and
+/// EIP-55 everywhere else.
+pub(crate) fn normalization_chain_id(scheme: ChecksumScheme, chain_id: Option<u64>) -> Option<u64> {
+    match scheme {
+        ChecksumScheme::Eip1191 { chain_id } => Some(chain_id),
+        ChecksumScheme::Eip55 => None,
+        ChecksumScheme::None | ChecksumScheme::Invalid => {
+            chain_id.filter(|id| EIP1191_CHAIN_IDS.contains(id))
+        }
+    }
+}
+
+/// Validate and describe an EVM address, reporting which checksum scheme matched
+pub fn get_evm_address_info_for_chain(
+    address: &str,
+    require_checksum: bool,
+    chain_id: Option<u64>,
+) -> EvmAddressInfo {
+    let metadata = registry::EvmValidator::default().metadata();
+    let address = normalize_evm_input(address);
+
+    let result = validate_evm_address_for_chain(&address, require_checksum, chain_id)
+        .and_then(|()| detect_checksum_scheme(&address, chain_id));
+
+    match result {
+        Ok(scheme) => {
+            let normalized = to_checksum(&address, normalization_chain_id(scheme, chain_id))
+                .unwrap_or_else(|_| address.clone());
 
             EvmAddressInfo {
                 chain: "evm".to_string(),
                 address_format: metadata.address_format,
                 address_example: metadata.address_example,
                 normalized_address: normalized,
-                checksum_valid,
+                checksum_valid: scheme.is_valid(),
+                checksum_scheme: scheme,
                 validation_reason: String::new(),
             }
         }
@@ -140,24 +256,31 @@ pub fn get_evm_address_info(address: &str, require_checksum: bool) -> EvmAddress
             address_example: metadata.address_example,
             normalized_address: String::new(),
             checksum_valid: false,
+            checksum_scheme: ChecksumScheme::Invalid,
             validation_reason: e.to_string(),
         },
     }
 }
 
-pub fn validate_solana_address(address: &str) -> Result<(), AddressError> {
-    let decoded = bs58::decode(address)
-        .into_vec()
-        .map_err(|e| AddressError::Base58Error(e.to_string()))?;
+/// Check that an address is 32 bytes of base58 and report whether it is a wallet
+/// (on the Ed25519 curve), a PDA (off the curve) or a well-known program.
+pub fn validate_solana_address(address: &str) -> Result<SolanaAddressKind, AddressError> {
+    let pubkey = solana::decode_pubkey(address)?;
+    Ok(solana::classify(&pubkey, address))
+}
 
-    if decoded.len() != 32 {
-        return Err(AddressError::InvalidLength {
-            expected: 32,
-            actual: decoded.len(),
-        });
+/// Like [`validate_solana_address`], but only accepts keys that can sign transactions
+pub fn validate_solana_signer(address: &str) -> Result<(), AddressError> {
+    match validate_solana_address(address)? {
+        SolanaAddressKind::Wallet => Ok(()),
+        SolanaAddressKind::OffCurve => Err(AddressError::NotASigner(
+            "off-curve address (PDA) has no private key".to_string(),
+        )),
+        SolanaAddressKind::Program(program) => Err(AddressError::NotASigner(format!(
+            "{:?} program id, not a wallet",
+            program
+        ))),
     }
-
-    Ok(())
 }
 
 #[cfg(test)]
@@ -194,4 +317,104 @@ mod tests {
         // Invalid Base58
         assert!(validate_solana_address("invalid0OIl").is_err());
     }
+
+    #[test]
+    fn test_solana_signer_validation() {
+        assert_eq!(
+            validate_solana_address("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr").unwrap(),
+            SolanaAddressKind::Program(WellKnownProgram::Memo)
+        );
+        assert!(matches!(
+            validate_solana_signer("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"),
+            Err(AddressError::NotASigner(_))
+        ));
+        assert!(matches!(
+            validate_solana_signer("11111111111111111111111111111111"),
+            Err(AddressError::NotASigner(_))
+        ));
+
+        let (pda, _) = find_program_address(
+            &[b"registry"],
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        )
+        .unwrap();
+        assert!(matches!(
+            validate_solana_signer(&pda),
+            Err(AddressError::NotASigner(_))
+        ));
+    }
+
+    // Test vectors from the EIP-1191 specification
+    const RSK_MAINNET: [&str; 2] = [
+        "0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD",
+        "0xFb6916095cA1Df60bb79ce92cE3EA74c37c5d359",
+    ];
+    const RSK_TESTNET: [&str; 2] = [
+        "0x5aAeb6053F3e94c9b9A09F33669435E7EF1BEaEd",
+        "0xFb6916095CA1dF60bb79CE92ce3Ea74C37c5D359",
+    ];
+
+    #[test]
+    fn test_eip1191_checksum() {
+        for (chain_id, vectors) in [(30, RSK_MAINNET), (31, RSK_TESTNET)] {
+            for expected in vectors {
+                let lower = expected.to_lowercase();
+                assert_eq!(to_checksum(&lower, Some(chain_id)).unwrap(), expected);
+                assert!(validate_evm_address_for_chain(expected, true, Some(chain_id)).is_ok());
+                assert_eq!(
+                    detect_checksum_scheme(expected, Some(chain_id)).unwrap(),
+                    ChecksumScheme::Eip1191 { chain_id }
+                );
+            }
+        }
+
+        // Salted checksums are not valid EIP-55, nor valid for another chain
+        assert!(validate_evm_address(RSK_MAINNET[0], true).is_err());
+        assert!(validate_evm_address_for_chain(RSK_MAINNET[0], true, Some(31)).is_err());
+    }
+
+    #[test]
+    fn test_detect_checksum_scheme() {
+        let eip55 = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+        assert_eq!(
+            detect_checksum_scheme(eip55, Some(30)).unwrap(),
+            ChecksumScheme::Eip55
+        );
+        assert_eq!(
+            detect_checksum_scheme(&eip55.to_lowercase(), None).unwrap(),
+            ChecksumScheme::None
+        );
+        assert_eq!(
+            detect_checksum_scheme("0x742D35Cc6634C0532925a3b844Bc454e4438f44e", None).unwrap(),
+            ChecksumScheme::Invalid
+        );
+    }
+
+    #[test]
+    fn test_evm_address_info_reports_scheme() {
+        let info = get_evm_address_info_for_chain(RSK_MAINNET[1], true, Some(30));
+        assert!(info.checksum_valid);
+        assert_eq!(
+            info.checksum_scheme,
+            ChecksumScheme::Eip1191 { chain_id: 30 }
+        );
+        assert_eq!(info.normalized_address, RSK_MAINNET[1]);
+
+        // Pasted with whitespace and an upper-case prefix, no checksum
+        let pasted = format!("  0X{}\n", &RSK_MAINNET[1][2..].to_lowercase());
+        let info = get_evm_address_info_for_chain(&pasted, false, Some(30));
+        assert!(info.validation_reason.is_empty());
+        assert!(!info.checksum_valid);
+        assert_eq!(info.checksum_scheme, ChecksumScheme::None);
+        assert_eq!(info.normalized_address, RSK_MAINNET[1]);
+
+        let info = get_evm_address_info("0x742d35cc6634c0532925a3b844bc454e4438f44e", false);
+        assert_eq!(
+            info.normalized_address,
+            "0x742d35Cc6634C0532925a3b844Bc454e4438f44e"
+        );
+
+        let info = get_evm_address_info("0x742D35Cc6634C0532925a3b844Bc454e4438f44e", true);
+        assert!(!info.validation_reason.is_empty());
+    }
 }
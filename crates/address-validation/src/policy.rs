@@ -0,0 +1,386 @@
+//! Allow and deny lists for screening counterparty addresses offline.
+//!
+//! Entries are validated and normalized on load (EIP-55 for EVM, canonical base58
+//! for Solana, the validator's normal form elsewhere), so lookups match however an
+//! address was written. Lists are kept per address family (`AddressValidator::chain`),
+//! so an `ethereum` entry also covers `etherlink` and `rsk`.
+
+use crate::registry::{AddressRegistry, ParsedAddress};
+use crate::{to_eip55_checksum, AddressError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PolicyError {
+    #[error("failed to read policy file: {0}")]
+    Io(String),
+    #[error("unsupported policy file format: {0}")]
+    UnsupportedFormat(String),
+    #[error("malformed policy file: {0}")]
+    Parse(String),
+    #[error("invalid entry at {location}: {source}")]
+    InvalidEntry {
+        location: String,
+        #[source]
+        source: AddressError,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListKind {
+    Allow,
+    Deny,
+}
+
+/// One line of a policy file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyEntry {
+    pub chain: String,
+    pub address: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CsvRow {
+    list: ListKind,
+    chain: String,
+    address: String,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct JsonPolicy {
+    #[serde(default)]
+    allow: Vec<PolicyEntry>,
+    #[serde(default)]
+    deny: Vec<PolicyEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum PolicyReason {
+    /// On the deny list; deny entries win over allow entries
+    Denylisted {
+        note: Option<String>,
+    },
+    Allowlisted {
+        note: Option<String>,
+    },
+    /// The chain has an allow list and the address is not on it
+    NotAllowlisted,
+    /// Neither list has anything to say about the address
+    NoMatch,
+    InvalidAddress {
+        error: String,
+    },
+}
+
+impl fmt::Display for PolicyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyReason::Denylisted { note: Some(note) } => write!(f, "denylisted: {}", note),
+            PolicyReason::Denylisted { note: None } => write!(f, "denylisted"),
+            PolicyReason::Allowlisted { note: Some(note) } => write!(f, "allowlisted: {}", note),
+            PolicyReason::Allowlisted { note: None } => write!(f, "allowlisted"),
+            PolicyReason::NotAllowlisted => write!(f, "not on the allow list"),
+            PolicyReason::NoMatch => write!(f, "not listed"),
+            PolicyReason::InvalidAddress { error } => write!(f, "invalid address: {}", error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyDecision {
+    pub permitted: bool,
+    #[serde(flatten)]
+    pub reason: PolicyReason,
+}
+
+impl PolicyDecision {
+    fn permit(reason: PolicyReason) -> Self {
+        Self {
+            permitted: true,
+            reason,
+        }
+    }
+
+    fn reject(reason: PolicyReason) -> Self {
+        Self {
+            permitted: false,
+            reason,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ChainLists {
+    allow: HashMap<String, Option<String>>,
+    deny: HashMap<String, Option<String>>,
+}
+
+/// Allow/deny lists keyed by address family
+#[derive(Clone)]
+pub struct AddressPolicy {
+    registry: AddressRegistry,
+    lists: HashMap<String, ChainLists>,
+}
+
+impl Default for AddressPolicy {
+    fn default() -> Self {
+        Self::with_registry(AddressRegistry::with_defaults())
+    }
+}
+
+impl AddressPolicy {
+    /// An empty policy over the default chains; permits every valid address
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_registry(registry: AddressRegistry) -> Self {
+        Self {
+            registry,
+            lists: HashMap::new(),
+        }
+    }
+
+    /// Load a `.csv` or `.json` policy file, chosen by extension
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PolicyError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| PolicyError::Io(format!("{}: {}", path.display(), e)))?;
+
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase);
+        let mut policy = Self::new();
+        match extension.as_deref() {
+            Some("csv") => policy.load_csv(&contents)?,
+            Some("json") => policy.load_json(&contents)?,
+            _ => return Err(PolicyError::UnsupportedFormat(path.display().to_string())),
+        }
+        Ok(policy)
+    }
+
+    /// Add rows of `list,chain,address[,reason]` with a header line. Lines starting
+    /// with `#` are ignored.
+    pub fn load_csv(&mut self, contents: &str) -> Result<(), PolicyError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .comment(Some(b'#'))
+            .flexible(true)
+            .from_reader(contents.as_bytes());
+
+        for row in reader.deserialize::<CsvRow>() {
+            let row = row.map_err(|e| PolicyError::Parse(e.to_string()))?;
+            let entry = PolicyEntry {
+                chain: row.chain,
+                address: row.address,
+                reason: row.reason.filter(|r| !r.is_empty()),
+            };
+            self.add(row.list, &entry)
+                .map_err(|source| PolicyError::InvalidEntry {
+                    location: format!("{} {}", entry.chain, entry.address),
+                    source,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Add entries from `{"allow": [PolicyEntry], "deny": [PolicyEntry]}`
+    pub fn load_json(&mut self, contents: &str) -> Result<(), PolicyError> {
+        let parsed: JsonPolicy =
+            serde_json::from_str(contents).map_err(|e| PolicyError::Parse(e.to_string()))?;
+
+        for (list, entries, name) in [
+            (ListKind::Allow, &parsed.allow, "allow"),
+            (ListKind::Deny, &parsed.deny, "deny"),
+        ] {
+            for (index, entry) in entries.iter().enumerate() {
+                self.add(list, entry)
+                    .map_err(|source| PolicyError::InvalidEntry {
+                        location: format!("{}[{}]", name, index),
+                        source,
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add(&mut self, list: ListKind, entry: &PolicyEntry) -> Result<(), AddressError> {
+        let (family, normalized) = self.normalize(&entry.chain, &entry.address)?;
+        let lists = self.lists.entry(family).or_default();
+        let target = match list {
+            ListKind::Allow => &mut lists.allow,
+            ListKind::Deny => &mut lists.deny,
+        };
+        target.insert(normalized, entry.reason.clone());
+        Ok(())
+    }
+
+    pub fn is_permitted(&self, chain: &str, address: &str) -> PolicyDecision {
+        let (family, normalized) = match self.normalize(chain, address) {
+            Ok(key) => key,
+            Err(e) => {
+                return PolicyDecision::reject(PolicyReason::InvalidAddress {
+                    error: e.to_string(),
+                })
+            }
+        };
+
+        let Some(lists) = self.lists.get(&family) else {
+            return PolicyDecision::permit(PolicyReason::NoMatch);
+        };
+        if let Some(note) = lists.deny.get(&normalized) {
+            return PolicyDecision::reject(PolicyReason::Denylisted { note: note.clone() });
+        }
+        if let Some(note) = lists.allow.get(&normalized) {
+            return PolicyDecision::permit(PolicyReason::Allowlisted { note: note.clone() });
+        }
+        if lists.allow.is_empty() {
+            PolicyDecision::permit(PolicyReason::NoMatch)
+        } else {
+            PolicyDecision::reject(PolicyReason::NotAllowlisted)
+        }
+    }
+
+    /// Chain families that have at least one entry, sorted
+    pub fn chains(&self) -> Vec<&str> {
+        let mut chains: Vec<&str> = self.lists.keys().map(String::as_str).collect();
+        chains.sort_unstable();
+        chains
+    }
+
+    fn normalize(&self, chain: &str, address: &str) -> Result<(String, String), AddressError> {
+        let validator = self.registry.get(chain)?;
+        let normalized = match validator.parse(address.trim())? {
+            // Chain-specific checksums (EIP-1191) would split one family into several keys
+            ParsedAddress::Evm { address, .. } => to_eip55_checksum(&address)?,
+            ParsedAddress::Solana { pubkey, .. } => bs58::encode(pubkey).into_string(),
+            other => other.address().to_string(),
+        };
+        Ok((validator.chain().to_string(), normalized))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SANCTIONED: &str = "0x8589427373D6D84E98730D7795D8f6f8731FDA16";
+    const PARTNER: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+
+    #[test]
+    fn test_deny_list_matches_any_evm_casing_and_alias() {
+        let mut policy = AddressPolicy::new();
+        policy
+            .load_csv(&format!(
+                "list,chain,address,reason\n# OFAC\ndeny,ethereum,{},\"Tornado Cash, router\"\n",
+                SANCTIONED.to_lowercase()
+            ))
+            .unwrap();
+
+        for chain in ["ethereum", "etherlink", "rsk"] {
+            let decision = policy.is_permitted(chain, SANCTIONED);
+            assert!(!decision.permitted, "{}", chain);
+            assert_eq!(
+                decision.reason,
+                PolicyReason::Denylisted {
+                    note: Some("Tornado Cash, router".to_string())
+                }
+            );
+        }
+        assert_eq!(
+            policy.is_permitted("evm", PARTNER),
+            PolicyDecision::permit(PolicyReason::NoMatch)
+        );
+    }
+
+    #[test]
+    fn test_allow_list_restricts_family() {
+        let mut policy = AddressPolicy::new();
+        policy
+            .load_json(&format!(
+                r#"{{"allow": [{{"chain": "evm", "address": "{}", "reason": "partner"}}],
+                    "deny": [{{"chain": "evm", "address": "{}"}}]}}"#,
+                PARTNER, SANCTIONED
+            ))
+            .unwrap();
+
+        assert!(
+            policy
+                .is_permitted("evm", &PARTNER.to_uppercase().replace("0X", "0x"))
+                .permitted
+        );
+        assert_eq!(
+            policy.is_permitted("evm", "0x0000000000000000000000000000000000000001"),
+            PolicyDecision::reject(PolicyReason::NotAllowlisted)
+        );
+        assert!(!policy.is_permitted("evm", SANCTIONED).permitted);
+
+        // Other families are unaffected by the EVM allow list
+        assert!(
+            policy
+                .is_permitted("solana", "11111111111111111111111111111111")
+                .permitted
+        );
+        assert_eq!(policy.chains(), vec!["evm"]);
+    }
+
+    #[test]
+    fn test_invalid_entries_and_addresses() {
+        let mut policy = AddressPolicy::new();
+        let err = policy
+            .load_csv("list,chain,address\ndeny,evm,0x1234\n")
+            .unwrap_err();
+        assert!(matches!(err, PolicyError::InvalidEntry { .. }));
+
+        let err = policy
+            .load_json(r#"{"deny": [{"chain": "dogecoin", "address": "D"}]}"#)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PolicyError::InvalidEntry {
+                source: AddressError::UnsupportedChain(_),
+                ..
+            }
+        ));
+
+        assert!(matches!(
+            policy.is_permitted("evm", "not-an-address").reason,
+            PolicyReason::InvalidAddress { .. }
+        ));
+        assert!(!policy.is_permitted("evm", "not-an-address").permitted);
+    }
+
+    #[test]
+    fn test_from_file_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let csv_path = dir.path().join("screening.csv");
+        std::fs::write(
+            &csv_path,
+            format!("list,chain,address\ndeny,evm,{}\n", SANCTIONED),
+        )
+        .unwrap();
+        let policy = AddressPolicy::from_file(&csv_path).unwrap();
+        assert!(!policy.is_permitted("evm", SANCTIONED).permitted);
+
+        let txt_path = dir.path().join("screening.txt");
+        std::fs::write(&txt_path, "").unwrap();
+        assert!(matches!(
+            AddressPolicy::from_file(&txt_path),
+            Err(PolicyError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            AddressPolicy::from_file(dir.path().join("missing.json")),
+            Err(PolicyError::Io(_))
+        ));
+    }
+}
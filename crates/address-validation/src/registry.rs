@@ -0,0 +1,291 @@
+use crate::bitcoin::{BitcoinAddressKind, BitcoinNetwork, BitcoinValidator};
+use crate::cosmos::CosmosValidator;
+use crate::solana::SolanaAddressKind;
+use crate::tezos::{TezosAddressKind, TezosValidator};
+use crate::{
+    detect_checksum_scheme, normalization_chain_id, to_checksum, validate_evm_address_for_chain,
+    validate_solana_address, AddressError, AddressMetadata, ChecksumScheme,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A successfully validated address with its chain-specific details
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "chain", rename_all = "snake_case")]
+pub enum ParsedAddress {
+    Evm {
+        /// Checksummed form (EIP-1191 on chains that use it, EIP-55 otherwise)
+        address: String,
+        /// Checksum the input was written with
+        checksum_scheme: ChecksumScheme,
+    },
+    Solana {
+        address: String,
+        pubkey: [u8; 32],
+        kind: SolanaAddressKind,
+    },
+    Bitcoin {
+        address: String,
+        network: BitcoinNetwork,
+        kind: BitcoinAddressKind,
+        /// Hash160, script hash or witness program, depending on `kind`
+        payload: Vec<u8>,
+    },
+    Tezos {
+        address: String,
+        kind: TezosAddressKind,
+        hash: [u8; 20],
+    },
+    Cosmos {
+        address: String,
+        hrp: String,
+        bytes: Vec<u8>,
+    },
+}
+
+impl ParsedAddress {
+    /// Normalized string form of the address
+    pub fn address(&self) -> &str {
+        match self {
+            ParsedAddress::Evm { address, .. }
+            | ParsedAddress::Solana { address, .. }
+            | ParsedAddress::Bitcoin { address, .. }
+            | ParsedAddress::Tezos { address, .. }
+            | ParsedAddress::Cosmos { address, .. } => address,
+        }
+    }
+}
+
+/// Validates and parses addresses for a single chain
+pub trait AddressValidator: Send + Sync {
+    /// Canonical chain name, as reported in `AddressMetadata::chain`
+    fn chain(&self) -> &str;
+
+    fn metadata(&self) -> AddressMetadata;
+
+    fn parse(&self, address: &str) -> Result<ParsedAddress, AddressError>;
+
+    fn validate(&self, address: &str) -> Result<(), AddressError> {
+        self.parse(address).map(|_| ())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EvmValidator {
+    pub require_checksum: bool,
+    /// Enables EIP-1191 checksums for this chain
+    pub chain_id: Option<u64>,
+}
+
+impl AddressValidator for EvmValidator {
+    fn chain(&self) -> &str {
+        "evm"
+    }
+
+    fn metadata(&self) -> AddressMetadata {
+        AddressMetadata {
+            chain: "evm".to_string(),
+            address_format: "0x-prefixed hex (42 chars, 20 bytes). EIP-55 checksum recommended."
+                .to_string(),
+            address_example: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+        }
+    }
+
+    fn parse(&self, address: &str) -> Result<ParsedAddress, AddressError> {
+        validate_evm_address_for_chain(address, self.require_checksum, self.chain_id)?;
+        let checksum_scheme = detect_checksum_scheme(address, self.chain_id)?;
+        Ok(ParsedAddress::Evm {
+            address: to_checksum(
+                address,
+                normalization_chain_id(checksum_scheme, self.chain_id),
+            )?,
+            checksum_scheme,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SolanaValidator;
+
+impl AddressValidator for SolanaValidator {
+    fn chain(&self) -> &str {
+        "solana"
+    }
+
+    fn metadata(&self) -> AddressMetadata {
+        AddressMetadata {
+            chain: "solana".to_string(),
+            address_format: "Base58 encoded; decodes to exactly 32 bytes (length varies)."
+                .to_string(),
+            address_example: "4Nd1mY3iQz9dKqG2m9X3pQxvGXn3a6TT5p7H1cDJ5b5P".to_string(),
+        }
+    }
+
+    fn parse(&self, address: &str) -> Result<ParsedAddress, AddressError> {
+        let kind = validate_solana_address(address)?;
+        let pubkey = crate::solana::decode_pubkey(address)?;
+        Ok(ParsedAddress::Solana {
+            address: address.to_string(),
+            pubkey,
+            kind,
+        })
+    }
+}
+
+/// Address validators keyed by chain id (case-insensitive)
+#[derive(Clone, Default)]
+pub struct AddressRegistry {
+    validators: HashMap<String, Arc<dyn AddressValidator>>,
+}
+
+impl AddressRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every chain this crate knows about, under its usual chain ids
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+
+        let evm: Arc<dyn AddressValidator> = Arc::new(EvmValidator::default());
+        for chain_id in ["evm", "ethereum", "etherlink"] {
+            registry.register(chain_id, evm.clone());
+        }
+        for (chain_id, id) in [("rsk", 30), ("rsk-testnet", 31)] {
+            registry.register(
+                chain_id,
+                Arc::new(EvmValidator {
+                    require_checksum: false,
+                    chain_id: Some(id),
+                }),
+            );
+        }
+        registry.register("solana", Arc::new(SolanaValidator));
+        registry.register(
+            "bitcoin",
+            Arc::new(BitcoinValidator::new(BitcoinNetwork::Mainnet)),
+        );
+        registry.register(
+            "bitcoin-testnet",
+            Arc::new(BitcoinValidator::new(BitcoinNetwork::Testnet)),
+        );
+        registry.register(
+            "bitcoin-regtest",
+            Arc::new(BitcoinValidator::new(BitcoinNetwork::Regtest)),
+        );
+        registry.register("tezos", Arc::new(TezosValidator));
+        registry.register("cosmos", Arc::new(CosmosValidator::new("cosmos")));
+
+        registry
+    }
+
+    /// Add or replace the validator for `chain_id`
+    pub fn register(&mut self, chain_id: &str, validator: Arc<dyn AddressValidator>) {
+        self.validators.insert(chain_id.to_lowercase(), validator);
+    }
+
+    pub fn get(&self, chain_id: &str) -> Result<&dyn AddressValidator, AddressError> {
+        self.validators
+            .get(&chain_id.to_lowercase())
+            .map(|v| v.as_ref())
+            .ok_or_else(|| AddressError::UnsupportedChain(chain_id.to_string()))
+    }
+
+    pub fn parse(&self, chain_id: &str, address: &str) -> Result<ParsedAddress, AddressError> {
+        self.get(chain_id)?.parse(address)
+    }
+
+    /// Registered chain ids, sorted
+    pub fn chain_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.validators.keys().map(String::as_str).collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana::WellKnownProgram;
+
+    #[test]
+    fn test_registry_aliases_share_validator() {
+        let registry = AddressRegistry::with_defaults();
+        for chain_id in ["evm", "Ethereum", "ETHERLINK"] {
+            assert_eq!(registry.get(chain_id).unwrap().chain(), "evm");
+        }
+        assert!(matches!(
+            registry.get("dogecoin"),
+            Err(AddressError::UnsupportedChain(_))
+        ));
+    }
+
+    #[test]
+    fn test_registry_parses_evm() {
+        let registry = AddressRegistry::with_defaults();
+        let parsed = registry
+            .parse("ethereum", "0x742d35cc6634c0532925a3b844bc454e4438f44e")
+            .unwrap();
+        assert_eq!(
+            parsed,
+            ParsedAddress::Evm {
+                address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+                checksum_scheme: ChecksumScheme::None,
+            }
+        );
+        assert_eq!(
+            parsed.address(),
+            "0x742d35Cc6634C0532925a3b844Bc454e4438f44e"
+        );
+    }
+
+    #[test]
+    fn test_registry_evm_chain_checksum() {
+        let registry = AddressRegistry::with_defaults();
+        let parsed = registry
+            .parse("rsk", "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed")
+            .unwrap();
+        assert_eq!(
+            parsed.address(),
+            "0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD"
+        );
+
+        let parsed = registry
+            .parse("rsk", "0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD")
+            .unwrap();
+        assert!(matches!(
+            parsed,
+            ParsedAddress::Evm {
+                checksum_scheme: ChecksumScheme::Eip1191 { chain_id: 30 },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_registry_parses_solana() {
+        let registry = AddressRegistry::with_defaults();
+        let parsed = registry
+            .parse("solana", "11111111111111111111111111111111")
+            .unwrap();
+        assert_eq!(
+            parsed,
+            ParsedAddress::Solana {
+                address: "11111111111111111111111111111111".to_string(),
+                pubkey: [0u8; 32],
+                kind: SolanaAddressKind::Program(WellKnownProgram::System),
+            }
+        );
+        assert!(registry.parse("solana", "invalid0OIl").is_err());
+    }
+
+    #[test]
+    fn test_registry_custom_validator() {
+        let mut registry = AddressRegistry::new();
+        registry.register("osmosis", Arc::new(CosmosValidator::new("osmo")));
+        assert_eq!(registry.chain_ids(), vec!["osmosis"]);
+        assert_eq!(registry.get("osmosis").unwrap().chain(), "cosmos");
+    }
+}
@@ -0,0 +1,262 @@
+use crate::AddressError;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const MAX_SEEDS: usize = 16;
+pub const MAX_SEED_LEN: usize = 32;
+
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WellKnownProgram {
+    System,
+    Memo,
+    MemoV1,
+    Token,
+    Token2022,
+    AssociatedToken,
+    ComputeBudget,
+    BpfLoaderUpgradeable,
+    Vote,
+    Stake,
+    SysvarClock,
+    SysvarRent,
+}
+
+const WELL_KNOWN_PROGRAMS: [(&str, WellKnownProgram); 12] = [
+    ("11111111111111111111111111111111", WellKnownProgram::System),
+    (
+        "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+        WellKnownProgram::Memo,
+    ),
+    (
+        "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo",
+        WellKnownProgram::MemoV1,
+    ),
+    (
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        WellKnownProgram::Token,
+    ),
+    (
+        "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+        WellKnownProgram::Token2022,
+    ),
+    (
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+        WellKnownProgram::AssociatedToken,
+    ),
+    (
+        "ComputeBudget111111111111111111111111111111",
+        WellKnownProgram::ComputeBudget,
+    ),
+    (
+        "BPFLoaderUpgradeab1e11111111111111111111111",
+        WellKnownProgram::BpfLoaderUpgradeable,
+    ),
+    (
+        "Vote111111111111111111111111111111111111111",
+        WellKnownProgram::Vote,
+    ),
+    (
+        "Stake11111111111111111111111111111111111111",
+        WellKnownProgram::Stake,
+    ),
+    (
+        "SysvarC1ock11111111111111111111111111111111",
+        WellKnownProgram::SysvarClock,
+    ),
+    (
+        "SysvarRent111111111111111111111111111111111",
+        WellKnownProgram::SysvarRent,
+    ),
+];
+
+impl WellKnownProgram {
+    pub fn from_address(address: &str) -> Option<Self> {
+        WELL_KNOWN_PROGRAMS
+            .iter()
+            .find(|(id, _)| *id == address)
+            .map(|(_, program)| *program)
+    }
+
+    pub fn program_id(self) -> &'static str {
+        WELL_KNOWN_PROGRAMS
+            .iter()
+            .find(|(_, program)| *program == self)
+            .map(|(id, _)| *id)
+            .expect("every program has an id")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "program", rename_all = "snake_case")]
+pub enum SolanaAddressKind {
+    /// On the Ed25519 curve, so it has a private key and can sign
+    Wallet,
+    /// Off the curve: a program derived address, which cannot sign
+    OffCurve,
+    /// A well-known program or sysvar id
+    Program(WellKnownProgram),
+}
+
+impl SolanaAddressKind {
+    pub fn can_sign(&self) -> bool {
+        matches!(self, SolanaAddressKind::Wallet)
+    }
+}
+
+/// Whether 32 bytes decompress to a point on the Ed25519 curve
+pub fn is_on_curve(bytes: &[u8; 32]) -> bool {
+    CompressedEdwardsY(*bytes).decompress().is_some()
+}
+
+pub fn classify(pubkey: &[u8; 32], address: &str) -> SolanaAddressKind {
+    // Checked first: some ids (e.g. the all-zero System program) happen to be on the curve
+    if let Some(program) = WellKnownProgram::from_address(address) {
+        SolanaAddressKind::Program(program)
+    } else if is_on_curve(pubkey) {
+        SolanaAddressKind::Wallet
+    } else {
+        SolanaAddressKind::OffCurve
+    }
+}
+
+pub(crate) fn decode_pubkey(address: &str) -> Result<[u8; 32], AddressError> {
+    let decoded = bs58::decode(address)
+        .into_vec()
+        .map_err(|e| AddressError::Base58Error(e.to_string()))?;
+
+    decoded
+        .as_slice()
+        .try_into()
+        .map_err(|_| AddressError::InvalidLength {
+            expected: 32,
+            actual: decoded.len(),
+        })
+}
+
+/// Derive the address for `seeds` under `program_id`, failing if it lands on the curve
+pub fn create_program_address(seeds: &[&[u8]], program_id: &str) -> Result<String, AddressError> {
+    let program = decode_pubkey(program_id)?;
+    let address = derive(seeds, &program)?
+        .ok_or_else(|| AddressError::InvalidSeeds("derived address is on the curve".to_string()))?;
+    Ok(bs58::encode(address).into_string())
+}
+
+/// Find the canonical PDA for `seeds` under `program_id`: the highest bump seed that
+/// yields an off-curve address. Returns the address and its bump.
+pub fn find_program_address(
+    seeds: &[&[u8]],
+    program_id: &str,
+) -> Result<(String, u8), AddressError> {
+    let program = decode_pubkey(program_id)?;
+    if seeds.len() >= MAX_SEEDS {
+        return Err(AddressError::InvalidSeeds(format!(
+            "at most {} seeds plus the bump",
+            MAX_SEEDS - 1
+        )));
+    }
+
+    for bump in (0..=u8::MAX).rev() {
+        let bump_seed = [bump];
+        let mut with_bump = seeds.to_vec();
+        with_bump.push(&bump_seed);
+        if let Some(address) = derive(&with_bump, &program)? {
+            return Ok((bs58::encode(address).into_string(), bump));
+        }
+    }
+
+    Err(AddressError::InvalidSeeds(
+        "no bump seed gives an off-curve address".to_string(),
+    ))
+}
+
+fn derive(seeds: &[&[u8]], program_id: &[u8; 32]) -> Result<Option<[u8; 32]>, AddressError> {
+    if seeds.len() > MAX_SEEDS {
+        return Err(AddressError::InvalidSeeds(format!(
+            "at most {} seeds",
+            MAX_SEEDS
+        )));
+    }
+    if let Some(seed) = seeds.iter().find(|seed| seed.len() > MAX_SEED_LEN) {
+        return Err(AddressError::InvalidSeeds(format!(
+            "seed of {} bytes exceeds {}",
+            seed.len(),
+            MAX_SEED_LEN
+        )));
+    }
+
+    let mut hasher = Sha256::new();
+    for seed in seeds {
+        hasher.update(seed);
+    }
+    hasher.update(program_id);
+    hasher.update(PDA_MARKER);
+    let address: [u8; 32] = hasher.finalize().into();
+
+    Ok((!is_on_curve(&address)).then_some(address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_well_known_programs() {
+        for (id, program) in WELL_KNOWN_PROGRAMS {
+            let pubkey = decode_pubkey(id).unwrap();
+            assert_eq!(classify(&pubkey, id), SolanaAddressKind::Program(program));
+            assert_eq!(program.program_id(), id);
+        }
+    }
+
+    #[test]
+    fn test_wallet_is_on_curve() {
+        // Ed25519 base point
+        let mut base = [0x66u8; 32];
+        base[0] = 0x58;
+        assert!(is_on_curve(&base));
+
+        let address = bs58::encode(base).into_string();
+        assert_eq!(classify(&base, &address), SolanaAddressKind::Wallet);
+        assert!(classify(&base, &address).can_sign());
+    }
+
+    #[test]
+    fn test_find_program_address_is_off_curve() {
+        let program = WellKnownProgram::Memo.program_id();
+        let (pda, bump) = find_program_address(&[b"evidence", b"phoenix"], program).unwrap();
+
+        let pubkey = decode_pubkey(&pda).unwrap();
+        assert!(!is_on_curve(&pubkey));
+        assert_eq!(classify(&pubkey, &pda), SolanaAddressKind::OffCurve);
+
+        // Deterministic, and reproducible from the bump
+        assert_eq!(
+            find_program_address(&[b"evidence", b"phoenix"], program).unwrap(),
+            (pda.clone(), bump)
+        );
+        assert_eq!(
+            create_program_address(&[b"evidence", b"phoenix", &[bump]], program).unwrap(),
+            pda
+        );
+    }
+
+    #[test]
+    fn test_seed_limits() {
+        let program = WellKnownProgram::Memo.program_id();
+        let long = [0u8; 33];
+        assert!(matches!(
+            find_program_address(&[&long], program),
+            Err(AddressError::InvalidSeeds(_))
+        ));
+
+        let seeds: Vec<&[u8]> = vec![b"s"; MAX_SEEDS];
+        assert!(matches!(
+            find_program_address(&seeds, program),
+            Err(AddressError::InvalidSeeds(_))
+        ));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::registry::{AddressValidator, ParsedAddress};
+use crate::{AddressError, AddressMetadata};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TezosAddressKind {
+    /// tz1, Ed25519 implicit account
+    Ed25519,
+    /// tz2, secp256k1 implicit account
+    Secp256k1,
+    /// tz3, P-256 implicit account
+    P256,
+    /// KT1, originated contract
+    Contract,
+}
+
+/// Base58Check prefix bytes and the resulting leading characters
+const PREFIXES: [(&str, [u8; 3], TezosAddressKind); 4] = [
+    ("tz1", [6, 161, 159], TezosAddressKind::Ed25519),
+    ("tz2", [6, 161, 161], TezosAddressKind::Secp256k1),
+    ("tz3", [6, 161, 164], TezosAddressKind::P256),
+    ("KT1", [2, 90, 121], TezosAddressKind::Contract),
+];
+
+/// tz1/tz2/tz3 implicit accounts and KT1 contracts
+#[derive(Debug, Clone, Default)]
+pub struct TezosValidator;
+
+impl AddressValidator for TezosValidator {
+    fn chain(&self) -> &str {
+        "tezos"
+    }
+
+    fn metadata(&self) -> AddressMetadata {
+        AddressMetadata {
+            chain: "tezos".to_string(),
+            address_format: "Base58Check with tz1/tz2/tz3 (implicit) or KT1 (contract) prefix; 36 chars, 20-byte hash."
+                .to_string(),
+            address_example: "tz1VSUr8wwNhLAzempoch5d6hLRiTh8Cjcjb".to_string(),
+        }
+    }
+
+    fn parse(&self, address: &str) -> Result<ParsedAddress, AddressError> {
+        let (_, prefix, kind) = PREFIXES
+            .iter()
+            .find(|(text, _, _)| address.starts_with(text))
+            .ok_or_else(|| {
+                AddressError::InvalidPrefix("must start with tz1, tz2, tz3 or KT1".to_string())
+            })?;
+
+        let decoded = bs58::decode(address)
+            .with_check(None)
+            .into_vec()
+            .map_err(|e| match e {
+                bs58::decode::Error::InvalidChecksum { .. } => AddressError::InvalidChecksum,
+                other => AddressError::Base58Error(other.to_string()),
+            })?;
+
+        if decoded.len() != 23 {
+            return Err(AddressError::InvalidLength {
+                expected: 23,
+                actual: decoded.len(),
+            });
+        }
+        if decoded[..3] != prefix[..] {
+            return Err(AddressError::InvalidPrefix(
+                "prefix bytes do not match address type".to_string(),
+            ));
+        }
+
+        let mut hash = [0u8; 20];
+        hash.copy_from_slice(&decoded[3..]);
+
+        Ok(ParsedAddress::Tezos {
+            address: address.to_string(),
+            kind: *kind,
+            hash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(prefix: [u8; 3], hash: [u8; 20]) -> String {
+        let mut bytes = prefix.to_vec();
+        bytes.extend_from_slice(&hash);
+        bs58::encode(bytes).with_check().into_string()
+    }
+
+    #[test]
+    fn test_tezos_known_address() {
+        let parsed = TezosValidator
+            .parse("tz1VSUr8wwNhLAzempoch5d6hLRiTh8Cjcjb")
+            .unwrap();
+        assert!(matches!(
+            parsed,
+            ParsedAddress::Tezos {
+                kind: TezosAddressKind::Ed25519,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_tezos_all_prefixes() {
+        for (text, prefix, kind) in PREFIXES {
+            let address = encode(prefix, [0x42; 20]);
+            assert!(address.starts_with(text), "{} for {}", address, text);
+            assert_eq!(
+                TezosValidator.parse(&address).unwrap(),
+                ParsedAddress::Tezos {
+                    address: address.clone(),
+                    kind,
+                    hash: [0x42; 20],
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_tezos_invalid() {
+        assert!(matches!(
+            TezosValidator.parse("tz4VSUr8wwNhLAzempoch5d6hLRiTh8Cjcjb"),
+            Err(AddressError::InvalidPrefix(_))
+        ));
+        assert!(matches!(
+            TezosValidator.parse("tz1VSUr8wwNhLAzempoch5d6hLRiTh8Cjcjc"),
+            Err(AddressError::InvalidChecksum)
+        ));
+    }
+}
@@ -0,0 +1,380 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::memo::EvidenceMemo;
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord, SignedAnchorTx};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// Standard relay policy limit for OP_RETURN payloads
+pub const MAX_OP_RETURN_BYTES: usize = 80;
+
+/// Record id characters kept in the memo so it fits in `MAX_OP_RETURN_BYTES`
+pub const OP_RETURN_ID_PREFIX_LEN: usize = 3;
+
+/// Block depth required before a transaction is reported as confirmed
+pub const DEFAULT_MIN_CONFIRMATIONS: i64 = 6;
+
+/// bitcoind's error code for a transaction id the wallet does not know
+const RPC_INVALID_ADDRESS_OR_KEY: i64 = -5;
+
+/// bitcoind's error code for a transaction whose inputs are missing or spent
+const RPC_VERIFY_ERROR: i64 = -25;
+
+/// bitcoind's error code for a transaction that is already in the chain
+const RPC_VERIFY_ALREADY_IN_CHAIN: i64 = -27;
+
+#[derive(Clone)]
+pub struct BitcoinProviderStub;
+
+#[async_trait]
+impl AnchorProvider for BitcoinProviderStub {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        Ok(ChainTxRef {
+            network: "bitcoin".to_string(),
+            chain: "regtest".to_string(),
+            tx_id: format!("fake:{}", &evidence.digest.hex),
+            confirmed: false,
+            timestamp: Some(Utc::now()),
+        })
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        let mut t = tx.clone();
+        t.confirmed = true;
+        Ok(t)
+    }
+
+    fn network(&self) -> &str {
+        "bitcoin"
+    }
+}
+
+/// Anchors evidence in an OP_RETURN output funded and signed by a bitcoind wallet.
+///
+/// The endpoint should point at the wallet RPC path (e.g. `http://127.0.0.1:18443/wallet/phoenix`)
+/// when the node has more than one wallet loaded.
+#[derive(Debug, Clone)]
+pub struct BitcoinProvider {
+    pub client: Client,
+    pub endpoint: String,
+    pub network: String,
+    pub rpc_user: Option<String>,
+    pub rpc_password: Option<String>,
+    pub min_confirmations: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BitcoinRpcRequest {
+    pub jsonrpc: String,
+    pub id: String,
+    pub method: String,
+    pub params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BitcoinRpcResponse {
+    pub result: Option<Value>,
+    pub error: Option<BitcoinRpcError>,
+    #[allow(dead_code)]
+    pub id: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BitcoinRpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FundedTransaction {
+    hex: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignedTransaction {
+    hex: String,
+    complete: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct DecodedTransaction {
+    txid: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WalletTransaction {
+    confirmations: i64,
+    blockhash: Option<String>,
+}
+
+impl BitcoinProvider {
+    pub fn new(
+        endpoint: String,
+        network: String,
+        rpc_user: Option<String>,
+        rpc_password: Option<String>,
+    ) -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            network,
+            rpc_user,
+            rpc_password,
+            min_confirmations: DEFAULT_MIN_CONFIRMATIONS,
+        })
+    }
+
+    /// Override the block depth required for `confirm` to report success
+    pub fn with_min_confirmations(mut self, min_confirmations: i64) -> Self {
+        self.min_confirmations = min_confirmations.max(1);
+        self
+    }
+
+    /// Send one JSON-RPC request, recording its duration in `phoenix_rpc_call_duration_seconds`
+    async fn rpc_call(&self, method: &str, params: Value) -> Result<Value, AnchorError> {
+        let started = Instant::now();
+        let result = self.send_rpc(method, params).await;
+        metrics::histogram!(
+            "phoenix_rpc_call_duration_seconds",
+            "network" => "bitcoin",
+            "method" => method.to_string(),
+            "outcome" => if result.is_ok() { "ok" } else { "error" },
+        )
+        .record(started.elapsed().as_secs_f64());
+        result
+    }
+
+    async fn send_rpc(&self, method: &str, params: Value) -> Result<Value, AnchorError> {
+        let request = BitcoinRpcRequest {
+            jsonrpc: "1.0".to_string(),
+            id: "phoenix-keeper".to_string(),
+            method: method.to_string(),
+            params,
+        };
+
+        let mut builder = self.client.post(&self.endpoint).json(&request);
+        if let Some(user) = &self.rpc_user {
+            builder = builder.basic_auth(user, self.rpc_password.as_ref());
+        }
+
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| AnchorError::Network(format!("HTTP request failed: {}", e)))?;
+
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(AnchorError::Provider(format!(
+                "RPC authentication failed: {}",
+                status
+            )));
+        }
+
+        // bitcoind reports RPC errors with HTTP 500/404 and a JSON body, so the body is
+        // parsed before the status code is considered.
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AnchorError::Network(format!("Failed to read response: {}", e)))?;
+
+        let rpc_response: BitcoinRpcResponse = match serde_json::from_str(&body) {
+            Ok(parsed) => parsed,
+            Err(e) if status.is_success() => {
+                return Err(AnchorError::Network(format!("Failed to parse JSON: {}", e)));
+            }
+            Err(_) => {
+                return Err(AnchorError::Network(format!("HTTP error: {}", status)));
+            }
+        };
+
+        if let Some(error) = rpc_response.error {
+            return Err(AnchorError::Provider(format!(
+                "RPC error {}: {}",
+                error.code, error.message
+            )));
+        }
+
+        rpc_response
+            .result
+            .ok_or_else(|| AnchorError::Provider("RPC response missing result field".to_string()))
+    }
+
+    /// Build, fund and sign the OP_RETURN transaction for `memo_data`
+    /// without sending it. The funding inputs stay locked in the wallet so a
+    /// later transaction does not spend them first.
+    async fn sign_op_return_transaction(
+        &self,
+        memo_data: &str,
+    ) -> Result<SignedAnchorTx, AnchorError> {
+        if memo_data.len() > MAX_OP_RETURN_BYTES {
+            return Err(AnchorError::Invalid(format!(
+                "memo is {} bytes, OP_RETURN allows at most {}",
+                memo_data.len(),
+                MAX_OP_RETURN_BYTES
+            )));
+        }
+
+        // A single data output; the wallet adds inputs and change when funding
+        let raw = self
+            .rpc_call(
+                "createrawtransaction",
+                json!([[], [{ "data": hex::encode(memo_data.as_bytes()) }]]),
+            )
+            .await?;
+        let raw_hex = raw.as_str().ok_or_else(|| {
+            AnchorError::Provider("createrawtransaction returned non-string".to_string())
+        })?;
+
+        let funded: FundedTransaction = serde_json::from_value(
+            self.rpc_call(
+                "fundrawtransaction",
+                json!([raw_hex, { "lockUnspents": true }]),
+            )
+            .await?,
+        )
+        .map_err(|e| AnchorError::Provider(format!("Failed to parse funded tx: {}", e)))?;
+
+        let signed: SignedTransaction = serde_json::from_value(
+            self.rpc_call("signrawtransactionwithwallet", json!([funded.hex]))
+                .await?,
+        )
+        .map_err(|e| AnchorError::Provider(format!("Failed to parse signed tx: {}", e)))?;
+
+        if !signed.complete {
+            return Err(AnchorError::Provider(
+                "wallet could not fully sign the transaction".to_string(),
+            ));
+        }
+
+        let decoded: DecodedTransaction = serde_json::from_value(
+            self.rpc_call("decoderawtransaction", json!([signed.hex]))
+                .await?,
+        )
+        .map_err(|e| AnchorError::Provider(format!("Failed to parse decoded tx: {}", e)))?;
+
+        Ok(SignedAnchorTx {
+            tx: ChainTxRef {
+                network: "bitcoin".to_string(),
+                chain: self.network.clone(),
+                tx_id: decoded.txid,
+                confirmed: false,
+                timestamp: None,
+            },
+            raw_tx: signed.hex,
+        })
+    }
+
+    async fn send_signed_transaction(
+        &self,
+        signed: &SignedAnchorTx,
+    ) -> Result<ChainTxRef, AnchorError> {
+        match self
+            .rpc_call("sendrawtransaction", json!([signed.raw_tx]))
+            .await
+        {
+            Ok(_) => {}
+            Err(e) if is_rpc_error(&e, RPC_VERIFY_ALREADY_IN_CHAIN) => {}
+            // Its inputs were spent by another transaction, so it can never confirm
+            Err(e) if is_rpc_error(&e, RPC_VERIFY_ERROR) => {
+                return Err(AnchorError::Expired(e.to_string()));
+            }
+            Err(e) => return Err(e),
+        }
+
+        tracing::info!(
+            txid = %signed.tx.tx_id,
+            "Anchored evidence to Bitcoin"
+        );
+
+        let mut tx = signed.tx.clone();
+        tx.timestamp = Some(Utc::now());
+        Ok(tx)
+    }
+
+    async fn get_wallet_transaction(&self, txid: &str) -> Result<WalletTransaction, AnchorError> {
+        let result = self.rpc_call("gettransaction", json!([txid])).await?;
+
+        serde_json::from_value(result)
+            .map_err(|e| AnchorError::Provider(format!("Failed to parse transaction: {}", e)))
+    }
+}
+
+#[async_trait]
+impl AnchorProvider for BitcoinProvider {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        // A v1 memo with its full record hash leaves room for only a short id prefix
+        let memo =
+            EvidenceMemo::for_record_with_id_prefix(evidence, OP_RETURN_ID_PREFIX_LEN).to_string();
+
+        let signed = self.sign_op_return_transaction(&memo).await?;
+        self.send_signed_transaction(&signed).await
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        let wallet_tx = self.get_wallet_transaction(&tx.tx_id).await?;
+
+        let mut confirmed_tx = tx.clone();
+
+        // Negative confirmations mean the transaction conflicts with the best chain
+        let is_confirmed =
+            wallet_tx.blockhash.is_some() && wallet_tx.confirmations >= self.min_confirmations;
+
+        confirmed_tx.confirmed = is_confirmed;
+        if is_confirmed {
+            tracing::info!(
+                txid = %tx.tx_id,
+                confirmations = wallet_tx.confirmations,
+                block_hash = ?wallet_tx.blockhash,
+                "Transaction confirmed on Bitcoin"
+            );
+        } else if wallet_tx.confirmations < 0 {
+            tracing::warn!(
+                txid = %tx.tx_id,
+                confirmations = wallet_tx.confirmations,
+                "Bitcoin transaction conflicts with the best chain"
+            );
+        }
+
+        Ok(confirmed_tx)
+    }
+
+    fn network(&self) -> &str {
+        "bitcoin"
+    }
+
+    async fn prepare(
+        &self,
+        evidence: &EvidenceRecord,
+    ) -> Result<Option<SignedAnchorTx>, AnchorError> {
+        let memo =
+            EvidenceMemo::for_record_with_id_prefix(evidence, OP_RETURN_ID_PREFIX_LEN).to_string();
+        Ok(Some(self.sign_op_return_transaction(&memo).await?))
+    }
+
+    async fn broadcast(&self, signed: &SignedAnchorTx) -> Result<ChainTxRef, AnchorError> {
+        self.send_signed_transaction(signed).await
+    }
+
+    /// Whether the wallet still holds `tx` in the mempool or the best chain.
+    /// A conflicted transaction, e.g. double spent after a reorg, is gone.
+    async fn is_known(&self, tx: &ChainTxRef) -> Result<bool, AnchorError> {
+        match self.get_wallet_transaction(&tx.tx_id).await {
+            Ok(wallet_tx) => Ok(wallet_tx.confirmations >= 0),
+            Err(e) if is_rpc_error(&e, RPC_INVALID_ADDRESS_OR_KEY) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Whether `error` is the bitcoind RPC error `code`
+fn is_rpc_error(error: &AnchorError, code: i64) -> bool {
+    matches!(error, AnchorError::Provider(msg) if msg.starts_with(&format!("RPC error {}:", code)))
+}
@@ -0,0 +1,360 @@
+use anchor_bitcoin::{BitcoinProvider, BitcoinProviderStub, DEFAULT_MIN_CONFIRMATIONS};
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use chrono::Utc;
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::memo::EvidenceMemo;
+use phoenix_evidence::model::{ChainTxRef, DigestAlgo, EvidenceDigest, EvidenceRecord};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+/// Minimal bitcoind wallet RPC mock
+#[derive(Default)]
+struct MockNode {
+    calls: Vec<String>,
+    op_return_hex: Option<String>,
+    confirmations: i64,
+    sign_complete: bool,
+    lock_unspents: bool,
+    sent_hex: Option<String>,
+    /// Error code and message returned by `sendrawtransaction`
+    send_error: Option<(i64, String)>,
+}
+
+type SharedNode = Arc<Mutex<MockNode>>;
+
+const MOCK_TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+async fn rpc_handler(
+    State(node): State<SharedNode>,
+    Json(req): Json<Value>,
+) -> (StatusCode, Json<Value>) {
+    let method = req["method"].as_str().unwrap_or_default().to_string();
+    let mut node = node.lock().unwrap();
+    node.calls.push(method.clone());
+
+    let result = match method.as_str() {
+        "createrawtransaction" => {
+            node.op_return_hex = req["params"][1][0]["data"].as_str().map(str::to_string);
+            json!("0200000000")
+        }
+        "fundrawtransaction" => {
+            node.lock_unspents = req["params"][1]["lockUnspents"] == true;
+            json!({ "hex": "02000000funded", "fee": 0.0001, "changepos": 1 })
+        }
+        "signrawtransactionwithwallet" => {
+            json!({ "hex": "02000000signed", "complete": node.sign_complete })
+        }
+        "decoderawtransaction" => json!({ "txid": MOCK_TXID, "vout": [] }),
+        "sendrawtransaction" => {
+            if let Some((code, message)) = &node.send_error {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "result": null,
+                        "error": { "code": code, "message": message },
+                        "id": req["id"],
+                    })),
+                );
+            }
+            node.sent_hex = req["params"][0].as_str().map(str::to_string);
+            json!(MOCK_TXID)
+        }
+        "gettransaction" => {
+            if req["params"][0] != MOCK_TXID {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "result": null,
+                        "error": { "code": -5, "message": "Invalid or non-wallet transaction id" },
+                        "id": req["id"],
+                    })),
+                );
+            }
+            let blockhash = if node.confirmations > 0 {
+                json!("000000000000000000024bead8df69990852c202db0e0097c1a12ea637d7e96d")
+            } else {
+                Value::Null
+            };
+            json!({ "confirmations": node.confirmations, "blockhash": blockhash })
+        }
+        _ => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "result": null,
+                    "error": { "code": -32601, "message": "Method not found" },
+                    "id": req["id"],
+                })),
+            );
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(json!({ "result": result, "error": null, "id": req["id"] })),
+    )
+}
+
+async fn spawn_mock_node(node: SharedNode) -> String {
+    let app = Router::new().route("/", post(rpc_handler)).with_state(node);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn sample_evidence() -> EvidenceRecord {
+    EvidenceRecord {
+        id: "test-evidence-btc".to_string(),
+        created_at: Utc::now(),
+        digest: EvidenceDigest {
+            algo: DigestAlgo::Sha256,
+            hex: "deadbeefcafebabe".to_string(),
+        },
+        payload_mime: None,
+        metadata: json!({}),
+    }
+}
+
+#[tokio::test]
+async fn test_bitcoin_provider_stub_anchor() {
+    let provider = BitcoinProviderStub;
+    let evidence = sample_evidence();
+
+    let tx_ref = provider.anchor(&evidence).await.unwrap();
+    assert_eq!(tx_ref.network, "bitcoin");
+    assert_eq!(tx_ref.chain, "regtest");
+    assert_eq!(tx_ref.tx_id, "fake:deadbeefcafebabe");
+    assert!(!tx_ref.confirmed);
+}
+
+#[tokio::test]
+async fn test_bitcoin_provider_stub_confirm() {
+    let provider = BitcoinProviderStub;
+    let tx_ref = ChainTxRef {
+        network: "bitcoin".to_string(),
+        chain: "regtest".to_string(),
+        tx_id: "fake:abcd1234".to_string(),
+        confirmed: false,
+        timestamp: Some(Utc::now()),
+    };
+
+    let confirmed = provider.confirm(&tx_ref).await.unwrap();
+    assert!(confirmed.confirmed);
+    assert_eq!(confirmed.tx_id, tx_ref.tx_id);
+}
+
+#[test]
+fn test_bitcoin_provider_new() {
+    let provider = BitcoinProvider::new(
+        "http://127.0.0.1:18443".to_string(),
+        "regtest".to_string(),
+        Some("user".to_string()),
+        Some("pass".to_string()),
+    )
+    .unwrap();
+
+    assert_eq!(provider.endpoint, "http://127.0.0.1:18443");
+    assert_eq!(provider.network, "regtest");
+    assert_eq!(provider.min_confirmations, DEFAULT_MIN_CONFIRMATIONS);
+
+    let provider = provider.with_min_confirmations(0);
+    assert_eq!(provider.min_confirmations, 1);
+}
+
+#[tokio::test]
+async fn test_bitcoin_provider_anchor_builds_op_return() {
+    let node = Arc::new(Mutex::new(MockNode {
+        sign_complete: true,
+        ..Default::default()
+    }));
+    let endpoint = spawn_mock_node(node.clone()).await;
+    let provider = BitcoinProvider::new(endpoint, "regtest".to_string(), None, None).unwrap();
+
+    let evidence = sample_evidence();
+    let tx_ref = provider.anchor(&evidence).await.unwrap();
+    assert_eq!(tx_ref.network, "bitcoin");
+    assert_eq!(tx_ref.chain, "regtest");
+    assert_eq!(tx_ref.tx_id, MOCK_TXID);
+    assert!(!tx_ref.confirmed);
+
+    let node = node.lock().unwrap();
+    assert_eq!(
+        node.calls,
+        vec![
+            "createrawtransaction",
+            "fundrawtransaction",
+            "signrawtransactionwithwallet",
+            "decoderawtransaction",
+            "sendrawtransaction",
+        ]
+    );
+    let memo = hex::decode(node.op_return_hex.as_ref().unwrap()).unwrap();
+    assert!(memo.len() <= anchor_bitcoin::MAX_OP_RETURN_BYTES);
+    let memo = EvidenceMemo::parse(std::str::from_utf8(&memo).unwrap()).unwrap();
+    assert!(memo.matches(&evidence));
+}
+
+#[tokio::test]
+async fn test_bitcoin_provider_prepare_then_broadcast() {
+    let node = Arc::new(Mutex::new(MockNode {
+        sign_complete: true,
+        ..Default::default()
+    }));
+    let endpoint = spawn_mock_node(node.clone()).await;
+    let provider = BitcoinProvider::new(endpoint, "regtest".to_string(), None, None).unwrap();
+
+    // The txid is known once the wallet has signed, before anything is sent
+    let signed = provider.prepare(&sample_evidence()).await.unwrap().unwrap();
+    assert_eq!(signed.tx.network, "bitcoin");
+    assert_eq!(signed.tx.chain, "regtest");
+    assert_eq!(signed.tx.tx_id, MOCK_TXID);
+    assert_eq!(signed.raw_tx, "02000000signed");
+    {
+        let node = node.lock().unwrap();
+        assert!(node.lock_unspents);
+        assert!(!node.calls.contains(&"sendrawtransaction".to_string()));
+    }
+
+    let tx_ref = provider.broadcast(&signed).await.unwrap();
+    assert_eq!(tx_ref.tx_id, MOCK_TXID);
+    assert!(tx_ref.timestamp.is_some());
+    assert_eq!(
+        node.lock().unwrap().sent_hex.as_deref(),
+        Some("02000000signed")
+    );
+}
+
+#[tokio::test]
+async fn test_bitcoin_provider_broadcast_again() {
+    let node = Arc::new(Mutex::new(MockNode {
+        sign_complete: true,
+        ..Default::default()
+    }));
+    let endpoint = spawn_mock_node(node.clone()).await;
+    let provider = BitcoinProvider::new(endpoint, "regtest".to_string(), None, None).unwrap();
+    let signed = provider.prepare(&sample_evidence()).await.unwrap().unwrap();
+
+    // Already mined: nothing to send
+    node.lock().unwrap().send_error = Some((-27, "Transaction already in block chain".into()));
+    assert_eq!(provider.broadcast(&signed).await.unwrap().tx_id, MOCK_TXID);
+
+    // Its inputs went to another transaction, so it has to be signed again
+    node.lock().unwrap().send_error = Some((-25, "bad-txns-inputs-missingorspent".into()));
+    assert!(matches!(
+        provider.broadcast(&signed).await,
+        Err(AnchorError::Expired(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_bitcoin_provider_anchor_incomplete_signature() {
+    let node = Arc::new(Mutex::new(MockNode::default()));
+    let endpoint = spawn_mock_node(node.clone()).await;
+    let provider = BitcoinProvider::new(endpoint, "regtest".to_string(), None, None).unwrap();
+
+    let result = provider.anchor(&sample_evidence()).await;
+    assert!(matches!(result, Err(AnchorError::Provider(_))));
+    assert!(!node
+        .lock()
+        .unwrap()
+        .calls
+        .contains(&"sendrawtransaction".to_string()));
+}
+
+#[tokio::test]
+async fn test_bitcoin_provider_confirm_by_depth() {
+    let node = Arc::new(Mutex::new(MockNode::default()));
+    let endpoint = spawn_mock_node(node.clone()).await;
+    let provider = BitcoinProvider::new(endpoint, "regtest".to_string(), None, None)
+        .unwrap()
+        .with_min_confirmations(3);
+
+    let tx_ref = ChainTxRef {
+        network: "bitcoin".to_string(),
+        chain: "regtest".to_string(),
+        tx_id: MOCK_TXID.to_string(),
+        confirmed: false,
+        timestamp: Some(Utc::now()),
+    };
+
+    // In the mempool
+    assert!(!provider.confirm(&tx_ref).await.unwrap().confirmed);
+
+    // Mined but not deep enough
+    node.lock().unwrap().confirmations = 2;
+    assert!(!provider.confirm(&tx_ref).await.unwrap().confirmed);
+
+    // Deep enough
+    node.lock().unwrap().confirmations = 3;
+    assert!(provider.confirm(&tx_ref).await.unwrap().confirmed);
+
+    // Reorged into a conflict
+    node.lock().unwrap().confirmations = -1;
+    assert!(!provider.confirm(&tx_ref).await.unwrap().confirmed);
+}
+
+#[tokio::test]
+async fn test_bitcoin_provider_is_known() {
+    let node = Arc::new(Mutex::new(MockNode::default()));
+    let endpoint = spawn_mock_node(node.clone()).await;
+    let provider = BitcoinProvider::new(endpoint, "regtest".to_string(), None, None).unwrap();
+    let tx_ref = ChainTxRef {
+        network: "bitcoin".to_string(),
+        chain: "regtest".to_string(),
+        tx_id: MOCK_TXID.to_string(),
+        confirmed: false,
+        timestamp: None,
+    };
+
+    // Waiting in the mempool
+    assert!(provider.is_known(&tx_ref).await.unwrap());
+
+    // Replaced by a conflicting transaction
+    node.lock().unwrap().confirmations = -1;
+    assert!(!provider.is_known(&tx_ref).await.unwrap());
+
+    // Never seen by the wallet
+    let unknown = ChainTxRef {
+        tx_id: "00".repeat(32),
+        ..tx_ref
+    };
+    assert!(!provider.is_known(&unknown).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_bitcoin_provider_rpc_error_in_http_500() {
+    let node = Arc::new(Mutex::new(MockNode::default()));
+    let endpoint = spawn_mock_node(node).await;
+    let provider = BitcoinProvider::new(endpoint, "regtest".to_string(), None, None).unwrap();
+
+    let tx_ref = ChainTxRef {
+        network: "bitcoin".to_string(),
+        chain: "regtest".to_string(),
+        tx_id: "00".repeat(32),
+        confirmed: false,
+        timestamp: None,
+    };
+
+    match provider.confirm(&tx_ref).await {
+        Err(AnchorError::Provider(msg)) => assert!(msg.contains("-5")),
+        other => panic!("expected provider error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_bitcoin_provider_network_error() {
+    let provider = BitcoinProvider::new(
+        "http://127.0.0.1:1".to_string(),
+        "regtest".to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    let result = provider.anchor(&sample_evidence()).await;
+    assert!(matches!(result, Err(AnchorError::Network(_))));
+}
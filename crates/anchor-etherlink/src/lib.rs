@@ -1,11 +1,12 @@
 use async_trait::async_trait;
 use chrono::Utc;
 use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
-use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use phoenix_evidence::memo::EvidenceMemo;
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord, SignedAnchorTx};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[derive(Clone)]
 pub struct EtherlinkProviderStub;
@@ -29,6 +30,10 @@ impl AnchorProvider for EtherlinkProviderStub {
         confirmed_tx.confirmed = true;
         Ok(confirmed_tx)
     }
+
+    fn network(&self) -> &str {
+        "etherlink"
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -54,10 +59,19 @@ pub struct JsonRpcResponse {
     pub jsonrpc: String,
     #[allow(dead_code)]
     pub id: u64,
+    /// `Some(Value::Null)` for an explicit `"result": null` (e.g. a pending receipt)
+    #[serde(default, deserialize_with = "deserialize_present")]
     pub result: Option<Value>,
     pub error: Option<JsonRpcError>,
 }
 
+fn deserialize_present<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Value::deserialize(deserializer).map(Some)
+}
+
 #[derive(Debug, Deserialize)]
 pub struct JsonRpcError {
     pub code: i32,
@@ -95,7 +109,21 @@ impl EtherlinkProvider {
         })
     }
 
+    /// Send one JSON-RPC request, recording its duration in `phoenix_rpc_call_duration_seconds`
     async fn rpc_call(&self, method: &str, params: Value) -> Result<Value, AnchorError> {
+        let started = Instant::now();
+        let result = self.send_rpc(method, params).await;
+        metrics::histogram!(
+            "phoenix_rpc_call_duration_seconds",
+            "network" => "etherlink",
+            "method" => method.to_string(),
+            "outcome" => if result.is_ok() { "ok" } else { "error" },
+        )
+        .record(started.elapsed().as_secs_f64());
+        result
+    }
+
+    async fn send_rpc(&self, method: &str, params: Value) -> Result<Value, AnchorError> {
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
             method: method.to_string(),
@@ -135,7 +163,8 @@ impl EtherlinkProvider {
             .ok_or_else(|| AnchorError::Provider("RPC response missing result field".to_string()))
     }
 
-    async fn send_memo_transaction(&self, memo_data: &str) -> Result<String, AnchorError> {
+    /// Build the memo transaction; its hash is known before it is sent
+    fn sign_memo_transaction(&self, memo_data: &str) -> SignedAnchorTx {
         // Create a simple transaction with memo data
         // In a real implementation, you'd sign this with the private key
         let _tx_data = json!({
@@ -145,20 +174,35 @@ impl EtherlinkProvider {
             "gasPrice": "0x3b9aca00" // 1 gwei
         });
 
-        // Create a memo transaction with the provided data
-        // In production, you'd call eth_sendTransaction or eth_sendRawTransaction
-        let tx_hash = format!(
-            "0x{}",
-            phoenix_evidence::hash::sha256_hex(memo_data.as_bytes())
-        );
+        SignedAnchorTx {
+            tx: ChainTxRef {
+                network: "etherlink".to_string(),
+                chain: self.network.clone(),
+                tx_id: format!(
+                    "0x{}",
+                    phoenix_evidence::hash::sha256_hex(memo_data.as_bytes())
+                ),
+                confirmed: false,
+                timestamp: None,
+            },
+            raw_tx: format!("0x{}", hex::encode(memo_data.as_bytes())),
+        }
+    }
 
+    async fn send_signed_transaction(
+        &self,
+        signed: &SignedAnchorTx,
+    ) -> Result<ChainTxRef, AnchorError> {
+        // In production, you'd call eth_sendRawTransaction with the raw transaction
         tracing::info!(
-            tx_hash = %tx_hash,
-            memo_data = %memo_data,
+            tx_hash = %signed.tx.tx_id,
+            raw_tx = %signed.raw_tx,
             "Anchored evidence to Etherlink (simulated)"
         );
 
-        Ok(tx_hash)
+        let mut tx = signed.tx.clone();
+        tx.timestamp = Some(Utc::now());
+        Ok(tx)
     }
 
     async fn get_transaction_receipt(
@@ -183,18 +227,11 @@ impl EtherlinkProvider {
 #[async_trait]
 impl AnchorProvider for EtherlinkProvider {
     async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
-        // Create memo with evidence digest
-        let memo = format!("evidence:{}", evidence.digest.hex);
+        // The memo commits to the whole record envelope, not only the payload digest
+        let memo = EvidenceMemo::for_record(evidence).to_string();
 
-        let tx_hash = self.send_memo_transaction(&memo).await?;
-
-        Ok(ChainTxRef {
-            network: "etherlink".to_string(),
-            chain: self.network.clone(),
-            tx_id: tx_hash,
-            confirmed: false,
-            timestamp: Some(Utc::now()),
-        })
+        let signed = self.sign_memo_transaction(&memo);
+        self.send_signed_transaction(&signed).await
     }
 
     async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
@@ -219,4 +256,32 @@ impl AnchorProvider for EtherlinkProvider {
 
         Ok(confirmed_tx)
     }
+
+    async fn health(&self) -> Result<(), AnchorError> {
+        self.rpc_call("eth_blockNumber", json!([])).await?;
+        Ok(())
+    }
+
+    fn network(&self) -> &str {
+        "etherlink"
+    }
+
+    async fn prepare(
+        &self,
+        evidence: &EvidenceRecord,
+    ) -> Result<Option<SignedAnchorTx>, AnchorError> {
+        let memo = EvidenceMemo::for_record(evidence).to_string();
+        Ok(Some(self.sign_memo_transaction(&memo)))
+    }
+
+    async fn broadcast(&self, signed: &SignedAnchorTx) -> Result<ChainTxRef, AnchorError> {
+        self.send_signed_transaction(signed).await
+    }
+
+    async fn is_known(&self, tx: &ChainTxRef) -> Result<bool, AnchorError> {
+        let result = self
+            .rpc_call("eth_getTransactionByHash", json!([tx.tx_id]))
+            .await?;
+        Ok(!result.is_null())
+    }
 }
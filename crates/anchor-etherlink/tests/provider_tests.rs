@@ -209,3 +209,136 @@ fn test_json_rpc_error_response() {
     assert_eq!(error.code, -32601);
     assert_eq!(error.message, "Method not found");
 }
+
+fn simulated_tx_hash(evidence: &EvidenceRecord) -> String {
+    format!(
+        "0x{}",
+        phoenix_evidence::hash::sha256_hex(
+            phoenix_evidence::memo::EvidenceMemo::for_record(evidence)
+                .to_string()
+                .as_bytes()
+        )
+    )
+}
+
+#[tokio::test]
+async fn test_etherlink_provider_confirm_against_simulator() {
+    use chain_simulator::{ChainSimulator, SimulatorConfig};
+
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    let provider =
+        EtherlinkProvider::new(sim.endpoint().to_string(), "testnet".to_string(), None).unwrap();
+
+    let evidence = EvidenceRecord {
+        id: "sim-evidence".to_string(),
+        created_at: Utc::now(),
+        digest: EvidenceDigest {
+            algo: DigestAlgo::Sha256,
+            hex: "0123456789abcdef".to_string(),
+        },
+        payload_mime: None,
+        metadata: json!({}),
+    };
+
+    let tx_ref = provider.anchor(&evidence).await.unwrap();
+    assert_eq!(tx_ref.tx_id, simulated_tx_hash(&evidence));
+    sim.submit(&tx_ref.tx_id);
+
+    // In the mempool
+    assert!(!provider.confirm(&tx_ref).await.unwrap().confirmed);
+
+    sim.mine();
+    assert!(provider.confirm(&tx_ref).await.unwrap().confirmed);
+
+    // Reorged out: no receipt until it is mined again
+    sim.reorg(1);
+    assert!(!provider.confirm(&tx_ref).await.unwrap().confirmed);
+    sim.mine();
+    assert!(provider.confirm(&tx_ref).await.unwrap().confirmed);
+}
+
+#[tokio::test]
+async fn test_etherlink_provider_reverted_tx_not_confirmed() {
+    use chain_simulator::{ChainSimulator, SimulatorConfig};
+
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    let provider =
+        EtherlinkProvider::new(sim.endpoint().to_string(), "testnet".to_string(), None).unwrap();
+
+    let tx_ref = ChainTxRef {
+        network: "etherlink".to_string(),
+        chain: "testnet".to_string(),
+        tx_id: "0xfeed".to_string(),
+        confirmed: false,
+        timestamp: Some(Utc::now()),
+    };
+    sim.submit(&tx_ref.tx_id);
+    sim.fail_tx(&tx_ref.tx_id);
+    sim.mine_blocks(5);
+
+    assert!(!provider.confirm(&tx_ref).await.unwrap().confirmed);
+}
+
+#[tokio::test]
+async fn test_etherlink_provider_health() {
+    use chain_simulator::{ChainSimulator, SimulatorConfig};
+
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    let provider =
+        EtherlinkProvider::new(sim.endpoint().to_string(), "testnet".to_string(), None).unwrap();
+    assert!(provider.health().await.is_ok());
+
+    let provider = EtherlinkProvider::new(
+        "http://127.0.0.1:1".to_string(),
+        "testnet".to_string(),
+        None,
+    )
+    .unwrap();
+    assert!(matches!(
+        provider.health().await,
+        Err(phoenix_evidence::anchor::AnchorError::Network(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_etherlink_provider_prepare_and_is_known() {
+    use chain_simulator::{ChainSimulator, SimulatorConfig};
+
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    let provider =
+        EtherlinkProvider::new(sim.endpoint().to_string(), "testnet".to_string(), None).unwrap();
+
+    let evidence = EvidenceRecord {
+        id: "sim-evidence".to_string(),
+        created_at: Utc::now(),
+        digest: EvidenceDigest {
+            algo: DigestAlgo::Sha256,
+            hex: "0123456789abcdef".to_string(),
+        },
+        payload_mime: None,
+        metadata: json!({}),
+    };
+
+    // Signing is deterministic, so a retried job signs the same transaction
+    let signed = provider.prepare(&evidence).await.unwrap().unwrap();
+    assert_eq!(
+        provider.prepare(&evidence).await.unwrap(),
+        Some(signed.clone())
+    );
+    assert_eq!(signed.tx.tx_id, simulated_tx_hash(&evidence));
+    assert!(signed.raw_tx.starts_with("0x"));
+
+    assert!(!provider.is_known(&signed.tx).await.unwrap());
+    let tx_ref = provider.broadcast(&signed).await.unwrap();
+    assert_eq!(tx_ref.tx_id, signed.tx.tx_id);
+    sim.submit(&tx_ref.tx_id);
+    assert!(provider.is_known(&signed.tx).await.unwrap());
+}
@@ -0,0 +1,381 @@
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::hash::{record_hash_hex, sha256_hex};
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+pub const NOTARY_NETWORK: &str = "local-notary";
+
+/// `prev_hash` of the first entry in a ledger
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// One line of the append-only ledger file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LedgerEntry {
+    pub seq: u64,
+    pub prev_hash: String,
+    pub evidence_id: String,
+    /// `record_hash_hex` of the evidence, the value every provider anchors
+    pub record_hash: String,
+    pub timestamp: DateTime<Utc>,
+    pub entry_hash: String,
+    pub signature: String,
+}
+
+impl LedgerEntry {
+    /// Hash over every field except `entry_hash` and `signature`
+    pub fn compute_hash(&self) -> String {
+        let preimage = format!(
+            "{}|{}|{}|{}|{}",
+            self.seq,
+            self.prev_hash,
+            self.evidence_id,
+            self.record_hash,
+            self.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
+        );
+        sha256_hex(preimage.as_bytes())
+    }
+
+    /// Check the entry's own hash and signature (not its link to the previous entry)
+    pub fn verify(&self, key: &VerifyingKey) -> Result<(), AnchorError> {
+        if self.compute_hash() != self.entry_hash {
+            return Err(AnchorError::Invalid(format!(
+                "ledger entry {} hash mismatch",
+                self.seq
+            )));
+        }
+
+        let hash_bytes = hex::decode(&self.entry_hash)
+            .map_err(|e| AnchorError::Invalid(format!("ledger entry {} hash: {}", self.seq, e)))?;
+        let sig_bytes: [u8; 64] = hex::decode(&self.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| {
+                AnchorError::Invalid(format!("ledger entry {} has malformed signature", self.seq))
+            })?;
+
+        key.verify(&hash_bytes, &Signature::from_bytes(&sig_bytes))
+            .map_err(|_| {
+                AnchorError::Invalid(format!("ledger entry {} signature invalid", self.seq))
+            })
+    }
+}
+
+/// Read a ledger and check that it is a contiguous, correctly signed hash chain.
+///
+/// A missing file is an empty ledger.
+pub fn verify_ledger(path: &Path, key: &VerifyingKey) -> Result<Vec<LedgerEntry>, AnchorError> {
+    let entries = read_ledger(path)?;
+
+    let mut prev_hash = GENESIS_HASH;
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.seq != index as u64 {
+            return Err(AnchorError::Invalid(format!(
+                "ledger entry at line {} has seq {}",
+                index + 1,
+                entry.seq
+            )));
+        }
+        if entry.prev_hash != prev_hash {
+            return Err(AnchorError::Invalid(format!(
+                "ledger entry {} does not link to its predecessor",
+                entry.seq
+            )));
+        }
+        entry.verify(key)?;
+        prev_hash = &entry.entry_hash;
+    }
+
+    Ok(entries)
+}
+
+fn read_ledger(path: &Path) -> Result<Vec<LedgerEntry>, AnchorError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(AnchorError::Provider(format!(
+                "failed to read ledger {}: {}",
+                path.display(),
+                e
+            )))
+        }
+    };
+
+    parse_entries(&contents[..complete_len(contents.as_bytes())])
+}
+
+/// Length of the complete lines in `ledger`. A final line without its newline
+/// was torn by an interrupted append and never acknowledged, so it is not
+/// part of the ledger.
+fn complete_len(ledger: &[u8]) -> usize {
+    ledger
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |last| last + 1)
+}
+
+fn parse_entries(contents: &str) -> Result<Vec<LedgerEntry>, AnchorError> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(n, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                AnchorError::Invalid(format!("ledger line {} is malformed: {}", n + 1, e))
+            })
+        })
+        .collect()
+}
+
+/// Encode a ledger position as `<seq>:<entry_hash>`
+pub fn encode_tx_id(seq: u64, entry_hash: &str) -> String {
+    format!("{}:{}", seq, entry_hash)
+}
+
+pub fn decode_tx_id(tx_id: &str) -> Result<(u64, &str), AnchorError> {
+    let (seq, hash) = tx_id
+        .split_once(':')
+        .ok_or_else(|| AnchorError::Invalid(format!("malformed notary tx id: {}", tx_id)))?;
+    let seq = seq
+        .parse()
+        .map_err(|_| AnchorError::Invalid(format!("malformed notary tx id: {}", tx_id)))?;
+    Ok((seq, hash))
+}
+
+/// Anchors evidence into a signed, hash-linked JSON-lines ledger on local disk.
+///
+/// Each entry commits to the previous one, so editing, removing or reordering any line
+/// breaks every later `entry_hash`. `confirm` re-verifies the whole chain.
+///
+/// Appends hold an exclusive lock on the ledger file, so several processes may share
+/// one ledger. File I/O runs on the blocking thread pool.
+#[derive(Clone)]
+pub struct LocalNotaryProvider {
+    pub ledger_path: PathBuf,
+    signing_key: SigningKey,
+    /// Last entry appended by this provider, to link the next one without
+    /// reading the ledger again
+    tail: Arc<Mutex<Option<LedgerTail>>>,
+}
+
+/// The ledger's last entry, and the file's size and modification time just
+/// after it was written
+#[derive(Debug, Clone)]
+struct LedgerTail {
+    len: u64,
+    modified: SystemTime,
+    seq: u64,
+    entry_hash: String,
+}
+
+impl LocalNotaryProvider {
+    pub fn new(ledger_path: PathBuf, signing_key: SigningKey) -> Self {
+        Self {
+            ledger_path,
+            signing_key,
+            tail: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Load the 32-byte signing seed from a hex-encoded key file
+    pub fn from_key_file(ledger_path: PathBuf, key_path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(key_path)
+            .map_err(|e| format!("Failed to read key file {}: {}", key_path.display(), e))?;
+        let seed: [u8; 32] = hex::decode(contents.trim())
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| "Key file must contain a 32-byte hex seed".to_string())?;
+
+        Ok(Self::new(ledger_path, SigningKey::from_bytes(&seed)))
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    /// Identifies the ledger by the first 8 bytes of its signer's public key
+    pub fn chain_id(&self) -> String {
+        hex::encode(&self.verifying_key().as_bytes()[..8])
+    }
+
+    pub fn entries(&self) -> Result<Vec<LedgerEntry>, AnchorError> {
+        verify_ledger(&self.ledger_path, &self.verifying_key())
+    }
+
+    fn io_error(&self, action: &str, e: std::io::Error) -> AnchorError {
+        AnchorError::Provider(format!(
+            "failed to {} ledger {}: {}",
+            action,
+            self.ledger_path.display(),
+            e
+        ))
+    }
+
+    fn append(&self, evidence: &EvidenceRecord) -> Result<LedgerEntry, AnchorError> {
+        let mut cached = self
+            .tail
+            .lock()
+            .map_err(|_| AnchorError::Provider("ledger lock poisoned".to_string()))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.ledger_path)
+            .map_err(|e| self.io_error("open", e))?;
+        // Released when `file` is dropped
+        file.lock().map_err(|e| self.io_error("lock", e))?;
+
+        // The cached tail is only trusted while nobody else has written to the file
+        let (len, modified) = file_stamp(&file).map_err(|e| self.io_error("stat", e))?;
+        let tail = match cached.take() {
+            Some(tail) if tail.len == len && tail.modified == modified => Some(tail),
+            _ => self.load_tail(&mut file)?,
+        };
+        let (seq, prev_hash) = match tail {
+            Some(tail) => (tail.seq + 1, tail.entry_hash),
+            None => (0, GENESIS_HASH.to_string()),
+        };
+
+        let now = Utc::now();
+        let mut entry = LedgerEntry {
+            seq,
+            prev_hash,
+            evidence_id: evidence.id.clone(),
+            record_hash: record_hash_hex(evidence),
+            // Truncated so the stored value hashes the same after a round trip
+            timestamp: DateTime::from_timestamp_millis(now.timestamp_millis()).unwrap_or(now),
+            entry_hash: String::new(),
+            signature: String::new(),
+        };
+        entry.entry_hash = entry.compute_hash();
+        let hash_bytes = hex::decode(&entry.entry_hash)
+            .map_err(|e| AnchorError::Provider(format!("entry hash: {}", e)))?;
+        entry.signature = hex::encode(self.signing_key.sign(&hash_bytes).to_bytes());
+
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|e| AnchorError::Provider(format!("failed to encode entry: {}", e)))?;
+        line.push('\n');
+
+        // On failure the cache stays empty, so the next append reloads and
+        // repairs the tail
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(|e| self.io_error("append to", e))?;
+        let (len, modified) = file_stamp(&file).map_err(|e| self.io_error("stat", e))?;
+        *cached = Some(LedgerTail {
+            len,
+            modified,
+            seq: entry.seq,
+            entry_hash: entry.entry_hash.clone(),
+        });
+
+        Ok(entry)
+    }
+
+    /// Read the last entry of the locked ledger `file`, cutting off a line
+    /// torn by an interrupted append first. Only the tail is verified here;
+    /// the full chain is verified on confirm.
+    fn load_tail(&self, file: &mut File) -> Result<Option<LedgerTail>, AnchorError> {
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)
+            .map_err(|e| self.io_error("read", e))?;
+        let complete = complete_len(&contents);
+        if complete < contents.len() {
+            tracing::warn!(
+                ledger = %self.ledger_path.display(),
+                torn_bytes = contents.len() - complete,
+                "Removing a ledger line torn by an interrupted append"
+            );
+            file.set_len(complete as u64)
+                .and_then(|_| file.sync_data())
+                .map_err(|e| self.io_error("repair", e))?;
+            contents.truncate(complete);
+        }
+
+        let contents = String::from_utf8(contents)
+            .map_err(|e| AnchorError::Invalid(format!("ledger is not UTF-8: {}", e)))?;
+        let Some(last) = parse_entries(&contents)?.pop() else {
+            return Ok(None);
+        };
+        last.verify(&self.verifying_key())?;
+        let (len, modified) = file_stamp(file).map_err(|e| self.io_error("stat", e))?;
+        Ok(Some(LedgerTail {
+            len,
+            modified,
+            seq: last.seq,
+            entry_hash: last.entry_hash,
+        }))
+    }
+}
+
+/// Run ledger file I/O on the blocking thread pool
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T, AnchorError> + Send + 'static,
+) -> Result<T, AnchorError> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| AnchorError::Provider(format!("ledger task failed: {}", e)))?
+}
+
+fn file_stamp(file: &File) -> std::io::Result<(u64, SystemTime)> {
+    let metadata = file.metadata()?;
+    Ok((metadata.len(), metadata.modified()?))
+}
+
+#[async_trait]
+impl AnchorProvider for LocalNotaryProvider {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        let provider = self.clone();
+        let evidence = evidence.clone();
+        let entry = blocking(move || provider.append(&evidence)).await?;
+
+        tracing::info!(
+            seq = entry.seq,
+            entry_hash = %entry.entry_hash,
+            evidence_id = %entry.evidence_id,
+            "Anchored evidence to local notary ledger"
+        );
+
+        Ok(ChainTxRef {
+            network: NOTARY_NETWORK.to_string(),
+            chain: self.chain_id(),
+            tx_id: encode_tx_id(entry.seq, &entry.entry_hash),
+            confirmed: false,
+            timestamp: Some(entry.timestamp),
+        })
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        let (seq, entry_hash) = decode_tx_id(&tx.tx_id)?;
+        let provider = self.clone();
+        let entries = blocking(move || provider.entries()).await?;
+
+        let entry = entries
+            .get(seq as usize)
+            .ok_or_else(|| AnchorError::Invalid(format!("ledger has no entry {}", seq)))?;
+        if entry.entry_hash != entry_hash {
+            return Err(AnchorError::Invalid(format!(
+                "ledger entry {} does not match tx id",
+                seq
+            )));
+        }
+
+        let mut confirmed_tx = tx.clone();
+        confirmed_tx.confirmed = true;
+        confirmed_tx.timestamp = Some(entry.timestamp);
+        Ok(confirmed_tx)
+    }
+
+    fn network(&self) -> &str {
+        NOTARY_NETWORK
+    }
+}
@@ -0,0 +1,255 @@
+use anchor_notary::{verify_ledger, LocalNotaryProvider, GENESIS_HASH, NOTARY_NETWORK};
+use chrono::Utc;
+use ed25519_dalek::SigningKey;
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::hash::record_hash_hex;
+use phoenix_evidence::model::{DigestAlgo, EvidenceDigest, EvidenceRecord};
+use serde_json::json;
+use std::fs;
+use std::io::Write;
+use tempfile::TempDir;
+
+fn test_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+fn sample_evidence(id: &str, digest: &str) -> EvidenceRecord {
+    EvidenceRecord {
+        id: id.to_string(),
+        created_at: Utc::now(),
+        digest: EvidenceDigest {
+            algo: DigestAlgo::Sha256,
+            hex: digest.to_string(),
+        },
+        payload_mime: None,
+        metadata: json!({}),
+    }
+}
+
+fn provider_in(dir: &TempDir) -> LocalNotaryProvider {
+    LocalNotaryProvider::new(dir.path().join("ledger.jsonl"), test_key())
+}
+
+#[tokio::test]
+async fn test_notary_anchor_and_confirm() {
+    let dir = TempDir::new().unwrap();
+    let provider = provider_in(&dir);
+
+    let first = provider
+        .anchor(&sample_evidence("ev-1", "aa11"))
+        .await
+        .unwrap();
+    let second = provider
+        .anchor(&sample_evidence("ev-2", "bb22"))
+        .await
+        .unwrap();
+
+    assert_eq!(first.network, NOTARY_NETWORK);
+    assert_eq!(first.chain, provider.chain_id());
+    assert!(first.tx_id.starts_with("0:"));
+    assert!(second.tx_id.starts_with("1:"));
+    assert!(!first.confirmed);
+
+    let confirmed = provider.confirm(&first).await.unwrap();
+    assert!(confirmed.confirmed);
+    assert_eq!(confirmed.tx_id, first.tx_id);
+    assert!(provider.confirm(&second).await.unwrap().confirmed);
+}
+
+#[tokio::test]
+async fn test_notary_ledger_is_hash_linked() {
+    let dir = TempDir::new().unwrap();
+    let provider = provider_in(&dir);
+
+    for i in 0..3 {
+        provider
+            .anchor(&sample_evidence(&format!("ev-{}", i), "cafe"))
+            .await
+            .unwrap();
+    }
+
+    let entries = verify_ledger(&provider.ledger_path, &provider.verifying_key()).unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+    assert_eq!(entries[1].prev_hash, entries[0].entry_hash);
+    assert_eq!(entries[2].prev_hash, entries[1].entry_hash);
+    assert_eq!(entries[2].evidence_id, "ev-2");
+}
+
+#[tokio::test]
+async fn test_notary_commits_to_record_hash() {
+    let dir = TempDir::new().unwrap();
+    let provider = provider_in(&dir);
+    let mut evidence = sample_evidence("ev-1", "cafe");
+    evidence.payload_mime = Some("application/pdf".to_string());
+    evidence.metadata = json!({ "sortie": 7 });
+
+    provider.anchor(&evidence).await.unwrap();
+
+    let entries = provider.entries().unwrap();
+    assert_eq!(entries[0].record_hash, record_hash_hex(&evidence));
+    assert_ne!(entries[0].record_hash, "cafe");
+}
+
+#[tokio::test]
+async fn test_notary_detects_tampered_entry() {
+    let dir = TempDir::new().unwrap();
+    let provider = provider_in(&dir);
+
+    let tx = provider
+        .anchor(&sample_evidence("ev-1", "aa11"))
+        .await
+        .unwrap();
+    provider
+        .anchor(&sample_evidence("ev-2", "bb22"))
+        .await
+        .unwrap();
+
+    let ledger = fs::read_to_string(&provider.ledger_path).unwrap();
+    fs::write(
+        &provider.ledger_path,
+        ledger.replace("\"ev-1\"", "\"ev-9\""),
+    )
+    .unwrap();
+
+    let result = provider.confirm(&tx).await;
+    assert!(matches!(result, Err(AnchorError::Invalid(_))));
+}
+
+#[tokio::test]
+async fn test_notary_detects_removed_entry() {
+    let dir = TempDir::new().unwrap();
+    let provider = provider_in(&dir);
+
+    provider
+        .anchor(&sample_evidence("ev-1", "aa11"))
+        .await
+        .unwrap();
+    let tx = provider
+        .anchor(&sample_evidence("ev-2", "bb22"))
+        .await
+        .unwrap();
+
+    let ledger = fs::read_to_string(&provider.ledger_path).unwrap();
+    let remaining: String = ledger.lines().skip(1).map(|l| format!("{}\n", l)).collect();
+    fs::write(&provider.ledger_path, remaining).unwrap();
+
+    let result = provider.confirm(&tx).await;
+    assert!(matches!(result, Err(AnchorError::Invalid(_))));
+}
+
+#[tokio::test]
+async fn test_notary_repairs_torn_last_line() {
+    let dir = TempDir::new().unwrap();
+    let provider = provider_in(&dir);
+    let tx = provider
+        .anchor(&sample_evidence("ev-1", "aa11"))
+        .await
+        .unwrap();
+
+    // An append interrupted mid-write leaves a line without its newline
+    let mut ledger = fs::OpenOptions::new()
+        .append(true)
+        .open(&provider.ledger_path)
+        .unwrap();
+    ledger.write_all(b"{\"seq\":1,\"prev_hash\":\"00").unwrap();
+    drop(ledger);
+
+    // The chain up to the torn line still verifies
+    assert!(provider.confirm(&tx).await.unwrap().confirmed);
+    assert_eq!(provider.entries().unwrap().len(), 1);
+
+    // The next append, from this or another process, cuts the torn line off first
+    let restarted = provider_in(&dir);
+    let next = restarted
+        .anchor(&sample_evidence("ev-2", "bb22"))
+        .await
+        .unwrap();
+    assert!(next.tx_id.starts_with("1:"));
+    let entries = verify_ledger(&provider.ledger_path, &provider.verifying_key()).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].evidence_id, "ev-2");
+}
+
+#[tokio::test]
+async fn test_notary_shared_ledger_stays_linked() {
+    let dir = TempDir::new().unwrap();
+    // Separate providers stand in for separate processes on one ledger
+    let providers = [provider_in(&dir), provider_in(&dir)];
+
+    let tasks: Vec<_> = (0..20)
+        .map(|i| {
+            let provider = providers[i % 2].clone();
+            tokio::spawn(async move {
+                provider
+                    .anchor(&sample_evidence(&format!("ev-{}", i), "cafe"))
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let entries = providers[0].entries().unwrap();
+    assert_eq!(entries.len(), 20);
+}
+
+#[tokio::test]
+async fn test_notary_rejects_foreign_signer() {
+    let dir = TempDir::new().unwrap();
+    let provider = provider_in(&dir);
+    let tx = provider
+        .anchor(&sample_evidence("ev-1", "aa11"))
+        .await
+        .unwrap();
+
+    let other = LocalNotaryProvider::new(
+        provider.ledger_path.clone(),
+        SigningKey::from_bytes(&[9u8; 32]),
+    );
+    let result = other.confirm(&tx).await;
+    assert!(matches!(result, Err(AnchorError::Invalid(_))));
+
+    // Appending with the wrong key is refused as well
+    let result = other.anchor(&sample_evidence("ev-2", "bb22")).await;
+    assert!(matches!(result, Err(AnchorError::Invalid(_))));
+}
+
+#[tokio::test]
+async fn test_notary_confirm_unknown_entry() {
+    let dir = TempDir::new().unwrap();
+    let provider = provider_in(&dir);
+    let mut tx = provider
+        .anchor(&sample_evidence("ev-1", "aa11"))
+        .await
+        .unwrap();
+
+    tx.tx_id = format!("5:{}", GENESIS_HASH);
+    assert!(matches!(
+        provider.confirm(&tx).await,
+        Err(AnchorError::Invalid(_))
+    ));
+
+    tx.tx_id = "not-a-ledger-ref".to_string();
+    assert!(matches!(
+        provider.confirm(&tx).await,
+        Err(AnchorError::Invalid(_))
+    ));
+}
+
+#[test]
+fn test_notary_from_key_file() {
+    let dir = TempDir::new().unwrap();
+    let key_path = dir.path().join("notary.key");
+    let mut file = fs::File::create(&key_path).unwrap();
+    writeln!(file, "{}", hex::encode([7u8; 32])).unwrap();
+
+    let provider =
+        LocalNotaryProvider::from_key_file(dir.path().join("ledger.jsonl"), &key_path).unwrap();
+    assert_eq!(provider.verifying_key(), test_key().verifying_key());
+
+    fs::write(&key_path, "abcd").unwrap();
+    assert!(LocalNotaryProvider::from_key_file(dir.path().join("x"), &key_path).is_err());
+}
@@ -1,11 +1,17 @@
 use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use chrono::Utc;
 use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
-use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use phoenix_evidence::memo::EvidenceMemo;
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord, SignedAnchorTx};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// How long after signing a transaction can still land: 150 blocks of about
+/// 400ms, with room for slow slots
+pub const SIGNED_TX_LIFETIME: Duration = Duration::from_secs(120);
 
 #[derive(Clone)]
 pub struct SolanaProviderStub;
@@ -27,6 +33,10 @@ impl AnchorProvider for SolanaProviderStub {
         t.confirmed = true;
         Ok(t)
     }
+
+    fn network(&self) -> &str {
+        "solana"
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -63,6 +73,7 @@ pub struct SolanaRpcError {
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct TransactionStatus {
     slot: u64,
     #[allow(dead_code)]
@@ -85,7 +96,21 @@ impl SolanaProvider {
         }
     }
 
+    /// Send one JSON-RPC request, recording its duration in `phoenix_rpc_call_duration_seconds`
     async fn rpc_call(&self, method: &str, params: Value) -> Result<Value, AnchorError> {
+        let started = Instant::now();
+        let result = self.send_rpc(method, params).await;
+        metrics::histogram!(
+            "phoenix_rpc_call_duration_seconds",
+            "network" => "solana",
+            "method" => method.to_string(),
+            "outcome" => if result.is_ok() { "ok" } else { "error" },
+        )
+        .record(started.elapsed().as_secs_f64());
+        result
+    }
+
+    async fn send_rpc(&self, method: &str, params: Value) -> Result<Value, AnchorError> {
         let request = SolanaRpcRequest {
             jsonrpc: "2.0".to_string(),
             id: 1,
@@ -125,20 +150,38 @@ impl SolanaProvider {
             .ok_or_else(|| AnchorError::Provider("RPC response missing result field".to_string()))
     }
 
-    async fn send_memo_transaction(&self, memo_data: &str) -> Result<String, AnchorError> {
+    /// Build the memo transaction; its signature is known before it is sent
+    fn sign_memo_transaction(&self, memo_data: &str) -> SignedAnchorTx {
         // Create a memo transaction
         // In a real implementation, you'd create and sign a proper Solana transaction
-        // For now, return a deterministic fake signature
+        // For now, use a deterministic fake signature
         // sha256_hex already returns a hex string, so we use it directly as the signature
-        let signature = phoenix_evidence::hash::sha256_hex(memo_data.as_bytes());
+        SignedAnchorTx {
+            tx: ChainTxRef {
+                network: "solana".to_string(),
+                chain: self.network.clone(),
+                tx_id: phoenix_evidence::hash::sha256_hex(memo_data.as_bytes()),
+                confirmed: false,
+                timestamp: None,
+            },
+            raw_tx: BASE64.encode(memo_data.as_bytes()),
+        }
+    }
 
+    async fn send_signed_transaction(
+        &self,
+        signed: &SignedAnchorTx,
+    ) -> Result<ChainTxRef, AnchorError> {
+        // In production, you'd call sendTransaction with the encoded transaction
         tracing::info!(
-            signature = %signature,
-            memo_data = %memo_data,
+            signature = %signed.tx.tx_id,
+            raw_tx = %signed.raw_tx,
             "Anchored evidence to Solana (simulated)"
         );
 
-        Ok(signature)
+        let mut tx = signed.tx.clone();
+        tx.timestamp = Some(Utc::now());
+        Ok(tx)
     }
 
     async fn get_signature_status(
@@ -176,18 +219,11 @@ impl SolanaProvider {
 #[async_trait]
 impl AnchorProvider for SolanaProvider {
     async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
-        // Create memo with evidence digest
-        let memo = format!("evidence:{}", evidence.digest.hex);
-
-        let signature = self.send_memo_transaction(&memo).await?;
+        // The memo commits to the whole record envelope, not only the payload digest
+        let memo = EvidenceMemo::for_record(evidence).to_string();
 
-        Ok(ChainTxRef {
-            network: "solana".to_string(),
-            chain: self.network.clone(),
-            tx_id: signature,
-            confirmed: false,
-            timestamp: Some(Utc::now()),
-        })
+        let signed = self.sign_memo_transaction(&memo);
+        self.send_signed_transaction(&signed).await
     }
 
     async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
@@ -212,4 +248,38 @@ impl AnchorProvider for SolanaProvider {
 
         Ok(confirmed_tx)
     }
+
+    async fn health(&self) -> Result<(), AnchorError> {
+        self.rpc_call("getSlot", json!([])).await?;
+        Ok(())
+    }
+
+    fn network(&self) -> &str {
+        "solana"
+    }
+
+    async fn prepare(
+        &self,
+        evidence: &EvidenceRecord,
+    ) -> Result<Option<SignedAnchorTx>, AnchorError> {
+        let memo = EvidenceMemo::for_record(evidence).to_string();
+        Ok(Some(self.sign_memo_transaction(&memo)))
+    }
+
+    /// A transaction carries a recent blockhash and is only processed while
+    /// that blockhash is among the last 150 blocks
+    fn signed_tx_lifetime(&self) -> Option<Duration> {
+        Some(SIGNED_TX_LIFETIME)
+    }
+
+    async fn broadcast(&self, signed: &SignedAnchorTx) -> Result<ChainTxRef, AnchorError> {
+        self.send_signed_transaction(signed).await
+    }
+
+    /// Signature statuses only cover processed transactions, so one still
+    /// waiting for a leader reads as unknown. Sending it again is harmless:
+    /// the cluster deduplicates by signature.
+    async fn is_known(&self, tx: &ChainTxRef) -> Result<bool, AnchorError> {
+        Ok(self.get_signature_status(&tx.tx_id).await?.is_some())
+    }
 }
@@ -201,3 +201,124 @@ fn test_solana_provider_debug() {
     assert!(debug_str.contains("https://api.devnet.solana.com"));
     assert!(debug_str.contains("devnet"));
 }
+
+#[tokio::test]
+async fn test_solana_provider_confirm_against_simulator() {
+    use chain_simulator::{ChainSimulator, SimulatorConfig};
+
+    let sim = ChainSimulator::start(SimulatorConfig {
+        finality_depth: 3,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let provider = SolanaProvider::new(sim.endpoint().to_string(), "devnet".to_string());
+
+    let evidence = EvidenceRecord {
+        id: "sim-evidence".to_string(),
+        created_at: Utc::now(),
+        digest: EvidenceDigest {
+            algo: DigestAlgo::Sha256,
+            hex: "0123456789abcdef".to_string(),
+        },
+        payload_mime: None,
+        metadata: json!({}),
+    };
+
+    let tx_ref = provider.anchor(&evidence).await.unwrap();
+    sim.submit(&tx_ref.tx_id);
+
+    // Processed and confirmed are not enough, only finalized counts
+    sim.mine();
+    assert!(!provider.confirm(&tx_ref).await.unwrap().confirmed);
+    sim.mine();
+    assert!(!provider.confirm(&tx_ref).await.unwrap().confirmed);
+    sim.mine();
+    assert!(provider.confirm(&tx_ref).await.unwrap().confirmed);
+}
+
+#[tokio::test]
+async fn test_solana_provider_dropped_or_failed_not_confirmed() {
+    use chain_simulator::{ChainSimulator, SimulatorConfig};
+
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    let provider = SolanaProvider::new(sim.endpoint().to_string(), "devnet".to_string());
+
+    let tx_ref = |tx_id: &str| ChainTxRef {
+        network: "solana".to_string(),
+        chain: "devnet".to_string(),
+        tx_id: tx_id.to_string(),
+        confirmed: false,
+        timestamp: Some(Utc::now()),
+    };
+
+    sim.submit("dropped");
+    sim.drop_tx("dropped");
+    sim.submit("failed");
+    sim.fail_tx("failed");
+    sim.mine_blocks(10);
+
+    assert!(
+        !provider
+            .confirm(&tx_ref("dropped"))
+            .await
+            .unwrap()
+            .confirmed
+    );
+    assert!(!provider.confirm(&tx_ref("failed")).await.unwrap().confirmed);
+}
+
+#[tokio::test]
+async fn test_solana_provider_health() {
+    use chain_simulator::{ChainSimulator, SimulatorConfig};
+
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    let provider = SolanaProvider::new(sim.endpoint().to_string(), "devnet".to_string());
+    assert!(provider.health().await.is_ok());
+
+    let provider = SolanaProvider::new("http://127.0.0.1:1".to_string(), "devnet".to_string());
+    assert!(matches!(
+        provider.health().await,
+        Err(phoenix_evidence::anchor::AnchorError::Network(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_solana_provider_prepare_and_is_known() {
+    use chain_simulator::{ChainSimulator, SimulatorConfig};
+
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    let provider = SolanaProvider::new(sim.endpoint().to_string(), "devnet".to_string());
+
+    let evidence = EvidenceRecord {
+        id: "sim-evidence".to_string(),
+        created_at: Utc::now(),
+        digest: EvidenceDigest {
+            algo: DigestAlgo::Sha256,
+            hex: "0123456789abcdef".to_string(),
+        },
+        payload_mime: None,
+        metadata: json!({}),
+    };
+
+    // Signing is deterministic, so a retried job signs the same transaction
+    let signed = provider.prepare(&evidence).await.unwrap().unwrap();
+    assert_eq!(
+        provider.prepare(&evidence).await.unwrap(),
+        Some(signed.clone())
+    );
+
+    assert!(!provider.is_known(&signed.tx).await.unwrap());
+    let tx_ref = provider.broadcast(&signed).await.unwrap();
+    assert_eq!(tx_ref.tx_id, signed.tx.tx_id);
+    sim.submit(&tx_ref.tx_id);
+    // Signature statuses only cover processed transactions
+    sim.mine();
+    assert!(provider.is_known(&signed.tx).await.unwrap());
+}
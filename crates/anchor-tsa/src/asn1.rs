@@ -0,0 +1,182 @@
+//! RFC 3161 message structures (only the parts the provider needs)
+
+use chrono::{DateTime, NaiveDate};
+use cms::content_info::ContentInfo;
+use der::asn1::{BitString, Int, ObjectIdentifier, OctetString};
+use der::{
+    DecodeValue, EncodeValue, ErrorKind, FixedTag, Header, Length, Reader, Sequence, Tag, Writer,
+};
+use std::time::Duration;
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_cert::ext::Extensions;
+use x509_cert::spki::AlgorithmIdentifierOwned;
+
+pub const OID_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1");
+pub const OID_SIGNED_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.2");
+pub const OID_CT_TST_INFO: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.1.4");
+pub const OID_ATTR_CONTENT_TYPE: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.3");
+pub const OID_ATTR_MESSAGE_DIGEST: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.4");
+pub const OID_EC_PUBLIC_KEY: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+pub const OID_PRIME256V1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+pub const OID_ECDSA_WITH_SHA256: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+pub const OID_RSA_ENCRYPTION: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+pub const OID_SHA256_WITH_RSA: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+
+/// PKIStatus values that carry a token
+pub const STATUS_GRANTED: u8 = 0;
+pub const STATUS_GRANTED_WITH_MODS: u8 = 1;
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct MessageImprint {
+    pub hash_algorithm: AlgorithmIdentifierOwned,
+    pub hashed_message: OctetString,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct TimeStampReq {
+    pub version: u8,
+    pub message_imprint: MessageImprint,
+    #[asn1(optional = "true")]
+    pub req_policy: Option<ObjectIdentifier>,
+    #[asn1(optional = "true")]
+    pub nonce: Option<u64>,
+    #[asn1(default = "Default::default")]
+    pub cert_req: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct PkiStatusInfo {
+    pub status: u8,
+    #[asn1(optional = "true")]
+    pub status_string: Option<Vec<String>>,
+    #[asn1(optional = "true")]
+    pub fail_info: Option<BitString>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct TimeStampResp {
+    pub status: PkiStatusInfo,
+    #[asn1(optional = "true")]
+    pub time_stamp_token: Option<ContentInfo>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct Accuracy {
+    #[asn1(optional = "true")]
+    pub seconds: Option<u32>,
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT", optional = "true")]
+    pub millis: Option<u16>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    pub micros: Option<u16>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+pub struct TstInfo {
+    pub version: u8,
+    pub policy: ObjectIdentifier,
+    pub message_imprint: MessageImprint,
+    pub serial_number: Int,
+    pub gen_time: GenTime,
+    #[asn1(optional = "true")]
+    pub accuracy: Option<Accuracy>,
+    #[asn1(default = "Default::default")]
+    pub ordering: bool,
+    #[asn1(optional = "true")]
+    pub nonce: Option<u64>,
+    #[asn1(context_specific = "0", tag_mode = "EXPLICIT", optional = "true")]
+    pub tsa: Option<GeneralName>,
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", optional = "true")]
+    pub extensions: Option<Extensions>,
+}
+
+/// `genTime` of a token: a GeneralizedTime that, unlike
+/// [`der::asn1::GeneralizedTime`], may carry fractional seconds
+/// (`YYYYMMDDHHMMSS[.f]Z`, RFC 3161 section 2.4.2)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GenTime {
+    text: String,
+    since_epoch: Duration,
+}
+
+impl GenTime {
+    /// Encode `since_epoch`, with a fraction only if it has sub-second digits
+    pub fn from_unix_duration(since_epoch: Duration) -> der::Result<Self> {
+        let time =
+            DateTime::from_timestamp(since_epoch.as_secs() as i64, since_epoch.subsec_nanos())
+                .ok_or_else(|| der::Error::from(ErrorKind::DateTime))?;
+        let mut text = time.format("%Y%m%d%H%M%S").to_string();
+        if since_epoch.subsec_nanos() > 0 {
+            let fraction = format!("{:09}", since_epoch.subsec_nanos());
+            text.push('.');
+            text.push_str(fraction.trim_end_matches('0'));
+        }
+        text.push('Z');
+        Ok(Self { text, since_epoch })
+    }
+
+    pub fn to_unix_duration(&self) -> Duration {
+        self.since_epoch
+    }
+}
+
+/// Parse `YYYYMMDDHHMMSS[.f]Z` in UTC, with up to nine fraction digits
+fn parse_gen_time(text: &str) -> Option<Duration> {
+    let text = text.strip_suffix('Z')?;
+    let (whole, fraction) = match text.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (text, None),
+    };
+    if whole.len() != 14 || !whole.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let field = |range: std::ops::Range<usize>| whole[range].parse::<u32>().ok();
+    let time = NaiveDate::from_ymd_opt(field(0..4)? as i32, field(4..6)?, field(6..8)?)?
+        .and_hms_opt(field(8..10)?, field(10..12)?, field(12..14)?)?
+        .and_utc();
+    let nanos = match fraction {
+        Some(f) if (1..=9).contains(&f.len()) && f.bytes().all(|b| b.is_ascii_digit()) => {
+            format!("{:0<9}", f).parse::<u32>().ok()?
+        }
+        Some(_) => return None,
+        None => 0,
+    };
+    let secs = u64::try_from(time.timestamp()).ok()?;
+    Some(Duration::new(secs, nanos))
+}
+
+impl FixedTag for GenTime {
+    const TAG: Tag = Tag::GeneralizedTime;
+}
+
+impl<'a> DecodeValue<'a> for GenTime {
+    fn decode_value<R: Reader<'a>>(reader: &mut R, header: Header) -> der::Result<Self> {
+        let bytes = reader.read_vec(header.length)?;
+        let text = String::from_utf8(bytes).map_err(|_| der::Error::from(ErrorKind::DateTime))?;
+        let since_epoch =
+            parse_gen_time(&text).ok_or_else(|| der::Error::from(ErrorKind::DateTime))?;
+        Ok(Self { text, since_epoch })
+    }
+}
+
+impl EncodeValue for GenTime {
+    fn value_len(&self) -> der::Result<Length> {
+        Length::try_from(self.text.len())
+    }
+
+    fn encode_value(&self, writer: &mut impl Writer) -> der::Result<()> {
+        writer.write(self.text.as_bytes())
+    }
+}
+
+pub fn sha256_algorithm() -> AlgorithmIdentifierOwned {
+    AlgorithmIdentifierOwned {
+        oid: OID_SHA256,
+        parameters: None,
+    }
+}
@@ -0,0 +1,534 @@
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use cms::cert::CertificateChoices;
+use cms::content_info::ContentInfo;
+use cms::signed_data::{SignedData, SignerIdentifier, SignerInfo};
+use der::asn1::{ObjectIdentifier, OctetString};
+use der::{Decode, DecodePem, Encode};
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::hash::record_hash_hex;
+use phoenix_evidence::model::{ChainTxRef, EvidenceRecord};
+use reqwest::Client;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use x509_cert::Certificate;
+
+pub mod asn1;
+
+use asn1::{
+    MessageImprint, TimeStampReq, TimeStampResp, TstInfo, OID_ATTR_CONTENT_TYPE,
+    OID_ATTR_MESSAGE_DIGEST, OID_CT_TST_INFO, OID_ECDSA_WITH_SHA256, OID_EC_PUBLIC_KEY,
+    OID_PRIME256V1, OID_RSA_ENCRYPTION, OID_SHA256, OID_SHA256_WITH_RSA, OID_SIGNED_DATA,
+    STATUS_GRANTED, STATUS_GRANTED_WITH_MODS,
+};
+
+pub const TIMESTAMP_QUERY_MIME: &str = "application/timestamp-query";
+pub const TIMESTAMP_REPLY_MIME: &str = "application/timestamp-reply";
+
+const OID_SUBJECT_KEY_IDENTIFIER: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.14");
+
+#[derive(Clone)]
+pub struct TsaProviderStub;
+
+#[async_trait]
+impl AnchorProvider for TsaProviderStub {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        Ok(ChainTxRef {
+            network: "rfc3161".to_string(),
+            chain: "stub-tsa".to_string(),
+            tx_id: format!("fake:{}", &evidence.digest.hex),
+            confirmed: false,
+            timestamp: Some(Utc::now()),
+        })
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        let mut t = tx.clone();
+        t.confirmed = true;
+        Ok(t)
+    }
+
+    fn network(&self) -> &str {
+        "rfc3161"
+    }
+}
+
+/// RFC 3161 trusted timestamping provider.
+///
+/// Timestamps the evidence record hash. The returned `ChainTxRef::tx_id` is the
+/// record hash hex; the DER token is kept in `token_dir` as `<tx_id>.tsr` and
+/// can be re-verified offline with [`verify_token`].
+#[derive(Debug, Clone)]
+pub struct TsaProvider {
+    pub client: Client,
+    pub endpoint: String,
+    /// Label stored as `ChainTxRef::chain`, e.g. the TSA operator name
+    pub name: String,
+    /// Where tokens are stored, one file per anchored record hash
+    pub token_dir: PathBuf,
+    /// DER certificates the signer must match or be issued by. `confirm`
+    /// refuses every token until at least one is configured.
+    pub trusted_certs: Vec<Vec<u8>>,
+}
+
+impl TsaProvider {
+    pub fn new(endpoint: String, name: String, token_dir: PathBuf) -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            name,
+            token_dir,
+            trusted_certs: Vec::new(),
+        })
+    }
+
+    /// Trust a TSA signing certificate, or the CA certificate that issues
+    /// them (DER)
+    pub fn with_trusted_certificate(mut self, cert_der: Vec<u8>) -> Self {
+        self.trusted_certs.push(cert_der);
+        self
+    }
+
+    /// The stored token for `tx_id`
+    pub async fn token(&self, tx_id: &str) -> Result<Vec<u8>, AnchorError> {
+        let path = token_path(&self.token_dir, tx_id)?;
+        tokio::fs::read(&path).await.map_err(|e| {
+            AnchorError::Invalid(format!(
+                "no token for {} in {}: {}",
+                tx_id,
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    async fn request_token(&self, digest: &[u8], nonce: u64) -> Result<Vec<u8>, AnchorError> {
+        let request = build_request(digest, nonce)
+            .to_der()
+            .map_err(|e| AnchorError::Invalid(format!("Failed to encode request: {}", e)))?;
+
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .header(reqwest::header::CONTENT_TYPE, TIMESTAMP_QUERY_MIME)
+            .header(reqwest::header::ACCEPT, TIMESTAMP_REPLY_MIME)
+            .body(request)
+            .send()
+            .await
+            .map_err(|e| AnchorError::Network(format!("HTTP request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AnchorError::Network(format!(
+                "HTTP error: {}",
+                response.status()
+            )));
+        }
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| AnchorError::Network(format!("Failed to read response: {}", e)))?;
+
+        let resp = TimeStampResp::from_der(&body)
+            .map_err(|e| AnchorError::Provider(format!("Failed to parse TimeStampResp: {}", e)))?;
+
+        if resp.status.status != STATUS_GRANTED && resp.status.status != STATUS_GRANTED_WITH_MODS {
+            let reason = resp
+                .status
+                .status_string
+                .map(|s| s.join("; "))
+                .unwrap_or_default();
+            return Err(AnchorError::Provider(format!(
+                "TSA rejected request (status {}): {}",
+                resp.status.status, reason
+            )));
+        }
+
+        let token = resp
+            .time_stamp_token
+            .ok_or_else(|| AnchorError::Provider("TSA response missing token".to_string()))?;
+
+        token
+            .to_der()
+            .map_err(|e| AnchorError::Provider(format!("Failed to encode token: {}", e)))
+    }
+}
+
+#[async_trait]
+impl AnchorProvider for TsaProvider {
+    async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError> {
+        decode_sha256_hex(&evidence.digest.hex)?;
+        // Timestamp the record hash, as the chain providers anchor it, so the
+        // token also covers the evidence id, mime type and metadata
+        let record_hash = record_hash_hex(evidence);
+        let digest = decode_sha256_hex(&record_hash)?;
+        let nonce = rand::random::<u64>() >> 1;
+
+        let token = self.request_token(&digest, nonce).await?;
+        let tst_info = parse_tst_info(&token)?;
+
+        if tst_info.message_imprint.hashed_message.as_bytes() != digest.as_slice() {
+            return Err(AnchorError::Provider(
+                "TSA token imprint does not match the submitted record hash".to_string(),
+            ));
+        }
+        if tst_info.nonce != Some(nonce) {
+            return Err(AnchorError::Provider(
+                "TSA token nonce does not match the request".to_string(),
+            ));
+        }
+
+        let path = token_path(&self.token_dir, &record_hash)?;
+        tokio::fs::write(&path, &token).await.map_err(|e| {
+            AnchorError::Provider(format!("failed to store token {}: {}", path.display(), e))
+        })?;
+
+        tracing::info!(
+            tsa = %self.name,
+            evidence_id = %evidence.id,
+            record_hash = %record_hash,
+            token_len = token.len(),
+            "Timestamped evidence with RFC 3161 TSA"
+        );
+
+        Ok(ChainTxRef {
+            network: "rfc3161".to_string(),
+            chain: self.name.clone(),
+            tx_id: record_hash,
+            confirmed: false,
+            timestamp: gen_time_utc(&tst_info),
+        })
+    }
+
+    async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError> {
+        let digest = decode_sha256_hex(&tx.tx_id)?;
+        let token = self.token(&tx.tx_id).await?;
+
+        let tst_info = verify_token(&token, &self.trusted_certs)?;
+        if tst_info.message_imprint.hashed_message.as_bytes() != digest.as_slice() {
+            return Err(AnchorError::Invalid(
+                "token imprint does not match the anchored record hash".to_string(),
+            ));
+        }
+
+        let mut confirmed_tx = tx.clone();
+        confirmed_tx.confirmed = true;
+        confirmed_tx.timestamp = gen_time_utc(&tst_info).or(tx.timestamp);
+
+        tracing::info!(
+            tsa = %self.name,
+            record_hash = %tx.tx_id,
+            "RFC 3161 token verified"
+        );
+
+        Ok(confirmed_tx)
+    }
+
+    fn network(&self) -> &str {
+        "rfc3161"
+    }
+}
+
+/// Build a SHA-256 TimeStampReq asking the TSA to include its certificate
+pub fn build_request(digest: &[u8], nonce: u64) -> TimeStampReq {
+    TimeStampReq {
+        version: 1,
+        message_imprint: MessageImprint {
+            hash_algorithm: asn1::sha256_algorithm(),
+            hashed_message: OctetString::new(digest).expect("digest fits in an OCTET STRING"),
+        },
+        req_policy: None,
+        nonce: Some(nonce),
+        cert_req: true,
+    }
+}
+
+/// Read a PEM or DER certificate file, as DER for
+/// [`TsaProvider::with_trusted_certificate`]
+pub fn read_certificate(path: &Path) -> Result<Vec<u8>, String> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| format!("failed to read certificate {}: {}", path.display(), e))?;
+    let cert = if bytes.starts_with(b"-----BEGIN") {
+        Certificate::from_pem(&bytes)
+    } else {
+        Certificate::from_der(&bytes)
+    }
+    .map_err(|e| format!("{} is not a certificate: {}", path.display(), e))?;
+    cert.to_der()
+        .map_err(|e| format!("failed to encode certificate {}: {}", path.display(), e))
+}
+
+/// Where the token for record hash `tx_id` is stored under `token_dir`
+pub fn token_path(token_dir: &Path, tx_id: &str) -> Result<PathBuf, AnchorError> {
+    // Only a well-formed hash may name a file
+    decode_sha256_hex(tx_id)?;
+    Ok(token_dir.join(format!("{}.tsr", tx_id)))
+}
+
+/// Decode the TSTInfo from a token without checking its signature
+pub fn parse_tst_info(token_der: &[u8]) -> Result<TstInfo, AnchorError> {
+    let signed_data = parse_signed_data(token_der)?;
+    let (tst_info, _) = decode_encapsulated_tst_info(&signed_data)?;
+    Ok(tst_info)
+}
+
+/// Verify a timestamp token's CMS signature and return its TSTInfo.
+///
+/// Checks the content-type and message-digest signed attributes, locates the signer
+/// certificate in the token and verifies an ECDSA P-256 or RSA PKCS#1 v1.5 SHA-256
+/// signature. The signer certificate must be valid at the token's time and be one of
+/// `trusted_certs` or directly issued by one of them; an empty list trusts nothing.
+pub fn verify_token(token_der: &[u8], trusted_certs: &[Vec<u8>]) -> Result<TstInfo, AnchorError> {
+    let signed_data = parse_signed_data(token_der)?;
+    let (tst_info, econtent) = decode_encapsulated_tst_info(&signed_data)?;
+
+    let signer = signed_data
+        .signer_infos
+        .0
+        .iter()
+        .next()
+        .ok_or_else(|| AnchorError::Invalid("token has no signer".to_string()))?;
+
+    if signer.digest_alg.oid != OID_SHA256 {
+        return Err(AnchorError::Invalid(format!(
+            "unsupported digest algorithm {}",
+            signer.digest_alg.oid
+        )));
+    }
+
+    let signed_attrs = signer
+        .signed_attrs
+        .as_ref()
+        .ok_or_else(|| AnchorError::Invalid("token has no signed attributes".to_string()))?;
+
+    let content_type: ObjectIdentifier = signed_attribute(signer, OID_ATTR_CONTENT_TYPE)?;
+    if content_type != OID_CT_TST_INFO {
+        return Err(AnchorError::Invalid(
+            "content-type attribute is not TSTInfo".to_string(),
+        ));
+    }
+
+    let message_digest: OctetString = signed_attribute(signer, OID_ATTR_MESSAGE_DIGEST)?;
+    if message_digest.as_bytes() != Sha256::digest(&econtent).as_slice() {
+        return Err(AnchorError::Invalid(
+            "message-digest attribute does not match TSTInfo".to_string(),
+        ));
+    }
+
+    let cert = find_signer_certificate(&signed_data, &signer.sid)?;
+    check_signer_trusted(cert, &tst_info, trusted_certs)?;
+
+    let signed_bytes = signed_attrs
+        .to_der()
+        .map_err(|e| AnchorError::Invalid(format!("Failed to encode signed attributes: {}", e)))?;
+    verify_signature(cert, &signed_bytes, signer.signature.as_bytes())?;
+
+    Ok(tst_info)
+}
+
+/// Require `cert` to be valid when the token was issued, and pinned in or
+/// issued by one of `trusted_certs`
+fn check_signer_trusted(
+    cert: &Certificate,
+    tst_info: &TstInfo,
+    trusted_certs: &[Vec<u8>],
+) -> Result<(), AnchorError> {
+    if trusted_certs.is_empty() {
+        return Err(AnchorError::Invalid(
+            "no trusted TSA certificates configured".to_string(),
+        ));
+    }
+
+    let validity = &cert.tbs_certificate.validity;
+    let gen_time = tst_info.gen_time.to_unix_duration();
+    if gen_time < validity.not_before.to_unix_duration()
+        || gen_time > validity.not_after.to_unix_duration()
+    {
+        return Err(AnchorError::Invalid(
+            "token was issued outside its signer certificate's validity".to_string(),
+        ));
+    }
+
+    let cert_der = cert
+        .to_der()
+        .map_err(|e| AnchorError::Invalid(format!("Failed to encode certificate: {}", e)))?;
+    if trusted_certs.contains(&cert_der) {
+        return Ok(());
+    }
+    let tbs_der = cert
+        .tbs_certificate
+        .to_der()
+        .map_err(|e| AnchorError::Invalid(format!("Failed to encode certificate: {}", e)))?;
+    let sha256_signed =
+        [OID_ECDSA_WITH_SHA256, OID_SHA256_WITH_RSA].contains(&cert.signature_algorithm.oid);
+    let issued_by_trusted = sha256_signed
+        && trusted_certs
+            .iter()
+            .filter_map(|der| Certificate::from_der(der).ok())
+            .filter(|issuer| issuer.tbs_certificate.subject == cert.tbs_certificate.issuer)
+            .any(|issuer| verify_signature(&issuer, &tbs_der, cert.signature.raw_bytes()).is_ok());
+    if issued_by_trusted {
+        Ok(())
+    } else {
+        Err(AnchorError::Invalid(
+            "token signer is not a trusted TSA certificate".to_string(),
+        ))
+    }
+}
+
+fn parse_signed_data(token_der: &[u8]) -> Result<SignedData, AnchorError> {
+    let content_info = ContentInfo::from_der(token_der)
+        .map_err(|e| AnchorError::Invalid(format!("token is not a ContentInfo: {}", e)))?;
+    if content_info.content_type != OID_SIGNED_DATA {
+        return Err(AnchorError::Invalid("token is not SignedData".to_string()));
+    }
+    content_info
+        .content
+        .decode_as::<SignedData>()
+        .map_err(|e| AnchorError::Invalid(format!("Failed to parse SignedData: {}", e)))
+}
+
+fn decode_encapsulated_tst_info(
+    signed_data: &SignedData,
+) -> Result<(TstInfo, Vec<u8>), AnchorError> {
+    let encap = &signed_data.encap_content_info;
+    if encap.econtent_type != OID_CT_TST_INFO {
+        return Err(AnchorError::Invalid(
+            "token does not encapsulate TSTInfo".to_string(),
+        ));
+    }
+    let econtent = encap
+        .econtent
+        .as_ref()
+        .ok_or_else(|| AnchorError::Invalid("token has no TSTInfo content".to_string()))?
+        .decode_as::<OctetString>()
+        .map_err(|e| AnchorError::Invalid(format!("Failed to read TSTInfo content: {}", e)))?
+        .into_bytes();
+    let tst_info = TstInfo::from_der(&econtent)
+        .map_err(|e| AnchorError::Invalid(format!("Failed to parse TSTInfo: {}", e)))?;
+    Ok((tst_info, econtent))
+}
+
+fn signed_attribute<T: der::DecodeOwned>(
+    signer: &SignerInfo,
+    oid: ObjectIdentifier,
+) -> Result<T, AnchorError> {
+    let value = signer
+        .signed_attrs
+        .as_ref()
+        .and_then(|attrs| attrs.iter().find(|a| a.oid == oid))
+        .and_then(|attr| attr.values.iter().next())
+        .ok_or_else(|| AnchorError::Invalid(format!("missing signed attribute {}", oid)))?;
+
+    value
+        .to_der()
+        .and_then(|bytes| T::from_der(&bytes))
+        .map_err(|e| AnchorError::Invalid(format!("malformed signed attribute {}: {}", oid, e)))
+}
+
+fn find_signer_certificate<'a>(
+    signed_data: &'a SignedData,
+    sid: &SignerIdentifier,
+) -> Result<&'a Certificate, AnchorError> {
+    let certs = signed_data
+        .certificates
+        .as_ref()
+        .ok_or_else(|| AnchorError::Invalid("token does not include certificates".to_string()))?;
+
+    certs
+        .0
+        .iter()
+        .filter_map(|choice| match choice {
+            CertificateChoices::Certificate(cert) => Some(cert),
+            _ => None,
+        })
+        .find(|cert| match sid {
+            SignerIdentifier::IssuerAndSerialNumber(ias) => {
+                cert.tbs_certificate.issuer == ias.issuer
+                    && cert.tbs_certificate.serial_number == ias.serial_number
+            }
+            SignerIdentifier::SubjectKeyIdentifier(ski) => cert
+                .tbs_certificate
+                .extensions
+                .iter()
+                .flatten()
+                .filter(|ext| ext.extn_id == OID_SUBJECT_KEY_IDENTIFIER)
+                .any(|ext| {
+                    OctetString::from_der(ext.extn_value.as_bytes())
+                        .map(|v| v == ski.0)
+                        .unwrap_or(false)
+                }),
+        })
+        .ok_or_else(|| AnchorError::Invalid("signer certificate not found in token".to_string()))
+}
+
+fn verify_signature(
+    cert: &Certificate,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), AnchorError> {
+    let spki = &cert.tbs_certificate.subject_public_key_info;
+    let key_bytes = spki.subject_public_key.raw_bytes();
+
+    if spki.algorithm.oid == OID_EC_PUBLIC_KEY {
+        use p256::ecdsa::signature::Verifier;
+
+        let curve = spki
+            .algorithm
+            .parameters
+            .as_ref()
+            .and_then(|p| p.decode_as::<ObjectIdentifier>().ok());
+        if curve != Some(OID_PRIME256V1) {
+            return Err(AnchorError::Invalid(format!(
+                "unsupported TSA key curve {}",
+                curve.map_or_else(|| "(none)".to_string(), |oid| oid.to_string())
+            )));
+        }
+        let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(key_bytes)
+            .map_err(|e| AnchorError::Invalid(format!("invalid P-256 public key: {}", e)))?;
+        let sig = p256::ecdsa::Signature::from_der(signature)
+            .map_err(|e| AnchorError::Invalid(format!("invalid ECDSA signature: {}", e)))?;
+        key.verify(message, &sig)
+            .map_err(|_| AnchorError::Invalid("token signature verification failed".to_string()))
+    } else if spki.algorithm.oid == OID_RSA_ENCRYPTION {
+        use rsa::pkcs1::DecodeRsaPublicKey;
+
+        let key = rsa::RsaPublicKey::from_pkcs1_der(key_bytes)
+            .map_err(|e| AnchorError::Invalid(format!("invalid RSA public key: {}", e)))?;
+        key.verify(
+            rsa::Pkcs1v15Sign::new::<Sha256>(),
+            &Sha256::digest(message),
+            signature,
+        )
+        .map_err(|_| AnchorError::Invalid("token signature verification failed".to_string()))
+    } else {
+        Err(AnchorError::Invalid(format!(
+            "unsupported TSA key algorithm {}",
+            spki.algorithm.oid
+        )))
+    }
+}
+
+fn decode_sha256_hex(digest_hex: &str) -> Result<Vec<u8>, AnchorError> {
+    let digest = hex::decode(digest_hex)
+        .map_err(|e| AnchorError::Invalid(format!("digest is not hex: {}", e)))?;
+    if digest.len() != 32 {
+        return Err(AnchorError::Invalid(format!(
+            "expected a 32-byte SHA-256 digest, got {} bytes",
+            digest.len()
+        )));
+    }
+    Ok(digest)
+}
+
+fn gen_time_utc(tst_info: &TstInfo) -> Option<DateTime<Utc>> {
+    let since_epoch = tst_info.gen_time.to_unix_duration();
+    Utc.timestamp_opt(since_epoch.as_secs() as i64, since_epoch.subsec_nanos())
+        .single()
+}
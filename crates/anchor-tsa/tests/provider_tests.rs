@@ -0,0 +1,537 @@
+use anchor_tsa::asn1::{
+    sha256_algorithm, GenTime, PkiStatusInfo, TimeStampReq, TimeStampResp, TstInfo,
+    OID_ATTR_CONTENT_TYPE, OID_ATTR_MESSAGE_DIGEST, OID_CT_TST_INFO, OID_ECDSA_WITH_SHA256,
+    OID_EC_PUBLIC_KEY, OID_PRIME256V1, OID_SIGNED_DATA,
+};
+use anchor_tsa::{
+    build_request, read_certificate, token_path, verify_token, TsaProvider, TsaProviderStub,
+};
+use axum::{body::Bytes, extract::State, http::header, response::IntoResponse, routing::post};
+use chrono::Utc;
+use cms::cert::{CertificateChoices, IssuerAndSerialNumber};
+use cms::content_info::{CmsVersion, ContentInfo};
+use cms::signed_data::{
+    CertificateSet, EncapsulatedContentInfo, SignedData, SignerIdentifier, SignerInfo, SignerInfos,
+};
+use der::asn1::{Any, BitString, Int, ObjectIdentifier, OctetString, SetOfVec};
+use der::{Decode, Encode};
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use phoenix_evidence::anchor::{AnchorError, AnchorProvider};
+use phoenix_evidence::hash::record_hash_hex;
+use phoenix_evidence::model::{ChainTxRef, DigestAlgo, EvidenceDigest, EvidenceRecord};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use x509_cert::attr::Attribute;
+use x509_cert::name::Name;
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned};
+use x509_cert::time::Validity;
+use x509_cert::{Certificate, TbsCertificate, Version};
+
+/// In-process RFC 3161 TSA signing with a fixed P-256 key
+struct StubTsa {
+    key: SigningKey,
+    cert: Certificate,
+    reject: bool,
+    wrong_imprint: bool,
+    /// Issue genTime with milliseconds, as many public TSAs do
+    fractional_time: bool,
+}
+
+impl StubTsa {
+    fn new(seed: u8) -> Self {
+        let key = SigningKey::from_slice(&[seed; 32]).unwrap();
+        let name = Name::from_str("CN=Phoenix Test TSA").unwrap();
+        let cert = issue_certificate(seed, &key, name.clone(), &key, name);
+        Self::with_certificate(key, cert)
+    }
+
+    /// A TSA whose certificate is issued by the CA with key seed `ca_seed`;
+    /// returns the CA certificate (DER) alongside
+    fn issued_by(seed: u8, ca_seed: u8) -> (Self, Vec<u8>) {
+        let ca_key = SigningKey::from_slice(&[ca_seed; 32]).unwrap();
+        let ca_name = Name::from_str("CN=Phoenix Test CA").unwrap();
+        let ca_cert =
+            issue_certificate(ca_seed, &ca_key, ca_name.clone(), &ca_key, ca_name.clone());
+
+        let key = SigningKey::from_slice(&[seed; 32]).unwrap();
+        let name = Name::from_str("CN=Phoenix Test TSA").unwrap();
+        let cert = issue_certificate(seed, &key, name, &ca_key, ca_name);
+        (Self::with_certificate(key, cert), ca_cert.to_der().unwrap())
+    }
+
+    fn with_certificate(key: SigningKey, cert: Certificate) -> Self {
+        Self {
+            key,
+            cert,
+            reject: false,
+            wrong_imprint: false,
+            fractional_time: false,
+        }
+    }
+
+    fn cert_der(&self) -> Vec<u8> {
+        self.cert.to_der().unwrap()
+    }
+
+    fn respond(&self, req: &TimeStampReq) -> TimeStampResp {
+        if self.reject {
+            return TimeStampResp {
+                status: PkiStatusInfo {
+                    status: 2,
+                    status_string: Some(vec!["policy not supported".to_string()]),
+                    fail_info: None,
+                },
+                time_stamp_token: None,
+            };
+        }
+
+        let mut imprint = req.message_imprint.clone();
+        if self.wrong_imprint {
+            imprint.hashed_message = OctetString::new([0u8; 32]).unwrap();
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let mut gen_time = Duration::from_secs(now.as_secs());
+        if self.fractional_time {
+            gen_time += Duration::from_millis(250);
+        }
+        let tst_info = TstInfo {
+            version: 1,
+            policy: ObjectIdentifier::new_unwrap("1.3.6.1.4.1.99999.1"),
+            message_imprint: imprint,
+            serial_number: Int::new(&[0x2a]).unwrap(),
+            gen_time: GenTime::from_unix_duration(gen_time).unwrap(),
+            accuracy: None,
+            ordering: false,
+            nonce: req.nonce,
+            tsa: None,
+            extensions: None,
+        };
+        let tst_der = tst_info.to_der().unwrap();
+
+        let signed_attrs = SetOfVec::try_from(vec![
+            Attribute {
+                oid: OID_ATTR_CONTENT_TYPE,
+                values: SetOfVec::try_from(vec![Any::encode_from(&OID_CT_TST_INFO).unwrap()])
+                    .unwrap(),
+            },
+            Attribute {
+                oid: OID_ATTR_MESSAGE_DIGEST,
+                values: SetOfVec::try_from(vec![Any::encode_from(
+                    &OctetString::new(Sha256::digest(&tst_der).to_vec()).unwrap(),
+                )
+                .unwrap()])
+                .unwrap(),
+            },
+        ])
+        .unwrap();
+        let sig: Signature = self.key.sign(&signed_attrs.to_der().unwrap());
+
+        let signer = SignerInfo {
+            version: CmsVersion::V1,
+            sid: SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+                issuer: self.cert.tbs_certificate.issuer.clone(),
+                serial_number: self.cert.tbs_certificate.serial_number.clone(),
+            }),
+            digest_alg: sha256_algorithm(),
+            signed_attrs: Some(signed_attrs),
+            signature_algorithm: ecdsa_sha256(),
+            signature: OctetString::new(sig.to_der().as_bytes()).unwrap(),
+            unsigned_attrs: None,
+        };
+
+        let signed_data = SignedData {
+            version: CmsVersion::V3,
+            digest_algorithms: SetOfVec::try_from(vec![sha256_algorithm()]).unwrap(),
+            encap_content_info: EncapsulatedContentInfo {
+                econtent_type: OID_CT_TST_INFO,
+                econtent: Some(Any::encode_from(&OctetString::new(tst_der).unwrap()).unwrap()),
+            },
+            certificates: Some(CertificateSet(
+                SetOfVec::try_from(vec![CertificateChoices::Certificate(self.cert.clone())])
+                    .unwrap(),
+            )),
+            crls: None,
+            signer_infos: SignerInfos(SetOfVec::try_from(vec![signer]).unwrap()),
+        };
+
+        TimeStampResp {
+            status: PkiStatusInfo {
+                status: 0,
+                status_string: None,
+                fail_info: None,
+            },
+            time_stamp_token: Some(ContentInfo {
+                content_type: OID_SIGNED_DATA,
+                content: Any::encode_from(&signed_data).unwrap(),
+            }),
+        }
+    }
+}
+
+/// A one-hour P-256 certificate for `subject_key`, signed by `issuer_key`
+fn issue_certificate(
+    serial: u8,
+    subject_key: &SigningKey,
+    subject: Name,
+    issuer_key: &SigningKey,
+    issuer: Name,
+) -> Certificate {
+    let point = subject_key.verifying_key().to_encoded_point(false);
+    let tbs = TbsCertificate {
+        version: Version::V3,
+        serial_number: SerialNumber::new(&[serial]).unwrap(),
+        signature: ecdsa_sha256(),
+        issuer,
+        validity: Validity::from_now(Duration::from_secs(3600)).unwrap(),
+        subject,
+        subject_public_key_info: SubjectPublicKeyInfoOwned {
+            algorithm: AlgorithmIdentifierOwned {
+                oid: OID_EC_PUBLIC_KEY,
+                parameters: Some(Any::encode_from(&OID_PRIME256V1).unwrap()),
+            },
+            subject_public_key: BitString::from_bytes(point.as_bytes()).unwrap(),
+        },
+        issuer_unique_id: None,
+        subject_unique_id: None,
+        extensions: None,
+    };
+    let sig: Signature = issuer_key.sign(&tbs.to_der().unwrap());
+    Certificate {
+        tbs_certificate: tbs,
+        signature_algorithm: ecdsa_sha256(),
+        signature: BitString::from_bytes(sig.to_der().as_bytes()).unwrap(),
+    }
+}
+
+fn ecdsa_sha256() -> AlgorithmIdentifierOwned {
+    AlgorithmIdentifierOwned {
+        oid: OID_ECDSA_WITH_SHA256,
+        parameters: None,
+    }
+}
+
+async fn tsa_handler(State(tsa): State<Arc<Mutex<StubTsa>>>, body: Bytes) -> impl IntoResponse {
+    let req = TimeStampReq::from_der(&body).unwrap();
+    let resp = tsa.lock().unwrap().respond(&req);
+    (
+        [(header::CONTENT_TYPE, "application/timestamp-reply")],
+        resp.to_der().unwrap(),
+    )
+}
+
+async fn spawn_stub_tsa(tsa: Arc<Mutex<StubTsa>>) -> String {
+    let app = axum::Router::new()
+        .route("/", post(tsa_handler))
+        .with_state(tsa);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn sample_evidence() -> EvidenceRecord {
+    EvidenceRecord {
+        id: "test-evidence-tsa".to_string(),
+        created_at: Utc::now(),
+        digest: EvidenceDigest {
+            algo: DigestAlgo::Sha256,
+            hex: phoenix_evidence::hash::sha256_hex(b"engagement report"),
+        },
+        payload_mime: None,
+        metadata: json!({}),
+    }
+}
+
+#[tokio::test]
+async fn test_tsa_provider_stub() {
+    let provider = TsaProviderStub;
+    let evidence = sample_evidence();
+
+    let tx_ref = provider.anchor(&evidence).await.unwrap();
+    assert_eq!(tx_ref.network, "rfc3161");
+    assert_eq!(tx_ref.tx_id, format!("fake:{}", evidence.digest.hex));
+    assert!(!tx_ref.confirmed);
+
+    let confirmed = provider.confirm(&tx_ref).await.unwrap();
+    assert!(confirmed.confirmed);
+}
+
+#[test]
+fn test_build_request_roundtrip() {
+    let digest = Sha256::digest(b"payload");
+    let req = build_request(&digest, 42);
+    let der = req.to_der().unwrap();
+
+    let decoded = TimeStampReq::from_der(&der).unwrap();
+    assert_eq!(decoded.version, 1);
+    assert_eq!(decoded.nonce, Some(42));
+    assert!(decoded.cert_req);
+    assert_eq!(
+        decoded.message_imprint.hashed_message.as_bytes(),
+        digest.as_slice()
+    );
+}
+
+#[test]
+fn test_token_path_requires_record_hash() {
+    let dir = std::path::Path::new("/var/lib/tsa");
+    let hash = phoenix_evidence::hash::sha256_hex(b"record");
+    assert_eq!(
+        token_path(dir, &hash).unwrap(),
+        dir.join(format!("{}.tsr", hash))
+    );
+
+    assert!(matches!(
+        token_path(dir, "../../etc/passwd"),
+        Err(AnchorError::Invalid(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_tsa_provider_anchor_and_confirm() {
+    let tsa = Arc::new(Mutex::new(StubTsa::new(7)));
+    let endpoint = spawn_stub_tsa(tsa.clone()).await;
+    let dir = tempfile::tempdir().unwrap();
+    let trusted = tsa.lock().unwrap().cert_der();
+    let provider = TsaProvider::new(endpoint, "stub-tsa".to_string(), dir.path().to_path_buf())
+        .unwrap()
+        .with_trusted_certificate(trusted.clone());
+    let evidence = sample_evidence();
+
+    let tx_ref = provider.anchor(&evidence).await.unwrap();
+    assert_eq!(tx_ref.network, "rfc3161");
+    assert_eq!(tx_ref.chain, "stub-tsa");
+    assert_eq!(tx_ref.tx_id, record_hash_hex(&evidence));
+    assert!(!tx_ref.confirmed);
+    assert!(tx_ref.timestamp.is_some());
+
+    let confirmed = provider.confirm(&tx_ref).await.unwrap();
+    assert!(confirmed.confirmed);
+
+    // The stored proof verifies offline as well, over the record hash the
+    // chain providers anchor
+    let token = std::fs::read(token_path(dir.path(), &tx_ref.tx_id).unwrap()).unwrap();
+    let tst_info = verify_token(&token, &[trusted]).unwrap();
+    assert_eq!(
+        hex::encode(tst_info.message_imprint.hashed_message.as_bytes()),
+        record_hash_hex(&evidence)
+    );
+}
+
+#[test]
+fn test_read_certificate_pem_or_der() {
+    use der::{pem::LineEnding, EncodePem};
+
+    let cert = &StubTsa::new(7).cert;
+    let dir = tempfile::tempdir().unwrap();
+    let pem = dir.path().join("tsa.pem");
+    let der = dir.path().join("tsa.der");
+    std::fs::write(&pem, cert.to_pem(LineEnding::LF).unwrap()).unwrap();
+    std::fs::write(&der, cert.to_der().unwrap()).unwrap();
+
+    assert_eq!(read_certificate(&pem).unwrap(), cert.to_der().unwrap());
+    assert_eq!(read_certificate(&der).unwrap(), cert.to_der().unwrap());
+    std::fs::write(&der, b"not a certificate").unwrap();
+    assert!(read_certificate(&der).is_err());
+    assert!(read_certificate(&dir.path().join("missing.pem")).is_err());
+}
+
+#[test]
+fn test_gen_time_with_fractional_seconds() {
+    // 2024-02-29T23:59:59.5Z, as a TSA encodes it
+    let text = b"20240229235959.5Z";
+    let mut der = vec![0x18, text.len() as u8];
+    der.extend_from_slice(text);
+    let gen_time = GenTime::from_der(&der).unwrap();
+    assert_eq!(
+        gen_time.to_unix_duration(),
+        Duration::from_millis(1_709_251_199_500)
+    );
+    assert_eq!(gen_time.to_der().unwrap(), der);
+
+    let whole = GenTime::from_unix_duration(Duration::from_secs(1_709_251_199)).unwrap();
+    assert_eq!(&whole.to_der().unwrap()[2..], b"20240229235959Z");
+
+    for bad in [
+        &b"20240229235959.Z"[..],
+        b"20240229235959,5Z",
+        b"2024022923595Z",
+        b"20240230000000Z",
+        b"20240229235959.1234567890Z",
+    ] {
+        let mut der = vec![0x18, bad.len() as u8];
+        der.extend_from_slice(bad);
+        assert!(GenTime::from_der(&der).is_err(), "{:?}", bad);
+    }
+}
+
+#[tokio::test]
+async fn test_tsa_provider_accepts_fractional_gen_time() {
+    let tsa = Arc::new(Mutex::new(StubTsa::new(7)));
+    tsa.lock().unwrap().fractional_time = true;
+    let endpoint = spawn_stub_tsa(tsa.clone()).await;
+    let dir = tempfile::tempdir().unwrap();
+    let trusted = tsa.lock().unwrap().cert_der();
+    let provider = TsaProvider::new(endpoint, "stub-tsa".to_string(), dir.path().to_path_buf())
+        .unwrap()
+        .with_trusted_certificate(trusted);
+
+    let tx_ref = provider.anchor(&sample_evidence()).await.unwrap();
+    assert_eq!(tx_ref.timestamp.unwrap().timestamp_subsec_millis(), 250);
+    assert!(provider.confirm(&tx_ref).await.unwrap().confirmed);
+}
+
+#[tokio::test]
+async fn test_tsa_provider_requires_trusted_certificate() {
+    let tsa = Arc::new(Mutex::new(StubTsa::new(7)));
+    let endpoint = spawn_stub_tsa(tsa.clone()).await;
+    let dir = tempfile::tempdir().unwrap();
+    let trusted = tsa.lock().unwrap().cert_der();
+    let untrusted = StubTsa::new(9).cert_der();
+
+    // Nothing pinned: a self-signed token proves nothing
+    let unpinned = TsaProvider::new(
+        endpoint.clone(),
+        "stub-tsa".to_string(),
+        dir.path().to_path_buf(),
+    )
+    .unwrap();
+    let tx_ref = unpinned.anchor(&sample_evidence()).await.unwrap();
+    match unpinned.confirm(&tx_ref).await {
+        Err(AnchorError::Invalid(msg)) => assert!(msg.contains("no trusted TSA certificates")),
+        other => panic!("expected invalid, got {:?}", other),
+    }
+
+    let pinned = unpinned.clone().with_trusted_certificate(trusted);
+    assert!(pinned.confirm(&tx_ref).await.unwrap().confirmed);
+
+    let mispinned = unpinned.with_trusted_certificate(untrusted);
+    assert!(matches!(
+        mispinned.confirm(&tx_ref).await,
+        Err(AnchorError::Invalid(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_tsa_provider_trusts_issuing_ca() {
+    let (stub, ca_cert) = StubTsa::issued_by(7, 11);
+    let tsa = Arc::new(Mutex::new(stub));
+    let endpoint = spawn_stub_tsa(tsa).await;
+    let dir = tempfile::tempdir().unwrap();
+
+    let provider = TsaProvider::new(endpoint, "stub-tsa".to_string(), dir.path().to_path_buf())
+        .unwrap()
+        .with_trusted_certificate(ca_cert);
+    let tx_ref = provider.anchor(&sample_evidence()).await.unwrap();
+    assert!(provider.confirm(&tx_ref).await.unwrap().confirmed);
+
+    // A different CA did not issue the signer
+    let (_, other_ca) = StubTsa::issued_by(7, 12);
+    let wrong_ca = TsaProvider::new(
+        "http://127.0.0.1:1".to_string(),
+        "stub-tsa".to_string(),
+        dir.path().to_path_buf(),
+    )
+    .unwrap()
+    .with_trusted_certificate(other_ca);
+    assert!(matches!(
+        wrong_ca.confirm(&tx_ref).await,
+        Err(AnchorError::Invalid(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_tsa_provider_rejects_tampered_token() {
+    let tsa = Arc::new(Mutex::new(StubTsa::new(7)));
+    let endpoint = spawn_stub_tsa(tsa.clone()).await;
+    let dir = tempfile::tempdir().unwrap();
+    let trusted = tsa.lock().unwrap().cert_der();
+    let provider = TsaProvider::new(endpoint, "stub-tsa".to_string(), dir.path().to_path_buf())
+        .unwrap()
+        .with_trusted_certificate(trusted);
+    let evidence = sample_evidence();
+
+    let tx_ref = provider.anchor(&evidence).await.unwrap();
+    let path = token_path(dir.path(), &tx_ref.tx_id).unwrap();
+    let token = std::fs::read(&path).unwrap();
+
+    // A valid token filed under a different record hash is rejected
+    let other = phoenix_evidence::hash::sha256_hex(b"other");
+    std::fs::write(token_path(dir.path(), &other).unwrap(), &token).unwrap();
+    let swapped = ChainTxRef {
+        tx_id: other,
+        ..tx_ref.clone()
+    };
+    assert!(matches!(
+        provider.confirm(&swapped).await,
+        Err(AnchorError::Invalid(_))
+    ));
+
+    // Flip a bit in the final byte, which belongs to the signature
+    let mut tampered = token;
+    let last = tampered.len() - 1;
+    tampered[last] ^= 0x01;
+    std::fs::write(&path, &tampered).unwrap();
+    assert!(matches!(
+        provider.confirm(&tx_ref).await,
+        Err(AnchorError::Invalid(_))
+    ));
+
+    // And a missing token cannot confirm
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        provider.confirm(&tx_ref).await,
+        Err(AnchorError::Invalid(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_tsa_provider_rejection_and_imprint_mismatch() {
+    let tsa = Arc::new(Mutex::new(StubTsa::new(7)));
+    let endpoint = spawn_stub_tsa(tsa.clone()).await;
+    let dir = tempfile::tempdir().unwrap();
+    let provider =
+        TsaProvider::new(endpoint, "stub-tsa".to_string(), dir.path().to_path_buf()).unwrap();
+
+    tsa.lock().unwrap().reject = true;
+    match provider.anchor(&sample_evidence()).await {
+        Err(AnchorError::Provider(msg)) => assert!(msg.contains("policy not supported")),
+        other => panic!("expected provider error, got {:?}", other),
+    }
+
+    {
+        let mut stub = tsa.lock().unwrap();
+        stub.reject = false;
+        stub.wrong_imprint = true;
+    }
+    assert!(matches!(
+        provider.anchor(&sample_evidence()).await,
+        Err(AnchorError::Provider(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_tsa_provider_invalid_digest() {
+    let dir = tempfile::tempdir().unwrap();
+    let provider = TsaProvider::new(
+        "http://127.0.0.1:1".to_string(),
+        "tsa".to_string(),
+        dir.path().to_path_buf(),
+    )
+    .unwrap();
+    let mut evidence = sample_evidence();
+    evidence.digest.hex = "abcd".to_string();
+
+    assert!(matches!(
+        provider.anchor(&evidence).await,
+        Err(AnchorError::Invalid(_))
+    ));
+}
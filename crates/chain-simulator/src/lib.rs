@@ -0,0 +1,472 @@
+//! In-process JSON-RPC chain for integration tests.
+//!
+//! Serves the subset of Ethereum (`eth_*`) and Solana methods the anchor providers call,
+//! backed by one deterministic chain. Nothing happens on its own: tests submit
+//! transactions, mine blocks, and force reorgs or drops through [`ChainSimulator`].
+
+use axum::{extract::State, routing::post, Json, Router};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Clone)]
+pub struct SimulatorConfig {
+    /// Returned by `eth_chainId`
+    pub chain_id: u64,
+    /// Confirmations (including the containing block) at which a block is final
+    pub finality_depth: u64,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            chain_id: 128123,
+            finality_depth: 3,
+        }
+    }
+}
+
+/// Where a transaction currently sits from the simulator's point of view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxStatus {
+    Unknown,
+    Pending,
+    Included {
+        block: u64,
+        confirmations: u64,
+        finalized: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Block {
+    number: u64,
+    hash: String,
+    parent_hash: String,
+    txs: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct SimTx {
+    from: Option<String>,
+    failed: bool,
+}
+
+#[derive(Debug)]
+struct ChainState {
+    config: SimulatorConfig,
+    blocks: Vec<Block>,
+    mempool: Vec<String>,
+    txs: HashMap<String, SimTx>,
+    /// Bumped on every reorg so replacement blocks get new hashes
+    fork: u64,
+}
+
+type SharedState = Arc<Mutex<ChainState>>;
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+impl ChainState {
+    fn new(config: SimulatorConfig) -> Self {
+        let genesis = Block {
+            number: 0,
+            hash: format!("0x{}", sha256_hex(b"genesis")),
+            parent_hash: format!("0x{}", "0".repeat(64)),
+            txs: Vec::new(),
+        };
+        Self {
+            config,
+            blocks: vec![genesis],
+            mempool: Vec::new(),
+            txs: HashMap::new(),
+            fork: 0,
+        }
+    }
+
+    fn head(&self) -> u64 {
+        self.blocks.len() as u64 - 1
+    }
+
+    fn finalized_height(&self) -> u64 {
+        (self.head() + 1).saturating_sub(self.config.finality_depth.max(1))
+    }
+
+    fn submit(&mut self, tx_id: &str, from: Option<&str>) -> bool {
+        if self.txs.contains_key(tx_id) {
+            return false;
+        }
+        self.txs.insert(
+            tx_id.to_string(),
+            SimTx {
+                from: from.map(str::to_lowercase),
+                failed: false,
+            },
+        );
+        self.mempool.push(tx_id.to_string());
+        true
+    }
+
+    fn mine(&mut self) -> u64 {
+        let parent = self.blocks.last().expect("genesis block");
+        let number = parent.number + 1;
+        let txs = std::mem::take(&mut self.mempool);
+        let preimage = format!("{}:{}:{}:{}", number, parent.hash, self.fork, txs.join(","));
+        let block = Block {
+            number,
+            hash: format!("0x{}", sha256_hex(preimage.as_bytes())),
+            parent_hash: parent.hash.clone(),
+            txs,
+        };
+        self.blocks.push(block);
+        number
+    }
+
+    fn reorg(&mut self, depth: u64) {
+        let keep = self.blocks.len().saturating_sub(depth as usize).max(1);
+        let orphaned: Vec<String> = self
+            .blocks
+            .drain(keep..)
+            .flat_map(|block| block.txs)
+            .collect();
+        // Orphaned transactions go back ahead of anything submitted since
+        self.mempool.splice(0..0, orphaned);
+        self.fork += 1;
+    }
+
+    fn drop_tx(&mut self, tx_id: &str) -> bool {
+        let before = self.mempool.len();
+        self.mempool.retain(|id| id != tx_id);
+        let dropped = self.mempool.len() != before;
+        if dropped {
+            self.txs.remove(tx_id);
+        }
+        dropped
+    }
+
+    fn find_block(&self, tx_id: &str) -> Option<&Block> {
+        self.blocks
+            .iter()
+            .find(|block| block.txs.iter().any(|id| id == tx_id))
+    }
+
+    fn status(&self, tx_id: &str) -> TxStatus {
+        if !self.txs.contains_key(tx_id) {
+            return TxStatus::Unknown;
+        }
+        match self.find_block(tx_id) {
+            Some(block) => TxStatus::Included {
+                block: block.number,
+                confirmations: self.head() - block.number + 1,
+                finalized: block.number <= self.finalized_height(),
+            },
+            None => TxStatus::Pending,
+        }
+    }
+
+    fn nonce(&self, address: &str, include_pending: bool) -> u64 {
+        let address = address.to_lowercase();
+        let sent_by = |id: &String| {
+            self.txs
+                .get(id)
+                .and_then(|tx| tx.from.as_deref())
+                .is_some_and(|from| from == address)
+        };
+        let mined = self
+            .blocks
+            .iter()
+            .flat_map(|block| block.txs.iter())
+            .filter(|id| sent_by(id))
+            .count();
+        let pending = if include_pending {
+            self.mempool.iter().filter(|id| sent_by(id)).count()
+        } else {
+            0
+        };
+        (mined + pending) as u64
+    }
+
+    fn block_by_tag(&self, tag: &str) -> Option<&Block> {
+        let number = match tag {
+            "latest" | "pending" | "safe" => self.head(),
+            "earliest" => 0,
+            "finalized" => self.finalized_height(),
+            hex => u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok()?,
+        };
+        self.blocks.get(number as usize)
+    }
+}
+
+/// Handle to a running simulator; the server stops when this is dropped
+pub struct ChainSimulator {
+    state: SharedState,
+    endpoint: String,
+    server: JoinHandle<()>,
+}
+
+impl ChainSimulator {
+    pub async fn start(config: SimulatorConfig) -> std::io::Result<Self> {
+        let state = Arc::new(Mutex::new(ChainState::new(config)));
+        let app = Router::new()
+            .route("/", post(rpc_handler))
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let endpoint = format!("http://{}", listener.local_addr()?);
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        Ok(Self {
+            state,
+            endpoint,
+            server,
+        })
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut ChainState) -> T) -> T {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut state)
+    }
+
+    /// Put a transaction with a known id into the mempool.
+    ///
+    /// Useful for providers that derive the id locally rather than broadcasting.
+    /// Returns false if the id is already known.
+    pub fn submit(&self, tx_id: &str) -> bool {
+        self.with_state(|s| s.submit(tx_id, None))
+    }
+
+    /// Like [`submit`](Self::submit), counting towards `from`'s nonce
+    pub fn submit_from(&self, from: &str, tx_id: &str) -> bool {
+        self.with_state(|s| s.submit(tx_id, Some(from)))
+    }
+
+    /// Mine one block containing the whole mempool and return its number
+    pub fn mine(&self) -> u64 {
+        self.with_state(|s| s.mine())
+    }
+
+    pub fn mine_blocks(&self, count: u64) -> u64 {
+        self.with_state(|s| {
+            for _ in 0..count {
+                s.mine();
+            }
+            s.head()
+        })
+    }
+
+    /// Discard the newest `depth` blocks (never genesis); their transactions return
+    /// to the mempool and will be re-mined into blocks with different hashes.
+    pub fn reorg(&self, depth: u64) {
+        self.with_state(|s| s.reorg(depth))
+    }
+
+    /// Evict a pending transaction as if the mempool had dropped it
+    pub fn drop_tx(&self, tx_id: &str) -> bool {
+        self.with_state(|s| s.drop_tx(tx_id))
+    }
+
+    /// Make a transaction execute unsuccessfully once included
+    pub fn fail_tx(&self, tx_id: &str) -> bool {
+        self.with_state(|s| match s.txs.get_mut(tx_id) {
+            Some(tx) => {
+                tx.failed = true;
+                true
+            }
+            None => false,
+        })
+    }
+
+    pub fn head(&self) -> u64 {
+        self.with_state(|s| s.head())
+    }
+
+    pub fn finalized_height(&self) -> u64 {
+        self.with_state(|s| s.finalized_height())
+    }
+
+    pub fn status(&self, tx_id: &str) -> TxStatus {
+        self.with_state(|s| s.status(tx_id))
+    }
+}
+
+impl Drop for ChainSimulator {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+type RpcResult = Result<Value, (i64, String)>;
+
+async fn rpc_handler(State(state): State<SharedState>, Json(request): Json<Value>) -> Json<Value> {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request["method"].as_str().unwrap_or_default();
+    let params = &request["params"];
+
+    let result = {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        dispatch(&mut state, method, params)
+    };
+
+    Json(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    })
+}
+
+fn dispatch(state: &mut ChainState, method: &str, params: &Value) -> RpcResult {
+    match method {
+        // Ethereum
+        "eth_chainId" => Ok(hex_quantity(state.config.chain_id)),
+        "eth_blockNumber" => Ok(hex_quantity(state.head())),
+        "eth_getTransactionCount" => {
+            let address = str_param(params, 0)?;
+            let tag = params[1].as_str().unwrap_or("latest");
+            Ok(hex_quantity(state.nonce(address, tag == "pending")))
+        }
+        "eth_sendRawTransaction" => {
+            let raw = str_param(params, 0)?;
+            let bytes = hex::decode(raw.trim_start_matches("0x"))
+                .map_err(|e| (INVALID_PARAMS, format!("invalid raw transaction: {}", e)))?;
+            let tx_hash = format!("0x{}", sha256_hex(&bytes));
+            if !state.submit(&tx_hash, None) {
+                return Err((-32000, "already known".to_string()));
+            }
+            Ok(json!(tx_hash))
+        }
+        "eth_getTransactionReceipt" => {
+            let tx_hash = str_param(params, 0)?;
+            Ok(eth_receipt(state, tx_hash))
+        }
+        "eth_getTransactionByHash" => {
+            let tx_hash = str_param(params, 0)?;
+            Ok(eth_transaction(state, tx_hash))
+        }
+        "eth_getBlockByNumber" => {
+            let tag = str_param(params, 0)?;
+            Ok(state
+                .block_by_tag(tag)
+                .map(|block| {
+                    json!({
+                        "number": hex_quantity(block.number),
+                        "hash": block.hash,
+                        "parentHash": block.parent_hash,
+                        "transactions": block.txs,
+                    })
+                })
+                .unwrap_or(Value::Null))
+        }
+
+        // Solana
+        "getSlot" => Ok(json!(state.head())),
+        "getLatestBlockhash" => {
+            let head = state.blocks.last().expect("genesis block");
+            Ok(json!({
+                "context": { "slot": head.number },
+                "value": {
+                    "blockhash": head.hash.trim_start_matches("0x"),
+                    "lastValidBlockHeight": head.number + 150,
+                },
+            }))
+        }
+        "sendTransaction" => {
+            let encoded = str_param(params, 0)?;
+            let signature = sha256_hex(encoded.as_bytes());
+            if !state.submit(&signature, None) {
+                return Err((-32002, "transaction already processed".to_string()));
+            }
+            Ok(json!(signature))
+        }
+        "getSignatureStatuses" => {
+            let signatures = params[0]
+                .as_array()
+                .ok_or((INVALID_PARAMS, "expected signature list".to_string()))?;
+            let value: Vec<Value> = signatures
+                .iter()
+                .map(|sig| solana_status(state, sig.as_str().unwrap_or_default()))
+                .collect();
+            Ok(json!({ "context": { "slot": state.head() }, "value": value }))
+        }
+
+        _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    }
+}
+
+fn str_param(params: &Value, index: usize) -> Result<&str, (i64, String)> {
+    params[index]
+        .as_str()
+        .ok_or((INVALID_PARAMS, format!("missing string param {}", index)))
+}
+
+fn hex_quantity(value: u64) -> Value {
+    json!(format!("0x{:x}", value))
+}
+
+/// Pending transactions have a null `blockNumber`
+fn eth_transaction(state: &ChainState, tx_hash: &str) -> Value {
+    let Some(tx) = state.txs.get(tx_hash) else {
+        return Value::Null;
+    };
+    let block = state.find_block(tx_hash);
+    json!({
+        "hash": tx_hash,
+        "blockNumber": block.map(|block| hex_quantity(block.number)),
+        "blockHash": block.map(|block| block.hash.clone()),
+        "from": tx.from,
+    })
+}
+
+fn eth_receipt(state: &ChainState, tx_hash: &str) -> Value {
+    let (Some(tx), Some(block)) = (state.txs.get(tx_hash), state.find_block(tx_hash)) else {
+        return Value::Null;
+    };
+    json!({
+        "transactionHash": tx_hash,
+        "blockNumber": hex_quantity(block.number),
+        "blockHash": block.hash,
+        "from": tx.from,
+        "status": if tx.failed { "0x0" } else { "0x1" },
+    })
+}
+
+fn solana_status(state: &ChainState, signature: &str) -> Value {
+    let failed = state.txs.get(signature).is_some_and(|tx| tx.failed);
+    match state.status(signature) {
+        TxStatus::Included {
+            block,
+            confirmations,
+            finalized,
+        } => {
+            let (confirmations, level) = if finalized {
+                (Value::Null, "finalized")
+            } else if confirmations > 1 {
+                (json!(confirmations - 1), "confirmed")
+            } else {
+                (json!(0), "processed")
+            };
+            json!({
+                "slot": block,
+                "confirmations": confirmations,
+                "err": if failed { json!({ "InstructionError": [0, { "Custom": 1 }] }) } else { Value::Null },
+                "confirmationStatus": level,
+            })
+        }
+        TxStatus::Pending | TxStatus::Unknown => Value::Null,
+    }
+}
@@ -0,0 +1,182 @@
+use chain_simulator::{ChainSimulator, SimulatorConfig, TxStatus};
+use serde_json::{json, Value};
+
+async fn rpc(sim: &ChainSimulator, method: &str, params: Value) -> Value {
+    reqwest::Client::new()
+        .post(sim.endpoint())
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_blocks_and_finality() {
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(sim.head(), 0);
+
+    sim.submit("0xaa");
+    assert_eq!(sim.status("0xaa"), TxStatus::Pending);
+
+    let block = sim.mine();
+    assert_eq!(
+        sim.status("0xaa"),
+        TxStatus::Included {
+            block,
+            confirmations: 1,
+            finalized: false
+        }
+    );
+
+    sim.mine_blocks(2);
+    assert_eq!(sim.finalized_height(), block);
+    assert!(matches!(
+        sim.status("0xaa"),
+        TxStatus::Included {
+            confirmations: 3,
+            finalized: true,
+            ..
+        }
+    ));
+
+    let res = rpc(&sim, "eth_blockNumber", json!([])).await;
+    assert_eq!(res["result"], "0x3");
+    let res = rpc(&sim, "eth_getBlockByNumber", json!(["finalized", false])).await;
+    assert_eq!(res["result"]["number"], "0x1");
+    assert_eq!(res["result"]["transactions"], json!(["0xaa"]));
+}
+
+#[tokio::test]
+async fn test_nonces_track_mempool_and_blocks() {
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    let sender = "0x00000000000000000000000000000000000000Ab";
+
+    sim.submit_from(sender, "0x01");
+    sim.submit_from(sender, "0x02");
+
+    let latest = rpc(&sim, "eth_getTransactionCount", json!([sender, "latest"])).await;
+    let pending = rpc(&sim, "eth_getTransactionCount", json!([sender, "pending"])).await;
+    assert_eq!(latest["result"], "0x0");
+    assert_eq!(pending["result"], "0x2");
+
+    sim.mine();
+    let latest = rpc(
+        &sim,
+        "eth_getTransactionCount",
+        json!([sender.to_lowercase(), "latest"]),
+    )
+    .await;
+    assert_eq!(latest["result"], "0x2");
+}
+
+#[tokio::test]
+async fn test_send_raw_transaction_and_receipt() {
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+
+    let res = rpc(&sim, "eth_sendRawTransaction", json!(["0xf86c0a85"])).await;
+    let tx_hash = res["result"].as_str().unwrap().to_string();
+
+    let res = rpc(&sim, "eth_getTransactionReceipt", json!([tx_hash])).await;
+    assert!(res["result"].is_null());
+    let res = rpc(&sim, "eth_getTransactionByHash", json!([tx_hash])).await;
+    assert_eq!(res["result"]["hash"], tx_hash.as_str());
+    assert!(res["result"]["blockNumber"].is_null());
+
+    sim.mine();
+    let res = rpc(&sim, "eth_getTransactionReceipt", json!([tx_hash])).await;
+    assert_eq!(res["result"]["blockNumber"], "0x1");
+    assert_eq!(res["result"]["status"], "0x1");
+    let res = rpc(&sim, "eth_getTransactionByHash", json!([tx_hash])).await;
+    assert_eq!(res["result"]["blockNumber"], "0x1");
+    let res = rpc(&sim, "eth_getTransactionByHash", json!(["0xbb"])).await;
+    assert!(res["result"].is_null());
+
+    let res = rpc(&sim, "eth_sendRawTransaction", json!(["0xf86c0a85"])).await;
+    assert_eq!(res["error"]["code"], -32000);
+}
+
+#[tokio::test]
+async fn test_reorg_returns_transactions_to_mempool() {
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    sim.submit("0xaa");
+    sim.mine();
+    let before = rpc(&sim, "eth_getBlockByNumber", json!(["0x1", false])).await;
+
+    sim.reorg(1);
+    assert_eq!(sim.head(), 0);
+    assert_eq!(sim.status("0xaa"), TxStatus::Pending);
+
+    sim.mine();
+    let after = rpc(&sim, "eth_getBlockByNumber", json!(["0x1", false])).await;
+    assert_ne!(before["result"]["hash"], after["result"]["hash"]);
+    assert_eq!(after["result"]["transactions"], json!(["0xaa"]));
+}
+
+#[tokio::test]
+async fn test_dropped_transaction_is_forgotten() {
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    sim.submit("0xaa");
+    assert!(sim.drop_tx("0xaa"));
+    sim.mine();
+    assert_eq!(sim.status("0xaa"), TxStatus::Unknown);
+    assert!(!sim.drop_tx("0xaa"));
+}
+
+#[tokio::test]
+async fn test_solana_signature_statuses() {
+    let sim = ChainSimulator::start(SimulatorConfig {
+        finality_depth: 4,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let res = rpc(&sim, "sendTransaction", json!(["AQID"])).await;
+    let signature = res["result"].as_str().unwrap().to_string();
+    sim.submit("failing");
+    sim.fail_tx("failing");
+
+    let status = |res: &Value, i: usize| res["result"]["value"][i].clone();
+    let params = json!([[signature, "failing", "unknown"], {"searchTransactionHistory": true}]);
+
+    let res = rpc(&sim, "getSignatureStatuses", params.clone()).await;
+    assert!(status(&res, 0).is_null());
+
+    sim.mine();
+    let res = rpc(&sim, "getSignatureStatuses", params.clone()).await;
+    assert_eq!(status(&res, 0)["confirmationStatus"], "processed");
+    assert!(!status(&res, 1)["err"].is_null());
+    assert!(status(&res, 2).is_null());
+
+    sim.mine();
+    let res = rpc(&sim, "getSignatureStatuses", params.clone()).await;
+    assert_eq!(status(&res, 0)["confirmationStatus"], "confirmed");
+    assert_eq!(status(&res, 0)["confirmations"], 1);
+
+    sim.mine_blocks(2);
+    let res = rpc(&sim, "getSignatureStatuses", params).await;
+    assert_eq!(status(&res, 0)["confirmationStatus"], "finalized");
+    assert!(status(&res, 0)["confirmations"].is_null());
+}
+
+#[tokio::test]
+async fn test_unknown_method() {
+    let sim = ChainSimulator::start(SimulatorConfig::default())
+        .await
+        .unwrap();
+    let res = rpc(&sim, "eth_mining", json!([])).await;
+    assert_eq!(res["error"]["code"], -32601);
+}
@@ -31,6 +31,15 @@ pub mod model {
         pub confirmed: bool,
         pub timestamp: Option<DateTime<Utc>>,
     }
+
+    /// A signed anchor transaction whose id is known before it is broadcast
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct SignedAnchorTx {
+        /// Where the transaction lands once sent; `tx_id` is the signed transaction's id
+        pub tx: ChainTxRef,
+        /// Encoded signed transaction, sent as is when it is broadcast again
+        pub raw_tx: String,
+    }
 }
 
 pub mod hash {
@@ -43,6 +52,199 @@ pub mod hash {
         let out = hasher.finalize();
         out.encode_hex::<String>()
     }
+
+    /// Serialize with object keys sorted and no insignificant whitespace, so the same
+    /// value always produces the same bytes regardless of map ordering.
+    pub fn canonical_json(value: &serde_json::Value) -> String {
+        let mut out = String::new();
+        write_canonical(value, &mut out);
+        out
+    }
+
+    fn write_canonical(value: &serde_json::Value, out: &mut String) {
+        use serde_json::Value;
+        match value {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                out.push('{');
+                for (i, key) in keys.into_iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&Value::String(key.clone()).to_string());
+                    out.push(':');
+                    write_canonical(&map[key], out);
+                }
+                out.push('}');
+            }
+            Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_canonical(item, out);
+                }
+                out.push(']');
+            }
+            scalar => out.push_str(&scalar.to_string()),
+        }
+    }
+
+    /// Hash of the canonical record envelope: id, created_at (millisecond precision),
+    /// digest, payload_mime and metadata.
+    pub fn record_hash_hex(record: &super::model::EvidenceRecord) -> String {
+        let envelope = serde_json::json!({
+            "id": record.id,
+            "created_at": record
+                .created_at
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            "digest": record.digest,
+            "payload_mime": record.payload_mime,
+            "metadata": record.metadata,
+        });
+        sha256_hex(canonical_json(&envelope).as_bytes())
+    }
+}
+
+/// On-chain memo formats.
+///
+/// Version 1 is `phx1:<algo>:<record-hash>:<id-prefix>`, where the record hash is
+/// [`hash::record_hash_hex`]. Memos written before versioning were `evidence:<payload-digest>`.
+pub mod memo {
+    use super::hash::record_hash_hex;
+    use super::model::{DigestAlgo, EvidenceRecord};
+    use std::fmt;
+
+    pub const LEGACY_PREFIX: &str = "evidence:";
+    pub const V1_PREFIX: &str = "phx1:";
+    pub const DEFAULT_ID_PREFIX_LEN: usize = 8;
+
+    #[derive(Debug, thiserror::Error, PartialEq, Eq)]
+    pub enum MemoError {
+        #[error("not an evidence memo")]
+        UnknownFormat,
+        #[error("unsupported memo version: {0}")]
+        UnsupportedVersion(String),
+        #[error("malformed memo: {0}")]
+        Malformed(String),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum EvidenceMemo {
+        /// `evidence:<digest>`; covers the payload digest only
+        Legacy { payload_digest: String },
+        V1 {
+            algo: DigestAlgo,
+            record_hash: String,
+            id_prefix: String,
+        },
+    }
+
+    impl EvidenceMemo {
+        pub fn for_record(record: &EvidenceRecord) -> Self {
+            Self::for_record_with_id_prefix(record, DEFAULT_ID_PREFIX_LEN)
+        }
+
+        /// Build a v1 memo keeping at most `id_prefix_len` characters of the record id
+        pub fn for_record_with_id_prefix(record: &EvidenceRecord, id_prefix_len: usize) -> Self {
+            EvidenceMemo::V1 {
+                algo: DigestAlgo::Sha256,
+                record_hash: record_hash_hex(record),
+                id_prefix: record.id.chars().take(id_prefix_len).collect(),
+            }
+        }
+
+        pub fn parse(memo: &str) -> Result<Self, MemoError> {
+            if let Some(digest) = memo.strip_prefix(LEGACY_PREFIX) {
+                if !is_hex(digest) {
+                    return Err(MemoError::Malformed("digest is not hex".to_string()));
+                }
+                return Ok(EvidenceMemo::Legacy {
+                    payload_digest: digest.to_string(),
+                });
+            }
+
+            if let Some(rest) = memo.strip_prefix(V1_PREFIX) {
+                // The id prefix is last so it may itself contain ':'
+                let mut parts = rest.splitn(3, ':');
+                let (Some(algo), Some(record_hash), Some(id_prefix)) =
+                    (parts.next(), parts.next(), parts.next())
+                else {
+                    return Err(MemoError::Malformed("expected 4 fields".to_string()));
+                };
+                let algo = match algo {
+                    "sha256" => DigestAlgo::Sha256,
+                    other => {
+                        return Err(MemoError::Malformed(format!("unknown algorithm {}", other)))
+                    }
+                };
+                if record_hash.len() != 64 || !is_hex(record_hash) {
+                    return Err(MemoError::Malformed(
+                        "record hash is not 32 bytes of hex".to_string(),
+                    ));
+                }
+                return Ok(EvidenceMemo::V1 {
+                    algo,
+                    record_hash: record_hash.to_string(),
+                    id_prefix: id_prefix.to_string(),
+                });
+            }
+
+            match memo.split_once(':') {
+                Some((version, _))
+                    if version.len() > 3
+                        && version.starts_with("phx")
+                        && version[3..].chars().all(|c| c.is_ascii_digit()) =>
+                {
+                    Err(MemoError::UnsupportedVersion(version.to_string()))
+                }
+                _ => Err(MemoError::UnknownFormat),
+            }
+        }
+
+        /// Whether this memo was produced for `record`
+        pub fn matches(&self, record: &EvidenceRecord) -> bool {
+            match self {
+                EvidenceMemo::Legacy { payload_digest } => {
+                    payload_digest.eq_ignore_ascii_case(&record.digest.hex)
+                }
+                EvidenceMemo::V1 {
+                    record_hash,
+                    id_prefix,
+                    ..
+                } => {
+                    record_hash.eq_ignore_ascii_case(&record_hash_hex(record))
+                        && record.id.starts_with(id_prefix.as_str())
+                }
+            }
+        }
+    }
+
+    impl fmt::Display for EvidenceMemo {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                EvidenceMemo::Legacy { payload_digest } => {
+                    write!(f, "{}{}", LEGACY_PREFIX, payload_digest)
+                }
+                EvidenceMemo::V1 {
+                    algo,
+                    record_hash,
+                    id_prefix,
+                } => {
+                    let algo = match algo {
+                        DigestAlgo::Sha256 => "sha256",
+                    };
+                    write!(f, "{}{}:{}:{}", V1_PREFIX, algo, record_hash, id_prefix)
+                }
+            }
+        }
+    }
+
+    fn is_hex(s: &str) -> bool {
+        !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit())
+    }
 }
 
 pub mod convert {
@@ -86,6 +288,7 @@ pub mod convert {
 pub mod anchor {
     use super::model::*;
     use async_trait::async_trait;
+    use std::time::Duration;
 
     #[derive(Debug, thiserror::Error)]
     pub enum AnchorError {
@@ -95,12 +298,60 @@ pub mod anchor {
         Invalid(String),
         #[error("provider: {0}")]
         Provider(String),
+        /// A signed transaction can no longer be included, e.g. its blockhash
+        /// expired or its nonce was used by another; it must be signed again
+        #[error("expired: {0}")]
+        Expired(String),
     }
 
     #[async_trait]
     pub trait AnchorProvider: Send + Sync {
         async fn anchor(&self, evidence: &EvidenceRecord) -> Result<ChainTxRef, AnchorError>;
         async fn confirm(&self, tx: &ChainTxRef) -> Result<ChainTxRef, AnchorError>;
+
+        /// Check that the chain can be reached. Providers without a remote
+        /// endpoint are always healthy.
+        async fn health(&self) -> Result<(), AnchorError> {
+            Ok(())
+        }
+
+        /// The `ChainTxRef::network` this provider anchors to, used to label
+        /// failures that have no transaction reference
+        fn network(&self) -> &str {
+            "unknown"
+        }
+
+        /// Sign the anchor transaction for `evidence` without sending it, so
+        /// the caller can record its id first. Providers that only learn the
+        /// id by sending return `None` and are anchored through `anchor`.
+        async fn prepare(
+            &self,
+            _evidence: &EvidenceRecord,
+        ) -> Result<Option<SignedAnchorTx>, AnchorError> {
+            Ok(None)
+        }
+
+        /// How long a transaction returned by `prepare` can still be included
+        /// after it was signed, or `None` when it does not expire
+        fn signed_tx_lifetime(&self) -> Option<Duration> {
+            None
+        }
+
+        /// Send a transaction returned by `prepare`. Sending one the chain
+        /// already has is not an error; one it can no longer include is
+        /// `AnchorError::Expired`.
+        async fn broadcast(&self, _signed: &SignedAnchorTx) -> Result<ChainTxRef, AnchorError> {
+            Err(AnchorError::Invalid(
+                "provider does not sign transactions ahead of sending".to_string(),
+            ))
+        }
+
+        /// Whether the chain has seen `tx`, pending or included
+        async fn is_known(&self, _tx: &ChainTxRef) -> Result<bool, AnchorError> {
+            Err(AnchorError::Invalid(
+                "provider does not sign transactions ahead of sending".to_string(),
+            ))
+        }
     }
 }
 
@@ -277,4 +528,103 @@ mod tests {
         assert_eq!(deserialized.confirmed, tx_ref.confirmed);
         assert_eq!(deserialized.timestamp, tx_ref.timestamp);
     }
+
+    fn memo_record() -> model::EvidenceRecord {
+        model::EvidenceRecord {
+            id: "0f4c2a9e-1b7d-4c35-9a51-7f8e2d3c4b5a".to_string(),
+            created_at: "2024-05-01T12:00:00.123Z".parse().unwrap(),
+            digest: model::EvidenceDigest {
+                algo: model::DigestAlgo::Sha256,
+                hex: "abcd1234".to_string(),
+            },
+            payload_mime: Some("application/json".to_string()),
+            metadata: json!({"b": 1, "a": [true, null]}),
+        }
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_keys() {
+        let value = json!({"z": 1, "a": {"y": "x", "b": [1, 2]}});
+        assert_eq!(
+            hash::canonical_json(&value),
+            r#"{"a":{"b":[1,2],"y":"x"},"z":1}"#
+        );
+    }
+
+    #[test]
+    fn test_record_hash_covers_envelope() {
+        let record = memo_record();
+        let base = hash::record_hash_hex(&record);
+        assert_eq!(base.len(), 64);
+
+        let mut changed = record.clone();
+        changed.metadata = json!({"b": 2, "a": [true, null]});
+        assert_ne!(hash::record_hash_hex(&changed), base);
+
+        let mut changed = record.clone();
+        changed.payload_mime = None;
+        assert_ne!(hash::record_hash_hex(&changed), base);
+
+        let mut changed = record.clone();
+        changed.created_at += chrono::Duration::milliseconds(1);
+        assert_ne!(hash::record_hash_hex(&changed), base);
+
+        // Sub-millisecond precision is not part of the envelope
+        let mut changed = record;
+        changed.created_at += chrono::Duration::microseconds(10);
+        assert_eq!(hash::record_hash_hex(&changed), base);
+    }
+
+    #[test]
+    fn test_memo_v1_roundtrip() {
+        let record = memo_record();
+        let memo = memo::EvidenceMemo::for_record(&record);
+        let encoded = memo.to_string();
+
+        assert!(encoded.starts_with("phx1:sha256:"));
+        assert!(encoded.ends_with(":0f4c2a9e"));
+        assert_eq!(memo::EvidenceMemo::parse(&encoded).unwrap(), memo);
+        assert!(memo.matches(&record));
+
+        let mut other = record;
+        other.digest.hex = "ffff".to_string();
+        assert!(!memo.matches(&other));
+    }
+
+    #[test]
+    fn test_memo_legacy_parse() {
+        let record = memo_record();
+        let memo = memo::EvidenceMemo::parse("evidence:abcd1234").unwrap();
+        assert_eq!(
+            memo,
+            memo::EvidenceMemo::Legacy {
+                payload_digest: "abcd1234".to_string()
+            }
+        );
+        assert!(memo.matches(&record));
+        assert_eq!(memo.to_string(), "evidence:abcd1234");
+    }
+
+    #[test]
+    fn test_memo_parse_errors() {
+        use memo::{EvidenceMemo, MemoError};
+
+        assert_eq!(EvidenceMemo::parse("hello"), Err(MemoError::UnknownFormat));
+        assert_eq!(
+            EvidenceMemo::parse("phx2:sha512:00:abc"),
+            Err(MemoError::UnsupportedVersion("phx2".to_string()))
+        );
+        assert!(matches!(
+            EvidenceMemo::parse("phx1:sha256:abcd:id"),
+            Err(MemoError::Malformed(_))
+        ));
+        assert!(matches!(
+            EvidenceMemo::parse(&format!("phx1:md5:{}:id", "0".repeat(64))),
+            Err(MemoError::Malformed(_))
+        ));
+        assert!(matches!(
+            EvidenceMemo::parse("evidence:xyz"),
+            Err(MemoError::Malformed(_))
+        ));
+    }
 }
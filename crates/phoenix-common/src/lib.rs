@@ -1 +1,6 @@
+pub mod metrics;
+pub mod migrations;
+pub mod outbox;
 pub mod schema;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
@@ -0,0 +1,87 @@
+//! Prometheus metrics shared by the keeper and the API, served on each
+//! binary's `/metrics` endpoint.
+
+use metrics::{describe_counter, describe_gauge, describe_histogram, gauge, Unit};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+
+pub const JOBS_ANCHORED: &str = "phoenix_jobs_anchored_total";
+pub const JOBS_CONFIRMED: &str = "phoenix_jobs_confirmed_total";
+pub const JOBS_FAILED: &str = "phoenix_jobs_failed_total";
+pub const JOBS_RETRIED: &str = "phoenix_jobs_retried_total";
+pub const TX_REFS_DROPPED: &str = "phoenix_tx_refs_dropped_total";
+pub const EVIDENCE_SUBMITTED: &str = "phoenix_evidence_submitted_total";
+pub const ANCHOR_LATENCY: &str = "phoenix_anchor_latency_seconds";
+pub const CONFIRM_LATENCY: &str = "phoenix_confirm_latency_seconds";
+/// Recorded by the anchor providers' `rpc_call`
+pub const RPC_CALL_DURATION: &str = "phoenix_rpc_call_duration_seconds";
+pub const QUEUE_DEPTH: &str = "phoenix_queue_depth";
+pub const UNCONFIRMED_TX_REFS: &str = "phoenix_unconfirmed_tx_refs";
+
+/// Histogram buckets in seconds, from a fast local RPC to a slow block inclusion
+const LATENCY_BUCKETS: &[f64] = &[
+    0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Install the process-wide Prometheus recorder, or return the one already installed.
+///
+/// Call this at startup: metrics recorded before the recorder exists are dropped.
+pub fn install_recorder() -> PrometheusHandle {
+    HANDLE
+        .get_or_init(|| {
+            let handle = PrometheusBuilder::new()
+                .set_buckets(LATENCY_BUCKETS)
+                .expect("latency buckets are not empty")
+                .install_recorder()
+                .expect("no other metrics recorder is installed");
+            describe();
+            handle
+        })
+        .clone()
+}
+
+fn describe() {
+    describe_counter!(JOBS_ANCHORED, "Evidence jobs anchored, by network");
+    describe_counter!(
+        JOBS_CONFIRMED,
+        "Anchored transactions seen confirmed, by network"
+    );
+    describe_counter!(JOBS_FAILED, "Evidence jobs failed permanently, by network");
+    describe_counter!(
+        JOBS_RETRIED,
+        "Evidence jobs scheduled for retry after a temporary failure, by network"
+    );
+    describe_counter!(
+        TX_REFS_DROPPED,
+        "Anchored transactions given up on unconfirmed and their jobs requeued, by network"
+    );
+    describe_counter!(EVIDENCE_SUBMITTED, "Evidence records accepted by the API");
+    describe_histogram!(
+        ANCHOR_LATENCY,
+        Unit::Seconds,
+        "Time spent in the provider's anchor call"
+    );
+    describe_histogram!(
+        CONFIRM_LATENCY,
+        Unit::Seconds,
+        "Time spent in the provider's confirm call"
+    );
+    describe_histogram!(
+        RPC_CALL_DURATION,
+        Unit::Seconds,
+        "Duration of JSON-RPC calls to chain endpoints"
+    );
+    describe_gauge!(QUEUE_DEPTH, "Evidence jobs waiting in the outbox");
+    describe_gauge!(
+        UNCONFIRMED_TX_REFS,
+        "Anchored transactions not yet confirmed"
+    );
+}
+
+/// Set the outbox gauges, read from the database when `/metrics` is scraped
+pub fn set_outbox_gauges(queue_depth: i64, unconfirmed_tx_refs: i64) {
+    gauge!(QUEUE_DEPTH).set(queue_depth as f64);
+    gauge!(UNCONFIRMED_TX_REFS).set(unconfirmed_tx_refs as f64);
+}
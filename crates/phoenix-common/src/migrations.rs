@@ -0,0 +1,832 @@
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Row, Sqlite};
+use thiserror::Error;
+
+pub mod postgres;
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("Database error: {0}")]
+    Database(sqlx::Error),
+    #[error("Migration error: {0}")]
+    Migration(String),
+    #[error("Migration {version} ({name}) was changed after it was applied")]
+    ChecksumMismatch { version: i32, name: String },
+    #[error("Database schema version {database} is newer than this binary supports ({binary})")]
+    DatabaseNewer { database: i32, binary: i32 },
+}
+
+impl From<sqlx::Error> for MigrationError {
+    fn from(error: sqlx::Error) -> Self {
+        MigrationError::Database(error)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, MigrationError>;
+
+/// A single schema change. `up` must never be edited once released: the
+/// checksum recorded when it was applied is verified on every start.
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    /// Hex SHA-256 of the `up` SQL
+    pub fn checksum(&self) -> String {
+        hex::encode(Sha256::digest(self.up.as_bytes()))
+    }
+}
+
+/// The schema shared by the API, the keeper and every other binary on the outbox database
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        up: r#"
+        CREATE TABLE IF NOT EXISTS outbox_jobs (
+            id TEXT PRIMARY KEY,
+            payload_sha256 TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'queued',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            created_ms INTEGER NOT NULL,
+            updated_ms INTEGER NOT NULL,
+            next_attempt_ms INTEGER NOT NULL DEFAULT 0
+        );
+        "#,
+        down: "DROP TABLE IF EXISTS outbox_jobs;",
+    },
+    Migration {
+        version: 2,
+        name: "add_tx_refs_table",
+        up: r#"
+        CREATE TABLE IF NOT EXISTS outbox_tx_refs (
+            job_id TEXT NOT NULL,
+            network TEXT NOT NULL,
+            chain TEXT NOT NULL,
+            tx_id TEXT NOT NULL,
+            confirmed INTEGER NOT NULL DEFAULT 0,
+            timestamp INTEGER,
+            PRIMARY KEY (job_id, network, chain, tx_id)
+        );
+        "#,
+        down: "DROP TABLE IF EXISTS outbox_tx_refs;",
+    },
+    Migration {
+        version: 3,
+        name: "add_job_indexes",
+        up: r#"
+        CREATE INDEX IF NOT EXISTS idx_outbox_jobs_status ON outbox_jobs(status);
+        CREATE INDEX IF NOT EXISTS idx_outbox_jobs_created_ms ON outbox_jobs(created_ms);
+        CREATE INDEX IF NOT EXISTS idx_outbox_jobs_next_attempt ON outbox_jobs(next_attempt_ms);
+        "#,
+        down: r#"
+        DROP INDEX IF EXISTS idx_outbox_jobs_status;
+        DROP INDEX IF EXISTS idx_outbox_jobs_created_ms;
+        DROP INDEX IF EXISTS idx_outbox_jobs_next_attempt;
+        "#,
+    },
+    Migration {
+        version: 4,
+        name: "add_tx_refs_indexes",
+        up: r#"
+        CREATE INDEX IF NOT EXISTS idx_outbox_tx_refs_job_id ON outbox_tx_refs(job_id);
+        CREATE INDEX IF NOT EXISTS idx_outbox_tx_refs_confirmed ON outbox_tx_refs(confirmed);
+        "#,
+        down: r#"
+        DROP INDEX IF EXISTS idx_outbox_tx_refs_job_id;
+        DROP INDEX IF EXISTS idx_outbox_tx_refs_confirmed;
+        "#,
+    },
+    Migration {
+        version: 5,
+        name: "add_countermeasure_deployments_table",
+        up: r#"
+        CREATE TABLE IF NOT EXISTS countermeasure_deployments (
+            id TEXT PRIMARY KEY,
+            job_id TEXT NOT NULL,
+            deployed_at INTEGER NOT NULL,
+            deployed_by TEXT NOT NULL,
+            countermeasure_type TEXT NOT NULL,
+            effectiveness_score REAL,
+            notes TEXT,
+            created_ms INTEGER NOT NULL,
+            updated_ms INTEGER NOT NULL,
+            FOREIGN KEY (job_id) REFERENCES outbox_jobs(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_countermeasure_deployments_job_id ON countermeasure_deployments(job_id);
+        CREATE INDEX IF NOT EXISTS idx_countermeasure_deployments_deployed_at ON countermeasure_deployments(deployed_at);
+        CREATE INDEX IF NOT EXISTS idx_countermeasure_deployments_type ON countermeasure_deployments(countermeasure_type);
+        "#,
+        down: "DROP TABLE IF EXISTS countermeasure_deployments;",
+    },
+    Migration {
+        version: 6,
+        name: "add_signal_disruption_audit_table",
+        up: r#"
+        CREATE TABLE IF NOT EXISTS signal_disruption_audit (
+            id TEXT PRIMARY KEY,
+            target_id TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            event_timestamp INTEGER NOT NULL,
+            detected_by TEXT NOT NULL,
+            severity TEXT NOT NULL,
+            outcome TEXT NOT NULL,
+            evidence_blob TEXT,
+            created_ms INTEGER NOT NULL,
+            updated_ms INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_signal_disruption_audit_target_id ON signal_disruption_audit(target_id);
+        CREATE INDEX IF NOT EXISTS idx_signal_disruption_audit_event_timestamp ON signal_disruption_audit(event_timestamp);
+        CREATE INDEX IF NOT EXISTS idx_signal_disruption_audit_event_type ON signal_disruption_audit(event_type);
+        CREATE INDEX IF NOT EXISTS idx_signal_disruption_audit_severity ON signal_disruption_audit(severity);
+        "#,
+        down: "DROP TABLE IF EXISTS signal_disruption_audit;",
+    },
+    Migration {
+        version: 7,
+        name: "add_jamming_operations_table",
+        up: r#"
+        CREATE TABLE IF NOT EXISTS jamming_operations (
+            id TEXT PRIMARY KEY,
+            operation_id TEXT NOT NULL UNIQUE,
+            job_id TEXT NOT NULL,
+            started_ms INTEGER NOT NULL,
+            ended_ms INTEGER,
+            target_frequency_range TEXT NOT NULL,
+            power_level REAL NOT NULL,
+            success_metric REAL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            created_ms INTEGER NOT NULL,
+            updated_ms INTEGER NOT NULL,
+            FOREIGN KEY (job_id) REFERENCES outbox_jobs(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_jamming_operations_job_id ON jamming_operations(job_id);
+        CREATE INDEX IF NOT EXISTS idx_jamming_operations_started_ms ON jamming_operations(started_ms);
+        CREATE INDEX IF NOT EXISTS idx_jamming_operations_operation_id ON jamming_operations(operation_id);
+        CREATE INDEX IF NOT EXISTS idx_jamming_operations_target_frequency ON jamming_operations(target_frequency_range);
+        "#,
+        down: "DROP TABLE IF EXISTS jamming_operations;",
+    },
+    Migration {
+        version: 8,
+        name: "update_tx_refs_primary_key",
+        up: r#"
+        -- Databases created before migrations were shared may have outbox_tx_refs
+        -- keyed without tx_id, so rebuild it with the current primary key
+        CREATE TABLE outbox_tx_refs_new (
+            job_id TEXT NOT NULL,
+            network TEXT NOT NULL,
+            chain TEXT NOT NULL,
+            tx_id TEXT NOT NULL,
+            confirmed INTEGER NOT NULL DEFAULT 0,
+            timestamp INTEGER,
+            PRIMARY KEY (job_id, network, chain, tx_id)
+        );
+        INSERT OR IGNORE INTO outbox_tx_refs_new
+        SELECT job_id, network, chain, tx_id, confirmed, timestamp
+        FROM outbox_tx_refs;
+        DROP TABLE outbox_tx_refs;
+        ALTER TABLE outbox_tx_refs_new RENAME TO outbox_tx_refs;
+        CREATE INDEX IF NOT EXISTS idx_outbox_tx_refs_job_id ON outbox_tx_refs(job_id);
+        CREATE INDEX IF NOT EXISTS idx_outbox_tx_refs_confirmed ON outbox_tx_refs(confirmed);
+        "#,
+        // Version 2 already creates this shape, so there is nothing to undo
+        down: "",
+    },
+    Migration {
+        version: 9,
+        name: "add_job_leases",
+        up: r#"
+        ALTER TABLE outbox_jobs ADD COLUMN lease_owner TEXT;
+        ALTER TABLE outbox_jobs ADD COLUMN lease_until_ms INTEGER;
+        CREATE INDEX IF NOT EXISTS idx_outbox_jobs_lease ON outbox_jobs(status, lease_until_ms);
+        -- Jobs left in_progress by a crashed keeper get a lease so the reaper can requeue them
+        UPDATE outbox_jobs SET lease_until_ms = updated_ms + 300000 WHERE status = 'in_progress';
+        "#,
+        down: r#"
+        DROP INDEX IF EXISTS idx_outbox_jobs_lease;
+        ALTER TABLE outbox_jobs DROP COLUMN lease_until_ms;
+        ALTER TABLE outbox_jobs DROP COLUMN lease_owner;
+        "#,
+    },
+    Migration {
+        version: 10,
+        name: "add_dead_letter_tables",
+        up: r#"
+        ALTER TABLE outbox_jobs ADD COLUMN failure_category TEXT;
+        ALTER TABLE outbox_jobs ADD COLUMN provider TEXT;
+        CREATE TABLE IF NOT EXISTS outbox_job_attempts (
+            id INTEGER PRIMARY KEY,
+            job_id TEXT NOT NULL,
+            attempt INTEGER NOT NULL,
+            worker TEXT,
+            outcome TEXT NOT NULL,
+            category TEXT,
+            error TEXT,
+            recorded_ms INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_outbox_job_attempts_job_id ON outbox_job_attempts(job_id);
+        CREATE TABLE IF NOT EXISTS admin_audit_log (
+            id TEXT PRIMARY KEY,
+            action TEXT NOT NULL,
+            job_id TEXT,
+            actor TEXT NOT NULL,
+            details TEXT,
+            created_ms INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_admin_audit_log_job_id ON admin_audit_log(job_id);
+        "#,
+        down: r#"
+        DROP TABLE IF EXISTS admin_audit_log;
+        DROP TABLE IF EXISTS outbox_job_attempts;
+        ALTER TABLE outbox_jobs DROP COLUMN provider;
+        ALTER TABLE outbox_jobs DROP COLUMN failure_category;
+        "#,
+    },
+    Migration {
+        version: 11,
+        name: "add_attempt_timing",
+        up: r#"
+        ALTER TABLE outbox_job_attempts RENAME COLUMN recorded_ms TO finished_ms;
+        ALTER TABLE outbox_job_attempts ADD COLUMN started_ms INTEGER;
+        ALTER TABLE outbox_job_attempts ADD COLUMN latency_ms INTEGER;
+        ALTER TABLE outbox_job_attempts ADD COLUMN provider TEXT;
+        ALTER TABLE outbox_job_attempts ADD COLUMN endpoint TEXT;
+        "#,
+        down: r#"
+        ALTER TABLE outbox_job_attempts DROP COLUMN endpoint;
+        ALTER TABLE outbox_job_attempts DROP COLUMN provider;
+        ALTER TABLE outbox_job_attempts DROP COLUMN latency_ms;
+        ALTER TABLE outbox_job_attempts DROP COLUMN started_ms;
+        ALTER TABLE outbox_job_attempts RENAME COLUMN finished_ms TO recorded_ms;
+        "#,
+    },
+    Migration {
+        version: 12,
+        name: "add_anchor_intents",
+        up: r#"
+        -- Signed transactions recorded before broadcast, until the job records its tx ref
+        CREATE TABLE IF NOT EXISTS outbox_anchor_intents (
+            job_id TEXT PRIMARY KEY,
+            network TEXT NOT NULL,
+            chain TEXT NOT NULL,
+            tx_id TEXT NOT NULL,
+            raw_tx TEXT NOT NULL,
+            created_ms INTEGER NOT NULL
+        );
+        "#,
+        down: r#"
+        DROP TABLE IF EXISTS outbox_anchor_intents;
+        "#,
+    },
+    Migration {
+        version: 13,
+        name: "add_tx_ref_scheduling",
+        up: r#"
+        ALTER TABLE outbox_tx_refs ADD COLUMN created_ms INTEGER;
+        ALTER TABLE outbox_tx_refs ADD COLUMN checks INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE outbox_tx_refs ADD COLUMN next_check_ms INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE outbox_tx_refs ADD COLUMN dropped_ms INTEGER;
+        -- Existing unconfirmed refs get the full maximum age from the upgrade
+        UPDATE outbox_tx_refs SET created_ms = CAST(strftime('%s', 'now') AS INTEGER) * 1000;
+        CREATE INDEX IF NOT EXISTS idx_outbox_tx_refs_next_check ON outbox_tx_refs(confirmed, next_check_ms);
+        "#,
+        down: r#"
+        DROP INDEX IF EXISTS idx_outbox_tx_refs_next_check;
+        ALTER TABLE outbox_tx_refs DROP COLUMN dropped_ms;
+        ALTER TABLE outbox_tx_refs DROP COLUMN next_check_ms;
+        ALTER TABLE outbox_tx_refs DROP COLUMN checks;
+        ALTER TABLE outbox_tx_refs DROP COLUMN created_ms;
+        "#,
+    },
+    Migration {
+        version: 14,
+        name: "add_job_priority_and_tenant",
+        up: r#"
+        ALTER TABLE outbox_jobs ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE outbox_jobs ADD COLUMN tenant TEXT;
+        -- Virtual time each tenant has been served, for weighted fair claiming.
+        -- Jobs without a tenant share the '' row.
+        CREATE TABLE IF NOT EXISTS outbox_tenant_shares (
+            tenant TEXT PRIMARY KEY,
+            last_start REAL NOT NULL,
+            pass REAL NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_outbox_jobs_claim ON outbox_jobs(status, priority, tenant, created_ms);
+        "#,
+        down: r#"
+        DROP INDEX IF EXISTS idx_outbox_jobs_claim;
+        DROP TABLE IF EXISTS outbox_tenant_shares;
+        ALTER TABLE outbox_jobs DROP COLUMN tenant;
+        ALTER TABLE outbox_jobs DROP COLUMN priority;
+        "#,
+    },
+    Migration {
+        version: 15,
+        name: "add_job_record_fields",
+        up: r#"
+        -- The rest of the evidence record the keeper hashes and anchors.
+        -- metadata is the submitted JSON, NULL when none was given.
+        ALTER TABLE outbox_jobs ADD COLUMN payload_mime TEXT;
+        ALTER TABLE outbox_jobs ADD COLUMN metadata TEXT;
+        "#,
+        down: r#"
+        ALTER TABLE outbox_jobs DROP COLUMN metadata;
+        ALTER TABLE outbox_jobs DROP COLUMN payload_mime;
+        "#,
+    },
+];
+
+/// Newest schema version this binary knows about
+pub fn latest_version() -> i32 {
+    latest_of(MIGRATIONS)
+}
+
+fn latest_of(migrations: &[Migration]) -> i32 {
+    migrations.last().map(|m| m.version).unwrap_or(0)
+}
+
+fn check_not_newer(migrations: &[Migration], current_version: i32) -> Result<()> {
+    let binary = latest_of(migrations);
+    if current_version > binary {
+        return Err(MigrationError::DatabaseNewer {
+            database: current_version,
+            binary,
+        });
+    }
+    Ok(())
+}
+
+/// Compare recorded `(version, checksum)` rows with `migrations`. Returns the
+/// migrations whose checksum was never recorded and should be backfilled.
+fn check_recorded<'a>(
+    migrations: &'a [Migration],
+    recorded: &[(i32, Option<String>)],
+) -> Result<Vec<&'a Migration>> {
+    let mut backfill = Vec::new();
+    for (version, checksum) in recorded {
+        let Some(migration) = migrations.iter().find(|m| m.version == *version) else {
+            return Err(MigrationError::Migration(format!(
+                "applied migration {} is unknown to this binary",
+                version
+            )));
+        };
+
+        match checksum {
+            Some(checksum) if *checksum != migration.checksum() => {
+                return Err(MigrationError::ChecksumMismatch {
+                    version: *version,
+                    name: migration.name.to_string(),
+                });
+            }
+            Some(_) => {}
+            None => backfill.push(migration),
+        }
+    }
+    Ok(backfill)
+}
+
+/// Database migration system
+/// Handles schema versioning and migrations
+pub struct MigrationManager {
+    pool: Pool<Sqlite>,
+}
+
+impl MigrationManager {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Initialize migration tracking table
+    async fn init_migration_table(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                applied_at INTEGER NOT NULL,
+                checksum TEXT
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Tracking tables created before checksums were recorded
+        let has_checksum = sqlx::query(
+            "SELECT 1 FROM pragma_table_info('schema_migrations') WHERE name = 'checksum'",
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .is_some();
+        if !has_checksum {
+            sqlx::query("ALTER TABLE schema_migrations ADD COLUMN checksum TEXT")
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Get current schema version
+    async fn get_current_version(&self) -> Result<i32> {
+        let result =
+            sqlx::query_scalar::<_, Option<i32>>("SELECT MAX(version) FROM schema_migrations")
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(result.unwrap_or(0))
+    }
+
+    /// Refuse databases written by a newer binary, and check that applied
+    /// migrations still match their SQL. Rows from before checksums were
+    /// recorded are backfilled.
+    async fn verify_applied(&self) -> Result<i32> {
+        let current_version = self.get_current_version().await?;
+        check_not_newer(MIGRATIONS, current_version)?;
+
+        let rows = sqlx::query("SELECT version, checksum FROM schema_migrations")
+            .fetch_all(&self.pool)
+            .await?;
+        let recorded: Vec<(i32, Option<String>)> =
+            rows.iter().map(|row| (row.get(0), row.get(1))).collect();
+        for migration in check_recorded(MIGRATIONS, &recorded)? {
+            sqlx::query("UPDATE schema_migrations SET checksum = ?1 WHERE version = ?2")
+                .bind(migration.checksum())
+                .bind(migration.version)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(current_version)
+    }
+
+    /// Apply a migration. Returns false if another process applied it first.
+    async fn apply_migration(&self, migration: &Migration) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        // Record first: this takes the write lock, so a concurrent binary
+        // migrating the same database waits here and then sees the row
+        let now = chrono::Utc::now().timestamp_millis();
+        let recorded = sqlx::query(
+            "INSERT INTO schema_migrations (version, name, applied_at, checksum) VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(migration.version)
+        .bind(migration.name)
+        .bind(now)
+        .bind(migration.checksum())
+        .execute(&mut *tx)
+        .await;
+        match recorded {
+            Ok(_) => {}
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Ok(false),
+            Err(e) => return Err(e.into()),
+        }
+
+        execute_statements(&mut tx, migration.up).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Run all pending migrations
+    pub async fn migrate(&self) -> Result<()> {
+        self.init_migration_table().await?;
+        let current_version = self.verify_applied().await?;
+
+        // Apply pending migrations
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current_version) {
+            tracing::info!(
+                "Applying migration {}: {}",
+                migration.version,
+                migration.name
+            );
+            self.apply_migration(migration).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Revert applied migrations newer than `target`, newest first
+    pub async fn migrate_down(&self, target: i32) -> Result<()> {
+        self.init_migration_table().await?;
+        let current_version = self.verify_applied().await?;
+
+        for migration in MIGRATIONS
+            .iter()
+            .rev()
+            .filter(|m| m.version > target && m.version <= current_version)
+        {
+            tracing::info!(
+                "Reverting migration {}: {}",
+                migration.version,
+                migration.name
+            );
+            let mut tx = self.pool.begin().await?;
+            execute_statements(&mut tx, migration.down).await?;
+            sqlx::query("DELETE FROM schema_migrations WHERE version = ?1")
+                .bind(migration.version)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check if migrations are up to date
+    pub async fn is_up_to_date(&self) -> Result<bool> {
+        self.init_migration_table().await?;
+        let current_version = self.get_current_version().await?;
+        Ok(current_version >= latest_version())
+    }
+
+    /// Get migration status
+    pub async fn get_status(&self) -> Result<MigrationStatus> {
+        self.init_migration_table().await?;
+        let current_version = self.get_current_version().await?;
+        let latest_version = latest_version();
+
+        let migrations = sqlx::query(
+            "SELECT version, name, applied_at, checksum FROM schema_migrations ORDER BY version",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let applied_migrations = migrations
+            .into_iter()
+            .map(|row| AppliedMigration {
+                version: row.get::<i32, _>(0),
+                name: row.get::<String, _>(1),
+                applied_at: row.get::<i64, _>(2),
+                checksum: row.get::<Option<String>, _>(3),
+            })
+            .collect();
+
+        Ok(MigrationStatus {
+            current_version,
+            latest_version,
+            is_up_to_date: current_version >= latest_version,
+            applied_migrations,
+        })
+    }
+}
+
+/// Execute migration SQL - split on semicolons and execute each statement
+async fn execute_statements(tx: &mut sqlx::Transaction<'_, Sqlite>, sql: &str) -> Result<()> {
+    for statement in split_statements(sql) {
+        sqlx::query(statement).execute(&mut **tx).await?;
+    }
+    Ok(())
+}
+
+/// Migration SQL must not contain semicolons other than statement terminators,
+/// including inside comments
+fn split_statements(sql: &str) -> impl Iterator<Item = &str> {
+    sql.split(';').map(|s| s.trim()).filter(|s| !s.is_empty())
+}
+
+#[derive(Debug, Clone)]
+pub struct AppliedMigration {
+    pub version: i32,
+    pub name: String,
+    pub applied_at: i64,
+    /// `None` until the first start after checksums were introduced
+    pub checksum: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub current_version: i32,
+    pub latest_version: i32,
+    pub is_up_to_date: bool,
+    pub applied_migrations: Vec<AppliedMigration>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn create_test_pool() -> Pool<Sqlite> {
+        // Use in-memory database with shared cache for the connection pool
+        let db_url = "sqlite::memory:";
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(db_url)
+            .await
+            .unwrap();
+
+        // Enable foreign key support
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    async fn table_names(pool: &Pool<Sqlite>) -> Vec<String> {
+        sqlx::query("SELECT name FROM sqlite_master WHERE type='table'")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.get::<String, _>(0))
+            .collect()
+    }
+
+    async fn tx_refs_primary_key(pool: &Pool<Sqlite>) -> Vec<String> {
+        sqlx::query("SELECT name FROM pragma_table_info('outbox_tx_refs') WHERE pk > 0 ORDER BY pk")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.get::<String, _>(0))
+            .collect()
+    }
+
+    #[test]
+    fn test_versions_are_sequential() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as i32 + 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migration_system() {
+        let pool = create_test_pool().await;
+        let migration_manager = MigrationManager::new(pool);
+
+        // Run migrations
+        migration_manager.migrate().await.unwrap();
+
+        // Check status
+        let status = migration_manager.get_status().await.unwrap();
+        assert!(status.is_up_to_date);
+        assert_eq!(status.current_version, latest_version());
+        assert_eq!(status.applied_migrations.len(), MIGRATIONS.len());
+        assert!(status
+            .applied_migrations
+            .iter()
+            .all(|m| m.checksum.is_some()));
+
+        // Verify tables exist
+        let table_names = table_names(&migration_manager.pool).await;
+        assert!(table_names.contains(&"outbox_jobs".to_string()));
+        assert!(table_names.contains(&"outbox_tx_refs".to_string()));
+        assert!(table_names.contains(&"schema_migrations".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_migration_idempotency() {
+        let pool = create_test_pool().await;
+        let migration_manager = MigrationManager::new(pool);
+
+        // Run migrations twice
+        migration_manager.migrate().await.unwrap();
+        migration_manager.migrate().await.unwrap();
+
+        // Should still be up to date
+        assert!(migration_manager.is_up_to_date().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_refuses_newer_database() {
+        let pool = create_test_pool().await;
+        let migration_manager = MigrationManager::new(pool.clone());
+        migration_manager.migrate().await.unwrap();
+
+        sqlx::query(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (99, 'future', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        match migration_manager.migrate().await {
+            Err(MigrationError::DatabaseNewer { database, binary }) => {
+                assert_eq!(database, 99);
+                assert_eq!(binary, latest_version());
+            }
+            other => panic!("expected DatabaseNewer, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detects_edited_migration() {
+        let pool = create_test_pool().await;
+        let migration_manager = MigrationManager::new(pool.clone());
+        migration_manager.migrate().await.unwrap();
+
+        sqlx::query("UPDATE schema_migrations SET checksum = 'edited' WHERE version = 3")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            migration_manager.migrate().await,
+            Err(MigrationError::ChecksumMismatch { version: 3, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_backfills_checksums_on_legacy_tracking_table() {
+        let pool = create_test_pool().await;
+
+        // Tracking table as the API created it before migrations moved here
+        sqlx::query(
+            "CREATE TABLE schema_migrations (version INTEGER PRIMARY KEY, name TEXT NOT NULL, applied_at INTEGER NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (1, 'initial_schema', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(MIGRATIONS[0].up).execute(&pool).await.unwrap();
+
+        let migration_manager = MigrationManager::new(pool);
+        migration_manager.migrate().await.unwrap();
+
+        let status = migration_manager.get_status().await.unwrap();
+        assert_eq!(status.current_version, latest_version());
+        assert_eq!(
+            status.applied_migrations[0].checksum.as_deref(),
+            Some(MIGRATIONS[0].checksum().as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upgrades_untracked_keeper_schema() {
+        let pool = create_test_pool().await;
+
+        // Old shared schema: tx_refs keyed without tx_id, no tracking table
+        sqlx::query(
+            r#"
+            CREATE TABLE outbox_tx_refs (
+                job_id TEXT NOT NULL,
+                network TEXT NOT NULL,
+                chain TEXT NOT NULL,
+                tx_id TEXT NOT NULL,
+                confirmed INTEGER NOT NULL,
+                timestamp INTEGER,
+                PRIMARY KEY (job_id, network, chain)
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO outbox_tx_refs VALUES ('job-1', 'ghostnet', 'etherlink', '0xabc', 1, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        MigrationManager::new(pool.clone()).migrate().await.unwrap();
+
+        assert_eq!(
+            tx_refs_primary_key(&pool).await,
+            vec!["job_id", "network", "chain", "tx_id"]
+        );
+        let kept: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM outbox_tx_refs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(kept, 1);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_down_and_up_again() {
+        let pool = create_test_pool().await;
+        let migration_manager = MigrationManager::new(pool.clone());
+        migration_manager.migrate().await.unwrap();
+
+        migration_manager.migrate_down(4).await.unwrap();
+        let status = migration_manager.get_status().await.unwrap();
+        assert_eq!(status.current_version, 4);
+        let tables = table_names(&pool).await;
+        assert!(tables.contains(&"outbox_jobs".to_string()));
+        assert!(!tables.contains(&"jamming_operations".to_string()));
+        assert!(!tables.contains(&"countermeasure_deployments".to_string()));
+
+        migration_manager.migrate().await.unwrap();
+        assert!(migration_manager.is_up_to_date().await.unwrap());
+        assert!(table_names(&pool)
+            .await
+            .contains(&"jamming_operations".to_string()));
+
+        migration_manager.migrate_down(0).await.unwrap();
+        assert_eq!(table_names(&pool).await, vec!["schema_migrations"]);
+    }
+}
@@ -0,0 +1,573 @@
+//! PostgreSQL flavour of the shared migrations.
+//!
+//! Same tables, indexes and version names as the SQLite set, but with 64-bit
+//! integer columns for millisecond timestamps and double precision reals.
+//! `update_tx_refs_primary_key` only repairs SQLite history, so it is empty
+//! here but keeps its number so a version means the same on both backends.
+
+use super::{
+    check_not_newer, check_recorded, latest_of, split_statements, AppliedMigration, Migration,
+    MigrationStatus, Result,
+};
+use sqlx::{Pool, Postgres, Row};
+
+pub const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        up: r#"
+        CREATE TABLE IF NOT EXISTS outbox_jobs (
+            id TEXT PRIMARY KEY,
+            payload_sha256 TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'queued',
+            attempts BIGINT NOT NULL DEFAULT 0,
+            last_error TEXT,
+            created_ms BIGINT NOT NULL,
+            updated_ms BIGINT NOT NULL,
+            next_attempt_ms BIGINT NOT NULL DEFAULT 0
+        );
+        "#,
+        down: "DROP TABLE IF EXISTS outbox_jobs;",
+    },
+    Migration {
+        version: 2,
+        name: "add_tx_refs_table",
+        up: r#"
+        CREATE TABLE IF NOT EXISTS outbox_tx_refs (
+            job_id TEXT NOT NULL,
+            network TEXT NOT NULL,
+            chain TEXT NOT NULL,
+            tx_id TEXT NOT NULL,
+            confirmed INTEGER NOT NULL DEFAULT 0,
+            timestamp BIGINT,
+            PRIMARY KEY (job_id, network, chain, tx_id)
+        );
+        "#,
+        down: "DROP TABLE IF EXISTS outbox_tx_refs;",
+    },
+    Migration {
+        version: 3,
+        name: "add_job_indexes",
+        up: r#"
+        CREATE INDEX IF NOT EXISTS idx_outbox_jobs_status ON outbox_jobs(status);
+        CREATE INDEX IF NOT EXISTS idx_outbox_jobs_created_ms ON outbox_jobs(created_ms);
+        CREATE INDEX IF NOT EXISTS idx_outbox_jobs_next_attempt ON outbox_jobs(next_attempt_ms);
+        "#,
+        down: r#"
+        DROP INDEX IF EXISTS idx_outbox_jobs_status;
+        DROP INDEX IF EXISTS idx_outbox_jobs_created_ms;
+        DROP INDEX IF EXISTS idx_outbox_jobs_next_attempt;
+        "#,
+    },
+    Migration {
+        version: 4,
+        name: "add_tx_refs_indexes",
+        up: r#"
+        CREATE INDEX IF NOT EXISTS idx_outbox_tx_refs_job_id ON outbox_tx_refs(job_id);
+        CREATE INDEX IF NOT EXISTS idx_outbox_tx_refs_confirmed ON outbox_tx_refs(confirmed);
+        "#,
+        down: r#"
+        DROP INDEX IF EXISTS idx_outbox_tx_refs_job_id;
+        DROP INDEX IF EXISTS idx_outbox_tx_refs_confirmed;
+        "#,
+    },
+    Migration {
+        version: 5,
+        name: "add_countermeasure_deployments_table",
+        up: r#"
+        CREATE TABLE IF NOT EXISTS countermeasure_deployments (
+            id TEXT PRIMARY KEY,
+            job_id TEXT NOT NULL REFERENCES outbox_jobs(id) ON DELETE CASCADE,
+            deployed_at BIGINT NOT NULL,
+            deployed_by TEXT NOT NULL,
+            countermeasure_type TEXT NOT NULL,
+            effectiveness_score DOUBLE PRECISION,
+            notes TEXT,
+            created_ms BIGINT NOT NULL,
+            updated_ms BIGINT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_countermeasure_deployments_job_id ON countermeasure_deployments(job_id);
+        CREATE INDEX IF NOT EXISTS idx_countermeasure_deployments_deployed_at ON countermeasure_deployments(deployed_at);
+        CREATE INDEX IF NOT EXISTS idx_countermeasure_deployments_type ON countermeasure_deployments(countermeasure_type);
+        "#,
+        down: "DROP TABLE IF EXISTS countermeasure_deployments;",
+    },
+    Migration {
+        version: 6,
+        name: "add_signal_disruption_audit_table",
+        up: r#"
+        CREATE TABLE IF NOT EXISTS signal_disruption_audit (
+            id TEXT PRIMARY KEY,
+            target_id TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            event_timestamp BIGINT NOT NULL,
+            detected_by TEXT NOT NULL,
+            severity TEXT NOT NULL,
+            outcome TEXT NOT NULL,
+            evidence_blob TEXT,
+            created_ms BIGINT NOT NULL,
+            updated_ms BIGINT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_signal_disruption_audit_target_id ON signal_disruption_audit(target_id);
+        CREATE INDEX IF NOT EXISTS idx_signal_disruption_audit_event_timestamp ON signal_disruption_audit(event_timestamp);
+        CREATE INDEX IF NOT EXISTS idx_signal_disruption_audit_event_type ON signal_disruption_audit(event_type);
+        CREATE INDEX IF NOT EXISTS idx_signal_disruption_audit_severity ON signal_disruption_audit(severity);
+        "#,
+        down: "DROP TABLE IF EXISTS signal_disruption_audit;",
+    },
+    Migration {
+        version: 7,
+        name: "add_jamming_operations_table",
+        up: r#"
+        CREATE TABLE IF NOT EXISTS jamming_operations (
+            id TEXT PRIMARY KEY,
+            operation_id TEXT NOT NULL UNIQUE,
+            job_id TEXT NOT NULL REFERENCES outbox_jobs(id) ON DELETE CASCADE,
+            started_ms BIGINT NOT NULL,
+            ended_ms BIGINT,
+            target_frequency_range TEXT NOT NULL,
+            power_level DOUBLE PRECISION NOT NULL,
+            success_metric DOUBLE PRECISION,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            created_ms BIGINT NOT NULL,
+            updated_ms BIGINT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_jamming_operations_job_id ON jamming_operations(job_id);
+        CREATE INDEX IF NOT EXISTS idx_jamming_operations_started_ms ON jamming_operations(started_ms);
+        CREATE INDEX IF NOT EXISTS idx_jamming_operations_operation_id ON jamming_operations(operation_id);
+        CREATE INDEX IF NOT EXISTS idx_jamming_operations_target_frequency ON jamming_operations(target_frequency_range);
+        "#,
+        down: "DROP TABLE IF EXISTS jamming_operations;",
+    },
+    Migration {
+        version: 8,
+        name: "update_tx_refs_primary_key",
+        // Version 2 already keys outbox_tx_refs by tx_id here, so this only
+        // keeps the version numbers in step with the SQLite set
+        up: "",
+        down: "",
+    },
+    Migration {
+        version: 9,
+        name: "add_job_leases",
+        up: r#"
+        ALTER TABLE outbox_jobs ADD COLUMN IF NOT EXISTS lease_owner TEXT;
+        ALTER TABLE outbox_jobs ADD COLUMN IF NOT EXISTS lease_until_ms BIGINT;
+        CREATE INDEX IF NOT EXISTS idx_outbox_jobs_lease ON outbox_jobs(status, lease_until_ms);
+        -- Jobs left in_progress by a crashed keeper get a lease so the reaper can requeue them
+        UPDATE outbox_jobs SET lease_until_ms = updated_ms + 300000 WHERE status = 'in_progress';
+        "#,
+        down: r#"
+        DROP INDEX IF EXISTS idx_outbox_jobs_lease;
+        ALTER TABLE outbox_jobs DROP COLUMN IF EXISTS lease_until_ms;
+        ALTER TABLE outbox_jobs DROP COLUMN IF EXISTS lease_owner;
+        "#,
+    },
+    Migration {
+        version: 10,
+        name: "add_dead_letter_tables",
+        up: r#"
+        ALTER TABLE outbox_jobs ADD COLUMN IF NOT EXISTS failure_category TEXT;
+        ALTER TABLE outbox_jobs ADD COLUMN IF NOT EXISTS provider TEXT;
+        CREATE TABLE IF NOT EXISTS outbox_job_attempts (
+            id BIGSERIAL PRIMARY KEY,
+            job_id TEXT NOT NULL,
+            attempt BIGINT NOT NULL,
+            worker TEXT,
+            outcome TEXT NOT NULL,
+            category TEXT,
+            error TEXT,
+            recorded_ms BIGINT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_outbox_job_attempts_job_id ON outbox_job_attempts(job_id);
+        CREATE TABLE IF NOT EXISTS admin_audit_log (
+            id TEXT PRIMARY KEY,
+            action TEXT NOT NULL,
+            job_id TEXT,
+            actor TEXT NOT NULL,
+            details TEXT,
+            created_ms BIGINT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_admin_audit_log_job_id ON admin_audit_log(job_id);
+        "#,
+        down: r#"
+        DROP TABLE IF EXISTS admin_audit_log;
+        DROP TABLE IF EXISTS outbox_job_attempts;
+        ALTER TABLE outbox_jobs DROP COLUMN IF EXISTS provider;
+        ALTER TABLE outbox_jobs DROP COLUMN IF EXISTS failure_category;
+        "#,
+    },
+    Migration {
+        version: 11,
+        name: "add_attempt_timing",
+        up: r#"
+        ALTER TABLE outbox_job_attempts RENAME COLUMN recorded_ms TO finished_ms;
+        ALTER TABLE outbox_job_attempts ADD COLUMN IF NOT EXISTS started_ms BIGINT;
+        ALTER TABLE outbox_job_attempts ADD COLUMN IF NOT EXISTS latency_ms BIGINT;
+        ALTER TABLE outbox_job_attempts ADD COLUMN IF NOT EXISTS provider TEXT;
+        ALTER TABLE outbox_job_attempts ADD COLUMN IF NOT EXISTS endpoint TEXT;
+        "#,
+        down: r#"
+        ALTER TABLE outbox_job_attempts DROP COLUMN IF EXISTS endpoint;
+        ALTER TABLE outbox_job_attempts DROP COLUMN IF EXISTS provider;
+        ALTER TABLE outbox_job_attempts DROP COLUMN IF EXISTS latency_ms;
+        ALTER TABLE outbox_job_attempts DROP COLUMN IF EXISTS started_ms;
+        ALTER TABLE outbox_job_attempts RENAME COLUMN finished_ms TO recorded_ms;
+        "#,
+    },
+    Migration {
+        version: 12,
+        name: "add_anchor_intents",
+        up: r#"
+        -- Signed transactions recorded before broadcast, until the job records its tx ref
+        CREATE TABLE IF NOT EXISTS outbox_anchor_intents (
+            job_id TEXT PRIMARY KEY,
+            network TEXT NOT NULL,
+            chain TEXT NOT NULL,
+            tx_id TEXT NOT NULL,
+            raw_tx TEXT NOT NULL,
+            created_ms BIGINT NOT NULL
+        );
+        "#,
+        down: r#"
+        DROP TABLE IF EXISTS outbox_anchor_intents;
+        "#,
+    },
+    Migration {
+        version: 13,
+        name: "add_tx_ref_scheduling",
+        up: r#"
+        ALTER TABLE outbox_tx_refs ADD COLUMN IF NOT EXISTS created_ms BIGINT;
+        ALTER TABLE outbox_tx_refs ADD COLUMN IF NOT EXISTS checks BIGINT NOT NULL DEFAULT 0;
+        ALTER TABLE outbox_tx_refs ADD COLUMN IF NOT EXISTS next_check_ms BIGINT NOT NULL DEFAULT 0;
+        ALTER TABLE outbox_tx_refs ADD COLUMN IF NOT EXISTS dropped_ms BIGINT;
+        -- Existing unconfirmed refs get the full maximum age from the upgrade
+        UPDATE outbox_tx_refs SET created_ms = (EXTRACT(EPOCH FROM now()) * 1000)::BIGINT;
+        CREATE INDEX IF NOT EXISTS idx_outbox_tx_refs_next_check ON outbox_tx_refs(confirmed, next_check_ms);
+        "#,
+        down: r#"
+        DROP INDEX IF EXISTS idx_outbox_tx_refs_next_check;
+        ALTER TABLE outbox_tx_refs DROP COLUMN IF EXISTS dropped_ms;
+        ALTER TABLE outbox_tx_refs DROP COLUMN IF EXISTS next_check_ms;
+        ALTER TABLE outbox_tx_refs DROP COLUMN IF EXISTS checks;
+        ALTER TABLE outbox_tx_refs DROP COLUMN IF EXISTS created_ms;
+        "#,
+    },
+    Migration {
+        version: 14,
+        name: "add_job_priority_and_tenant",
+        up: r#"
+        ALTER TABLE outbox_jobs ADD COLUMN IF NOT EXISTS priority BIGINT NOT NULL DEFAULT 0;
+        ALTER TABLE outbox_jobs ADD COLUMN IF NOT EXISTS tenant TEXT;
+        -- Virtual time each tenant has been served, for weighted fair claiming.
+        -- Jobs without a tenant share the '' row.
+        CREATE TABLE IF NOT EXISTS outbox_tenant_shares (
+            tenant TEXT PRIMARY KEY,
+            last_start DOUBLE PRECISION NOT NULL,
+            pass DOUBLE PRECISION NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_outbox_jobs_claim ON outbox_jobs(status, priority, tenant, created_ms);
+        "#,
+        down: r#"
+        DROP INDEX IF EXISTS idx_outbox_jobs_claim;
+        DROP TABLE IF EXISTS outbox_tenant_shares;
+        ALTER TABLE outbox_jobs DROP COLUMN IF EXISTS tenant;
+        ALTER TABLE outbox_jobs DROP COLUMN IF EXISTS priority;
+        "#,
+    },
+    Migration {
+        version: 15,
+        name: "add_job_record_fields",
+        up: r#"
+        -- The rest of the evidence record the keeper hashes and anchors.
+        -- metadata is the submitted JSON, NULL when none was given.
+        ALTER TABLE outbox_jobs ADD COLUMN IF NOT EXISTS payload_mime TEXT;
+        ALTER TABLE outbox_jobs ADD COLUMN IF NOT EXISTS metadata TEXT;
+        "#,
+        down: r#"
+        ALTER TABLE outbox_jobs DROP COLUMN IF EXISTS metadata;
+        ALTER TABLE outbox_jobs DROP COLUMN IF EXISTS payload_mime;
+        "#,
+    },
+];
+
+/// Newest PostgreSQL schema version this binary knows about
+pub fn latest_version() -> i32 {
+    latest_of(POSTGRES_MIGRATIONS)
+}
+
+/// `MigrationManager` for PostgreSQL databases
+pub struct PgMigrationManager {
+    pool: Pool<Postgres>,
+}
+
+impl PgMigrationManager {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    async fn init_migration_table(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                applied_at BIGINT NOT NULL,
+                checksum TEXT
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_current_version(&self) -> Result<i32> {
+        let result =
+            sqlx::query_scalar::<_, Option<i32>>("SELECT MAX(version) FROM schema_migrations")
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(result.unwrap_or(0))
+    }
+
+    async fn verify_applied(&self) -> Result<i32> {
+        let current_version = self.get_current_version().await?;
+        check_not_newer(POSTGRES_MIGRATIONS, current_version)?;
+
+        let rows = sqlx::query("SELECT version, checksum FROM schema_migrations")
+            .fetch_all(&self.pool)
+            .await?;
+        let recorded: Vec<(i32, Option<String>)> =
+            rows.iter().map(|row| (row.get(0), row.get(1))).collect();
+        for migration in check_recorded(POSTGRES_MIGRATIONS, &recorded)? {
+            sqlx::query("UPDATE schema_migrations SET checksum = $1 WHERE version = $2")
+                .bind(migration.checksum())
+                .bind(migration.version)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(current_version)
+    }
+
+    /// Apply a migration. Returns false if another process applied it first.
+    async fn apply_migration(&self, migration: &Migration) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        // Record first: a replica migrating concurrently blocks on this row
+        // and then fails the insert, so each migration runs exactly once
+        let now = chrono::Utc::now().timestamp_millis();
+        let recorded = sqlx::query(
+            "INSERT INTO schema_migrations (version, name, applied_at, checksum) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(migration.version)
+        .bind(migration.name)
+        .bind(now)
+        .bind(migration.checksum())
+        .execute(&mut *tx)
+        .await;
+        match recorded {
+            Ok(_) => {}
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Ok(false),
+            Err(e) => return Err(e.into()),
+        }
+
+        for statement in split_statements(migration.up) {
+            sqlx::query(statement).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Run all pending migrations
+    pub async fn migrate(&self) -> Result<()> {
+        self.init_migration_table().await?;
+        let current_version = self.verify_applied().await?;
+
+        for migration in POSTGRES_MIGRATIONS
+            .iter()
+            .filter(|m| m.version > current_version)
+        {
+            tracing::info!(
+                "Applying migration {}: {}",
+                migration.version,
+                migration.name
+            );
+            self.apply_migration(migration).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Revert applied migrations newer than `target`, newest first
+    pub async fn migrate_down(&self, target: i32) -> Result<()> {
+        self.init_migration_table().await?;
+        let current_version = self.verify_applied().await?;
+
+        for migration in POSTGRES_MIGRATIONS
+            .iter()
+            .rev()
+            .filter(|m| m.version > target && m.version <= current_version)
+        {
+            tracing::info!(
+                "Reverting migration {}: {}",
+                migration.version,
+                migration.name
+            );
+            let mut tx = self.pool.begin().await?;
+            for statement in split_statements(migration.down) {
+                sqlx::query(statement).execute(&mut *tx).await?;
+            }
+            sqlx::query("DELETE FROM schema_migrations WHERE version = $1")
+                .bind(migration.version)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn is_up_to_date(&self) -> Result<bool> {
+        self.init_migration_table().await?;
+        let current_version = self.get_current_version().await?;
+        Ok(current_version >= latest_version())
+    }
+
+    pub async fn get_status(&self) -> Result<MigrationStatus> {
+        self.init_migration_table().await?;
+        let current_version = self.get_current_version().await?;
+        let latest_version = latest_version();
+
+        let migrations = sqlx::query(
+            "SELECT version, name, applied_at, checksum FROM schema_migrations ORDER BY version",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let applied_migrations = migrations
+            .into_iter()
+            .map(|row| AppliedMigration {
+                version: row.get::<i32, _>(0),
+                name: row.get::<String, _>(1),
+                applied_at: row.get::<i64, _>(2),
+                checksum: row.get::<Option<String>, _>(3),
+            })
+            .collect();
+
+        Ok(MigrationStatus {
+            current_version,
+            latest_version,
+            is_up_to_date: current_version >= latest_version,
+            applied_migrations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::MigrationError;
+    use crate::testing::postgres_test_pool;
+
+    #[test]
+    fn test_versions_are_sequential() {
+        for (index, migration) in POSTGRES_MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as i32 + 1);
+        }
+    }
+
+    #[test]
+    fn test_versions_match_sqlite() {
+        let versions = |migrations: &[Migration]| {
+            migrations
+                .iter()
+                .map(|m| (m.version, m.name))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            versions(POSTGRES_MIGRATIONS),
+            versions(crate::migrations::MIGRATIONS)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pg_migrate_is_idempotent() {
+        let Some(pool) = postgres_test_pool().await else {
+            return;
+        };
+        let manager = PgMigrationManager::new(pool.clone());
+        manager.migrate().await.unwrap();
+        manager.migrate().await.unwrap();
+
+        let status = manager.get_status().await.unwrap();
+        assert!(status.is_up_to_date);
+        assert_eq!(status.current_version, latest_version());
+        assert!(status
+            .applied_migrations
+            .iter()
+            .all(|m| m.checksum.is_some()));
+
+        // Millisecond timestamps need 64-bit columns
+        sqlx::query(
+            "INSERT INTO outbox_jobs (id, payload_sha256, created_ms, updated_ms) VALUES ('a', 'b', $1, $1)",
+        )
+        .bind(chrono::Utc::now().timestamp_millis())
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pg_concurrent_migrations() {
+        let Some(pool) = postgres_test_pool().await else {
+            return;
+        };
+        let replicas = (0..4).map(|_| {
+            let manager = PgMigrationManager::new(pool.clone());
+            tokio::spawn(async move { manager.migrate().await })
+        });
+        for replica in replicas {
+            replica.await.unwrap().unwrap();
+        }
+
+        let status = PgMigrationManager::new(pool).get_status().await.unwrap();
+        assert_eq!(status.applied_migrations.len(), POSTGRES_MIGRATIONS.len());
+    }
+
+    #[tokio::test]
+    async fn test_pg_guards_and_down_migrations() {
+        let Some(pool) = postgres_test_pool().await else {
+            return;
+        };
+        let manager = PgMigrationManager::new(pool.clone());
+        manager.migrate().await.unwrap();
+
+        manager.migrate_down(2).await.unwrap();
+        assert_eq!(manager.get_status().await.unwrap().current_version, 2);
+        manager.migrate().await.unwrap();
+
+        sqlx::query("UPDATE schema_migrations SET checksum = 'edited' WHERE version = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.migrate().await,
+            Err(MigrationError::ChecksumMismatch { version: 1, .. })
+        ));
+
+        sqlx::query(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (99, 'future', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(matches!(
+            manager.migrate().await,
+            Err(MigrationError::DatabaseNewer { database: 99, .. })
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+//! Outcome vocabulary for outbox jobs, shared by the keeper that records
+//! attempts and the API that reports dead-lettered jobs.
+
+use std::fmt;
+
+/// Why a job attempt failed, derived from the error the keeper recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureCategory {
+    Network,
+    Provider,
+    Invalid,
+    LeaseExpired,
+    /// The keeper shut down before the provider answered
+    Interrupted,
+    /// The anchored transaction was not confirmed within the maximum age
+    Dropped,
+    Unknown,
+}
+
+impl FailureCategory {
+    /// Classify an error message as written to `last_error`.
+    ///
+    /// The keeper stores `AnchorError`'s display text, so the prefix identifies the variant.
+    pub fn classify(error: &str) -> Self {
+        if error.starts_with("network error:") {
+            FailureCategory::Network
+        } else if error.starts_with("provider:") {
+            FailureCategory::Provider
+        } else if error.starts_with("invalid state:") {
+            FailureCategory::Invalid
+        } else if error == LEASE_EXPIRED_ERROR {
+            FailureCategory::LeaseExpired
+        } else if error == SHUTDOWN_ERROR {
+            FailureCategory::Interrupted
+        } else if error.starts_with(TX_DROPPED_ERROR) {
+            FailureCategory::Dropped
+        } else {
+            FailureCategory::Unknown
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FailureCategory::Network => "network",
+            FailureCategory::Provider => "provider",
+            FailureCategory::Invalid => "invalid",
+            FailureCategory::LeaseExpired => "lease_expired",
+            FailureCategory::Interrupted => "interrupted",
+            FailureCategory::Dropped => "dropped",
+            FailureCategory::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for FailureCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How one attempt at a job ended, as recorded in `outbox_job_attempts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    Done,
+    /// Temporary failure; the job was requeued with backoff
+    Retry,
+    /// Permanent failure; the job is dead-lettered
+    Failed,
+    /// The worker's lease ran out and the reaper requeued the job
+    LeaseExpired,
+}
+
+impl AttemptOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AttemptOutcome::Done => "done",
+            AttemptOutcome::Retry => "retry",
+            AttemptOutcome::Failed => "failed",
+            AttemptOutcome::LeaseExpired => "lease_expired",
+        }
+    }
+}
+
+/// `last_error` written by the lease reaper
+pub const LEASE_EXPIRED_ERROR: &str = "lease expired";
+
+/// `last_error` written when the keeper requeues a job it could not finish before shutting down
+pub const SHUTDOWN_ERROR: &str = "keeper shut down before the anchor completed";
+
+/// Prefix of the `last_error` written when a job is requeued because its
+/// transaction was never confirmed
+pub const TX_DROPPED_ERROR: &str = "transaction dropped";
+
+/// PostgreSQL `NOTIFY` channel for newly queued jobs; the payload is the job id
+pub const JOBS_QUEUED_CHANNEL: &str = "phoenix_outbox_jobs";
+
+/// Priority of a job submitted without one. Higher priorities are claimed first.
+pub const DEFAULT_PRIORITY: i64 = 0;
+
+/// Range of `outbox_jobs.priority`; below the default is for bulk work such as backfills
+pub const MIN_PRIORITY: i64 = -10;
+pub const MAX_PRIORITY: i64 = 10;
+
+/// `next_attempt_ms` of a job submitted with `hold`: it is never due, so the
+/// keeper leaves it alone until `POST /evidence/{id}/release` makes it due now
+pub const HELD_NEXT_ATTEMPT_MS: i64 = i64::MAX;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_anchor_errors() {
+        assert_eq!(
+            FailureCategory::classify("network error: connection refused"),
+            FailureCategory::Network
+        );
+        assert_eq!(
+            FailureCategory::classify("provider: nonce too low"),
+            FailureCategory::Provider
+        );
+        assert_eq!(
+            FailureCategory::classify("invalid state: digest mismatch"),
+            FailureCategory::Invalid
+        );
+        assert_eq!(
+            FailureCategory::classify(LEASE_EXPIRED_ERROR),
+            FailureCategory::LeaseExpired
+        );
+        assert_eq!(
+            FailureCategory::classify(SHUTDOWN_ERROR),
+            FailureCategory::Interrupted
+        );
+        assert_eq!(
+            FailureCategory::classify("transaction dropped: 0xabc not confirmed after 3600s"),
+            FailureCategory::Dropped
+        );
+        assert_eq!(
+            FailureCategory::classify("edited by hand"),
+            FailureCategory::Unknown
+        );
+        assert_eq!(FailureCategory::LeaseExpired.to_string(), "lease_expired");
+    }
+}
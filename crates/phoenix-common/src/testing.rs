@@ -0,0 +1,168 @@
+//! Database fixtures shared by the test suites.
+//!
+//! SQLite tests get a schema-ready pool on a file the caller owns (usually a
+//! `tempfile::NamedTempFile`) and seed jobs with [`TestJob`].
+//!
+//! PostgreSQL tests need a real server: point `PHOENIX_TEST_POSTGRES_URL` at a disposable server (a local
+//! `pg_ctl`-managed cluster is enough, no container needed). Each call creates
+//! a fresh database on it; tests skip when the variable is unset.
+
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::{Pool, Postgres, Sqlite};
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+pub const POSTGRES_URL_ENV: &str = "PHOENIX_TEST_POSTGRES_URL";
+
+static NEXT_DATABASE: AtomicU32 = AtomicU32::new(0);
+
+/// A pool on a new, empty database, or `None` if no test server is configured
+pub async fn postgres_test_pool() -> Option<Pool<Postgres>> {
+    let Ok(server_url) = std::env::var(POSTGRES_URL_ENV) else {
+        eprintln!("{} not set; skipping PostgreSQL test", POSTGRES_URL_ENV);
+        return None;
+    };
+
+    let name = format!(
+        "phoenix_test_{}_{}_{}",
+        std::process::id(),
+        chrono::Utc::now().timestamp_millis(),
+        NEXT_DATABASE.fetch_add(1, Ordering::Relaxed)
+    );
+    let admin = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&server_url)
+        .await
+        .expect("connect to test PostgreSQL server");
+    sqlx::query(&format!("CREATE DATABASE {}", name))
+        .execute(&admin)
+        .await
+        .expect("create test database");
+    admin.close().await;
+
+    let options = server_url
+        .parse::<PgConnectOptions>()
+        .expect("valid PostgreSQL URL")
+        .database(&name);
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect_with(options)
+        .await
+        .expect("connect to test database");
+    Some(pool)
+}
+
+/// A pool on the SQLite database at `path`, migrated to the latest schema
+pub async fn sqlite_test_pool(path: &Path) -> Pool<Sqlite> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(5)
+        .connect(&format!("sqlite://{}", path.display()))
+        .await
+        .expect("connect to test SQLite database");
+    crate::schema::ensure_schema(&pool)
+        .await
+        .expect("initialize test schema");
+    pool
+}
+
+/// An `outbox_jobs` row to seed a test database with
+///
+/// Defaults to a queued, never-attempted job created at the epoch and due
+/// immediately; the `with_*` methods override single columns.
+#[derive(Debug, Clone)]
+pub struct TestJob {
+    id: String,
+    digest: String,
+    status: String,
+    attempts: i64,
+    last_error: Option<String>,
+    created_ms: i64,
+    next_attempt_ms: i64,
+    provider: Option<String>,
+}
+
+impl TestJob {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            digest: "abcd1234".to_string(),
+            status: "queued".to_string(),
+            attempts: 0,
+            last_error: None,
+            created_ms: 0,
+            next_attempt_ms: 0,
+            provider: None,
+        }
+    }
+
+    pub fn with_digest(mut self, digest: &str) -> Self {
+        self.digest = digest.to_string();
+        self
+    }
+
+    pub fn with_status(mut self, status: &str) -> Self {
+        self.status = status.to_string();
+        self
+    }
+
+    pub fn with_attempts(mut self, attempts: i64) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    pub fn with_last_error(mut self, last_error: &str) -> Self {
+        self.last_error = Some(last_error.to_string());
+        self
+    }
+
+    /// Sets both `created_ms` and `updated_ms`
+    pub fn with_created_ms(mut self, created_ms: i64) -> Self {
+        self.created_ms = created_ms;
+        self
+    }
+
+    pub fn with_next_attempt_ms(mut self, next_attempt_ms: i64) -> Self {
+        self.next_attempt_ms = next_attempt_ms;
+        self
+    }
+
+    pub fn with_provider(mut self, provider: &str) -> Self {
+        self.provider = Some(provider.to_string());
+        self
+    }
+
+    pub async fn insert(&self, pool: &Pool<Sqlite>) {
+        sqlx::query(
+            "INSERT INTO outbox_jobs (id, payload_sha256, status, attempts, last_error, created_ms, updated_ms, next_attempt_ms, provider) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, ?7, ?8)",
+        )
+        .bind(&self.id)
+        .bind(&self.digest)
+        .bind(&self.status)
+        .bind(self.attempts)
+        .bind(&self.last_error)
+        .bind(self.created_ms)
+        .bind(self.next_attempt_ms)
+        .bind(&self.provider)
+        .execute(pool)
+        .await
+        .expect("insert test job");
+    }
+
+    pub async fn insert_postgres(&self, pool: &Pool<Postgres>) {
+        sqlx::query(
+            "INSERT INTO outbox_jobs (id, payload_sha256, status, attempts, last_error, created_ms, updated_ms, next_attempt_ms, provider) VALUES ($1, $2, $3, $4, $5, $6, $6, $7, $8)",
+        )
+        .bind(&self.id)
+        .bind(&self.digest)
+        .bind(&self.status)
+        .bind(self.attempts)
+        .bind(&self.last_error)
+        .bind(self.created_ms)
+        .bind(self.next_attempt_ms)
+        .bind(&self.provider)
+        .execute(pool)
+        .await
+        .expect("insert test job");
+    }
+}
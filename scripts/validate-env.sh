@@ -180,8 +180,15 @@ validate_env() {
                 log_error "Neither KEEPER_DB_URL nor DATABASE_URL is set (one is required)"
             fi
             
-            # Provider configuration (optional, defaults to stub)
-            log_info "KEEPER_PROVIDER: ${KEEPER_PROVIDER:-stub} (optional, defaults to 'stub')"
+            # Provider configuration (required, no default)
+            if [ -n "${KEEPER_PROVIDER:-}" ]; then
+                log_info "KEEPER_PROVIDER: ${KEEPER_PROVIDER}"
+            else
+                log_error "KEEPER_PROVIDER is not set (required: etherlink, solana, multi or stub)"
+            fi
+            if [ -n "${KEEPER_USE_STUB:-}" ]; then
+                log_error "KEEPER_USE_STUB is no longer supported; set KEEPER_PROVIDER=stub or etherlink instead"
+            fi
             
             if [ "${KEEPER_PROVIDER:-}" = "etherlink" ]; then
                 validate_url "ETHERLINK_ENDPOINT" "${ETHERLINK_ENDPOINT}" "false" || true